@@ -1,20 +1,97 @@
+use super::Prefetch;
 use anyhow::{bail, Context, Result};
-use htg::{download::DownloadConfig, SrtmServiceBuilder};
+use htg::{download::DownloadConfig, SrtmResolution, SrtmServiceBuilder, Units, VOID_VALUE};
 use indicatif::{ProgressBar, ProgressStyle};
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// How to handle an output column whose name already exists in the input CSV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColumnConflict {
+    /// Abort with an error
+    Error,
+    /// Append a numeric suffix (e.g. `elevation_2`)
+    Suffix,
+}
+
+/// Batch output file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// CSV rows
+    Csv,
+    /// GeoJSON FeatureCollection
+    Geojson,
+    /// One `{"lat", "lon", "elevation"}` JSON object per line, written as
+    /// each chunk is looked up (stdout unless `--output` is given)
+    Jsonl,
+}
+
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Geojson => "geojson",
+            OutputFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Input and output column names for CSV processing.
+pub struct CsvColumns {
+    pub lat: String,
+    pub lon: String,
+    pub elevation: String,
+    pub resolution: String,
+    pub on_conflict: ColumnConflict,
+}
+
+/// Format and buffering of the batch output file.
+pub struct WriterOptions {
+    /// Output format; the input format when `None`
+    pub format: Option<OutputFormat>,
+    /// Unit for output elevations (not GPX, whose `<ele>` is always meters)
+    pub units: Units,
+    /// Decimal places of interpolated elevations (not GPX, whose `<ele>`
+    /// always has two)
+    pub precision: usize,
+    /// Capacity of the output `BufWriter` in bytes
+    pub buffer_size: usize,
+    /// Flush the output after every N rows (CSV only)
+    pub flush_every: Option<u64>,
+    /// Read, look up, and write CSV rows in chunks instead of loading the
+    /// whole input first (CSV only)
+    pub stream: bool,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            units: Units::Meters,
+            precision: 2,
+            buffer_size: 8 * 1024,
+            flush_every: None,
+            stream: false,
+        }
+    }
+}
 
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     data_dir: Option<PathBuf>,
     cache_size: u64,
     auto_download: bool,
+    download_concurrency: usize,
     input: PathBuf,
     output: Option<PathBuf>,
-    lat_col: String,
-    lon_col: String,
+    columns: CsvColumns,
     interpolate: bool,
+    include_resolution: bool,
+    input_crs: Option<u32>,
+    writer_options: WriterOptions,
 ) -> Result<()> {
     // Build the service
     let mut builder = match data_dir {
@@ -31,6 +108,10 @@ pub fn run(
     }
 
     let service = builder.build().context("Failed to create SRTM service")?;
+    let prefetch = auto_download.then(|| Prefetch {
+        concurrency: download_concurrency,
+        ..Prefetch::default()
+    });
 
     // Detect file format
     let extension = input
@@ -40,22 +121,119 @@ pub fn run(
         .to_lowercase();
 
     match extension.as_str() {
-        "csv" => process_csv(&service, &input, output, &lat_col, &lon_col, interpolate),
-        "geojson" | "json" => process_geojson(&service, &input, output, interpolate),
+        "csv"
+            if writer_options.format == Some(OutputFormat::Geojson)
+                && writer_options.flush_every.is_some() =>
+        {
+            bail!("--flush-every is only supported for CSV output")
+        }
+        "csv" if writer_options.format == Some(OutputFormat::Geojson) && writer_options.stream => {
+            bail!("--stream is only supported for CSV output")
+        }
+        "csv" => process_csv(
+            &service,
+            &input,
+            output,
+            &columns,
+            interpolate,
+            prefetch.as_ref(),
+            include_resolution,
+            input_crs,
+            &writer_options,
+        ),
+        "geojson" | "json" if include_resolution => {
+            bail!("--include-resolution is only supported for CSV input")
+        }
+        "geojson" | "json" if input_crs.is_some() => {
+            bail!("--input-crs is only supported for CSV input")
+        }
+        "geojson" | "json" if writer_options.flush_every.is_some() => {
+            bail!("--flush-every is only supported for CSV input")
+        }
+        "geojson" | "json" if writer_options.stream => {
+            bail!("--stream is only supported for CSV input")
+        }
+        "geojson" | "json" => process_geojson(
+            &service,
+            &input,
+            output,
+            &columns.elevation,
+            interpolate,
+            &writer_options,
+        ),
+        "gpx" if writer_options.units != Units::Meters => {
+            bail!("--units is not supported for GPX input: <ele> is always in meters")
+        }
+        "gpx" if writer_options.format.is_some() => {
+            bail!("--output-format is not supported for GPX input")
+        }
+        "gpx"
+            if include_resolution
+                || input_crs.is_some()
+                || writer_options.flush_every.is_some()
+                || writer_options.stream =>
+        {
+            bail!("--include-resolution, --input-crs, --flush-every, and --stream are not supported for GPX input")
+        }
+        "gpx" => process_gpx(&service, &input, output, writer_options.buffer_size),
         _ => bail!(
-            "Unsupported file format: {}. Use .csv or .geojson",
+            "Unsupported file format: {}. Use .csv, .geojson, or .gpx",
             extension
         ),
     }
 }
 
+/// Pick the name for a new output column, resolving clashes with `existing`.
+fn output_column(existing: &[&str], wanted: &str, on_conflict: ColumnConflict) -> Result<String> {
+    if !existing.contains(&wanted) {
+        return Ok(wanted.to_string());
+    }
+
+    match on_conflict {
+        ColumnConflict::Error => bail!(
+            "Column '{}' already exists in the input CSV. Choose another name with \
+             --elevation-col/--resolution-col or pass --on-column-conflict suffix",
+            wanted
+        ),
+        ColumnConflict::Suffix => Ok((2..)
+            .map(|n| format!("{}_{}", wanted, n))
+            .find(|name| !existing.contains(&name.as_str()))
+            .unwrap()),
+    }
+}
+
+/// Reproject `(y, x)` pairs in `input_crs` to WGS84 `(lat, lon)`.
+#[cfg(feature = "proj")]
+fn reproject(coords: Vec<(f64, f64)>, input_crs: Option<u32>) -> Result<Vec<(f64, f64)>> {
+    let Some(epsg) = input_crs else {
+        return Ok(coords);
+    };
+    let crs = htg::proj::Crs::from_epsg(epsg)?;
+    Ok(coords
+        .into_iter()
+        .map(|(y, x)| crs.to_wgs84(x, y))
+        .collect())
+}
+
+#[cfg(not(feature = "proj"))]
+fn reproject(coords: Vec<(f64, f64)>, input_crs: Option<u32>) -> Result<Vec<(f64, f64)>> {
+    if input_crs.is_some() {
+        bail!("--input-crs requires htg-cli to be built with the `proj` feature");
+    }
+    Ok(coords)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn process_csv(
     service: &htg::SrtmService,
     input: &PathBuf,
     output: Option<PathBuf>,
-    lat_col: &str,
-    lon_col: &str,
+    columns: &CsvColumns,
     interpolate: bool,
+    prefetch: Option<&Prefetch>,
+    include_resolution: bool,
+    input_crs: Option<u32>,
+    writer_options: &WriterOptions,
 ) -> Result<()> {
     let file = File::open(input).context("Failed to open input file")?;
     let mut reader = csv::Reader::from_reader(BufReader::new(file));
@@ -64,17 +242,137 @@ fn process_csv(
     let headers = reader.headers()?.clone();
     let lat_idx = headers
         .iter()
-        .position(|h| h == lat_col)
-        .with_context(|| format!("Column '{}' not found in CSV", lat_col))?;
+        .position(|h| h == columns.lat)
+        .with_context(|| format!("Column '{}' not found in CSV", columns.lat))?;
     let lon_idx = headers
         .iter()
-        .position(|h| h == lon_col)
-        .with_context(|| format!("Column '{}' not found in CSV", lon_col))?;
+        .position(|h| h == columns.lon)
+        .with_context(|| format!("Column '{}' not found in CSV", columns.lon))?;
+
+    // JSON lines carry only the coordinates, so output column names don't matter
+    if writer_options.format == Some(OutputFormat::Jsonl) {
+        let mut out = jsonl_writer(output.as_deref(), writer_options.buffer_size)?;
+        for_each_chunk(
+            service,
+            &mut reader,
+            (lat_idx, lon_idx),
+            interpolate,
+            prefetch,
+            include_resolution,
+            input_crs,
+            writer_options.units,
+            |_, coords, elevations, resolutions| {
+                write_jsonl(
+                    &mut out,
+                    coords,
+                    elevations,
+                    resolutions,
+                    writer_options.precision,
+                )
+            },
+        )?;
+
+        if let Some(output) = output {
+            println!("Output written to: {}", output.display());
+        }
+        return Ok(());
+    }
+
+    // Name the output columns before doing any work so conflicts fail fast
+    let mut new_headers: Vec<&str> = headers.iter().collect();
+    let elevation_col = output_column(&new_headers, &columns.elevation, columns.on_conflict)?;
+    new_headers.push(&elevation_col);
+    let resolution_col = if include_resolution {
+        Some(output_column(
+            &new_headers,
+            &columns.resolution,
+            columns.on_conflict,
+        )?)
+    } else {
+        None
+    };
+    if let Some(resolution_col) = &resolution_col {
+        new_headers.push(resolution_col);
+    }
+
+    // Prepare output
+    let format = writer_options.format.unwrap_or(OutputFormat::Csv);
+    let output_path = output.unwrap_or_else(|| default_output_path(input, format.extension()));
+
+    if writer_options.stream {
+        let mut writer = csv_writer(&output_path, writer_options.buffer_size)?;
+        writer.write_record(&new_headers)?;
+
+        // The total is unknown without reading everything, so count rows
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} [{elapsed_precise}] {pos} rows ({per_sec})")?,
+        );
+
+        let mut rows_written = 0;
+        for_each_chunk(
+            service,
+            &mut reader,
+            (lat_idx, lon_idx),
+            interpolate,
+            prefetch,
+            include_resolution,
+            input_crs,
+            writer_options.units,
+            |records, _, elevations, resolutions| {
+                for (i, record) in records.iter().enumerate() {
+                    rows_written += 1;
+                    let resolution = resolutions.map(|r| r[i]);
+                    write_enriched_row(
+                        &mut writer,
+                        record,
+                        elevations[i],
+                        resolution,
+                        rows_written,
+                        writer_options,
+                    )?;
+                }
+                pb.inc(records.len() as u64);
+                Ok(())
+            },
+        )?;
+
+        pb.finish_with_message("done");
+        writer.flush()?;
+
+        println!("Output written to: {}", output_path.display());
+        return Ok(());
+    }
 
     // Collect records for progress bar
     let records: Vec<_> = reader.records().collect::<Result<_, _>>()?;
     let total = records.len() as u64;
 
+    // Parse coordinates up front
+    let coords = records
+        .iter()
+        .map(|record| record_coords(record, lat_idx, lon_idx))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Look up in WGS84; the output keeps the original coordinate values
+    let coords = reproject(coords, input_crs)?;
+
+    // Fetch every missing tile before the lookup pass rather than one by
+    // one as the service first needs them
+    if let Some(prefetch) = prefetch {
+        prefetch.fetch_missing(service.data_dir(), coords.iter().copied())?;
+    }
+
+    // Look up every row at once; the batch path loads each tile only once
+    let (elevations, resolutions) = lookup_elevations(
+        service,
+        &coords,
+        interpolate,
+        include_resolution,
+        writer_options.units,
+    );
+
     let pb = ProgressBar::new(total);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -84,51 +382,69 @@ fn process_csv(
             .progress_chars("#>-"),
     );
 
-    // Prepare output
-    let output_path = output.unwrap_or_else(|| {
-        let stem = input.file_stem().unwrap().to_string_lossy();
-        input.with_file_name(format!("{}_elevation.csv", stem))
-    });
-    let output_file = File::create(&output_path).context("Failed to create output file")?;
-    let mut writer = csv::Writer::from_writer(BufWriter::new(output_file));
+    if format == OutputFormat::Geojson {
+        // Keep every column except the coordinates as a feature property
+        let properties: Vec<(usize, &str)> = headers
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != lat_idx && i != lon_idx)
+            .collect();
+
+        let mut features = Vec::with_capacity(records.len());
+        for (i, (record, &(lat, lon))) in records.iter().zip(&coords).enumerate() {
+            let mut feature_properties = geojson::JsonObject::new();
+            for &(idx, name) in &properties {
+                feature_properties.insert(name.to_string(), record.get(idx).unwrap_or("").into());
+            }
+            feature_properties.insert(
+                elevation_col.clone(),
+                elevations[i].to_json(writer_options.precision),
+            );
+            if let (Some(resolutions), Some(resolution_col)) = (&resolutions, &resolution_col) {
+                feature_properties.insert(
+                    resolution_col.clone(),
+                    resolutions[i].map(|r| r.as_str()).into(),
+                );
+            }
+
+            features.push(geojson::Feature {
+                geometry: Some(geojson::Geometry::new(geojson::Value::Point(vec![
+                    lon, lat,
+                ]))),
+                properties: Some(feature_properties),
+                ..Default::default()
+            });
+            pb.inc(1);
+        }
+        pb.finish_with_message("done");
+
+        let collection = geojson::GeoJson::FeatureCollection(geojson::FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        });
+        write_geojson(&output_path, &collection, writer_options.buffer_size)?;
+
+        println!("Output written to: {}", output_path.display());
+        return Ok(());
+    }
+
+    let mut writer = csv_writer(&output_path, writer_options.buffer_size)?;
 
     // Write header
-    let mut new_headers: Vec<&str> = headers.iter().collect();
-    new_headers.push("elevation");
     writer.write_record(&new_headers)?;
 
     // Process records
-    for record in records {
-        let lat: f64 = record
-            .get(lat_idx)
-            .context("Missing latitude")?
-            .parse()
-            .context("Invalid latitude")?;
-        let lon: f64 = record
-            .get(lon_idx)
-            .context("Missing longitude")?
-            .parse()
-            .context("Invalid longitude")?;
-
-        let elevation = if interpolate {
-            service
-                .get_elevation_interpolated(lat, lon)
-                .ok()
-                .flatten()
-                .map(|e| format!("{:.2}", e))
-                .unwrap_or_else(|| "void".to_string())
-        } else {
-            service
-                .get_elevation(lat, lon)
-                .ok()
-                .flatten()
-                .map(|e| e.to_string())
-                .unwrap_or_else(|| "void".to_string())
-        };
-
-        let mut new_record: Vec<&str> = record.iter().collect();
-        new_record.push(&elevation);
-        writer.write_record(&new_record)?;
+    for (i, record) in records.iter().enumerate() {
+        let resolution = resolutions.as_ref().map(|r| r[i]);
+        write_enriched_row(
+            &mut writer,
+            record,
+            elevations[i],
+            resolution,
+            i as u64 + 1,
+            writer_options,
+        )?;
 
         pb.inc(1);
     }
@@ -140,11 +456,226 @@ fn process_csv(
     Ok(())
 }
 
+/// Rows read, looked up, and written together by `--stream`: enough for the
+/// batch path to group lookups by tile while keeping memory use flat.
+const STREAM_CHUNK_ROWS: usize = 4096;
+
+/// Read, reproject, prefetch, and look up the CSV records of `reader` in
+/// chunks of [`STREAM_CHUNK_ROWS`], handing each chunk's records, WGS84
+/// coordinates, elevations, and resolutions to `write_chunk`.
+#[allow(clippy::too_many_arguments)]
+fn for_each_chunk<R, F>(
+    service: &htg::SrtmService,
+    reader: &mut csv::Reader<R>,
+    (lat_idx, lon_idx): (usize, usize),
+    interpolate: bool,
+    prefetch: Option<&Prefetch>,
+    include_resolution: bool,
+    input_crs: Option<u32>,
+    units: Units,
+    mut write_chunk: F,
+) -> Result<()>
+where
+    R: std::io::Read,
+    F: FnMut(
+        &[csv::StringRecord],
+        &[(f64, f64)],
+        &[Elevation],
+        Option<&[Option<SrtmResolution>]>,
+    ) -> Result<()>,
+{
+    let mut records = reader.records();
+    let mut chunk = Vec::with_capacity(STREAM_CHUNK_ROWS);
+    loop {
+        chunk.clear();
+        for record in records.by_ref().take(STREAM_CHUNK_ROWS) {
+            chunk.push(record?);
+        }
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        let coords = chunk
+            .iter()
+            .map(|record| record_coords(record, lat_idx, lon_idx))
+            .collect::<Result<Vec<_>>>()?;
+        let coords = reproject(coords, input_crs)?;
+        if let Some(prefetch) = prefetch {
+            prefetch.fetch_missing(service.data_dir(), coords.iter().copied())?;
+        }
+
+        let (elevations, resolutions) =
+            lookup_elevations(service, &coords, interpolate, include_resolution, units);
+        write_chunk(&chunk, &coords, &elevations, resolutions.as_deref())?;
+    }
+}
+
+/// Parse the `(lat, lon)` columns of a CSV record.
+fn record_coords(record: &csv::StringRecord, lat_idx: usize, lon_idx: usize) -> Result<(f64, f64)> {
+    let lat: f64 = record
+        .get(lat_idx)
+        .context("Missing latitude")?
+        .parse()
+        .context("Invalid latitude")?;
+    let lon: f64 = record
+        .get(lon_idx)
+        .context("Missing longitude")?
+        .parse()
+        .context("Invalid longitude")?;
+    Ok((lat, lon))
+}
+
+/// Write an input record followed by its elevation (and resolution, when
+/// requested) columns.
+fn write_enriched_row<W: Write>(
+    writer: &mut csv::Writer<W>,
+    record: &csv::StringRecord,
+    elevation: Elevation,
+    resolution: Option<Option<SrtmResolution>>,
+    rows_written: u64,
+    writer_options: &WriterOptions,
+) -> Result<()> {
+    let elevation = elevation.to_csv(writer_options.precision);
+    let mut new_record: Vec<&str> = record.iter().collect();
+    new_record.push(&elevation);
+    if let Some(resolution) = resolution {
+        new_record.push(resolution.map(|r| r.as_str()).unwrap_or(""));
+    }
+    write_row(
+        writer,
+        &new_record,
+        rows_written,
+        writer_options.flush_every,
+    )
+}
+
+/// An elevation looked up for one CSV row.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Elevation {
+    /// Nearest sample, [`VOID_VALUE`] for void or missing data
+    Nearest(i16),
+    /// Bilinear interpolation, NaN for void or missing data
+    Interpolated(f64),
+}
+
+impl Elevation {
+    /// Convert to `units`, keeping void as void.
+    fn in_units(self, units: Units) -> Self {
+        match self {
+            Elevation::Nearest(VOID_VALUE) => self,
+            Elevation::Nearest(e) => Elevation::Nearest(units.convert_whole(e)),
+            Elevation::Interpolated(e) => Elevation::Interpolated(units.convert(e)),
+        }
+    }
+
+    /// CSV cell text, with `void` for missing data and interpolated values
+    /// to `precision` decimal places.
+    fn to_csv(self, precision: usize) -> String {
+        match self {
+            Elevation::Nearest(VOID_VALUE) => "void".to_string(),
+            Elevation::Nearest(e) => e.to_string(),
+            Elevation::Interpolated(e) if e.is_nan() => "void".to_string(),
+            Elevation::Interpolated(e) => super::format_rounded(e, precision),
+        }
+    }
+
+    /// GeoJSON property value, with `null` for missing data and interpolated
+    /// values rounded to `precision` decimal places.
+    fn to_json(self, precision: usize) -> serde_json::Value {
+        match self {
+            Elevation::Nearest(VOID_VALUE) => serde_json::Value::Null,
+            Elevation::Nearest(e) => e.into(),
+            Elevation::Interpolated(e) if e.is_nan() => serde_json::Value::Null,
+            Elevation::Interpolated(e) => super::round_to(e, precision).into(),
+        }
+    }
+}
+
+/// Look up all coordinates through the tile-grouped batch API, in input
+/// order and in `units`, plus the covering tile's resolution when requested.
+fn lookup_elevations(
+    service: &htg::SrtmService,
+    coords: &[(f64, f64)],
+    interpolate: bool,
+    include_resolution: bool,
+    units: Units,
+) -> (Vec<Elevation>, Option<Vec<Option<SrtmResolution>>>) {
+    // The batch path groups by tile, so the covering resolution comes for free
+    let (nearest, resolutions) = if include_resolution {
+        let (nearest, resolutions): (Vec<_>, Vec<_>) = service
+            .get_elevations_batch_with_resolution(coords, VOID_VALUE)
+            .into_iter()
+            .unzip();
+        (Some(nearest), Some(resolutions))
+    } else {
+        (None, None)
+    };
+
+    let elevations = if interpolate {
+        service
+            .get_elevations_batch_interpolated(coords, f64::NAN)
+            .into_iter()
+            .map(|e| Elevation::Interpolated(e).in_units(units))
+            .collect()
+    } else {
+        nearest
+            .unwrap_or_else(|| service.get_elevations_batch(coords, VOID_VALUE))
+            .into_iter()
+            .map(|e| Elevation::Nearest(e).in_units(units))
+            .collect()
+    };
+    (elevations, resolutions)
+}
+
+/// `<input stem>_elevation.<extension>` next to the input file.
+fn default_output_path(input: &Path, extension: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap().to_string_lossy();
+    input.with_file_name(format!("{}_elevation.{}", stem, extension))
+}
+
+/// Write a GeoJSON document through a `buffer_size`-byte buffer.
+fn write_geojson(path: &Path, geojson: &geojson::GeoJson, buffer_size: usize) -> Result<()> {
+    let output_file = File::create(path).context("Failed to create output file")?;
+    let mut writer = BufWriter::with_capacity(buffer_size, output_file);
+    serde_json::to_writer_pretty(&mut writer, geojson)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Create the CSV output writer with a `buffer_size`-byte buffer.
+fn csv_writer(path: &Path, buffer_size: usize) -> Result<csv::Writer<BufWriter<File>>> {
+    let file = File::create(path).context("Failed to create output file")?;
+    Ok(csv::Writer::from_writer(BufWriter::with_capacity(
+        buffer_size,
+        file,
+    )))
+}
+
+/// Write one CSV row, flushing to disk once every `flush_every` rows.
+///
+/// `rows_written` is the 1-based count of data rows including this one.
+// `u64::is_multiple_of` is newer than the MSRV
+#[allow(clippy::manual_is_multiple_of)]
+fn write_row<W: Write>(
+    writer: &mut csv::Writer<W>,
+    record: &[&str],
+    rows_written: u64,
+    flush_every: Option<u64>,
+) -> Result<()> {
+    writer.write_record(record)?;
+    if matches!(flush_every, Some(n) if rows_written % n == 0) {
+        writer.flush()?;
+    }
+    Ok(())
+}
+
 fn process_geojson(
     service: &htg::SrtmService,
     input: &PathBuf,
     output: Option<PathBuf>,
+    elevation_col: &str,
     interpolate: bool,
+    writer_options: &WriterOptions,
 ) -> Result<()> {
     let file = File::open(input).context("Failed to open input file")?;
     let reader = BufReader::new(file);
@@ -152,15 +683,49 @@ fn process_geojson(
     let geojson: geojson::GeoJson =
         serde_json::from_reader(reader).context("Failed to parse GeoJSON")?;
 
+    if writer_options.format == Some(OutputFormat::Jsonl) {
+        let coords = geojson_points(geojson);
+        let (elevations, _) =
+            lookup_elevations(service, &coords, interpolate, false, writer_options.units);
+        let mut out = jsonl_writer(output.as_deref(), writer_options.buffer_size)?;
+        write_jsonl(
+            &mut out,
+            &coords,
+            &elevations,
+            None,
+            writer_options.precision,
+        )?;
+        if let Some(output) = output {
+            println!("Output written to: {}", output.display());
+        }
+        return Ok(());
+    }
+
+    if writer_options.format == Some(OutputFormat::Csv) {
+        return geojson_points_to_csv(
+            service,
+            geojson,
+            &output.unwrap_or_else(|| default_output_path(input, OutputFormat::Csv.extension())),
+            elevation_col,
+            interpolate,
+            writer_options,
+        );
+    }
+
     let result = match geojson {
         geojson::GeoJson::Geometry(geometry) => {
-            let enriched = add_elevations_to_geometry(service, geometry, interpolate)?;
+            let enriched =
+                add_elevations_to_geometry(service, geometry, interpolate, writer_options)?;
             geojson::GeoJson::Geometry(enriched)
         }
         geojson::GeoJson::Feature(mut feature) => {
             if let Some(geometry) = feature.geometry.take() {
-                feature.geometry =
-                    Some(add_elevations_to_geometry(service, geometry, interpolate)?);
+                feature.geometry = Some(add_elevations_to_geometry(
+                    service,
+                    geometry,
+                    interpolate,
+                    writer_options,
+                )?);
             }
             geojson::GeoJson::Feature(feature)
         }
@@ -174,8 +739,12 @@ fn process_geojson(
 
             for feature in &mut fc.features {
                 if let Some(geometry) = feature.geometry.take() {
-                    feature.geometry =
-                        Some(add_elevations_to_geometry(service, geometry, interpolate)?);
+                    feature.geometry = Some(add_elevations_to_geometry(
+                        service,
+                        geometry,
+                        interpolate,
+                        writer_options,
+                    )?);
                 }
                 pb.inc(1);
             }
@@ -185,23 +754,134 @@ fn process_geojson(
     };
 
     // Write output
-    let output_path = output.unwrap_or_else(|| {
-        let stem = input.file_stem().unwrap().to_string_lossy();
-        input.with_file_name(format!("{}_elevation.geojson", stem))
-    });
-    let output_file = File::create(&output_path).context("Failed to create output file")?;
-    let mut writer = BufWriter::new(output_file);
-    serde_json::to_writer_pretty(&mut writer, &result)?;
+    let output_path =
+        output.unwrap_or_else(|| default_output_path(input, OutputFormat::Geojson.extension()));
+    write_geojson(&output_path, &result, writer_options.buffer_size)?;
+
+    println!("Output written to: {}", output_path.display());
+    Ok(())
+}
+
+/// Write every Point/MultiPoint position in `geojson` as a `lon,lat,elevation`
+/// CSV row. Other geometries are skipped with a warning.
+fn geojson_points_to_csv(
+    service: &htg::SrtmService,
+    geojson: geojson::GeoJson,
+    output_path: &Path,
+    elevation_col: &str,
+    interpolate: bool,
+    writer_options: &WriterOptions,
+) -> Result<()> {
+    let coords = geojson_points(geojson);
+    let (elevations, _) =
+        lookup_elevations(service, &coords, interpolate, false, writer_options.units);
+
+    let mut writer = csv_writer(output_path, writer_options.buffer_size)?;
+    writer.write_record(["lon", "lat", elevation_col])?;
+    for (&(lat, lon), elevation) in coords.iter().zip(elevations) {
+        writer.write_record([
+            lon.to_string(),
+            lat.to_string(),
+            elevation.to_csv(writer_options.precision),
+        ])?;
+    }
     writer.flush()?;
 
     println!("Output written to: {}", output_path.display());
     Ok(())
 }
 
+/// The `(lat, lon)` of every Point/MultiPoint position in `geojson`. Other
+/// geometries are skipped with a warning.
+fn geojson_points(geojson: geojson::GeoJson) -> Vec<(f64, f64)> {
+    fn collect_points(geometry: &geojson::Geometry, points: &mut Vec<(f64, f64)>) -> usize {
+        use geojson::Value;
+
+        let mut push = |pos: &[f64]| {
+            if let [lon, lat, ..] = *pos {
+                points.push((lat, lon));
+            }
+        };
+        match &geometry.value {
+            Value::Point(pos) => push(pos),
+            Value::MultiPoint(positions) => positions.iter().for_each(|pos| push(pos)),
+            Value::GeometryCollection(geometries) => {
+                return geometries.iter().map(|g| collect_points(g, points)).sum();
+            }
+            _ => return 1,
+        }
+        0
+    }
+
+    let geometries: Vec<geojson::Geometry> = match geojson {
+        geojson::GeoJson::Geometry(geometry) => vec![geometry],
+        geojson::GeoJson::Feature(feature) => feature.geometry.into_iter().collect(),
+        geojson::GeoJson::FeatureCollection(fc) => {
+            fc.features.into_iter().filter_map(|f| f.geometry).collect()
+        }
+    };
+
+    let mut coords = Vec::new();
+    let skipped: usize = geometries
+        .iter()
+        .map(|g| collect_points(g, &mut coords))
+        .sum();
+    if skipped > 0 {
+        eprintln!("Skipped {} non-point geometries", skipped);
+    }
+    coords
+}
+
+/// One line of JSON lines output.
+#[derive(serde::Serialize)]
+struct JsonlRecord {
+    lat: f64,
+    lon: f64,
+    elevation: serde_json::Value,
+    /// Present only with `--include-resolution`; `null` without a tile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution: Option<Option<&'static str>>,
+}
+
+/// JSON lines output: `path`, or stdout when `None`.
+fn jsonl_writer(path: Option<&Path>, buffer_size: usize) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::with_capacity(
+            buffer_size,
+            File::create(path).context("Failed to create output file")?,
+        )),
+        None => Box::new(std::io::stdout().lock()),
+    })
+}
+
+/// Write one JSON object per coordinate, flushing after every line so
+/// downstream consumers see results as they are produced.
+fn write_jsonl<W: Write + ?Sized>(
+    out: &mut W,
+    coords: &[(f64, f64)],
+    elevations: &[Elevation],
+    resolutions: Option<&[Option<SrtmResolution>]>,
+    precision: usize,
+) -> Result<()> {
+    for (i, (&(lat, lon), elevation)) in coords.iter().zip(elevations).enumerate() {
+        let record = JsonlRecord {
+            lat,
+            lon,
+            elevation: elevation.to_json(precision),
+            resolution: resolutions.map(|r| r[i].map(|r| r.as_str())),
+        };
+        serde_json::to_writer(&mut *out, &record)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
 fn add_elevations_to_geometry(
     service: &htg::SrtmService,
     geometry: geojson::Geometry,
     interpolate: bool,
+    options: &WriterOptions,
 ) -> Result<geojson::Geometry> {
     use geojson::Value;
 
@@ -209,6 +889,7 @@ fn add_elevations_to_geometry(
         service: &htg::SrtmService,
         pos: &mut Vec<f64>,
         interpolate: bool,
+        options: &WriterOptions,
     ) {
         if pos.len() >= 2 {
             let lon = pos[0];
@@ -218,9 +899,15 @@ fn add_elevations_to_geometry(
                     .get_elevation_interpolated(lat, lon)
                     .ok()
                     .flatten()
-                    .unwrap_or(0.0)
+                    .map_or(0.0, |e| {
+                        super::round_to(options.units.convert(e), options.precision)
+                    })
             } else {
-                service.get_elevation(lat, lon).ok().flatten().unwrap_or(0) as f64
+                service
+                    .get_elevation(lat, lon)
+                    .ok()
+                    .flatten()
+                    .map_or(0, |e| options.units.convert_whole(e)) as f64
             };
             if pos.len() == 2 {
                 pos.push(elevation);
@@ -234,9 +921,10 @@ fn add_elevations_to_geometry(
         service: &htg::SrtmService,
         positions: &mut Vec<Vec<f64>>,
         interpolate: bool,
+        options: &WriterOptions,
     ) {
         for pos in positions {
-            add_elevation_to_position(service, pos, interpolate);
+            add_elevation_to_position(service, pos, interpolate, options);
         }
     }
 
@@ -244,53 +932,60 @@ fn add_elevations_to_geometry(
         service: &htg::SrtmService,
         coords: &mut Vec<Vec<f64>>,
         interpolate: bool,
+        options: &WriterOptions,
     ) {
-        process_positions(service, coords, interpolate);
+        process_positions(service, coords, interpolate, options);
     }
 
     fn process_polygon(
         service: &htg::SrtmService,
         rings: &mut Vec<Vec<Vec<f64>>>,
         interpolate: bool,
+        options: &WriterOptions,
     ) {
         for ring in rings {
-            process_positions(service, ring, interpolate);
+            process_positions(service, ring, interpolate, options);
         }
     }
 
     let value = match geometry.value {
         Value::Point(mut coords) => {
-            add_elevation_to_position(service, &mut coords, interpolate);
+            add_elevation_to_position(service, &mut coords, interpolate, options);
             Value::Point(coords)
         }
         Value::MultiPoint(mut coords) => {
-            process_positions(service, &mut coords, interpolate);
+            process_positions(service, &mut coords, interpolate, options);
             Value::MultiPoint(coords)
         }
         Value::LineString(mut coords) => {
-            process_line_string(service, &mut coords, interpolate);
+            process_line_string(service, &mut coords, interpolate, options);
             Value::LineString(coords)
         }
         Value::MultiLineString(mut lines) => {
             for line in &mut lines {
-                process_line_string(service, line, interpolate);
+                process_line_string(service, line, interpolate, options);
             }
             Value::MultiLineString(lines)
         }
         Value::Polygon(mut rings) => {
-            process_polygon(service, &mut rings, interpolate);
+            process_polygon(service, &mut rings, interpolate, options);
             Value::Polygon(rings)
         }
         Value::MultiPolygon(mut polys) => {
             for poly in &mut polys {
-                process_polygon(service, poly, interpolate);
+                process_polygon(service, poly, interpolate, options);
             }
             Value::MultiPolygon(polys)
         }
         Value::GeometryCollection(geometries) => {
             let mut new_geometries = Vec::new();
             for geom in geometries {
-                new_geometries.push(add_elevations_to_geometry(service, geom, interpolate)?);
+                new_geometries.push(add_elevations_to_geometry(
+                    service,
+                    geom,
+                    interpolate,
+                    options,
+                )?);
             }
             Value::GeometryCollection(new_geometries)
         }
@@ -298,3 +993,840 @@ fn add_elevations_to_geometry(
 
     Ok(geojson::Geometry::new(value))
 }
+
+/// Fill `<ele>` for every waypoint, route point, and track point of a GPX
+/// file. Everything else in the document is copied through unchanged.
+fn process_gpx(
+    service: &htg::SrtmService,
+    input: &Path,
+    output: Option<PathBuf>,
+    buffer_size: usize,
+) -> Result<()> {
+    let xml = std::fs::read_to_string(input).context("Failed to open input file")?;
+    let (gpx, points, filled) = add_elevations_to_gpx(service, &xml)?;
+
+    let output_path = output.unwrap_or_else(|| default_output_path(input, "gpx"));
+    let file = File::create(&output_path).context("Failed to create output file")?;
+    let mut writer = BufWriter::with_capacity(buffer_size, file);
+    writer.write_all(&gpx)?;
+    writer.flush()?;
+
+    println!(
+        "Filled {} of {} GPX points; output written to: {}",
+        filled,
+        points,
+        output_path.display()
+    );
+    Ok(())
+}
+
+/// GPX elements located by `lat`/`lon` attributes.
+const GPX_POINTS: [&[u8]; 3] = [b"wpt", b"rtept", b"trkpt"];
+
+fn is_gpx_point(element: &BytesStart) -> bool {
+    GPX_POINTS.contains(&element.local_name().as_ref())
+}
+
+/// `(lat, lon)` of a GPX point element.
+fn gpx_point_coords(element: &BytesStart) -> Result<(f64, f64)> {
+    let coordinate = |name: &str| -> Result<f64> {
+        let attribute = element
+            .try_get_attribute(name)?
+            .with_context(|| format!("GPX point without a '{}' attribute", name))?;
+        let value = attribute.unescape_value()?;
+        value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid GPX {} '{}'", name, value))
+    };
+    Ok((coordinate("lat")?, coordinate("lon")?))
+}
+
+/// Write `<ele>` for `point`, with the same namespace prefix.
+fn write_gpx_ele(writer: &mut Writer<Vec<u8>>, point: &BytesStart, elevation: f64) -> Result<()> {
+    let name = match point.name().prefix() {
+        Some(prefix) => format!("{}:ele", String::from_utf8_lossy(prefix.as_ref())),
+        None => "ele".to_string(),
+    };
+    writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+    writer.write_event(Event::Text(BytesText::new(&super::format_rounded(
+        elevation, 2,
+    ))))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+/// Rewrite a GPX document with interpolated `<ele>` values, replacing any
+/// existing ones. Points over void or missing data keep their original
+/// `<ele>`, if any.
+///
+/// Returns the new document, the number of points, and how many were filled.
+fn add_elevations_to_gpx(service: &htg::SrtmService, xml: &str) -> Result<(Vec<u8>, usize, usize)> {
+    // First pass: collect the points for one tile-grouped batch lookup
+    let mut coords = Vec::new();
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event().context("Failed to parse GPX")? {
+            Event::Start(e) | Event::Empty(e) if is_gpx_point(&e) => {
+                coords.push(gpx_point_coords(&e)?)
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let elevations = service.get_elevations_batch_interpolated(&coords, f64::NAN);
+    let filled = elevations.iter().filter(|e| !e.is_nan()).count();
+
+    // Second pass: copy events, inserting <ele> as the first child of each
+    // point (as the GPX schema requires) and dropping the old one
+    let mut elevations = elevations.into_iter();
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    // Number of open elements before the current event
+    let mut depth = 0usize;
+    // Depth of the point currently receiving a new <ele>
+    let mut filled_point: Option<usize> = None;
+    // Depth of an old <ele> being dropped
+    let mut skipping: Option<usize> = None;
+
+    loop {
+        let event = reader.read_event().context("Failed to parse GPX")?;
+        if let Some(ele_depth) = skipping {
+            match event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => {
+                    depth -= 1;
+                    if depth == ele_depth {
+                        skipping = None;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let in_filled_point = filled_point.map(|d| d + 1) == Some(depth);
+        match event {
+            Event::Start(e) if is_gpx_point(&e) => {
+                let elevation = elevations.next().expect("one elevation per point");
+                writer.write_event(Event::Start(e.borrow()))?;
+                if !elevation.is_nan() {
+                    write_gpx_ele(&mut writer, &e, elevation)?;
+                    filled_point = Some(depth);
+                }
+                depth += 1;
+            }
+            Event::Empty(e) if is_gpx_point(&e) => {
+                let elevation = elevations.next().expect("one elevation per point");
+                if elevation.is_nan() {
+                    writer.write_event(Event::Empty(e))?;
+                } else {
+                    writer.write_event(Event::Start(e.borrow()))?;
+                    write_gpx_ele(&mut writer, &e, elevation)?;
+                    writer.write_event(Event::End(e.to_end()))?;
+                }
+            }
+            Event::Start(e) if in_filled_point && e.local_name().as_ref() == b"ele" => {
+                skipping = Some(depth);
+                depth += 1;
+            }
+            Event::Empty(e) if in_filled_point && e.local_name().as_ref() == b"ele" => {}
+            Event::Start(e) => {
+                depth += 1;
+                writer.write_event(Event::Start(e))?;
+            }
+            Event::End(e) => {
+                depth -= 1;
+                if filled_point == Some(depth) {
+                    filled_point = None;
+                }
+                writer.write_event(Event::End(e))?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    Ok((writer.into_inner(), coords.len(), filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htg::test_util::write_test_tile;
+    use tempfile::TempDir;
+
+    fn columns(on_conflict: ColumnConflict) -> CsvColumns {
+        CsvColumns {
+            lat: "lat".to_string(),
+            lon: "lon".to_string(),
+            elevation: "elevation".to_string(),
+            resolution: "resolution".to_string(),
+            on_conflict,
+        }
+    }
+
+    fn run_csv(dir: &TempDir, on_conflict: ColumnConflict) -> Result<String> {
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, "lat,lon,elevation\n35.5,138.5,old\n").unwrap();
+        let output = dir.path().join("out.csv");
+
+        let service = htg::SrtmService::new(dir.path(), 1);
+        process_csv(
+            &service,
+            &input,
+            Some(output.clone()),
+            &columns(on_conflict),
+            false,
+            None,
+            false,
+            None,
+            &WriterOptions::default(),
+        )?;
+        Ok(std::fs::read_to_string(output).unwrap())
+    }
+
+    #[test]
+    fn test_existing_elevation_column_errors() {
+        let dir = TempDir::new().unwrap();
+        let err = run_csv(&dir, ColumnConflict::Error).unwrap_err();
+        assert!(err.to_string().contains("'elevation' already exists"));
+    }
+
+    #[test]
+    fn test_existing_elevation_column_suffixed() {
+        let dir = TempDir::new().unwrap();
+        let output = run_csv(&dir, ColumnConflict::Suffix).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("lat,lon,elevation,elevation_2"));
+        assert_eq!(lines.next(), Some("35.5,138.5,old,void"));
+    }
+
+    #[test]
+    fn test_output_column_suffix_skips_taken_names() {
+        let existing = ["elevation", "elevation_2"];
+        assert_eq!(
+            output_column(&existing, "elevation", ColumnConflict::Suffix).unwrap(),
+            "elevation_3"
+        );
+        assert_eq!(
+            output_column(&existing, "height", ColumnConflict::Error).unwrap(),
+            "height"
+        );
+    }
+
+    #[cfg(feature = "proj")]
+    #[test]
+    fn test_web_mercator_input_matches_wgs84() {
+        let dir = TempDir::new().unwrap();
+
+        // SRTM3 tile with a diagonal gradient so every sample differs
+        let mut data = Vec::with_capacity(1201 * 1201 * 2);
+        for row in 0..1201i16 {
+            for col in 0..1201i16 {
+                data.extend_from_slice(&(row * 2 + col).to_be_bytes());
+            }
+        }
+        std::fs::write(dir.path().join("N35E138.hgt"), data).unwrap();
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        let points = [(35.3606, 138.7274), (35.71, 138.22), (35.05, 138.93)];
+        let mercator = htg::proj::Crs::WebMercator;
+
+        let mut wgs84_csv = String::from("lat,lon\n");
+        let mut mercator_csv = String::from("y,x\n");
+        for &(lat, lon) in &points {
+            wgs84_csv.push_str(&format!("{},{}\n", lat, lon));
+            let (x, y) = mercator.from_wgs84(lat, lon);
+            mercator_csv.push_str(&format!("{},{}\n", y, x));
+        }
+
+        let run = |name: &str, csv: &str, lat: &str, lon: &str, crs: Option<u32>| {
+            let input = dir.path().join(format!("{}.csv", name));
+            let output = dir.path().join(format!("{}_out.csv", name));
+            std::fs::write(&input, csv).unwrap();
+            let columns = CsvColumns {
+                lat: lat.to_string(),
+                lon: lon.to_string(),
+                ..columns(ColumnConflict::Error)
+            };
+            process_csv(
+                &service,
+                &input,
+                Some(output.clone()),
+                &columns,
+                false,
+                None,
+                false,
+                crs,
+                &WriterOptions::default(),
+            )
+            .unwrap();
+            let mut reader = csv::Reader::from_path(output).unwrap();
+            reader
+                .records()
+                .map(|r| r.unwrap().get(2).unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let expected = run("wgs84", &wgs84_csv, "lat", "lon", None);
+        let actual = run("mercator", &mercator_csv, "y", "x", Some(3857));
+
+        assert_eq!(expected.len(), points.len());
+        assert!(expected.iter().all(|e| e != "void"));
+        assert_eq!(actual, expected);
+
+        // Output keeps the original projected coordinates
+        let output = std::fs::read_to_string(dir.path().join("mercator_out.csv")).unwrap();
+        assert_eq!(
+            output.lines().nth(1).unwrap().split(',').next(),
+            mercator_csv.lines().nth(1).unwrap().split(',').next()
+        );
+    }
+
+    #[cfg(feature = "proj")]
+    #[test]
+    fn test_unsupported_input_crs() {
+        let dir = TempDir::new().unwrap();
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, "lat,lon\n0,0\n").unwrap();
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        let err = process_csv(
+            &service,
+            &input,
+            None,
+            &columns(ColumnConflict::Error),
+            false,
+            None,
+            false,
+            Some(27700),
+            &WriterOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("EPSG:27700"));
+    }
+
+    #[test]
+    fn test_batch_matches_per_row_queries() {
+        let dir = TempDir::new().unwrap();
+
+        // SRTM3 tile with a diagonal gradient and a void block
+        let mut data = Vec::with_capacity(1201 * 1201 * 2);
+        for row in 0..1201i16 {
+            for col in 0..1201i16 {
+                let elevation = if (100..110).contains(&row) && (100..110).contains(&col) {
+                    VOID_VALUE
+                } else {
+                    row * 2 + col
+                };
+                data.extend_from_slice(&elevation.to_be_bytes());
+            }
+        }
+        std::fs::write(dir.path().join("N35E138.hgt"), data).unwrap();
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        // 5000 pseudo-random points in the tile, each tagged with its row id
+        let mut csv = String::from("id,lat,lon\n");
+        let mut coords = Vec::new();
+        let mut state = 12345u64;
+        for id in 0..5000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let lat = 35.0 + (state >> 11) as f64 / (1u64 << 53) as f64;
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let lon = 138.0 + (state >> 11) as f64 / (1u64 << 53) as f64;
+            csv.push_str(&format!("{},{},{}\n", id, lat, lon));
+            coords.push((lat, lon));
+        }
+        // Void block near the north-west corner
+        csv.push_str("void,35.9125,138.0875\n");
+        coords.push((35.9125, 138.0875));
+
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, &csv).unwrap();
+        let input_rows: Vec<Vec<&str>> = csv
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').collect())
+            .collect();
+
+        for interpolate in [false, true] {
+            let output = dir.path().join("out.csv");
+            process_csv(
+                &service,
+                &input,
+                Some(output.clone()),
+                &columns(ColumnConflict::Error),
+                interpolate,
+                None,
+                false,
+                None,
+                &WriterOptions::default(),
+            )
+            .unwrap();
+
+            let mut reader = csv::Reader::from_path(&output).unwrap();
+            let rows: Vec<_> = reader.records().map(|r| r.unwrap()).collect();
+            assert_eq!(rows.len(), coords.len());
+            assert_eq!(rows.last().unwrap().get(3), Some("void"));
+
+            for ((row, input_row), &(lat, lon)) in rows.iter().zip(&input_rows).zip(&coords) {
+                let expected = if interpolate {
+                    service
+                        .get_elevation_interpolated(lat, lon)
+                        .unwrap()
+                        .map(|e| crate::commands::format_rounded(e, 2))
+                } else {
+                    service
+                        .get_elevation(lat, lon)
+                        .unwrap()
+                        .map(|e| e.to_string())
+                };
+                assert_eq!(&row.iter().take(3).collect::<Vec<_>>(), input_row);
+                assert_eq!(row.get(3).unwrap(), expected.as_deref().unwrap_or("void"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_matches_buffered_output() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+
+        // More rows than fit in one chunk, with some outside the tile
+        let rows = STREAM_CHUNK_ROWS * 2 + 123;
+        let mut csv = String::from("id,lat,lon\n");
+        for id in 0..rows {
+            let lat = if id % 97 == 0 {
+                40.5
+            } else {
+                35.0 + (id % 1000) as f64 / 1000.0
+            };
+            let lon = 138.0 + (id % 777) as f64 / 777.0;
+            csv.push_str(&format!("{},{},{}\n", id, lat, lon));
+        }
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, &csv).unwrap();
+
+        let run = |name: &str, stream: bool| {
+            let output = dir.path().join(name);
+            process_csv(
+                &service,
+                &input,
+                Some(output.clone()),
+                &columns(ColumnConflict::Error),
+                true,
+                None,
+                true,
+                None,
+                &WriterOptions {
+                    stream,
+                    flush_every: Some(1000),
+                    ..WriterOptions::default()
+                },
+            )
+            .unwrap();
+            std::fs::read_to_string(output).unwrap()
+        };
+
+        let streamed = run("streamed.csv", true);
+        assert_eq!(streamed.lines().count(), rows + 1);
+        assert_eq!(streamed, run("buffered.csv", false));
+    }
+
+    /// SRTM3 tile at N35E138 with `row * 2 + col` elevations.
+    fn create_gradient_tile(dir: &TempDir) -> htg::SrtmService {
+        write_test_tile(&dir.path().join("N35E138.hgt"), |row, col| {
+            (row * 2 + col) as i16
+        });
+        htg::SrtmService::new(dir.path(), 1)
+    }
+
+    #[test]
+    fn test_csv_to_geojson() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, "name,lat,lon\nsummit,35.5,138.5\nsea,10.5,-30.5\n").unwrap();
+
+        process_csv(
+            &service,
+            &input,
+            None,
+            &columns(ColumnConflict::Error),
+            false,
+            None,
+            true,
+            None,
+            &WriterOptions {
+                format: Some(OutputFormat::Geojson),
+                ..WriterOptions::default()
+            },
+        )
+        .unwrap();
+
+        let output = std::fs::read_to_string(dir.path().join("points_elevation.geojson")).unwrap();
+        let geojson: geojson::FeatureCollection = output.parse().unwrap();
+        assert_eq!(geojson.features.len(), 2);
+
+        let summit = &geojson.features[0];
+        assert_eq!(
+            summit.geometry.as_ref().unwrap().value,
+            geojson::Value::Point(vec![138.5, 35.5])
+        );
+        let properties = summit.properties.as_ref().unwrap();
+        assert_eq!(properties.len(), 3);
+        assert_eq!(properties["name"], "summit");
+        assert_eq!(properties["elevation"], 1800);
+        assert_eq!(properties["resolution"], "srtm3");
+
+        let sea = geojson.features[1].properties.as_ref().unwrap();
+        assert_eq!(sea["name"], "sea");
+        assert!(sea["elevation"].is_null());
+        assert!(sea["resolution"].is_null());
+    }
+
+    #[test]
+    fn test_csv_prefetches_tiles_concurrently() {
+        let mut server = mockito::Server::new();
+        let mocks: Vec<_> = [("N35E138", 100i16), ("N36E138", 200), ("N35E139", 300)]
+            .into_iter()
+            .map(|(name, elevation)| {
+                server
+                    .mock("GET", format!("/{}.hgt", name).as_str())
+                    .with_status(200)
+                    .with_body(
+                        (0..1201 * 1201)
+                            .flat_map(|_| elevation.to_be_bytes())
+                            .collect::<Vec<u8>>(),
+                    )
+                    .expect(1)
+                    .create()
+            })
+            .collect();
+
+        let dir = TempDir::new().unwrap();
+        // No downloader: a tile not fetched up front would read as void
+        let service = htg::SrtmService::new(dir.path(), 10);
+        let input = dir.path().join("points.csv");
+        std::fs::write(
+            &input,
+            "lat,lon\n35.5,138.5\n36.5,138.5\n35.5,139.5\n35.6,138.6\n",
+        )
+        .unwrap();
+        let prefetch = Prefetch {
+            config: DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt", server.url()))
+                .with_max_retries(0),
+            concurrency: 3,
+        };
+
+        process_csv(
+            &service,
+            &input,
+            None,
+            &columns(ColumnConflict::Error),
+            false,
+            Some(&prefetch),
+            false,
+            None,
+            &WriterOptions::default(),
+        )
+        .unwrap();
+
+        for mock in &mocks {
+            mock.assert();
+        }
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("points_elevation.csv")).unwrap(),
+            "lat,lon,elevation\n35.5,138.5,100\n36.5,138.5,200\n35.5,139.5,300\n35.6,138.6,100\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_units_feet() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, "lat,lon\n35.5,138.5\n10.5,-30.5\n").unwrap();
+
+        let run = |interpolate: bool| {
+            process_csv(
+                &service,
+                &input,
+                None,
+                &columns(ColumnConflict::Error),
+                interpolate,
+                None,
+                false,
+                None,
+                &WriterOptions {
+                    units: Units::Feet,
+                    ..WriterOptions::default()
+                },
+            )
+            .unwrap();
+            std::fs::read_to_string(dir.path().join("points_elevation.csv")).unwrap()
+        };
+
+        // 1800m is 5905.512ft; void stays void
+        assert_eq!(
+            run(false),
+            "lat,lon,elevation\n35.5,138.5,5906\n10.5,-30.5,void\n"
+        );
+        assert_eq!(
+            run(true),
+            "lat,lon,elevation\n35.5,138.5,5905.51\n10.5,-30.5,void\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_precision() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let input = dir.path().join("points.csv");
+        std::fs::write(&input, "lat,lon\n35.5,138.5\n10.5,-30.5\n").unwrap();
+
+        let run = |interpolate: bool, precision: usize| {
+            process_csv(
+                &service,
+                &input,
+                None,
+                &columns(ColumnConflict::Error),
+                interpolate,
+                None,
+                false,
+                None,
+                &WriterOptions {
+                    units: Units::Feet,
+                    precision,
+                    ..WriterOptions::default()
+                },
+            )
+            .unwrap();
+            std::fs::read_to_string(dir.path().join("points_elevation.csv")).unwrap()
+        };
+
+        // 1800m is 5905.512ft
+        assert_eq!(
+            run(true, 0),
+            "lat,lon,elevation\n35.5,138.5,5906\n10.5,-30.5,void\n"
+        );
+        assert_eq!(
+            run(true, 4),
+            "lat,lon,elevation\n35.5,138.5,5905.5120\n10.5,-30.5,void\n"
+        );
+        // Nearest-neighbor output is always whole
+        assert_eq!(run(false, 4), run(false, 0));
+    }
+
+    #[test]
+    fn test_geojson_z_precision() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let geometry = geojson::Geometry::new(geojson::Value::Point(vec![138.5, 35.5]));
+
+        let options = WriterOptions {
+            units: Units::Feet,
+            precision: 1,
+            ..WriterOptions::default()
+        };
+        let enriched = add_elevations_to_geometry(&service, geometry, true, &options).unwrap();
+
+        // 1800m is 5905.512ft
+        assert_eq!(
+            enriched.value,
+            geojson::Value::Point(vec![138.5, 35.5, 5905.5])
+        );
+    }
+
+    #[test]
+    fn test_jsonl_output() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let options = WriterOptions {
+            format: Some(OutputFormat::Jsonl),
+            ..WriterOptions::default()
+        };
+        let parse = |path: &Path| -> Vec<serde_json::Value> {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+
+        let input = dir.path().join("points.csv");
+        std::fs::write(
+            &input,
+            "lat,lon,name\n35.5,138.5,a\n10.5,-30.5,b\n35.5,138.5,c\n",
+        )
+        .unwrap();
+        let output = dir.path().join("points.jsonl");
+        process_csv(
+            &service,
+            &input,
+            Some(output.clone()),
+            &columns(ColumnConflict::Error),
+            false,
+            None,
+            false,
+            None,
+            &options,
+        )
+        .unwrap();
+
+        let lines = parse(&output);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({"lat": 35.5, "lon": 138.5, "elevation": 1800})
+        );
+        assert!(lines[1]["elevation"].is_null());
+
+        let input = dir.path().join("points.geojson");
+        std::fs::write(
+            &input,
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {},
+                 "geometry": {"type": "MultiPoint", "coordinates": [[138.5, 35.5], [-30.5, 10.5]]}}
+            ]}"#,
+        )
+        .unwrap();
+        process_geojson(
+            &service,
+            &input,
+            Some(output.clone()),
+            "elevation",
+            false,
+            &options,
+        )
+        .unwrap();
+
+        let lines = parse(&output);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["elevation"], 1800);
+        assert!(lines[1]["elevation"].is_null());
+    }
+
+    #[test]
+    fn test_geojson_to_csv() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let input = dir.path().join("points.geojson");
+        std::fs::write(
+            &input,
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "Point", "coordinates": [138.5, 35.5]}},
+                {"type": "Feature", "properties": {}, "geometry": {"type": "MultiPoint", "coordinates": [[138.25, 35.75], [-30.5, 10.5]]}},
+                {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[138.1, 35.1], [138.2, 35.2]]}}
+            ]}"#,
+        )
+        .unwrap();
+        let output = dir.path().join("out.csv");
+
+        process_geojson(
+            &service,
+            &input,
+            Some(output.clone()),
+            "elevation",
+            false,
+            &WriterOptions {
+                format: Some(OutputFormat::Csv),
+                ..WriterOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(output).unwrap(),
+            "lon,lat,elevation\n138.5,35.5,1800\n138.25,35.75,900\n-30.5,10.5,void\n"
+        );
+    }
+
+    #[test]
+    fn test_gpx_track_gains_elevations() {
+        let dir = TempDir::new().unwrap();
+        let service = create_gradient_tile(&dir);
+        let input = dir.path().join("hike.gpx");
+        std::fs::write(
+            &input,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>Morning hike</name></metadata>
+  <wpt lat="35.5" lon="138.5"><name>Summit &amp; hut</name></wpt>
+  <trk>
+    <name>Ridge</name>
+    <trkseg>
+      <trkpt lat="35.75" lon="138.25"><time>2024-05-01T06:00:00Z</time></trkpt>
+      <trkpt lat="35.5" lon="138.5"><ele>1.0</ele><time>2024-05-01T06:10:00Z</time></trkpt>
+      <trkpt lat="35.25" lon="138.75"/>
+      <trkpt lat="10.5" lon="-30.5"><ele>42.0</ele></trkpt>
+    </trkseg>
+  </trk>
+</gpx>
+"#,
+        )
+        .unwrap();
+
+        process_gpx(&service, &input, None, 8 * 1024).unwrap();
+        let output = std::fs::read_to_string(dir.path().join("hike_elevation.gpx")).unwrap();
+
+        // Every track point has exactly one <ele>; the one over missing data
+        // keeps its original value
+        let trkpts: Vec<&str> = output.split("<trkpt").skip(1).collect();
+        assert_eq!(trkpts.len(), 4);
+        for trkpt in &trkpts {
+            let trkpt = &trkpt[..trkpt.find("</trkpt>").unwrap()];
+            assert_eq!(trkpt.matches("<ele>").count(), 1, "{}", trkpt);
+        }
+        assert!(trkpts[0].contains("><ele>900.00</ele><time>2024-05-01T06:00:00Z</time>"));
+        assert!(trkpts[1].contains("><ele>1800.00</ele><time>2024-05-01T06:10:00Z</time>"));
+        // Self-closing points are expanded
+        assert!(trkpts[2].starts_with(r#" lat="35.25" lon="138.75"><ele>2700.00</ele></trkpt>"#));
+        assert!(trkpts[3].contains("<ele>42.0</ele>"));
+        assert!(output.contains(
+            r#"<wpt lat="35.5" lon="138.5"><ele>1800.00</ele><name>Summit &amp; hut</name></wpt>"#
+        ));
+
+        // Metadata is preserved
+        assert!(output.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(output.contains("<metadata><name>Morning hike</name></metadata>"));
+        assert!(output.contains("<name>Ridge</name>"));
+    }
+
+    fn write_rows_observing_size(flush_every: Option<u64>) -> Vec<u64> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.csv");
+        let mut writer = csv_writer(&path, 64 * 1024).unwrap();
+
+        (1..=5)
+            .map(|i| {
+                write_row(&mut writer, &["35.5", "138.5", "500"], i, flush_every).unwrap();
+                std::fs::metadata(&path).unwrap().len()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flush_every_row_grows_file() {
+        let sizes = write_rows_observing_size(Some(1));
+        let row_len = "35.5,138.5,500\n".len() as u64;
+        assert_eq!(sizes, (1..=5).map(|i| i * row_len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_flush_every_n_rows() {
+        let sizes = write_rows_observing_size(Some(2));
+        let row_len = "35.5,138.5,500\n".len() as u64;
+        assert_eq!(
+            sizes,
+            vec![0, 2 * row_len, 2 * row_len, 4 * row_len, 4 * row_len]
+        );
+    }
+
+    #[test]
+    fn test_buffered_until_end() {
+        let sizes = write_rows_observing_size(None);
+        assert_eq!(sizes, vec![0; 5]);
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::{bail, Context, Result};
+use htg::{
+    download::{DownloadConfig, Downloader},
+    filename::{
+        filename_to_lat_lon, is_valid_srtm_coord, lat_lon_to_filename, tiles_covering_bbox,
+    },
+    BoundingBox,
+};
+use std::path::{Path, PathBuf};
+
+/// Parse a `min_lat,min_lon,max_lat,max_lon` bounding box.
+pub fn parse_bbox(s: &str) -> Result<BoundingBox, String> {
+    let values = s
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid coordinate '{}'", v))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [min_lat, min_lon, max_lat, max_lon] if min_lat <= max_lat && min_lon <= max_lon => {
+            Ok(BoundingBox::new(min_lat, min_lon, max_lat, max_lon))
+        }
+        [_, _, _, _] => Err("bounding box minimums must not exceed maximums".to_string()),
+        _ => Err(format!(
+            "expected 'min_lat,min_lon,max_lat,max_lon', got '{}'",
+            s
+        )),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    data_dir: Option<PathBuf>,
+    tile: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    bbox: Option<BoundingBox>,
+    source: String,
+    url: Option<String>,
+    skip_absent: bool,
+) -> Result<()> {
+    let data_dir = data_dir
+        .context("HTG_DATA_DIR environment variable not set. Use --data-dir or set HTG_DATA_DIR")?;
+
+    let config = match url {
+        Some(url_template) => DownloadConfig::with_url_template(url_template),
+        None => DownloadConfig::from_source_name(&source)
+            .with_context(|| format!("Unknown download source '{}'", source))?,
+    };
+
+    let filenames = required_tiles(tile, lat, lon, bbox)?;
+    let summary = download(config, &data_dir, filenames, skip_absent)?;
+    let failed = summary.failed;
+
+    print!(
+        "Downloaded {} tile(s), {} already present",
+        summary.downloaded, summary.skipped
+    );
+    if skip_absent {
+        print!(", {} not on the server", summary.absent);
+    }
+    println!(", {} failed", failed.len());
+    if !failed.is_empty() {
+        bail!("Failed to download: {}", failed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Filenames of the tiles selected by `--tile`, `--lat/--lon`, or `--bbox`.
+fn required_tiles(
+    tile: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    bbox: Option<BoundingBox>,
+) -> Result<Vec<String>> {
+    match (tile, lat, lon, bbox) {
+        (Some(tile), None, None, None) => {
+            let filename = format!("{}.hgt", tile.trim_end_matches(".hgt").to_uppercase());
+            if filename_to_lat_lon(&filename).is_none() {
+                bail!("Invalid tile name '{}': expected e.g. N35E138", tile);
+            }
+            Ok(vec![filename])
+        }
+        (None, Some(lat), Some(lon), None) => {
+            if !is_valid_srtm_coord(lat, lon) {
+                bail!("Coordinates ({}, {}) are outside SRTM coverage", lat, lon);
+            }
+            Ok(vec![lat_lon_to_filename(lat, lon)])
+        }
+        (None, None, None, Some(bbox)) => {
+            let filenames =
+                tiles_covering_bbox(bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon);
+            if filenames.is_empty() {
+                bail!("Bounding box does not overlap SRTM coverage");
+            }
+            Ok(filenames)
+        }
+        _ => bail!("Specify exactly one of --tile, --lat/--lon, or --bbox"),
+    }
+}
+
+/// Outcome of [`download`].
+#[derive(Debug, Default)]
+struct Summary {
+    /// Tiles fetched successfully.
+    downloaded: usize,
+    /// Tiles already in the data directory.
+    skipped: usize,
+    /// Tiles the server does not have (only checked with `skip_absent`).
+    absent: usize,
+    /// Names of the tiles that failed to download.
+    failed: Vec<String>,
+}
+
+/// Download the tiles missing from `data_dir`.
+///
+/// With `skip_absent`, each missing tile is first probed with
+/// [`Downloader::tile_exists`] and tiles the server does not have (e.g.
+/// ocean tiles) are left out instead of failing. Tiles whose probe errors
+/// are still attempted.
+fn download(
+    config: DownloadConfig,
+    data_dir: &Path,
+    filenames: Vec<String>,
+    skip_absent: bool,
+) -> Result<Summary> {
+    let total = filenames.len();
+    let mut missing: Vec<String> = filenames
+        .into_iter()
+        .filter(|name| {
+            !data_dir.join(name).exists() && !data_dir.join(format!("{}.zip", name)).exists()
+        })
+        .collect();
+    let skipped = total - missing.len();
+
+    let mut absent = 0;
+    if skip_absent {
+        let prober = Downloader::new(config.clone())?;
+        missing.retain(|name| {
+            let exists = !matches!(prober.tile_exists(name), Ok(false));
+            if !exists {
+                println!("{}: not on the server", name);
+                absent += 1;
+            }
+            exists
+        });
+    }
+
+    let mut failed = Vec::new();
+    for (filename, result) in super::download_tiles(config, data_dir, missing, 1)? {
+        match result {
+            Ok(_) => println!("{}: ok", filename),
+            Err(e) => {
+                eprintln!("{}: failed ({})", filename, e);
+                failed.push(filename);
+            }
+        }
+    }
+
+    Ok(Summary {
+        downloaded: total - skipped - absent - failed.len(),
+        skipped,
+        absent,
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SRTM3_SIZE: usize = 1201 * 1201 * 2;
+
+    #[test]
+    fn test_parse_bbox() {
+        let bbox = parse_bbox("35.5, 138.5, 36.5, 139").unwrap();
+        assert_eq!(
+            (bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon),
+            (35.5, 138.5, 36.5, 139.0)
+        );
+        assert!(parse_bbox("35.5,138.5,36.5").is_err());
+        assert!(parse_bbox("36.5,138.5,35.5,139").is_err());
+        assert!(parse_bbox("a,138.5,36.5,139").is_err());
+    }
+
+    #[test]
+    fn test_required_tiles() {
+        assert_eq!(
+            required_tiles(Some("n35e138".into()), None, None, None).unwrap(),
+            vec!["N35E138.hgt"]
+        );
+        assert_eq!(
+            required_tiles(None, Some(35.5), Some(-0.5), None).unwrap(),
+            vec!["N35W001.hgt"]
+        );
+        let bbox = BoundingBox::new(35.5, 138.5, 36.5, 139.0);
+        assert_eq!(
+            required_tiles(None, None, None, Some(bbox)).unwrap(),
+            vec!["N35E138.hgt", "N36E138.hgt"]
+        );
+
+        assert!(required_tiles(Some("Q35E138".into()), None, None, None).is_err());
+        assert!(required_tiles(None, Some(70.0), Some(10.0), None).is_err());
+        assert!(required_tiles(None, None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_download_from_mock_server() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/N35E138.hgt")
+            .with_status(200)
+            .with_body(vec![0u8; SRTM3_SIZE])
+            .expect(1)
+            .create();
+        let missing = server.mock("GET", "/N36E138.hgt").with_status(404).create();
+
+        let dir = TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt", server.url()))
+                .with_max_retries(0);
+
+        let summary = download(
+            config.clone(),
+            dir.path(),
+            vec!["N35E138.hgt".into(), "N36E138.hgt".into()],
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            (summary.failed, summary.downloaded, summary.skipped),
+            (vec!["N36E138.hgt".to_string()], 1, 0)
+        );
+        assert_eq!(
+            std::fs::metadata(dir.path().join("N35E138.hgt"))
+                .unwrap()
+                .len(),
+            SRTM3_SIZE as u64
+        );
+
+        // Tiles already on disk are not fetched again
+        let summary = download(config, dir.path(), vec!["N35E138.hgt".into()], false).unwrap();
+        assert_eq!(
+            (summary.failed.len(), summary.downloaded, summary.skipped),
+            (0, 0, 1)
+        );
+
+        mock.assert();
+        missing.assert();
+    }
+
+    #[test]
+    fn test_download_skips_absent_tiles() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("HEAD", "/N35E138.hgt")
+            .with_status(200)
+            .create();
+        server
+            .mock("GET", "/N35E138.hgt")
+            .with_status(200)
+            .with_body(vec![0u8; SRTM3_SIZE])
+            .create();
+        server
+            .mock("HEAD", "/N00W150.hgt")
+            .with_status(404)
+            .create();
+        let never_fetched = server
+            .mock("GET", "/N00W150.hgt")
+            .with_status(404)
+            .expect(0)
+            .create();
+
+        let dir = TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt", server.url()))
+                .with_max_retries(0);
+
+        let summary = download(
+            config,
+            dir.path(),
+            vec!["N35E138.hgt".into(), "N00W150.hgt".into()],
+            true,
+        )
+        .unwrap();
+        assert!(summary.failed.is_empty());
+        assert_eq!((summary.downloaded, summary.absent), (1, 1));
+        assert!(dir.path().join("N35E138.hgt").exists());
+        never_fetched.assert();
+    }
+}
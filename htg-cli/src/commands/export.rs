@@ -0,0 +1,311 @@
+use anyhow::{bail, Context, Result};
+use htg::{
+    filename::{filename_to_lat_lon, tiles_covering_bbox},
+    BoundingBox, VOID_VALUE,
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use tiff::encoder::{colortype, TiffEncoder};
+use tiff::tags::Tag;
+
+/// Output raster format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Single-band Int16 GeoTIFF in EPSG:4326
+    Geotiff,
+    /// 16-bit grayscale PNG, normalized to the elevation range
+    Png,
+}
+
+/// A north-up raster of samples on a regular lat/lon grid.
+struct Grid {
+    width: usize,
+    height: usize,
+    /// Row-major samples, north to south and west to east.
+    data: Vec<i16>,
+    /// Longitude of the first column of sample points.
+    west: f64,
+    /// Latitude of the first row of sample points.
+    north: f64,
+    /// Sample spacing in degrees.
+    step: f64,
+}
+
+pub fn run(
+    data_dir: Option<PathBuf>,
+    auto_download: bool,
+    tile: Option<String>,
+    bbox: Option<BoundingBox>,
+    format: ExportFormat,
+    output: PathBuf,
+) -> Result<()> {
+    let data_dir = data_dir
+        .context("HTG_DATA_DIR environment variable not set. Use --data-dir or set HTG_DATA_DIR")?;
+
+    let (filenames, bbox) = match (tile, bbox) {
+        (Some(tile), None) => {
+            let filename = format!("{}.hgt", tile.trim_end_matches(".hgt").to_uppercase());
+            let (lat, lon) = filename_to_lat_lon(&filename)
+                .with_context(|| format!("Invalid tile name '{}': expected e.g. N35E138", tile))?;
+            let bbox = BoundingBox::new(lat as f64, lon as f64, lat as f64 + 1.0, lon as f64 + 1.0);
+            (vec![filename], bbox)
+        }
+        (None, Some(bbox)) => (
+            tiles_covering_bbox(bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon),
+            bbox,
+        ),
+        _ => bail!("Specify exactly one of --tile or --bbox"),
+    };
+
+    if auto_download {
+        // Tile centers; fetch_missing_tiles skips tiles already present
+        let centers = filenames
+            .iter()
+            .filter_map(|name| filename_to_lat_lon(name))
+            .map(|(lat, lon)| (lat as f64 + 0.5, lon as f64 + 0.5));
+        super::fetch_missing_tiles(&data_dir, centers)?;
+    }
+
+    let grid = read_grid(&data_dir, &filenames, &bbox)?;
+    match format {
+        ExportFormat::Geotiff => write_geotiff(&grid, &output)?,
+        ExportFormat::Png => {
+            let (min, max) = write_png(&grid, &output)?;
+            println!("Elevation range: {} to {} m (mapped to 0-65535)", min, max);
+        }
+    }
+
+    println!(
+        "Wrote {}x{} raster to: {}",
+        grid.width,
+        grid.height,
+        output.display()
+    );
+    Ok(())
+}
+
+/// Mosaic the samples of `filenames` covering `bbox` into one grid.
+///
+/// The bbox is snapped outwards to the sample grid. Areas without a tile are
+/// filled with [`VOID_VALUE`]; all tiles must share one resolution.
+fn read_grid(data_dir: &Path, filenames: &[String], bbox: &BoundingBox) -> Result<Grid> {
+    let mut tiles = Vec::new();
+    for filename in filenames {
+        tiles.extend(super::load_tile_for_scan(data_dir, filename)?);
+    }
+
+    let Some(first) = tiles.first() else {
+        bail!(
+            "No tiles found in {} (try `htg download`)",
+            data_dir.display()
+        );
+    };
+    let samples = first.samples();
+    if tiles.iter().any(|t| t.samples() != samples) {
+        bail!("Cannot export tiles with mixed resolutions");
+    }
+
+    // Global sample indices from the north-west corner of the world
+    let n = (samples - 1) as i64;
+    let nf = n as f64;
+    let col0 = ((bbox.min_lon + 180.0) * nf + 1e-6).floor() as i64;
+    let col1 = ((bbox.max_lon + 180.0) * nf - 1e-6).ceil() as i64;
+    let row0 = ((90.0 - bbox.max_lat) * nf + 1e-6).floor() as i64;
+    let row1 = ((90.0 - bbox.min_lat) * nf - 1e-6).ceil() as i64;
+    let width = (col1 - col0 + 1) as usize;
+    let height = (row1 - row0 + 1) as usize;
+
+    let mut data = vec![VOID_VALUE; width * height];
+    for tile in &tiles {
+        let tile_row = (89 - tile.base_lat() as i64) * n;
+        let tile_col = (tile.base_lon() as i64 + 180) * n;
+
+        let r0 = row0.max(tile_row);
+        let r1 = row1.min(tile_row + n);
+        let c0 = col0.max(tile_col);
+        let c1 = col1.min(tile_col + n);
+        if r0 > r1 || c0 > c1 {
+            continue;
+        }
+
+        let cols = (c1 - c0 + 1) as usize;
+        let window = tile.read_window(
+            (r0 - tile_row) as usize,
+            (c0 - tile_col) as usize,
+            (r1 - r0 + 1) as usize,
+            cols,
+        )?;
+        for (i, row) in window.chunks_exact(cols).enumerate() {
+            let start = (r0 - row0) as usize + i;
+            let start = start * width + (c0 - col0) as usize;
+            data[start..start + cols].copy_from_slice(row);
+        }
+    }
+
+    Ok(Grid {
+        width,
+        height,
+        data,
+        west: col0 as f64 / nf - 180.0,
+        north: 90.0 - row0 as f64 / nf,
+        step: 1.0 / nf,
+    })
+}
+
+/// Write the grid as an Int16 GeoTIFF georeferenced in EPSG:4326.
+///
+/// Like `.hgt` files, samples are points on the grid (`PixelIsPoint`).
+fn write_geotiff(grid: &Grid, path: &Path) -> Result<()> {
+    let file = File::create(path).context("Failed to create output file")?;
+    let mut encoder = TiffEncoder::new(BufWriter::new(file))?;
+    let mut image =
+        encoder.new_image::<colortype::GrayI16>(grid.width as u32, grid.height as u32)?;
+
+    let tags = image.encoder();
+    tags.write_tag(Tag::ModelPixelScaleTag, &[grid.step, grid.step, 0.0][..])?;
+    tags.write_tag(
+        Tag::ModelTiepointTag,
+        &[0.0, 0.0, 0.0, grid.west, grid.north, 0.0][..],
+    )?;
+    // Header, then (key, location, count, value): geographic model,
+    // PixelIsPoint raster, WGS 84
+    tags.write_tag(
+        Tag::GeoKeyDirectoryTag,
+        &[
+            1u16, 1, 0, 3, 1024, 0, 1, 2, 1025, 0, 1, 2, 2048, 0, 1, 4326,
+        ][..],
+    )?;
+    tags.write_tag(Tag::GdalNodata, VOID_VALUE.to_string().as_str())?;
+
+    image
+        .write_data(&grid.data)
+        .context("Failed to write GeoTIFF")?;
+    Ok(())
+}
+
+/// Write the grid as a 16-bit grayscale PNG stretched over its elevation
+/// range, with void samples as black. Returns the `(min, max)` elevation.
+fn write_png(grid: &Grid, path: &Path) -> Result<(i16, i16)> {
+    let valid = grid.data.iter().copied().filter(|&e| e != VOID_VALUE);
+    let min = valid.clone().min().unwrap_or(0);
+    let max = valid.max().unwrap_or(0);
+    let range = (max as f64 - min as f64).max(1.0);
+
+    let mut pixels = Vec::with_capacity(grid.data.len() * 2);
+    for &elevation in &grid.data {
+        let value = if elevation == VOID_VALUE {
+            0
+        } else {
+            ((elevation as f64 - min as f64) / range * 65535.0).round() as u16
+        };
+        pixels.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let file = File::create(path).context("Failed to create output file")?;
+    let mut encoder =
+        png::Encoder::new(BufWriter::new(file), grid.width as u32, grid.height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Sixteen);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+
+    Ok((min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htg::filename::coords_to_filename;
+    use htg::test_util::write_test_tile;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+    use tiff::decoder::{Decoder, DecodingResult};
+
+    /// SRTM3 tile with `f(row, col)` as elevation.
+    fn create_tile(dir: &Path, lat: i32, lon: i32, f: impl Fn(usize, usize) -> i16) {
+        write_test_tile(&dir.join(coords_to_filename(lat, lon)), f);
+    }
+
+    #[test]
+    fn test_export_geotiff_tile() {
+        let dir = TempDir::new().unwrap();
+        create_tile(dir.path(), 35, 138, |row, col| (row + col) as i16);
+        let output = dir.path().join("N35E138.tif");
+
+        run(
+            Some(dir.path().to_path_buf()),
+            false,
+            Some("N35E138".to_string()),
+            None,
+            ExportFormat::Geotiff,
+            output.clone(),
+        )
+        .unwrap();
+
+        let file = std::fs::read(&output).unwrap();
+        let mut decoder = Decoder::new(Cursor::new(file)).unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), (1201, 1201));
+
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap();
+        assert_eq!(tiepoint, vec![0.0, 0.0, 0.0, 138.0, 36.0, 0.0]);
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).unwrap();
+        assert!((scale[0] - 1.0 / 1200.0).abs() < 1e-12);
+
+        let DecodingResult::I16(data) = decoder.read_image().unwrap() else {
+            panic!("expected Int16 samples");
+        };
+        assert_eq!(data[0], 0);
+        assert_eq!(data[1201 * 1201 - 1], 2400);
+    }
+
+    #[test]
+    fn test_bbox_crop_spans_tiles() {
+        let dir = TempDir::new().unwrap();
+        // Western tile climbs eastwards from 0; the eastern one continues at 1200
+        create_tile(dir.path(), 35, 138, |_, col| col as i16);
+        create_tile(dir.path(), 35, 139, |_, col| (1200 + col) as i16);
+
+        let bbox = BoundingBox::new(35.5, 138.9, 35.6, 139.1);
+        let filenames = tiles_covering_bbox(bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon);
+        let grid = read_grid(dir.path(), &filenames, &bbox).unwrap();
+
+        assert_eq!((grid.width, grid.height), (241, 121));
+        assert!((grid.west - 138.9).abs() < 1e-9);
+        assert!((grid.north - 35.6).abs() < 1e-9);
+        assert_eq!(grid.data[0], 1080);
+        assert_eq!(grid.data[120], 1200);
+        assert_eq!(grid.data[grid.width * grid.height - 1], 1320);
+    }
+
+    #[test]
+    fn test_bbox_without_tiles_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let bbox = BoundingBox::new(35.5, 138.5, 35.6, 138.6);
+        assert!(read_grid(dir.path(), &["N35E138.hgt".to_string()], &bbox).is_err());
+    }
+
+    #[test]
+    fn test_export_png_normalized() {
+        let dir = TempDir::new().unwrap();
+        let grid = Grid {
+            width: 3,
+            height: 1,
+            data: vec![100, VOID_VALUE, 300],
+            west: 0.0,
+            north: 0.0,
+            step: 1.0,
+        };
+        let output = dir.path().join("out.png");
+        assert_eq!(write_png(&grid, &output).unwrap(), (100, 300));
+
+        let decoder = png::Decoder::new(File::open(&output).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (3, 1));
+        assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+        assert_eq!(&buf[..6], &[0, 0, 0, 0, 255, 255]);
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::{bail, Context, Result};
-use htg::{filename::lat_lon_to_filename, SrtmResolution, SrtmTile, VOID_VALUE};
-use std::path::PathBuf;
+use htg::{filename::lat_lon_to_filename, AccessPattern, SrtmResolution, SrtmTile, TileStats};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn run(
     data_dir: Option<PathBuf>,
@@ -37,43 +39,24 @@ pub fn run(
     // Parse coordinates from filename first (needed for elevation sampling)
     let (base_lat, base_lon) = htg::filename::filename_to_lat_lon(&filename).unwrap_or((0, 0));
 
-    // Load tile with coordinates
-    let tile = SrtmTile::from_file_with_coords(&tile_path, base_lat, base_lon)
-        .context("Failed to load tile")?;
+    // Load tile with coordinates; the statistics pass reads it end to end
+    let tile =
+        SrtmTile::from_file_with_advice(&tile_path, base_lat, base_lon, AccessPattern::Sequential)
+            .context("Failed to load tile")?;
 
     // Get file metadata
     let metadata = std::fs::metadata(&tile_path)?;
     let file_size = metadata.len();
 
-    // Calculate min/max elevation by sampling through the grid
+    // Statistics come from a sidecar JSON when it is newer than the tile
     let samples = tile.samples();
-    let (mut min_elev, mut max_elev) = (i16::MAX, i16::MIN);
-    let mut void_count = 0u64;
-
-    // Iterate through all samples using lat/lon coordinates
-    // Row 0 = north edge, Row samples-1 = south edge
-    // Col 0 = west edge, Col samples-1 = east edge
-    for row in 0..samples {
-        for col in 0..samples {
-            // Calculate lat/lon for this sample
-            let lat = (base_lat as f64) + 1.0 - (row as f64 / (samples - 1) as f64);
-            let lon = (base_lon as f64) + (col as f64 / (samples - 1) as f64);
-
-            if let Ok(elev) = tile.get_elevation(lat, lon) {
-                if elev == VOID_VALUE {
-                    void_count += 1;
-                } else {
-                    min_elev = min_elev.min(elev);
-                    max_elev = max_elev.max(elev);
-                }
-            }
-        }
-    }
+    let (stats, _) = load_or_compute_stats(&tile, &tile_path);
 
     // Format resolution string
     let resolution_str = match tile.resolution() {
-        SrtmResolution::Srtm1 => "SRTM1 (~30m)",
-        SrtmResolution::Srtm3 => "SRTM3 (~90m)",
+        SrtmResolution::Srtm1 => "SRTM1 (~30m)".to_string(),
+        SrtmResolution::Srtm3 => "SRTM3 (~90m)".to_string(),
+        custom @ SrtmResolution::Custom { .. } => format!("Custom (~{:.0}m)", custom.meters()),
     };
 
     // Display information
@@ -98,20 +81,92 @@ pub fn run(
     println!("File size: {}", format_size(file_size));
     println!();
 
-    if min_elev <= max_elev {
-        println!("Min elevation: {}m", min_elev);
-        println!("Max elevation: {}m", max_elev);
+    if let (Some(min), Some(max)) = (stats.min, stats.max) {
+        println!("Min elevation: {}m", min);
+        println!("Max elevation: {}m", max);
+    }
+    if let Some(mean) = stats.mean {
+        println!("Mean elevation: {:.1}m", mean);
     }
 
-    let total_samples = (samples * samples) as u64;
-    if void_count > 0 {
-        let void_pct = (void_count as f64 / total_samples as f64) * 100.0;
-        println!("Void samples: {} ({:.1}%)", void_count, void_pct);
+    if stats.void_count > 0 {
+        let void_pct = (stats.void_count as f64 / stats.sample_count as f64) * 100.0;
+        println!("Void samples: {} ({:.1}%)", stats.void_count, void_pct);
     }
 
     Ok(())
 }
 
+/// On-disk form of [`TileStats`], stored as `{filename}.stats.json` next to the tile.
+#[derive(Serialize, Deserialize)]
+struct StatsSidecar {
+    min: Option<i16>,
+    max: Option<i16>,
+    mean: Option<f64>,
+    void_count: u64,
+    sample_count: u64,
+}
+
+impl From<TileStats> for StatsSidecar {
+    fn from(stats: TileStats) -> Self {
+        Self {
+            min: stats.min,
+            max: stats.max,
+            mean: stats.mean,
+            void_count: stats.void_count,
+            sample_count: stats.sample_count,
+        }
+    }
+}
+
+impl From<StatsSidecar> for TileStats {
+    fn from(sidecar: StatsSidecar) -> Self {
+        Self {
+            min: sidecar.min,
+            max: sidecar.max,
+            mean: sidecar.mean,
+            void_count: sidecar.void_count,
+            sample_count: sidecar.sample_count,
+        }
+    }
+}
+
+fn sidecar_path(tile_path: &Path) -> PathBuf {
+    let mut name = tile_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".stats.json");
+    tile_path.with_file_name(name)
+}
+
+/// Read cached stats for a tile, or scan it and write the sidecar.
+///
+/// The sidecar is ignored when the tile was modified after it was written.
+/// Returns the stats and whether they came from the sidecar.
+fn load_or_compute_stats(tile: &SrtmTile, tile_path: &Path) -> (TileStats, bool) {
+    let sidecar = sidecar_path(tile_path);
+
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let fresh = match (modified(&sidecar), modified(tile_path)) {
+        (Some(sidecar_time), Some(tile_time)) => sidecar_time >= tile_time,
+        _ => false,
+    };
+
+    if fresh {
+        let cached = fs::read(&sidecar)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<StatsSidecar>(&bytes).ok());
+        if let Some(cached) = cached {
+            return (cached.into(), true);
+        }
+    }
+
+    let stats = tile.compute_stats();
+    // Best effort: a read-only data directory just means no caching
+    if let Ok(json) = serde_json::to_vec(&StatsSidecar::from(stats)) {
+        let _ = fs::write(&sidecar, json);
+    }
+    (stats, false)
+}
+
 fn get_tile_path(data_dir: Option<PathBuf>, filename: &str) -> Result<PathBuf> {
     match data_dir {
         Some(dir) => Ok(dir.join(filename)),
@@ -139,3 +194,51 @@ fn format_size(bytes: u64) -> String {
         format!("{} bytes", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stats_sidecar_reused() {
+        let temp_dir = TempDir::new().unwrap();
+        let tile_path = temp_dir.path().join("N35E138.hgt");
+        let mut data = vec![0u8; 1201 * 1201 * 2];
+        data[..2].copy_from_slice(&1234i16.to_be_bytes());
+        fs::write(&tile_path, &data).unwrap();
+
+        let tile = SrtmTile::from_file_with_coords(&tile_path, 35, 138).unwrap();
+
+        let (first, reused) = load_or_compute_stats(&tile, &tile_path);
+        assert!(!reused);
+        assert_eq!(first, tile.compute_stats());
+        assert!(temp_dir.path().join("N35E138.hgt.stats.json").exists());
+
+        let (second, reused) = load_or_compute_stats(&tile, &tile_path);
+        assert!(reused);
+        assert_eq!(second.max, Some(1234));
+        assert_eq!(second.void_count, first.void_count);
+    }
+
+    #[test]
+    fn test_stats_sidecar_invalidated_by_newer_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        let tile_path = temp_dir.path().join("N35E138.hgt");
+        fs::write(&tile_path, vec![0u8; 1201 * 1201 * 2]).unwrap();
+        let tile = SrtmTile::from_file_with_coords(&tile_path, 35, 138).unwrap();
+        load_or_compute_stats(&tile, &tile_path);
+
+        // Backdate the sidecar so the tile looks newer
+        let sidecar = fs::File::options()
+            .write(true)
+            .open(sidecar_path(&tile_path))
+            .unwrap();
+        sidecar
+            .set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        let (_, reused) = load_or_compute_stats(&tile, &tile_path);
+        assert!(!reused);
+    }
+}
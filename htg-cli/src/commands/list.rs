@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use htg::filename::filename_to_lat_lon;
+use htg::{filename::filename_to_lat_lon, SrtmResolution};
 use std::fs;
 use std::path::PathBuf;
 
@@ -39,10 +39,6 @@ pub fn run(data_dir: Option<PathBuf>) -> Result<()> {
     // Sort by filename
     tiles.sort_by_key(|e| e.file_name());
 
-    // Detect resolution from file size
-    const SRTM1_SIZE: u64 = 3601 * 3601 * 2;
-    const SRTM3_SIZE: u64 = 1201 * 1201 * 2;
-
     let mut srtm1_count = 0;
     let mut srtm3_count = 0;
     let mut unknown_count = 0;
@@ -60,12 +56,12 @@ pub fn run(data_dir: Option<PathBuf>) -> Result<()> {
         let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
         total_size += size;
 
-        let resolution = match size {
-            s if s == SRTM1_SIZE => {
+        let resolution = match SrtmResolution::from_file_size(size as usize) {
+            Some(SrtmResolution::Srtm1) => {
                 srtm1_count += 1;
                 "SRTM1"
             }
-            s if s == SRTM3_SIZE => {
+            Some(SrtmResolution::Srtm3) => {
                 srtm3_count += 1;
                 "SRTM3"
             }
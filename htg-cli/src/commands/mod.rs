@@ -1,4 +1,180 @@
 pub mod batch;
+pub mod download;
+pub mod export;
 pub mod info;
 pub mod list;
+pub mod overviews;
+pub mod profile;
 pub mod query;
+pub mod stats;
+pub mod verify;
+
+use anyhow::{Context, Result};
+use htg::download::{DownloadConfig, Downloader};
+use htg::filename::{filename_to_lat_lon, is_valid_srtm_coord, lat_lon_to_filename};
+use htg::{AccessPattern, SrtmService, SrtmTile};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How tiles missing for a command's coordinates are fetched up front.
+#[derive(Clone, Debug)]
+pub struct Prefetch {
+    /// Where tiles are downloaded from
+    pub config: DownloadConfig,
+    /// Number of tiles downloaded at once
+    pub concurrency: usize,
+}
+
+impl Default for Prefetch {
+    fn default() -> Self {
+        Self {
+            config: DownloadConfig::ardupilot_srtm1(),
+            concurrency: 1,
+        }
+    }
+}
+
+impl Prefetch {
+    /// Download any tiles missing from `data_dir` for the given coordinates,
+    /// showing a byte-level progress bar for each fetch.
+    ///
+    /// Failures are reported on the progress bar and otherwise ignored; the
+    /// service falls back to its own download attempt (and void results).
+    pub fn fetch_missing(
+        &self,
+        data_dir: &Path,
+        coords: impl IntoIterator<Item = (f64, f64)>,
+    ) -> Result<()> {
+        let filenames: BTreeSet<String> = coords
+            .into_iter()
+            .filter(|&(lat, lon)| is_valid_srtm_coord(lat, lon))
+            .map(|(lat, lon)| lat_lon_to_filename(lat, lon))
+            .filter(|name| {
+                !data_dir.join(name).exists() && !data_dir.join(format!("{}.zip", name)).exists()
+            })
+            .collect();
+
+        if filenames.is_empty() {
+            return Ok(());
+        }
+
+        download_tiles(self.config.clone(), data_dir, filenames, self.concurrency)?;
+        Ok(())
+    }
+}
+
+/// Load tile `filename` from `data_dir` for a full scan, first extracting it
+/// from a local `.hgt.zip` like the service does. `None` if neither exists.
+pub fn load_tile_for_scan(data_dir: &Path, filename: &str) -> Result<Option<SrtmTile>> {
+    let (lat, lon) = filename_to_lat_lon(filename)
+        .with_context(|| format!("Invalid tile name '{}'", filename))?;
+    let path = data_dir.join(filename);
+    if !path.exists() {
+        if !data_dir.join(format!("{}.zip", filename)).exists() {
+            return Ok(None);
+        }
+        // The service extracts the archive next to it on first load
+        SrtmService::new(data_dir, 1)
+            .tile_at(lat as f64 + 0.5, lon as f64 + 0.5)
+            .with_context(|| format!("Failed to extract {}.zip", filename))?;
+    }
+    SrtmTile::from_file_with_advice(&path, lat, lon, AccessPattern::Sequential)
+        .map(Some)
+        .with_context(|| format!("Failed to load tile {}", filename))
+}
+
+/// Round `value` to `precision` decimal places (half away from zero).
+///
+/// Every `--precision` output goes through this, so text, CSV, JSON, and
+/// GeoJSON agree on the same value.
+pub fn round_to(value: f64, precision: usize) -> f64 {
+    let scale = 10f64.powi(precision as i32);
+    (value * scale).round() / scale
+}
+
+/// `value` rounded with [`round_to`] and written with `precision` decimals.
+pub fn format_rounded(value: f64, precision: usize) -> String {
+    format!("{:.*}", precision, round_to(value, precision))
+}
+
+/// Download any tiles missing from `data_dir` for the given coordinates from
+/// the default source, one at a time (see [`Prefetch::fetch_missing`]).
+pub fn fetch_missing_tiles(
+    data_dir: &Path,
+    coords: impl IntoIterator<Item = (f64, f64)>,
+) -> Result<()> {
+    Prefetch::default().fetch_missing(data_dir, coords)
+}
+
+/// Download tiles by filename into `data_dir`, up to `concurrency` at a
+/// time, showing a byte-level progress bar for each fetch.
+///
+/// Returns the outcome for each tile, in order; a failed tile does not stop
+/// the remaining downloads.
+pub fn download_tiles(
+    config: DownloadConfig,
+    data_dir: &Path,
+    filenames: impl IntoIterator<Item = String>,
+    concurrency: usize,
+) -> Result<Vec<(String, htg::Result<PathBuf>)>> {
+    let downloader = Downloader::new(config)?;
+    let style = ProgressStyle::default_bar()
+        .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
+        .progress_chars("#>-");
+    let progress = MultiProgress::new();
+
+    let filenames: Vec<String> = filenames.into_iter().collect();
+    let next = AtomicUsize::new(0);
+
+    // Each worker takes the next filename until none are left
+    let mut results: Vec<(usize, htg::Result<PathBuf>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.clamp(1, filenames.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(filename) = filenames.get(index) else {
+                            break;
+                        };
+                        let pb = progress.add(ProgressBar::new(0));
+                        pb.set_style(style.clone());
+                        pb.set_message(filename.clone());
+
+                        let result = downloader
+                            .download_tile_with_progress(filename, data_dir, |done, total| {
+                                if let Some(total) = total {
+                                    pb.set_length(total);
+                                }
+                                pb.set_position(done);
+                            })
+                            .map(|(path, _)| path);
+
+                        match &result {
+                            Ok(_) => pb.finish(),
+                            Err(e) => pb.abandon_with_message(format!(
+                                "{}: download failed ({})",
+                                filename, e
+                            )),
+                        }
+                        done.push((index, result));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    results.sort_by_key(|&(index, _)| index);
+    Ok(filenames
+        .into_iter()
+        .zip(results)
+        .map(|(filename, (_, result))| (filename, result))
+        .collect())
+}
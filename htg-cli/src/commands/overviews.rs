@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use htg::{filename::filename_to_lat_lon, overview::build_overviews, AccessPattern, SrtmTile};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fs;
+use std::path::PathBuf;
+
+pub fn run(data_dir: PathBuf, out_dir: PathBuf, levels: u32) -> Result<()> {
+    if !data_dir.exists() {
+        anyhow::bail!("Data directory does not exist: {}", data_dir.display());
+    }
+
+    // Collect source tiles; overview outputs (e.g. N35E138_L1.hgt) don't parse
+    // as tile names and are skipped
+    let mut tiles: Vec<_> = fs::read_dir(&data_dir)
+        .context("Failed to read data directory")?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let (lat, lon) = filename_to_lat_lon(&filename)?;
+            Some((filename, entry.path(), lat, lon))
+        })
+        .filter(|(filename, ..)| filename.to_lowercase().ends_with(".hgt"))
+        .collect();
+
+    if tiles.is_empty() {
+        println!("No .hgt files found in: {}", data_dir.display());
+        return Ok(());
+    }
+
+    tiles.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let pb = ProgressBar::new(tiles.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )?
+            .progress_chars("#>-"),
+    );
+
+    let mut written = 0;
+    for (filename, path, lat, lon) in &tiles {
+        let tile = SrtmTile::from_file_with_advice(path, *lat, *lon, AccessPattern::Sequential)
+            .with_context(|| format!("Failed to load tile {}", filename))?;
+        written += build_overviews(&tile, filename, &out_dir, levels)
+            .with_context(|| format!("Failed to write overviews for {}", filename))?
+            .len();
+        pb.inc(1);
+    }
+
+    pb.finish_with_message("done");
+
+    println!(
+        "Wrote {} overview files for {} tiles to: {}",
+        written,
+        tiles.len(),
+        out_dir.display()
+    );
+    Ok(())
+}
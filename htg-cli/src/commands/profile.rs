@@ -0,0 +1,290 @@
+use anyhow::{bail, Context, Result};
+use htg::{
+    download::DownloadConfig, profile::ascent_descent, ProfilePoint, SrtmServiceBuilder, Units,
+};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+struct ProfileSample {
+    distance_m: f64,
+    lat: f64,
+    lon: f64,
+    elevation: Option<f64>,
+}
+
+/// Totals over a profile, ignoring void samples and changes below the
+/// smoothing threshold.
+#[derive(Debug, Serialize, PartialEq)]
+struct ProfileSummary {
+    distance_m: f64,
+    ascent_m: f64,
+    descent_m: f64,
+    samples: usize,
+}
+
+#[derive(Serialize)]
+struct ProfileResponse {
+    summary: ProfileSummary,
+    points: Vec<ProfileSample>,
+}
+
+/// Parse a `lat,lon` pair.
+pub fn parse_lat_lon(s: &str) -> Result<(f64, f64), String> {
+    let (lat, lon) = s
+        .split_once(',')
+        .ok_or_else(|| format!("expected 'lat,lon', got '{}'", s))?;
+    let lat = lat
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid latitude '{}'", lat))?;
+    let lon = lon
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid longitude '{}'", lon))?;
+    Ok((lat, lon))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    data_dir: Option<PathBuf>,
+    cache_size: u64,
+    auto_download: bool,
+    from: Option<(f64, f64)>,
+    to: Vec<(f64, f64)>,
+    input: Option<PathBuf>,
+    spacing: f64,
+    interpolate: bool,
+    smoothing: f64,
+    units: Units,
+    json: bool,
+) -> Result<()> {
+    if !(smoothing >= 0.0 && smoothing.is_finite()) {
+        bail!("--smoothing must be a non-negative number of meters");
+    }
+
+    let route = match (from, input) {
+        (Some(from), None) if !to.is_empty() => std::iter::once(from).chain(to).collect(),
+        (None, Some(input)) => read_line_string(&input)?,
+        _ => bail!("Specify either --from with at least one --to, or an input GeoJSON LineString"),
+    };
+
+    // Build the service
+    let mut builder = match data_dir {
+        Some(dir) => SrtmServiceBuilder::new(dir),
+        None => SrtmServiceBuilder::from_env().context(
+            "HTG_DATA_DIR environment variable not set. Use --data-dir or set HTG_DATA_DIR",
+        )?,
+    };
+
+    builder = builder.cache_size(cache_size);
+
+    if auto_download {
+        builder = builder.auto_download(DownloadConfig::ardupilot_srtm1());
+    }
+
+    let service = builder.build().context("Failed to create SRTM service")?;
+
+    if auto_download {
+        super::fetch_missing_tiles(service.data_dir(), route.iter().copied())?;
+    }
+
+    let profile = service
+        .elevation_profile(&route, spacing, interpolate)
+        .context("Failed to compute elevation profile")?;
+
+    let stdout = std::io::stdout();
+    let summary = write_profile(&profile, smoothing, units, json, &mut stdout.lock())?;
+    if !json {
+        // Keep stdout valid CSV
+        eprintln!(
+            "Distance: {:.1} m, ascent: {:.1} m, descent: {:.1} m ({} samples)",
+            summary.distance_m, summary.ascent_m, summary.descent_m, summary.samples
+        );
+    }
+
+    Ok(())
+}
+
+/// Read route vertices from a GeoJSON LineString (bare, or the first
+/// LineString feature).
+fn read_line_string(path: &Path) -> Result<Vec<(f64, f64)>> {
+    let file = std::fs::File::open(path).context("Failed to open input file")?;
+    let geojson: geojson::GeoJson = serde_json::from_reader(std::io::BufReader::new(file))
+        .context("Failed to parse GeoJSON")?;
+
+    let geometries: Vec<geojson::Geometry> = match geojson {
+        geojson::GeoJson::Geometry(geometry) => vec![geometry],
+        geojson::GeoJson::Feature(feature) => feature.geometry.into_iter().collect(),
+        geojson::GeoJson::FeatureCollection(fc) => {
+            fc.features.into_iter().filter_map(|f| f.geometry).collect()
+        }
+    };
+
+    for geometry in geometries {
+        if let geojson::Value::LineString(positions) = geometry.value {
+            return positions
+                .iter()
+                .map(|p| match p.as_slice() {
+                    [lon, lat, ..] => Ok((*lat, *lon)),
+                    _ => bail!("Invalid position in LineString"),
+                })
+                .collect();
+        }
+    }
+    bail!("No LineString found in {}", path.display())
+}
+
+/// Total distance, ascent, and descent of a profile.
+fn summarize(profile: &[ProfilePoint], smoothing: f64) -> ProfileSummary {
+    let (ascent, descent) = ascent_descent(profile, smoothing);
+
+    ProfileSummary {
+        distance_m: profile.last().map_or(0.0, |p| p.distance_m),
+        ascent_m: ascent,
+        descent_m: descent,
+        samples: profile.len(),
+    }
+}
+
+/// Write the profile as CSV rows or a JSON document and return its summary.
+///
+/// Point elevations are written in `units`; the summary stays in meters.
+fn write_profile<W: Write>(
+    profile: &[ProfilePoint],
+    smoothing: f64,
+    units: Units,
+    json: bool,
+    out: &mut W,
+) -> Result<ProfileSummary> {
+    let summary = summarize(profile, smoothing);
+    let samples = profile.iter().map(|p| ProfileSample {
+        distance_m: p.distance_m,
+        lat: p.lat,
+        lon: p.lon,
+        elevation: p.elevation.map(|e| units.convert(e)),
+    });
+
+    if json {
+        let response = ProfileResponse {
+            summary,
+            points: samples.collect(),
+        };
+        serde_json::to_writer(&mut *out, &response)?;
+        writeln!(out)?;
+        return Ok(response.summary);
+    }
+
+    let mut writer = csv::Writer::from_writer(&mut *out);
+    for sample in samples {
+        writer.serialize(sample)?;
+    }
+    writer.flush()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htg::test_util::write_test_tile;
+    use tempfile::TempDir;
+
+    /// SRTM3 tile rising 1m per column towards the east.
+    fn create_ramp_tile(dir: &Path) {
+        write_test_tile(&dir.join("N35E138.hgt"), |_, col| col as i16);
+    }
+
+    #[test]
+    fn test_parse_lat_lon() {
+        assert_eq!(parse_lat_lon("35.5, 138.25"), Ok((35.5, 138.25)));
+        assert!(parse_lat_lon("35.5").is_err());
+        assert!(parse_lat_lon("a,1").is_err());
+    }
+
+    #[test]
+    fn test_profile_csv_and_ascent() {
+        let dir = TempDir::new().unwrap();
+        create_ramp_tile(dir.path());
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        // 0.25° east along a parallel: 300 columns of 1m climb, ~22.6 km
+        let route = [(35.5, 138.25), (35.5, 138.5)];
+        let profile = service.elevation_profile(&route, 100.0, false).unwrap();
+
+        let mut out = Vec::new();
+        let summary = write_profile(&profile, 0.0, Units::Meters, false, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        assert_eq!(summary.samples, profile.len());
+        assert_eq!(summary.samples, 228);
+        assert_eq!(csv.lines().count(), summary.samples + 1);
+        assert!(csv.starts_with("distance_m,lat,lon,elevation\n0.0,35.5,138.25,300.0\n"));
+        assert_eq!(summary.ascent_m, 300.0);
+        assert_eq!(summary.descent_m, 0.0);
+        assert!((summary.distance_m - 22_650.0).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_profile_json_round_trip_descent() {
+        let dir = TempDir::new().unwrap();
+        create_ramp_tile(dir.path());
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        // Out and back: climb then descend
+        let route = [(35.5, 138.25), (35.5, 138.3), (35.5, 138.25)];
+        let profile = service.elevation_profile(&route, 90.0, false).unwrap();
+
+        let mut out = Vec::new();
+        write_profile(&profile, 0.0, Units::Meters, true, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(json["summary"]["ascent_m"], 60.0);
+        assert_eq!(json["summary"]["descent_m"], 60.0);
+
+        // A threshold above the whole climb filters it out
+        let summary = summarize(&profile, 100.0);
+        assert_eq!((summary.ascent_m, summary.descent_m), (0.0, 0.0));
+        assert_eq!(
+            json["points"].as_array().unwrap().len(),
+            json["summary"]["samples"].as_u64().unwrap() as usize
+        );
+    }
+
+    #[test]
+    fn test_profile_units_feet() {
+        let dir = TempDir::new().unwrap();
+        create_ramp_tile(dir.path());
+        let service = htg::SrtmService::new(dir.path(), 1);
+
+        let route = [(35.5, 138.25), (35.5, 138.5)];
+        let profile = service.elevation_profile(&route, 100.0, false).unwrap();
+
+        let mut out = Vec::new();
+        let summary = write_profile(&profile, 0.0, Units::Feet, false, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        // 300m is 984.252ft; the totals stay in meters
+        assert!(csv.starts_with("distance_m,lat,lon,elevation\n0.0,35.5,138.25,984.252"));
+        assert_eq!(summary.ascent_m, 300.0);
+    }
+
+    #[test]
+    fn test_read_line_string() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("route.geojson");
+        std::fs::write(
+            &path,
+            r#"{"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[138.25, 35.5], [138.5, 35.6]]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_line_string(&path).unwrap(),
+            vec![(35.5, 138.25), (35.6, 138.5)]
+        );
+
+        std::fs::write(&path, r#"{"type": "Point", "coordinates": [138.25, 35.5]}"#).unwrap();
+        assert!(read_line_string(&path).is_err());
+    }
+}
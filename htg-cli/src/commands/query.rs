@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
-use htg::{download::DownloadConfig, SrtmServiceBuilder};
+use htg::{download::DownloadConfig, SrtmService, SrtmServiceBuilder, Units};
 use serde::Serialize;
+use std::io::Write;
 use std::path::PathBuf;
 
 #[derive(Serialize)]
@@ -8,10 +9,12 @@ struct ElevationResponse {
     lat: f64,
     lon: f64,
     elevation: Option<f64>,
+    units: Units,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     interpolated: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     data_dir: Option<PathBuf>,
     cache_size: u64,
@@ -19,6 +22,8 @@ pub fn run(
     lat: f64,
     lon: f64,
     interpolate: bool,
+    units: Units,
+    precision: usize,
     json: bool,
 ) -> Result<()> {
     // Build the service
@@ -37,23 +42,47 @@ pub fn run(
 
     let service = builder.build().context("Failed to create SRTM service")?;
 
+    if auto_download {
+        super::fetch_missing_tiles(service.data_dir(), [(lat, lon)])?;
+    }
+
+    let stdout = std::io::stdout();
+    query(
+        &service,
+        lat,
+        lon,
+        interpolate,
+        units,
+        precision,
+        json,
+        &mut stdout.lock(),
+    )
+}
+
+/// Look up one elevation in `units` and write it as text or JSON, with
+/// interpolated values rounded to `precision` decimal places.
+#[allow(clippy::too_many_arguments)]
+fn query<W: Write>(
+    service: &SrtmService,
+    lat: f64,
+    lon: f64,
+    interpolate: bool,
+    units: Units,
+    precision: usize,
+    json: bool,
+    out: &mut W,
+) -> Result<()> {
     // Query elevation
-    let (elevation, is_void) = if interpolate {
-        match service
+    let elevation = if interpolate {
+        service
             .get_elevation_interpolated(lat, lon)
             .context("Failed to get elevation")?
-        {
-            Some(elev) => (Some(elev), false),
-            None => (None, true),
-        }
+            .map(|elev| super::round_to(units.convert(elev), precision))
     } else {
-        match service
+        service
             .get_elevation(lat, lon)
             .context("Failed to get elevation")?
-        {
-            Some(elev) => (Some(elev as f64), false),
-            None => (None, true),
-        }
+            .map(|elev| f64::from(units.convert_whole(elev)))
     };
 
     // Output result
@@ -62,18 +91,84 @@ pub fn run(
             lat,
             lon,
             elevation,
+            units,
             interpolated: interpolate,
         };
-        println!("{}", serde_json::to_string(&response)?);
-    } else if is_void {
-        println!("void");
+        writeln!(out, "{}", serde_json::to_string(&response)?)?;
     } else if let Some(elev) = elevation {
         if interpolate {
-            println!("{:.2}", elev);
+            writeln!(out, "{}", super::format_rounded(elev, precision))?;
         } else {
-            println!("{}", elev as i16);
+            writeln!(out, "{}", elev as i16)?;
         }
+    } else {
+        writeln!(out, "void")?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htg::test_util::write_test_tile;
+    use tempfile::TempDir;
+
+    /// Flat SRTM3 tile at 500m.
+    fn create_flat_tile(dir: &TempDir) -> SrtmService {
+        write_test_tile(&dir.path().join("N35E138.hgt"), |_, _| 500);
+        SrtmService::new(dir.path(), 1)
+    }
+
+    fn output(service: &SrtmService, interpolate: bool, units: Units, json: bool) -> String {
+        let mut out = Vec::new();
+        query(service, 35.5, 138.5, interpolate, units, 2, json, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_query_units_feet() {
+        let dir = TempDir::new().unwrap();
+        let service = create_flat_tile(&dir);
+
+        assert_eq!(output(&service, false, Units::Meters, false), "500\n");
+        // 500m = 1640.42ft
+        assert_eq!(output(&service, false, Units::Feet, false), "1640\n");
+        assert_eq!(output(&service, true, Units::Feet, false), "1640.42\n");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&output(&service, false, Units::Feet, true)).unwrap();
+        assert_eq!(json["elevation"], 1640.0);
+        assert_eq!(json["units"], "feet");
+    }
+
+    #[test]
+    fn test_query_precision() {
+        let dir = TempDir::new().unwrap();
+        let service = create_flat_tile(&dir);
+        let output = |precision: usize| {
+            let mut out = Vec::new();
+            query(
+                &service,
+                35.5,
+                138.5,
+                true,
+                Units::Feet,
+                precision,
+                false,
+                &mut out,
+            )
+            .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // 500m = 1640.4199ft
+        assert_eq!(output(0), "1640\n");
+        assert_eq!(output(4), "1640.4200\n");
+
+        let mut out = Vec::new();
+        query(&service, 35.5, 138.5, true, Units::Feet, 1, true, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["elevation"], 1640.4);
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::{bail, Context, Result};
+use htg::{
+    filename::{filename_to_lat_lon, tiles_covering_bbox},
+    BoundingBox, TileStats,
+};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub fn run(
+    data_dir: Option<PathBuf>,
+    tile: Option<String>,
+    bbox: Option<BoundingBox>,
+) -> Result<()> {
+    let data_dir = data_dir
+        .context("HTG_DATA_DIR environment variable not set. Use --data-dir or set HTG_DATA_DIR")?;
+
+    let filenames = match (tile, bbox) {
+        (Some(tile), None) => {
+            let filename = format!("{}.hgt", tile.trim_end_matches(".hgt").to_uppercase());
+            if filename_to_lat_lon(&filename).is_none() {
+                bail!("Invalid tile name '{}': expected e.g. N35E138", tile);
+            }
+            vec![filename]
+        }
+        (None, Some(bbox)) => {
+            tiles_covering_bbox(bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon)
+        }
+        _ => bail!("Specify exactly one of --tile or --bbox"),
+    };
+
+    let tiles = tile_stats(&data_dir, &filenames)?;
+    if tiles.is_empty() {
+        bail!(
+            "No tiles found in {} (try `htg download`)",
+            data_dir.display()
+        );
+    }
+
+    println!(
+        "{:<12} {:>8} {:>8} {:>10} {:>8}",
+        "TILE", "MIN", "MAX", "MEAN", "VOID %"
+    );
+    println!("{}", "-".repeat(50));
+    for (filename, stats) in &tiles {
+        print_row(filename, stats);
+    }
+    println!("{}", "-".repeat(50));
+    print_row("TOTAL", &aggregate(tiles.iter().map(|(_, stats)| stats)));
+
+    println!();
+    println!(
+        "Scanned {} of {} tiles ({} missing)",
+        tiles.len(),
+        filenames.len(),
+        filenames.len() - tiles.len()
+    );
+    Ok(())
+}
+
+/// Compute the stats of each tile in `filenames` present in `data_dir`,
+/// scanning tiles in parallel. Zipped tiles are extracted; missing tiles are
+/// skipped.
+fn tile_stats(data_dir: &Path, filenames: &[String]) -> Result<Vec<(String, TileStats)>> {
+    filenames
+        .par_iter()
+        .filter_map(|filename| {
+            super::load_tile_for_scan(data_dir, filename)
+                .map(|tile| tile.map(|tile| (filename.clone(), tile.compute_stats())))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Combine per-tile stats, weighting each tile's mean by its non-void samples.
+fn aggregate<'a>(tiles: impl IntoIterator<Item = &'a TileStats>) -> TileStats {
+    let mut total = TileStats {
+        min: None,
+        max: None,
+        mean: None,
+        void_count: 0,
+        sample_count: 0,
+    };
+    let mut sum = 0.0;
+
+    for stats in tiles {
+        total.min = total.min.into_iter().chain(stats.min).min();
+        total.max = total.max.into_iter().chain(stats.max).max();
+        if let Some(mean) = stats.mean {
+            sum += mean * (stats.sample_count - stats.void_count) as f64;
+        }
+        total.void_count += stats.void_count;
+        total.sample_count += stats.sample_count;
+    }
+
+    let valid = total.sample_count - total.void_count;
+    total.mean = (valid > 0).then(|| sum / valid as f64);
+    total
+}
+
+fn print_row(label: &str, stats: &TileStats) {
+    let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+    let void_pct = if stats.sample_count == 0 {
+        0.0
+    } else {
+        stats.void_count as f64 / stats.sample_count as f64 * 100.0
+    };
+    println!(
+        "{:<12} {:>8} {:>8} {:>10} {:>7.2}%",
+        label,
+        or_dash(stats.min.map(|v| v.to_string())),
+        or_dash(stats.max.map(|v| v.to_string())),
+        or_dash(stats.mean.map(|v| format!("{:.1}", v))),
+        void_pct
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htg::test_util::{write_test_tile, SRTM3_SAMPLES};
+    use htg::VOID_VALUE;
+    use tempfile::TempDir;
+
+    /// SRTM3 tile filled with `value`, with `first` as its first sample.
+    fn write_tile(dir: &Path, name: &str, value: i16, first: i16) {
+        write_test_tile(&dir.join(name), |row, col| {
+            if (row, col) == (0, 0) {
+                first
+            } else {
+                value
+            }
+        });
+    }
+
+    #[test]
+    fn test_aggregates_across_tiles() {
+        let dir = TempDir::new().unwrap();
+        write_tile(dir.path(), "N35E138.hgt", 100, -20);
+        write_tile(dir.path(), "N35E139.hgt", 300, VOID_VALUE);
+
+        let filenames = tiles_covering_bbox(35.2, 138.2, 35.8, 140.8);
+        assert_eq!(filenames.len(), 3);
+        let mut tiles = tile_stats(dir.path(), &filenames).unwrap();
+        tiles.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[0].1.min, Some(-20));
+        assert_eq!(tiles[1].1.max, Some(300));
+
+        let total = aggregate(tiles.iter().map(|(_, stats)| stats));
+        assert_eq!(total.min, Some(-20));
+        assert_eq!(total.max, Some(300));
+        assert_eq!(total.void_count, 1);
+        assert_eq!(
+            total.sample_count,
+            2 * (SRTM3_SAMPLES * SRTM3_SAMPLES) as u64
+        );
+        // Equal valid sample counts, so close to the midpoint of 100 and 300
+        assert!((total.mean.unwrap() - 200.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_scans_zipped_tiles() {
+        let dir = TempDir::new().unwrap();
+        let staging = TempDir::new().unwrap();
+        write_tile(staging.path(), "N35E138.hgt", 100, -20);
+
+        let file = std::fs::File::create(dir.path().join("N35E138.hgt.zip")).unwrap();
+        let mut zip_writer = zip::ZipWriter::new(file);
+        zip_writer
+            .start_file("N35E138.hgt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(
+            &mut zip_writer,
+            &std::fs::read(staging.path().join("N35E138.hgt")).unwrap(),
+        )
+        .unwrap();
+        zip_writer.finish().unwrap();
+
+        let tiles = tile_stats(dir.path(), &["N35E138.hgt".to_string()]).unwrap();
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].1.min, Some(-20));
+        assert_eq!(tiles[0].1.max, Some(100));
+    }
+}
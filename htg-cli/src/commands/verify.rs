@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use htg::SrtmServiceBuilder;
+use std::path::PathBuf;
+
+pub fn run(data_dir: Option<PathBuf>, tiles: Vec<String>) -> Result<()> {
+    let builder = match data_dir {
+        Some(dir) => SrtmServiceBuilder::new(dir),
+        None => SrtmServiceBuilder::from_env().context(
+            "HTG_DATA_DIR environment variable not set. Use --data-dir or set HTG_DATA_DIR",
+        )?,
+    };
+
+    // Only one tile needs to be resident at a time
+    let service = builder
+        .cache_size(1)
+        .build()
+        .context("Failed to create SRTM service")?;
+
+    let tiles = if tiles.is_empty() {
+        service.scan_tile_files()
+    } else {
+        tiles
+            .into_iter()
+            .map(|t| {
+                if t.ends_with(".hgt") {
+                    t
+                } else {
+                    format!("{}.hgt", t)
+                }
+            })
+            .collect()
+    };
+
+    if tiles.is_empty() {
+        println!("No .hgt files found in: {}", service.data_dir().display());
+        return Ok(());
+    }
+
+    println!("{:<12} {:>8} {:>8}  NOTES", "TILE", "STATUS", "VOID %");
+    println!("{}", "-".repeat(44));
+
+    let mut bad = 0;
+    for filename in &tiles {
+        match service.validate_tile(filename) {
+            Ok(health) => {
+                let notes = if health.all_void {
+                    "all void"
+                } else if health.all_zero {
+                    "all zero"
+                } else if health.suspicious {
+                    "striped rows"
+                } else {
+                    ""
+                };
+                let status = if health.suspicious { "SUSPECT" } else { "OK" };
+                if health.suspicious {
+                    bad += 1;
+                }
+                println!(
+                    "{:<12} {:>8} {:>7.2}%  {}",
+                    filename,
+                    status,
+                    health.void_ratio * 100.0,
+                    notes
+                );
+            }
+            Err(e) => {
+                bad += 1;
+                println!("{:<12} {:>8} {:>8}  {}", filename, "ERROR", "-", e);
+            }
+        }
+    }
+
+    println!();
+    println!("Checked {} tiles, {} problem(s)", tiles.len(), bad);
+
+    if bad > 0 {
+        anyhow::bail!("{} tile(s) failed verification", bad);
+    }
+    Ok(())
+}
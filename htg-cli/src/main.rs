@@ -47,6 +47,14 @@ enum Commands {
         #[arg(short, long)]
         interpolate: bool,
 
+        /// Unit for output elevations
+        #[arg(long, value_enum, default_value = "meters")]
+        units: htg::Units,
+
+        /// Decimal places of interpolated elevations
+        #[arg(long, value_name = "N", default_value_t = 2, value_parser = clap::value_parser!(u8).range(..=15))]
+        precision: u8,
+
         /// Output result as JSON
         #[arg(short, long)]
         json: bool,
@@ -54,13 +62,19 @@ enum Commands {
 
     /// Process elevation for multiple coordinates from a file
     Batch {
-        /// Input file (CSV or GeoJSON)
+        /// Input file (CSV, GeoJSON, or GPX); GPX points get interpolated `<ele>` values
         input: PathBuf,
 
-        /// Output file (same format as input if not specified)
+        /// Output file (`<input>_elevation.<format>` if not specified, stdout for jsonl)
         #[arg(short, long)]
         output: Option<PathBuf>,
 
+        /// Output format (same as input if not specified); converting from CSV
+        /// writes Point features, from GeoJSON writes lon,lat,elevation rows.
+        /// `jsonl` writes one JSON object per point, to stdout unless --output is given
+        #[arg(long, visible_alias = "format", value_enum)]
+        output_format: Option<commands::batch::OutputFormat>,
+
         /// Column name for latitude (CSV only)
         #[arg(long, default_value = "lat")]
         lat_col: String,
@@ -72,6 +86,145 @@ enum Commands {
         /// Use bilinear interpolation
         #[arg(short, long)]
         interpolate: bool,
+
+        /// Unit for output elevations (CSV and GeoJSON; GPX is always meters)
+        #[arg(long, value_enum, default_value = "meters")]
+        units: htg::Units,
+
+        /// Decimal places of interpolated elevations (GPX always has two)
+        #[arg(long, value_name = "N", default_value_t = 2, value_parser = clap::value_parser!(u8).range(..=15))]
+        precision: u8,
+
+        /// Add a `resolution` column (srtm1/srtm3) from the covering tile (CSV only)
+        #[arg(long)]
+        include_resolution: bool,
+
+        /// Name of the output elevation column or property (CSV input or output)
+        #[arg(long, default_value = "elevation")]
+        elevation_col: String,
+
+        /// Name of the output resolution column (CSV only)
+        #[arg(long, default_value = "resolution")]
+        resolution_col: String,
+
+        /// What to do when an output column already exists in the input (CSV only)
+        #[arg(long, value_enum, default_value = "error")]
+        on_column_conflict: commands::batch::ColumnConflict,
+
+        /// EPSG code of the input coordinates (4326, 3857, or a WGS84 UTM zone);
+        /// the lat/lon columns then hold northing/easting (CSV only)
+        #[cfg(feature = "proj")]
+        #[arg(long, value_name = "EPSG")]
+        input_crs: Option<u32>,
+
+        /// Output buffer size in bytes
+        #[arg(long, value_name = "BYTES", default_value_t = 8 * 1024)]
+        buffer_size: usize,
+
+        /// Flush the output file every N rows so partial results are visible (CSV only)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        flush_every: Option<u64>,
+
+        /// Process CSV rows in chunks as they are read instead of loading the
+        /// whole file first; shows a row counter instead of a progress bar (CSV only)
+        #[arg(long)]
+        stream: bool,
+
+        /// With --auto-download, fetch up to N missing tiles at once before
+        /// looking up elevations (CSV only)
+        #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+        concurrency: u16,
+    },
+
+    /// Sample an elevation profile along a route
+    Profile {
+        /// Route start as "lat,lon"
+        #[arg(long, value_parser = commands::profile::parse_lat_lon, allow_hyphen_values = true, requires = "to", conflicts_with = "input")]
+        from: Option<(f64, f64)>,
+
+        /// Next route point as "lat,lon" (repeat for multi-segment routes)
+        #[arg(long, value_parser = commands::profile::parse_lat_lon, allow_hyphen_values = true, requires = "from")]
+        to: Vec<(f64, f64)>,
+
+        /// GeoJSON file containing a LineString route
+        input: Option<PathBuf>,
+
+        /// Maximum distance between samples in meters
+        #[arg(long, default_value = "30")]
+        spacing: f64,
+
+        /// Use bilinear interpolation for sub-pixel accuracy
+        #[arg(short, long)]
+        interpolate: bool,
+
+        /// Ignore elevation changes smaller than this many meters in the
+        /// ascent/descent totals
+        #[arg(long, default_value = "0")]
+        smoothing: f64,
+
+        /// Unit for the elevation column; distances and ascent/descent totals
+        /// stay in meters
+        #[arg(long, value_enum, default_value = "meters")]
+        units: htg::Units,
+
+        /// Output as JSON instead of CSV
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Download tiles into the data directory
+    Download {
+        /// Tile name (e.g., N35E138)
+        #[arg(long, conflicts_with_all = ["lat", "bbox"])]
+        tile: Option<String>,
+
+        /// Download the tile containing this latitude
+        #[arg(
+            long,
+            requires = "lon",
+            conflicts_with = "bbox",
+            allow_hyphen_values = true
+        )]
+        lat: Option<f64>,
+
+        /// Download the tile containing this longitude
+        #[arg(long, requires = "lat", allow_hyphen_values = true)]
+        lon: Option<f64>,
+
+        /// Download every tile overlapping "min_lat,min_lon,max_lat,max_lon"
+        #[arg(long, value_parser = commands::download::parse_bbox, allow_hyphen_values = true)]
+        bbox: Option<htg::BoundingBox>,
+
+        /// Named download source (ardupilot, ardupilot-srtm1, ardupilot-srtm3)
+        #[arg(long, default_value = "ardupilot-srtm1")]
+        source: String,
+
+        /// Custom URL template, overriding --source
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Check which tiles the server has first and skip the rest (e.g. ocean tiles)
+        #[arg(long)]
+        skip_absent: bool,
+    },
+
+    /// Export a tile or bounding box as a GeoTIFF or PNG raster
+    Export {
+        /// Tile name (e.g., N35E138)
+        #[arg(long, conflicts_with = "bbox")]
+        tile: Option<String>,
+
+        /// Crop to "min_lat,min_lon,max_lat,max_lon" (may span several tiles)
+        #[arg(long, value_parser = commands::download::parse_bbox, allow_hyphen_values = true)]
+        bbox: Option<htg::BoundingBox>,
+
+        /// Output raster format
+        #[arg(short, long, value_enum, default_value = "geotiff")]
+        format: commands::export::ExportFormat,
+
+        /// Output file
+        #[arg(short, long)]
+        output: PathBuf,
     },
 
     /// Display information about an SRTM tile
@@ -90,6 +243,38 @@ enum Commands {
 
     /// List available SRTM tiles
     List,
+
+    /// Summarize elevations (min/max/mean, void %) over a tile or bounding box
+    Stats {
+        /// Tile name (e.g., N35E138)
+        #[arg(long, conflicts_with = "bbox")]
+        tile: Option<String>,
+
+        /// Summarize every tile overlapping "min_lat,min_lon,max_lat,max_lon"
+        #[arg(long, value_parser = commands::download::parse_bbox, allow_hyphen_values = true)]
+        bbox: Option<htg::BoundingBox>,
+    },
+
+    /// Check tiles for signs of corruption (all zero, all void, striped)
+    Verify {
+        /// Tile names to check (e.g., N35E138); defaults to all tiles in the data directory
+        tiles: Vec<String>,
+    },
+
+    /// Generate downsampled overview tiles (factor 2, 4, 8, ...)
+    BuildOverviews {
+        /// Directory containing source .hgt files
+        #[arg(value_name = "DATA_DIR")]
+        source_dir: PathBuf,
+
+        /// Output directory for overview files
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Number of overview levels (level n downsamples by 2^n)
+        #[arg(long, default_value = "3")]
+        levels: u32,
+    },
 }
 
 fn main() -> Result<()> {
@@ -100,6 +285,8 @@ fn main() -> Result<()> {
             lat,
             lon,
             interpolate,
+            units,
+            precision,
             json,
         } => commands::query::run(
             cli.data_dir,
@@ -108,25 +295,105 @@ fn main() -> Result<()> {
             lat,
             lon,
             interpolate,
+            units,
+            precision.into(),
             json,
         ),
         Commands::Batch {
             input,
             output,
+            output_format,
             lat_col,
             lon_col,
             interpolate,
-        } => commands::batch::run(
+            units,
+            precision,
+            include_resolution,
+            elevation_col,
+            resolution_col,
+            on_column_conflict,
+            #[cfg(feature = "proj")]
+            input_crs,
+            buffer_size,
+            flush_every,
+            stream,
+            concurrency,
+        } => {
+            #[cfg(not(feature = "proj"))]
+            let input_crs = None;
+
+            commands::batch::run(
+                cli.data_dir,
+                cli.cache_size,
+                cli.auto_download,
+                concurrency.into(),
+                input,
+                output,
+                commands::batch::CsvColumns {
+                    lat: lat_col,
+                    lon: lon_col,
+                    elevation: elevation_col,
+                    resolution: resolution_col,
+                    on_conflict: on_column_conflict,
+                },
+                interpolate,
+                include_resolution,
+                input_crs,
+                commands::batch::WriterOptions {
+                    format: output_format,
+                    units,
+                    precision: precision.into(),
+                    buffer_size,
+                    flush_every,
+                    stream,
+                },
+            )
+        }
+        Commands::Profile {
+            from,
+            to,
+            input,
+            spacing,
+            interpolate,
+            smoothing,
+            units,
+            json,
+        } => commands::profile::run(
             cli.data_dir,
             cli.cache_size,
             cli.auto_download,
+            from,
+            to,
             input,
-            output,
-            lat_col,
-            lon_col,
+            spacing,
             interpolate,
+            smoothing,
+            units,
+            json,
         ),
+        Commands::Download {
+            tile,
+            lat,
+            lon,
+            bbox,
+            source,
+            url,
+            skip_absent,
+        } => commands::download::run(cli.data_dir, tile, lat, lon, bbox, source, url, skip_absent),
+        Commands::Export {
+            tile,
+            bbox,
+            format,
+            output,
+        } => commands::export::run(cli.data_dir, cli.auto_download, tile, bbox, format, output),
         Commands::Info { tile, lat, lon } => commands::info::run(cli.data_dir, tile, lat, lon),
         Commands::List => commands::list::run(cli.data_dir),
+        Commands::Stats { tile, bbox } => commands::stats::run(cli.data_dir, tile, bbox),
+        Commands::Verify { tiles } => commands::verify::run(cli.data_dir, tiles),
+        Commands::BuildOverviews {
+            source_dir,
+            output,
+            levels,
+        } => commands::overviews::run(source_dir, output, levels),
     }
 }
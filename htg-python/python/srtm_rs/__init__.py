@@ -4,6 +4,7 @@ from srtm_rs.srtm_rs import (  # type: ignore[import]
     CacheStats as CacheStats,
     PreloadStats as PreloadStats,
     SrtmService as SrtmService,
+    SrtmTile as SrtmTile,
     VOID_VALUE as VOID_VALUE,
     __version__ as __version__,
     filename_to_lat_lon as filename_to_lat_lon,
@@ -12,6 +13,7 @@ from srtm_rs.srtm_rs import (  # type: ignore[import]
 
 __all__ = [
     "SrtmService",
+    "SrtmTile",
     "CacheStats",
     "PreloadStats",
     "lat_lon_to_filename",
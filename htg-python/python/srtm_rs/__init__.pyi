@@ -1,6 +1,9 @@
 """Type stubs for srtm_rs - High-performance SRTM elevation library."""
 
-from typing import List, Optional, Tuple
+from typing import List, Literal, Optional, Tuple, Union, overload
+
+import numpy as np
+import numpy.typing as npt
 
 __version__: str
 VOID_VALUE: int
@@ -17,6 +20,12 @@ class CacheStats:
     miss_count: int
     """Number of cache misses."""
 
+    resident_bytes: int
+    """Total size of the cached tiles in bytes."""
+
+    eviction_count: int
+    """Number of tiles evicted to make room for others."""
+
     @property
     def hit_rate(self) -> float:
         """Cache hit rate (0.0 to 1.0)."""
@@ -80,26 +89,71 @@ class SrtmService:
         """
         ...
 
+    @overload
     def get_elevations_batch(
         self,
         coords: List[Tuple[float, float]],
         default: int = 0,
         rounding: str = "nearest",
-    ) -> List[int]:
+        interpolate: Literal[False] = False,
+    ) -> List[int]: ...
+    @overload
+    def get_elevations_batch(
+        self,
+        coords: List[Tuple[float, float]],
+        default: int = 0,
+        rounding: str = "nearest",
+        *,
+        interpolate: Literal[True],
+    ) -> List[float]: ...
+    def get_elevations_batch(
+        self,
+        coords: List[Tuple[float, float]],
+        default: int = 0,
+        rounding: str = "nearest",
+        interpolate: bool = False,
+    ) -> Union[List[int], List[float]]:
         """Get elevations for a batch of coordinates.
 
+        Coordinates are grouped by tile so each tile is loaded once, and the
+        GIL is released while the lookups run.
+
         Args:
             coords: List of (lat, lon) tuples.
             default: Default value for void/missing data.
             rounding: Rounding strategy for grid cell selection.
                 "nearest" (default): Round to closest cell (true nearest-neighbor).
                 "floor": Always round down (srtm.py compatible, southwest-biased).
+            interpolate: Use bilinear interpolation. The result then holds
+                floats; `rounding` must be "nearest".
+
+        Returns:
+            List of elevation values in meters, in the same order as `coords`.
+
+        Raises:
+            ValueError: If rounding is invalid or combined with interpolate.
+        """
+        ...
+
+    def get_elevations_numpy(
+        self, lats: npt.NDArray[np.float64], lons: npt.NDArray[np.float64]
+    ) -> npt.NDArray[np.float64]:
+        """Get elevations for NumPy arrays of coordinates.
+
+        The inputs are read in place, the lookup runs with the GIL released,
+        and the result is a new array. Requires the `numpy` build feature.
+
+        Args:
+            lats: 1-D float64 array of latitudes.
+            lons: 1-D float64 array of longitudes, same length as `lats`.
 
         Returns:
-            List of elevation values in meters.
+            float64 array of elevations in meters, with NaN for void data,
+            missing tiles, and out-of-bounds coordinates.
 
         Raises:
-            ValueError: If rounding is invalid.
+            ValueError: If the arrays have different lengths.
+            BufferError: If an input is not a float64 array.
         """
         ...
 
@@ -159,6 +213,100 @@ class SrtmService:
         """Get current cache statistics."""
         ...
 
+    def reset_stats(self) -> None:
+        """Reset the hit, miss, and eviction counters to zero (cached tiles are kept)."""
+        ...
+
+    def clear_cache(self) -> None:
+        """Remove all tiles from the cache."""
+        ...
+
+    def invalidate_tile(self, filename: str) -> None:
+        """Remove one tile (e.g. "N35E138.hgt") from the cache so it is reloaded."""
+        ...
+
+    def invalidate_bbox(
+        self, min_lat: float, min_lon: float, max_lat: float, max_lon: float
+    ) -> None:
+        """Remove every tile overlapping a bounding box from the cache."""
+        ...
+
+    def __enter__(self) -> "SrtmService": ...
+    def __exit__(
+        self,
+        exc_type: Optional[type] = None,
+        exc_value: Optional[BaseException] = None,
+        traceback: Optional[object] = None,
+    ) -> bool:
+        """Clear the cache when leaving a `with` block."""
+        ...
+
+class SrtmTile:
+    """A single SRTM tile, queried directly without the service cache.
+
+    Example:
+        >>> tile = SrtmTile("/path/to/srtm/N35E138.hgt")
+        >>> tile.get_elevation(35.3606, 138.7274)
+        3776
+    """
+
+    def __init__(
+        self, path: str, base_lat: Optional[int] = None, base_lon: Optional[int] = None
+    ) -> None:
+        """Load a tile from a .hgt file.
+
+        Args:
+            path: Path to the .hgt file.
+            base_lat: Latitude of the southwest corner. Parsed from the filename
+                (e.g. "N35E138.hgt") if omitted.
+            base_lon: Longitude of the southwest corner. Parsed from the filename
+                if omitted.
+
+        Raises:
+            ValueError: If the file size is not SRTM1/SRTM3, the file cannot be
+                read, or the base coordinates cannot be determined.
+        """
+        ...
+
+    @staticmethod
+    def from_bytes(data: bytes, base_lat: int, base_lon: int) -> "SrtmTile":
+        """Load a tile from the contents of a .hgt file.
+
+        Raises:
+            ValueError: If the data length is not SRTM1/SRTM3.
+        """
+        ...
+
+    def get_elevation(self, lat: float, lon: float) -> Optional[int]:
+        """Get elevation using nearest-neighbor lookup.
+
+        Only the position within the one-degree cell is used, so the
+        coordinates are expected to lie within this tile.
+
+        Returns:
+            Elevation in meters, or None for void data.
+
+        Raises:
+            ValueError: If the coordinates are not finite numbers.
+        """
+        ...
+
+    def resolution(self) -> str:
+        """Resolution of the tile ("srtm1" or "srtm3")."""
+        ...
+
+    def samples(self) -> int:
+        """Number of samples per row/column (3601 or 1201)."""
+        ...
+
+    def base_lat(self) -> int:
+        """Latitude of the southwest corner."""
+        ...
+
+    def base_lon(self) -> int:
+        """Longitude of the southwest corner."""
+        ...
+
 def lat_lon_to_filename(lat: float, lon: float) -> str:
     """Convert latitude/longitude to SRTM filename.
 
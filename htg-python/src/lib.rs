@@ -6,6 +6,8 @@ use std::sync::Arc;
 
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+#[cfg(feature = "numpy")]
+use pyo3::{buffer::PyBuffer, types::PyByteArray};
 
 // Use fully qualified path to avoid collision with the Python module name
 use ::htg as htg_lib;
@@ -23,6 +25,12 @@ struct CacheStats {
     /// Number of cache misses.
     #[pyo3(get)]
     miss_count: u64,
+    /// Total size of the cached tiles in bytes.
+    #[pyo3(get)]
+    resident_bytes: u64,
+    /// Number of tiles evicted to make room for others.
+    #[pyo3(get)]
+    eviction_count: u64,
 }
 
 #[pymethods]
@@ -40,11 +48,13 @@ impl CacheStats {
 
     fn __repr__(&self) -> String {
         format!(
-            "CacheStats(entry_count={}, hit_count={}, miss_count={}, hit_rate={:.2}%)",
+            "CacheStats(entry_count={}, hit_count={}, miss_count={}, hit_rate={:.2}%, resident_bytes={}, eviction_count={})",
             self.entry_count,
             self.hit_count,
             self.miss_count,
-            self.hit_rate() * 100.0
+            self.hit_rate() * 100.0,
+            self.resident_bytes,
+            self.eviction_count
         )
     }
 }
@@ -97,6 +107,12 @@ struct SrtmService {
     inner: Arc<htg_lib::SrtmService>,
 }
 
+// Queries release the GIL and share `inner` across Python threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<htg_lib::SrtmService>();
+};
+
 #[pymethods]
 impl SrtmService {
     /// Create a new SRTM service.
@@ -160,26 +176,32 @@ impl SrtmService {
 
     /// Get elevations for a batch of coordinates.
     ///
+    /// Coordinates are grouped by tile so each tile is loaded once, and the
+    /// GIL is released while the lookups run.
+    ///
     /// Args:
     ///     coords: List of (lat, lon) tuples.
     ///     default: Default value for void/missing data (default: 0).
     ///     rounding: Rounding strategy for grid cell selection.
     ///         "nearest" (default): Round to closest cell (true nearest-neighbor).
     ///         "floor": Always round down (srtm.py compatible, southwest-biased).
+    ///     interpolate: Use bilinear interpolation (default: False). The result
+    ///         then holds floats; `rounding` must be "nearest".
     ///
     /// Returns:
-    ///     List of elevation values in meters.
+    ///     List of elevation values in meters, in the same order as `coords`.
     ///
     /// Raises:
-    ///     ValueError: If rounding is invalid.
-    #[pyo3(signature = (coords, default=0, rounding="nearest"))]
+    ///     ValueError: If rounding is invalid or combined with interpolate.
+    #[pyo3(signature = (coords, default=0, rounding="nearest", interpolate=false))]
     fn get_elevations_batch(
         &self,
         py: Python<'_>,
         coords: Vec<(f64, f64)>,
         default: i16,
         rounding: &str,
-    ) -> PyResult<Vec<i16>> {
+        interpolate: bool,
+    ) -> PyResult<PyObject> {
         let use_floor = match rounding {
             "nearest" => false,
             "floor" => true,
@@ -190,14 +212,90 @@ impl SrtmService {
                 )))
             }
         };
+        if use_floor && interpolate {
+            return Err(PyValueError::new_err(
+                "rounding='floor' cannot be combined with interpolate=True",
+            ));
+        }
         let inner = Arc::clone(&self.inner);
-        Ok(py.allow_threads(move || {
+        if interpolate {
+            let elevations = py.allow_threads(move || {
+                inner.get_elevations_batch_interpolated(&coords, default as f64)
+            });
+            return Ok(elevations.into_py(py));
+        }
+        let elevations = py.allow_threads(move || {
             if use_floor {
                 inner.get_elevations_batch_floor(&coords, default)
             } else {
                 inner.get_elevations_batch(&coords, default)
             }
-        }))
+        });
+        Ok(elevations.into_py(py))
+    }
+
+    /// Get elevations for NumPy arrays of coordinates.
+    ///
+    /// The inputs are read in place through the buffer protocol, the lookup
+    /// runs with the GIL released, and the result is a new NumPy array.
+    ///
+    /// Args:
+    ///     lats: 1-D float64 array of latitudes.
+    ///     lons: 1-D float64 array of longitudes, same length as `lats`.
+    ///
+    /// Returns:
+    ///     float64 array of elevations in meters, with NaN for void data,
+    ///     missing tiles, and out-of-bounds coordinates.
+    ///
+    /// Raises:
+    ///     ValueError: If the arrays have different lengths.
+    ///     BufferError: If an input is not a float64 array.
+    #[cfg(feature = "numpy")]
+    fn get_elevations_numpy<'py>(
+        &self,
+        py: Python<'py>,
+        lats: &Bound<'py, PyAny>,
+        lons: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let lats = PyBuffer::<f64>::get_bound(lats)?;
+        let lons = PyBuffer::<f64>::get_bound(lons)?;
+        if lats.item_count() != lons.item_count() {
+            return Err(PyValueError::new_err(format!(
+                "lats and lons must have the same length ({} != {})",
+                lats.item_count(),
+                lons.item_count()
+            )));
+        }
+
+        let coords: Vec<(f64, f64)> = match (lats.as_slice(py), lons.as_slice(py)) {
+            (Some(lats), Some(lons)) => lats
+                .iter()
+                .zip(lons)
+                .map(|(lat, lon)| (lat.get(), lon.get()))
+                .collect(),
+            // Non-contiguous arrays (e.g. strided views) need a copy
+            _ => lats.to_vec(py)?.into_iter().zip(lons.to_vec(py)?).collect(),
+        };
+
+        let inner = Arc::clone(&self.inner);
+        let elevations =
+            py.allow_threads(move || inner.get_elevations_batch(&coords, htg_lib::VOID_VALUE));
+
+        let size = std::mem::size_of::<f64>();
+        let output = PyByteArray::new_bound_with(py, elevations.len() * size, |bytes| {
+            for (chunk, &elevation) in bytes.chunks_exact_mut(size).zip(&elevations) {
+                let value = if elevation == htg_lib::VOID_VALUE {
+                    f64::NAN
+                } else {
+                    elevation as f64
+                };
+                chunk.copy_from_slice(&value.to_ne_bytes());
+            }
+            Ok(())
+        })?;
+
+        // The array shares the bytearray's memory, so this is not another copy
+        PyModule::import_bound(py, "numpy")?.call_method1("frombuffer", (output, "float64"))
     }
 
     /// Get interpolated elevations for a batch of coordinates.
@@ -311,9 +409,67 @@ impl SrtmService {
             entry_count: stats.entry_count,
             hit_count: stats.hit_count,
             miss_count: stats.miss_count,
+            resident_bytes: stats.resident_bytes,
+            eviction_count: stats.eviction_count,
         }
     }
 
+    /// Reset the hit, miss, and eviction counters to zero.
+    ///
+    /// Cached tiles are kept, so entry_count and resident_bytes are
+    /// unchanged.
+    fn reset_stats(&self) {
+        self.inner.reset_stats();
+    }
+
+    /// Remove all tiles from the cache.
+    ///
+    /// The next query for each tile reloads it from disk.
+    fn clear_cache(&self) {
+        self.inner.clear_cache();
+    }
+
+    /// Remove one tile from the cache so it is reloaded on the next query.
+    ///
+    /// Use this after replacing a tile file on disk.
+    ///
+    /// Args:
+    ///     filename: Tile filename (e.g., "N35E138.hgt").
+    fn invalidate_tile(&self, filename: &str) {
+        self.inner.invalidate_tile(filename);
+    }
+
+    /// Remove every tile overlapping a bounding box from the cache.
+    ///
+    /// Use this after replacing the tiles of a region on disk.
+    ///
+    /// Args:
+    ///     min_lat: Southern edge in decimal degrees.
+    ///     min_lon: Western edge in decimal degrees.
+    ///     max_lat: Northern edge in decimal degrees.
+    ///     max_lon: Eastern edge in decimal degrees.
+    fn invalidate_bbox(&self, min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) {
+        self.inner
+            .invalidate_bbox(min_lat, min_lon, max_lat, max_lon);
+    }
+
+    /// Enter a `with` block, returning the service itself.
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Clear the cache when leaving a `with` block. Exceptions propagate.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> bool {
+        self.inner.clear_cache();
+        false
+    }
+
     fn __repr__(&self) -> String {
         let stats = self.cache_stats();
         format!(
@@ -324,6 +480,125 @@ impl SrtmService {
     }
 }
 
+/// A single SRTM tile, queried directly without the service cache.
+///
+/// Example:
+///     >>> tile = SrtmTile("/path/to/srtm/N35E138.hgt")
+///     >>> tile.get_elevation(35.3606, 138.7274)
+///     3776
+#[pyclass]
+struct SrtmTile {
+    inner: htg_lib::SrtmTile,
+}
+
+#[pymethods]
+impl SrtmTile {
+    /// Load a tile from a .hgt file.
+    ///
+    /// Args:
+    ///     path: Path to the .hgt file.
+    ///     base_lat: Latitude of the southwest corner. Parsed from the filename
+    ///         (e.g. "N35E138.hgt") if omitted.
+    ///     base_lon: Longitude of the southwest corner. Parsed from the filename
+    ///         if omitted.
+    ///
+    /// Raises:
+    ///     ValueError: If the file size is not SRTM1/SRTM3, the file cannot be
+    ///         read, or the base coordinates cannot be determined.
+    #[new]
+    #[pyo3(signature = (path, base_lat=None, base_lon=None))]
+    fn new(path: &str, base_lat: Option<i32>, base_lon: Option<i32>) -> PyResult<Self> {
+        let (base_lat, base_lon) = match (base_lat, base_lon) {
+            (Some(lat), Some(lon)) => (lat, lon),
+            (None, None) => std::path::Path::new(path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(htg_lib::filename::filename_to_lat_lon)
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "Cannot parse tile coordinates from '{}'; pass base_lat and base_lon",
+                        path
+                    ))
+                })?,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "base_lat and base_lon must be given together",
+                ))
+            }
+        };
+        let inner = htg_lib::SrtmTile::from_file_with_coords(path, base_lat, base_lon)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(SrtmTile { inner })
+    }
+
+    /// Load a tile from the contents of a .hgt file.
+    ///
+    /// Args:
+    ///     data: Raw .hgt bytes.
+    ///     base_lat: Latitude of the southwest corner.
+    ///     base_lon: Longitude of the southwest corner.
+    ///
+    /// Raises:
+    ///     ValueError: If the data length is not SRTM1/SRTM3.
+    #[staticmethod]
+    fn from_bytes(data: &[u8], base_lat: i32, base_lon: i32) -> PyResult<Self> {
+        let inner = htg_lib::SrtmTile::from_bytes(data, base_lat, base_lon)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(SrtmTile { inner })
+    }
+
+    /// Get elevation at the specified coordinates using nearest-neighbor lookup.
+    ///
+    /// Only the position within the one-degree cell is used, so the
+    /// coordinates are expected to lie within this tile.
+    ///
+    /// Args:
+    ///     lat: Latitude in decimal degrees, within the tile.
+    ///     lon: Longitude in decimal degrees, within the tile.
+    ///
+    /// Returns:
+    ///     Elevation in meters, or None for void data.
+    ///
+    /// Raises:
+    ///     ValueError: If the coordinates are not finite numbers.
+    fn get_elevation(&self, lat: f64, lon: f64) -> PyResult<Option<i16>> {
+        match self.inner.get_elevation(lat, lon) {
+            Ok(htg_lib::VOID_VALUE) => Ok(None),
+            Ok(elevation) => Ok(Some(elevation)),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Resolution of the tile ("srtm1" or "srtm3").
+    fn resolution(&self) -> &'static str {
+        self.inner.resolution().as_str()
+    }
+
+    /// Number of samples per row/column (3601 or 1201).
+    fn samples(&self) -> usize {
+        self.inner.samples()
+    }
+
+    /// Latitude of the southwest corner.
+    fn base_lat(&self) -> i32 {
+        self.inner.base_lat()
+    }
+
+    /// Longitude of the southwest corner.
+    fn base_lon(&self) -> i32 {
+        self.inner.base_lon()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SrtmTile(base_lat={}, base_lon={}, resolution={})",
+            self.inner.base_lat(),
+            self.inner.base_lon(),
+            self.inner.resolution().as_str()
+        )
+    }
+}
+
 /// Convert latitude/longitude to SRTM filename.
 ///
 /// Args:
@@ -371,6 +646,7 @@ fn filename_to_lat_lon(filename: &str) -> Option<(i32, i32)> {
 #[pymodule]
 fn srtm_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<SrtmService>()?;
+    m.add_class::<SrtmTile>()?;
     m.add_class::<CacheStats>()?;
     m.add_class::<PreloadStats>()?;
     m.add_function(wrap_pyfunction!(lat_lon_to_filename, m)?)?;
@@ -0,0 +1,16 @@
+"""Shared fixtures for the srtm_rs tests."""
+
+import array
+import sys
+
+SAMPLES = 1201
+
+
+def write_tile(path, elevation_at):
+    """Write an SRTM3 tile with elevations from elevation_at(row, col)."""
+    data = array.array(
+        "h", (elevation_at(row, col) for row in range(SAMPLES) for col in range(SAMPLES))
+    )
+    if sys.byteorder == "little":
+        data.byteswap()
+    path.write_bytes(data.tobytes())
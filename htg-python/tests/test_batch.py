@@ -0,0 +1,52 @@
+"""Tests for SrtmService.get_elevations_batch."""
+
+import pytest
+
+import srtm_rs
+
+from conftest import SAMPLES, write_tile
+
+
+@pytest.fixture
+def service(tmp_path):
+    # Elevation increases eastwards; one void sample at the tile center
+    def elevation_at(row, col):
+        if (row, col) == (600, 600):
+            return srtm_rs.VOID_VALUE
+        return col
+
+    write_tile(tmp_path / "N35E138.hgt", elevation_at)
+    return srtm_rs.SrtmService(str(tmp_path), cache_size=10)
+
+
+def test_batch_preserves_order(service):
+    coords = [(35.25, 138.0 + (i % (SAMPLES - 1)) / (SAMPLES - 1)) for i in range(10_000)]
+
+    elevations = service.get_elevations_batch(coords)
+
+    assert len(elevations) == len(coords)
+    assert elevations == [i % (SAMPLES - 1) for i in range(10_000)]
+
+
+def test_batch_void_fill(service):
+    coords = [(35.5, 138.5), (35.25, 138.25), (50.5, 10.5)]
+
+    elevations = service.get_elevations_batch(coords, default=srtm_rs.VOID_VALUE)
+    assert elevations == [srtm_rs.VOID_VALUE, 300, srtm_rs.VOID_VALUE]
+
+    elevations = service.get_elevations_batch(coords, default=-1)
+    assert elevations == [-1, 300, -1]
+
+
+def test_batch_interpolated(service):
+    coords = [(35.25, 138.0 + 300.5 / (SAMPLES - 1)), (35.5, 138.5)]
+
+    elevations = service.get_elevations_batch(coords, default=-1, interpolate=True)
+
+    assert elevations[0] == pytest.approx(300.5)
+    assert elevations[1] == -1.0
+
+
+def test_batch_interpolate_rejects_floor(service):
+    with pytest.raises(ValueError):
+        service.get_elevations_batch([(35.5, 138.5)], rounding="floor", interpolate=True)
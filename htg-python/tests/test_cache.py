@@ -0,0 +1,85 @@
+"""Tests for cache control on SrtmService."""
+
+import srtm_rs
+
+from conftest import write_tile
+
+
+def make_service(tmp_path):
+    write_tile(tmp_path / "N35E138.hgt", lambda row, col: 100)
+    return srtm_rs.SrtmService(str(tmp_path), cache_size=10)
+
+
+def test_clear_cache_forces_miss(tmp_path):
+    service = make_service(tmp_path)
+    service.get_elevation(35.5, 138.5)
+    service.get_elevation(35.5, 138.5)
+    before = service.cache_stats()
+
+    service.clear_cache()
+    assert service.get_elevation(35.5, 138.5) == 100
+
+    after = service.cache_stats()
+    assert after.miss_count == before.miss_count + 1
+    assert after.hit_count == before.hit_count
+
+
+def test_invalidate_tile_reloads(tmp_path):
+    service = make_service(tmp_path)
+    assert service.get_elevation(35.5, 138.5) == 100
+
+    write_tile(tmp_path / "N35E138.hgt", lambda row, col: 200)
+    service.invalidate_tile("N35E138.hgt")
+
+    assert service.get_elevation(35.5, 138.5) == 200
+
+
+def test_invalidate_bbox_reloads_covered_tiles(tmp_path):
+    write_tile(tmp_path / "N35E138.hgt", lambda row, col: 100)
+    write_tile(tmp_path / "N35E139.hgt", lambda row, col: 100)
+    service = srtm_rs.SrtmService(str(tmp_path), cache_size=10)
+    service.get_elevation(35.5, 138.5)
+    service.get_elevation(35.5, 139.5)
+
+    service.invalidate_bbox(35.2, 139.2, 35.8, 139.8)
+    service.reset_stats()
+    service.get_elevation(35.5, 138.5)
+    service.get_elevation(35.5, 139.5)
+
+    stats = service.cache_stats()
+    assert stats.hit_count == 1
+    assert stats.miss_count == 1
+
+
+def test_context_manager_clears_cache(tmp_path):
+    with make_service(tmp_path) as service:
+        assert service.get_elevation(35.5, 138.5) == 100
+        misses = service.cache_stats().miss_count
+
+    service.get_elevation(35.5, 138.5)
+    assert service.cache_stats().miss_count == misses + 1
+
+
+def test_cache_stats_resident_bytes(tmp_path):
+    service = make_service(tmp_path)
+    assert service.cache_stats().resident_bytes == 0
+
+    service.get_elevation(35.5, 138.5)
+    assert service.cache_stats().resident_bytes == 1201 * 1201 * 2
+
+    service.clear_cache()
+    assert service.cache_stats().resident_bytes == 0
+
+
+def test_reset_stats(tmp_path):
+    service = make_service(tmp_path)
+    service.get_elevation(35.5, 138.5)
+    service.get_elevation(35.5, 138.5)
+
+    service.reset_stats()
+    stats = service.cache_stats()
+    assert stats.hit_count == 0
+    assert stats.miss_count == 0
+
+    service.get_elevation(35.5, 138.5)
+    assert service.cache_stats().hit_count == 1
@@ -0,0 +1,55 @@
+"""Tests for SrtmService.get_elevations_numpy."""
+
+import math
+
+import pytest
+
+import srtm_rs
+
+np = pytest.importorskip("numpy")
+
+from conftest import SAMPLES, write_tile  # noqa: E402
+
+
+@pytest.fixture
+def service(tmp_path):
+    def elevation_at(row, col):
+        if (row, col) == (600, 600):
+            return srtm_rs.VOID_VALUE
+        return row + col
+
+    write_tile(tmp_path / "N35E138.hgt", elevation_at)
+    return srtm_rs.SrtmService(str(tmp_path), cache_size=10)
+
+
+def test_numpy_matches_get_elevation(service):
+    rng = np.random.default_rng(42)
+    lats = rng.uniform(35.0, 35.999, 1000)
+    lons = rng.uniform(138.0, 138.999, 1000)
+    # Void sample and missing tile
+    lats[:2] = [35.5, 50.5]
+    lons[:2] = [138.5, 10.5]
+
+    result = service.get_elevations_numpy(lats, lons)
+
+    assert result.dtype == np.float64
+    assert result.shape == (1000,)
+    for lat, lon, value in zip(lats, lons, result):
+        expected = service.get_elevation(float(lat), float(lon))
+        if expected is None:
+            assert math.isnan(value)
+        else:
+            assert value == expected
+
+
+def test_numpy_strided_input(service):
+    coords = np.array([[35.25, 138.25], [35.75, 138.75]])
+
+    result = service.get_elevations_numpy(coords[:, 0], coords[:, 1])
+
+    assert list(result) == [(SAMPLES - 1) * 1.0, (SAMPLES - 1) * 1.0]
+
+
+def test_numpy_length_mismatch(service):
+    with pytest.raises(ValueError):
+        service.get_elevations_numpy(np.zeros(3), np.zeros(2))
@@ -0,0 +1,27 @@
+"""Concurrent queries against a shared SrtmService."""
+
+from concurrent.futures import ThreadPoolExecutor
+
+import srtm_rs
+
+from conftest import SAMPLES, write_tile
+
+
+def test_threads_share_service(tmp_path):
+    write_tile(tmp_path / "N35E138.hgt", lambda row, col: row + col)
+    service = srtm_rs.SrtmService(str(tmp_path), cache_size=10)
+    step = 1 / (SAMPLES - 1)
+
+    def query(worker):
+        for i in range(2000):
+            row, col = (worker * 97 + i) % SAMPLES, (worker * 31 + i * 7) % SAMPLES
+            lat, lon = 36.0 - row * step, 138.0 + col * step
+            if row == 0 or col == SAMPLES - 1:
+                continue  # Shared edges resolve to the neighboring (missing) tile
+            assert service.get_elevation(lat, lon) == row + col
+            assert abs(service.get_elevation_interpolated(lat, lon) - (row + col)) < 1e-6
+        return worker
+
+    with ThreadPoolExecutor(max_workers=8) as pool:
+        futures = [pool.submit(query, worker) for worker in range(16)]
+        assert sorted(f.result(timeout=60) for f in futures) == list(range(16))
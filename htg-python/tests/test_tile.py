@@ -0,0 +1,57 @@
+"""Tests for the SrtmTile binding."""
+
+import pytest
+
+import srtm_rs
+
+from conftest import write_tile
+
+
+@pytest.fixture
+def tile_path(tmp_path):
+    def elevation_at(row, col):
+        if (row, col) == (600, 600):
+            return 500
+        if (row, col) == (0, 0):
+            return srtm_rs.VOID_VALUE
+        return 10
+
+    path = tmp_path / "N35E138.hgt"
+    write_tile(path, elevation_at)
+    return path
+
+
+def test_tile_from_file(tile_path):
+    tile = srtm_rs.SrtmTile(str(tile_path))
+
+    assert tile.get_elevation(35.5, 138.5) == 500
+    assert tile.get_elevation(35.9999, 138.0) is None
+    assert tile.resolution() == "srtm3"
+    assert tile.samples() == 1201
+    assert (tile.base_lat(), tile.base_lon()) == (35, 138)
+
+
+def test_tile_from_bytes(tile_path):
+    tile = srtm_rs.SrtmTile.from_bytes(tile_path.read_bytes(), 35, 138)
+
+    assert tile.get_elevation(35.5, 138.5) == 500
+    assert tile.resolution() == "srtm3"
+
+
+def test_tile_invalid_size(tmp_path):
+    path = tmp_path / "N35E138.hgt"
+    path.write_bytes(b"\0" * 1000)
+
+    with pytest.raises(ValueError):
+        srtm_rs.SrtmTile(str(path))
+    with pytest.raises(ValueError):
+        srtm_rs.SrtmTile.from_bytes(b"\0" * 1000, 35, 138)
+
+
+def test_tile_unparseable_name(tile_path):
+    renamed = tile_path.with_name("tile.hgt")
+    tile_path.rename(renamed)
+
+    with pytest.raises(ValueError):
+        srtm_rs.SrtmTile(str(renamed))
+    assert srtm_rs.SrtmTile(str(renamed), 35, 138).get_elevation(35.5, 138.5) == 500
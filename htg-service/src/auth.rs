@@ -0,0 +1,130 @@
+//! Optional API-key authentication.
+//!
+//! When `HTG_API_KEY` is set, every route except `/health` requires an
+//! `X-API-Key` header carrying the same value; other requests get
+//! `401 Unauthorized`. Authenticated responses marked `public` in
+//! `Cache-Control` are downgraded to `private` so shared caches never serve
+//! them to clients without the key. When it is unset the middleware is not
+//! installed.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::handlers::{ErrorCode, ErrorResponse};
+
+/// Environment variable holding the API key.
+pub const API_KEY_ENV: &str = "HTG_API_KEY";
+
+/// Request header that must carry the API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Read the API key from `HTG_API_KEY`, treating an empty value as unset.
+pub fn api_key_from_env() -> Option<Arc<str>> {
+    std::env::var(API_KEY_ENV)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(Arc::from)
+}
+
+/// Middleware rejecting requests whose `X-API-Key` header does not match `key`.
+///
+/// Install with [`axum::middleware::from_fn_with_state`] as a route layer.
+pub async fn require_api_key(
+    State(key): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .map(|value| value.as_bytes());
+
+    match provided {
+        Some(provided) if constant_time_eq(provided, key.as_bytes()) => {
+            let mut response = next.run(request).await;
+            make_private(response.headers_mut());
+            response
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::new(
+                ErrorCode::Unauthorized,
+                "Missing or invalid X-API-Key header",
+            )),
+        )
+            .into_response(),
+    }
+}
+
+/// Replace a `public` `Cache-Control` directive with `private`.
+fn make_private(headers: &mut HeaderMap) {
+    let Some(value) = headers
+        .get(header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return;
+    };
+    let directives: Vec<&str> = value
+        .split(',')
+        .map(|directive| directive.trim())
+        .map(|directive| {
+            if directive.eq_ignore_ascii_case("public") {
+                "private"
+            } else {
+                directive
+            }
+        })
+        .collect();
+    if let Ok(value) = HeaderValue::from_str(&directives.join(", ")) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn test_make_private() {
+        let cache_control = |value: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(value) = value {
+                headers.insert(header::CACHE_CONTROL, value.parse().unwrap());
+            }
+            make_private(&mut headers);
+            headers
+                .get(header::CACHE_CONTROL)
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+
+        assert_eq!(
+            cache_control(Some("public, no-cache")).as_deref(),
+            Some("private, no-cache")
+        );
+        assert_eq!(
+            cache_control(Some("max-age=60")).as_deref(),
+            Some("max-age=60")
+        );
+        assert_eq!(cache_control(None), None);
+    }
+}
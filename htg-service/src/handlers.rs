@@ -7,11 +7,14 @@ use axum::{
     Json,
 };
 use geojson::Geometry;
+use htg::{filename::normalize_lon, Units, VoidPolicy};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{field, Instrument, Span};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::AppState;
+use crate::{metrics, AppState};
 
 /// Query parameters for elevation endpoint.
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
@@ -25,58 +28,308 @@ pub struct ElevationQuery {
     /// Whether to use bilinear interpolation for sub-pixel accuracy.
     /// When true, returns a floating-point elevation value.
     /// Default is false (nearest-neighbor lookup).
+    /// Alias for `method=bilinear`; ignored when `method` is given.
     #[serde(default)]
     #[param(example = false)]
     pub interpolate: bool,
+    /// Interpolation method: `nearest`, `bilinear`, or `bicubic`.
+    /// Default is `nearest` (or `bilinear` when `interpolate=true`).
+    #[serde(default)]
+    #[param(value_type = Option<InterpolationMethod>, example = "bilinear")]
+    pub method: Option<InterpolationMethod>,
+    /// How bilinear interpolation treats void corners: `strict` (default,
+    /// any void corner makes the result void), `ignore_void` (average the
+    /// non-void corners), or `nearest` (fall back to the nearest sample).
+    /// Only applies to `method=bilinear`.
+    #[serde(default)]
+    #[param(value_type = Option<VoidPolicy>, example = "ignore_void")]
+    pub void_policy: Option<VoidPolicy>,
+    /// Unit of the returned elevation: `meters` (default) or `feet`.
+    /// Nearest-neighbor results are rounded to whole feet.
+    #[serde(default)]
+    #[param(value_type = Option<Units>, example = "feet")]
+    pub units: Option<Units>,
+}
+
+/// Elevation lookup method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InterpolationMethod {
+    /// Nearest grid point (integer elevation).
+    Nearest,
+    /// Bilinear interpolation over the 4 surrounding grid points.
+    Bilinear,
+    /// Bicubic (Catmull-Rom) interpolation over the 16 surrounding grid points.
+    Bicubic,
+}
+
+impl ElevationQuery {
+    /// The method to use, honoring `interpolate` when `method` is absent.
+    pub fn effective_method(&self) -> InterpolationMethod {
+        self.method.unwrap_or(if self.interpolate {
+            InterpolationMethod::Bilinear
+        } else {
+            InterpolationMethod::Nearest
+        })
+    }
 }
 
 /// Successful elevation response.
 #[derive(Debug, Serialize, ToSchema)]
-#[schema(example = json!({"elevation": 3776, "lat": 35.3606, "lon": 138.7274}))]
+#[schema(example = json!({"elevation": 3776, "units": "meters", "lat": 35.3606, "lon": 138.7274, "method": "nearest", "resolution": "SRTM1"}))]
 pub struct ElevationResponse {
-    /// Elevation in meters (integer, nearest-neighbor lookup).
+    /// Elevation (integer, nearest-neighbor lookup).
     pub elevation: i16,
+    /// Unit of `elevation`.
+    pub units: Units,
     /// Latitude queried.
     pub lat: f64,
     /// Longitude queried.
     pub lon: f64,
+    /// Method used for the lookup (always `nearest`).
+    pub method: InterpolationMethod,
+    /// Resolution of the tile the value came from ("SRTM1" or "SRTM3").
+    #[schema(example = "SRTM1")]
+    pub resolution: String,
 }
 
 /// Successful interpolated elevation response.
 #[derive(Debug, Serialize, ToSchema)]
-#[schema(example = json!({"elevation": 3776.42, "lat": 35.3606, "lon": 138.7274, "interpolated": true}))]
+#[schema(example = json!({"elevation": 3776.42, "units": "meters", "lat": 35.3606, "lon": 138.7274, "interpolated": true, "method": "bilinear", "resolution": "SRTM1"}))]
 pub struct InterpolatedElevationResponse {
-    /// Elevation in meters (floating-point, bilinear interpolation).
+    /// Elevation (floating-point, interpolated).
     pub elevation: f64,
+    /// Unit of `elevation`.
+    pub units: Units,
     /// Latitude queried.
     pub lat: f64,
     /// Longitude queried.
     pub lon: f64,
     /// Whether interpolation was used.
     pub interpolated: bool,
+    /// Interpolation method used (`bilinear` or `bicubic`).
+    pub method: InterpolationMethod,
+    /// Resolution of the tile the value came from ("SRTM1" or "SRTM3").
+    #[schema(example = "SRTM1")]
+    pub resolution: String,
+}
+
+/// Response for a point inside an available tile whose sample is void.
+///
+/// Missing tiles are reported as `404` instead, so clients can tell "no data
+/// here" from "tile not loaded".
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({"elevation": null, "void": true, "lat": 27.9881, "lon": 86.925, "method": "nearest", "resolution": "SRTM3"}))]
+pub struct VoidElevationResponse {
+    /// Always `null`.
+    #[schema(value_type = Option<f64>)]
+    pub elevation: Option<f64>,
+    /// Always `true`.
+    pub void: bool,
+    /// Latitude queried.
+    pub lat: f64,
+    /// Longitude queried.
+    pub lon: f64,
+    /// Method used for the lookup.
+    pub method: InterpolationMethod,
+    /// Resolution of the tile the point falls in ("SRTM1" or "SRTM3").
+    #[schema(example = "SRTM3")]
+    pub resolution: String,
+}
+
+/// Request body for the batch elevation endpoint.
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({"coordinates": [[35.3606, 138.7274], [27.9881, 86.9250]], "interpolate": false}))]
+pub struct BatchElevationRequest {
+    /// Coordinates as `[lat, lon]` pairs in decimal degrees.
+    pub coordinates: Vec<[f64; 2]>,
+    /// Whether to use bilinear interpolation. Default is false.
+    #[serde(default)]
+    pub interpolate: bool,
+}
+
+/// Batch elevation response.
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({"elevations": [3776.0, null]}))]
+pub struct BatchElevationResponse {
+    /// Elevations in meters, aligned by index with the request coordinates.
+    /// `null` for void data or missing tiles.
+    pub elevations: Vec<Option<f64>>,
+}
+
+/// Machine-readable error category.
+///
+/// Clients should branch on this rather than on the message, which may
+/// change between releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Coordinates outside SRTM coverage.
+    OutOfBounds,
+    /// The tile covering the coordinates is not available.
+    TileNotFound,
+    /// Malformed request, such as an invalid geometry or bounding box.
+    InvalidRequest,
+    /// Missing or invalid API key.
+    Unauthorized,
+    /// Too many requests; retry later.
+    RateLimited,
+    /// Unexpected server-side failure.
+    Internal,
+}
+
+impl From<&htg::SrtmError> for ErrorCode {
+    fn from(e: &htg::SrtmError) -> Self {
+        match e {
+            htg::SrtmError::OutOfBounds { .. } => ErrorCode::OutOfBounds,
+            htg::SrtmError::FileNotFound { .. } | htg::SrtmError::TileNotAvailable { .. } => {
+                ErrorCode::TileNotFound
+            }
+            htg::SrtmError::InvalidCoordinate { .. } => ErrorCode::InvalidRequest,
+            _ => ErrorCode::Internal,
+        }
+    }
 }
 
 /// Error response.
 #[derive(Debug, Serialize, ToSchema)]
-#[schema(example = json!({"error": "Coordinates out of bounds: lat=91, lon=0"}))]
+#[schema(example = json!({
+    "code": "out_of_bounds",
+    "error": "Coordinates out of bounds: lat=91, lon=0"
+}))]
 pub struct ErrorResponse {
-    /// Error message.
+    /// Machine-readable error category.
+    pub code: ErrorCode,
+    /// Human-readable error message.
     pub error: String,
 }
 
+impl ErrorResponse {
+    /// Create an error response.
+    pub fn new(code: ErrorCode, error: impl Into<String>) -> Self {
+        Self {
+            code,
+            error: error.into(),
+        }
+    }
+}
+
+impl From<&htg::SrtmError> for ErrorResponse {
+    fn from(e: &htg::SrtmError) -> Self {
+        Self::new(e.into(), e.to_string())
+    }
+}
+
+/// Request body for the prefetch endpoint.
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({"min_lat": 35.0, "min_lon": 138.0, "max_lat": 37.0, "max_lon": 140.0}))]
+pub struct PrefetchRequest {
+    /// Southern edge in decimal degrees.
+    pub min_lat: f64,
+    /// Western edge in decimal degrees.
+    pub min_lon: f64,
+    /// Northern edge in decimal degrees.
+    pub max_lat: f64,
+    /// Eastern edge in decimal degrees.
+    pub max_lon: f64,
+}
+
+impl PrefetchRequest {
+    /// Number of 1° tiles the box overlaps, or `None` if it is not a valid box.
+    pub fn tile_count(&self) -> Option<u64> {
+        if !valid_bbox(self.min_lat, self.min_lon, self.max_lat, self.max_lon) {
+            return None;
+        }
+
+        let rows = self.max_lat.ceil() - self.min_lat.floor();
+        let cols = self.max_lon.ceil() - self.min_lon.floor();
+        Some((rows * cols) as u64)
+    }
+}
+
+/// Request body for the cache invalidation endpoint.
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({"min_lat": 35.0, "min_lon": 138.0, "max_lat": 36.0, "max_lon": 139.0}))]
+pub struct InvalidateRequest {
+    /// Southern edge in decimal degrees.
+    pub min_lat: f64,
+    /// Western edge in decimal degrees.
+    pub min_lon: f64,
+    /// Northern edge in decimal degrees.
+    pub max_lat: f64,
+    /// Eastern edge in decimal degrees.
+    pub max_lon: f64,
+}
+
+/// Whether the edges form a non-empty box within the valid lat/lon ranges.
+fn valid_bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> bool {
+    [min_lat, min_lon, max_lat, max_lon]
+        .iter()
+        .all(|v| v.is_finite())
+        && (-90.0..=90.0).contains(&min_lat)
+        && (-90.0..=90.0).contains(&max_lat)
+        && (-180.0..=180.0).contains(&min_lon)
+        && (-180.0..=180.0).contains(&max_lon)
+        && min_lat < max_lat
+        && min_lon < max_lon
+}
+
+/// Prefetch response.
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({"tiles_loaded": 3, "tiles_skipped": 1, "tiles_failed": 0}))]
+pub struct PrefetchResponse {
+    /// Tiles newly loaded into the cache.
+    pub tiles_loaded: u64,
+    /// Tiles that were already cached.
+    pub tiles_skipped: u64,
+    /// Tiles that exist locally but failed to load.
+    pub tiles_failed: u64,
+}
+
 /// Health check response.
 #[derive(Debug, Serialize, ToSchema)]
-#[schema(example = json!({"status": "healthy", "version": "0.1.0"}))]
+#[schema(example = json!({"status": "healthy", "version": "0.1.0", "tiles_available": 12, "auto_download": false}))]
 pub struct HealthResponse {
     /// Service status.
     pub status: String,
     /// Service version.
     pub version: String,
+    /// Number of tiles found in the data directory, rescanned at most every
+    /// [`TileScan::INTERVAL`].
+    pub tiles_available: usize,
+    /// Whether missing tiles are downloaded on demand.
+    pub auto_download: bool,
+}
+
+/// Cached count of the tiles in the data directory, so health checks don't
+/// list the directory on every request.
+#[derive(Debug, Default)]
+pub struct TileScan {
+    last: Mutex<Option<(Instant, usize)>>,
+}
+
+impl TileScan {
+    /// How long a count is reused before the directory is scanned again.
+    pub const INTERVAL: Duration = Duration::from_secs(30);
+
+    /// The cached count, rescanning `service`'s data directory if it is
+    /// older than [`Self::INTERVAL`].
+    async fn count(&self, service: &htg::async_service::AsyncSrtmService) -> usize {
+        if let Some((at, count)) = *self.last.lock().unwrap() {
+            if at.elapsed() < Self::INTERVAL {
+                return count;
+            }
+        }
+
+        let count = service.run(|s| s.scan_tile_files().len()).await;
+        *self.last.lock().unwrap() = Some((Instant::now(), count));
+        count
+    }
 }
 
 /// Cache statistics response.
 #[derive(Debug, Serialize, ToSchema)]
-#[schema(example = json!({"cached_tiles": 45, "cache_hits": 1234, "cache_misses": 56, "hit_rate": 0.956}))]
+#[schema(example = json!({"cached_tiles": 45, "cache_hits": 1234, "cache_misses": 56, "hit_rate": 0.956, "resident_bytes": 129_810_600, "eviction_count": 3}))]
 pub struct StatsResponse {
     /// Number of tiles in cache.
     pub cached_tiles: u64,
@@ -86,12 +339,23 @@ pub struct StatsResponse {
     pub cache_misses: u64,
     /// Cache hit rate (0.0 to 1.0).
     pub hit_rate: f64,
+    /// Total size of the cached tiles in bytes.
+    pub resident_bytes: u64,
+    /// Number of tiles evicted to make room for others.
+    pub eviction_count: u64,
 }
 
 /// Get elevation for given coordinates.
 ///
 /// Returns the elevation in meters for the specified latitude and longitude.
 /// Optionally uses bilinear interpolation for sub-pixel accuracy.
+///
+/// A point inside an available tile whose sample (or, when interpolating,
+/// any contributing sample) is void gets `200` with `"void": true` and a
+/// `null` elevation; a missing tile gets `404`.
+///
+/// Each query runs in an `elevation_query` tracing span with `lat`, `lon`,
+/// and `method`, plus the tile's `resolution` and `cache_hit` once it loads.
 #[utoipa::path(
     get,
     path = "/elevation",
@@ -99,6 +363,7 @@ pub struct StatsResponse {
     responses(
         (status = 200, description = "Elevation found", body = ElevationResponse),
         (status = 200, description = "Interpolated elevation found", body = InterpolatedElevationResponse),
+        (status = 200, description = "Point is in an available tile but the data is void", body = VoidElevationResponse),
         (status = 400, description = "Invalid coordinates", body = ErrorResponse),
         (status = 404, description = "Tile not available", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse),
@@ -110,112 +375,164 @@ pub async fn get_elevation(
     State(state): State<Arc<AppState>>,
     Query(query): Query<ElevationQuery>,
 ) -> impl IntoResponse {
-    tracing::debug!(
+    let span = tracing::info_span!(
+        "elevation_query",
         lat = query.lat,
         lon = query.lon,
-        interpolate = query.interpolate,
-        "Elevation query"
+        method = ?query.effective_method(),
+        resolution = field::Empty,
+        cache_hit = field::Empty,
     );
+    elevation_query(state, query).instrument(span).await
+}
 
-    if query.interpolate {
-        // Use bilinear interpolation
-        match state
-            .srtm_service
-            .get_elevation_interpolated(query.lat, query.lon)
-        {
-            Ok(Some(elevation)) => {
-                tracing::info!(
-                    lat = query.lat,
-                    lon = query.lon,
-                    elevation = elevation,
-                    interpolated = true,
-                    "Elevation found"
-                );
-                (
-                    StatusCode::OK,
-                    Json(InterpolatedElevationResponse {
-                        elevation,
-                        lat: query.lat,
-                        lon: query.lon,
-                        interpolated: true,
-                    }),
-                )
-                    .into_response()
-            }
-            Ok(None) => {
-                tracing::warn!(
-                    lat = query.lat,
-                    lon = query.lon,
-                    "No elevation data available"
-                );
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(ErrorResponse {
-                        error: format!(
-                            "No elevation data available for lat={}, lon={}",
-                            query.lat, query.lon
-                        ),
-                    }),
-                )
-                    .into_response()
+/// Body of [`get_elevation`], run inside its span.
+async fn elevation_query(state: Arc<AppState>, query: ElevationQuery) -> axum::response::Response {
+    let method = query.effective_method();
+    tracing::debug!("Elevation query");
+    let start = Instant::now();
+
+    let (lat, lon) = (query.lat, query.lon);
+    let units = query.units.unwrap_or_default();
+    let response = match method {
+        InterpolationMethod::Nearest => {
+            let result = state
+                .srtm_service
+                .run(move |service| service.get_elevation_detailed(lat, lon))
+                .await
+                .map(|lookup| {
+                    record_lookup(lookup.resolution, lookup.cache_hit);
+                    (lookup.elevation, lookup.resolution)
+                });
+            match result {
+                Ok((Some(elevation), resolution)) => {
+                    tracing::info!(
+                        lat = query.lat,
+                        lon = query.lon,
+                        elevation = elevation,
+                        "Elevation found"
+                    );
+                    (
+                        StatusCode::OK,
+                        Json(ElevationResponse {
+                            elevation: units.convert_whole(elevation),
+                            units,
+                            lat: query.lat,
+                            lon: query.lon,
+                            method,
+                            resolution: resolution_label(resolution),
+                        }),
+                    )
+                        .into_response()
+                }
+                Ok((None, resolution)) => void_response(query.lat, query.lon, method, resolution),
+                Err(e) => error_response(query.lat, query.lon, e),
             }
-            Err(e) => error_response(query.lat, query.lon, e),
         }
-    } else {
-        // Use nearest-neighbor lookup
-        match state.srtm_service.get_elevation(query.lat, query.lon) {
-            Ok(Some(elevation)) => {
-                tracing::info!(
-                    lat = query.lat,
-                    lon = query.lon,
-                    elevation = elevation,
-                    "Elevation found"
-                );
-                (
-                    StatusCode::OK,
-                    Json(ElevationResponse {
-                        elevation,
-                        lat: query.lat,
-                        lon: query.lon,
-                    }),
-                )
-                    .into_response()
-            }
-            Ok(None) => {
-                tracing::warn!(
-                    lat = query.lat,
-                    lon = query.lon,
-                    "No elevation data available"
-                );
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(ErrorResponse {
-                        error: format!(
-                            "No elevation data available for lat={}, lon={}",
-                            query.lat, query.lon
-                        ),
-                    }),
-                )
-                    .into_response()
+        InterpolationMethod::Bilinear | InterpolationMethod::Bicubic => {
+            // Resolving the tile first turns a missing tile into an error, so
+            // `None` from the interpolation below always means void data
+            let policy = query.void_policy.unwrap_or_default();
+            let result = state
+                .srtm_service
+                .run(move |service| {
+                    let (tile, cache_hit) = service.tile_at(lat, lon)?;
+                    let elevation = if method == InterpolationMethod::Bicubic {
+                        tile.get_elevation_bicubic(lat, lon)
+                    } else {
+                        tile.get_elevation_interpolated_with_policy(lat, lon, policy)
+                    }?;
+                    Ok((elevation, tile.resolution(), cache_hit))
+                })
+                .await
+                .map(|(elevation, resolution, cache_hit)| {
+                    record_lookup(resolution, cache_hit);
+                    (elevation, resolution)
+                });
+            match result {
+                Ok((Some(elevation), resolution)) => {
+                    tracing::info!(
+                        lat = query.lat,
+                        lon = query.lon,
+                        elevation = elevation,
+                        method = ?method,
+                        "Elevation found"
+                    );
+                    (
+                        StatusCode::OK,
+                        Json(InterpolatedElevationResponse {
+                            elevation: units.convert(elevation),
+                            units,
+                            lat: query.lat,
+                            lon: query.lon,
+                            interpolated: true,
+                            method,
+                            resolution: resolution_label(resolution),
+                        }),
+                    )
+                        .into_response()
+                }
+                Ok((None, resolution)) => void_response(query.lat, query.lon, method, resolution),
+                Err(e) => error_response(query.lat, query.lon, e),
             }
-            Err(e) => error_response(query.lat, query.lon, e),
         }
-    }
+    };
+
+    metrics::record_query("/elevation", response.status(), start);
+    response
+}
+
+/// Record how a query's tile was served on the current span.
+fn record_lookup(resolution: htg::SrtmResolution, cache_hit: bool) {
+    Span::current()
+        .record("resolution", resolution_label(resolution).as_str())
+        .record("cache_hit", cache_hit);
+}
+
+/// Uppercase label for a tile resolution (e.g., "SRTM1").
+fn resolution_label(resolution: htg::SrtmResolution) -> String {
+    resolution.as_str().to_uppercase()
+}
+
+/// Create the response for a point whose tile is available but void there.
+fn void_response(
+    lat: f64,
+    lon: f64,
+    method: InterpolationMethod,
+    resolution: htg::SrtmResolution,
+) -> axum::response::Response {
+    tracing::info!(lat = lat, lon = lon, "Elevation data is void");
+    (
+        StatusCode::OK,
+        Json(VoidElevationResponse {
+            elevation: None,
+            void: true,
+            lat,
+            lon,
+            method,
+            resolution: resolution_label(resolution),
+        }),
+    )
+        .into_response()
 }
 
 /// Create an error response for elevation queries.
 fn error_response(lat: f64, lon: f64, e: htg::SrtmError) -> axum::response::Response {
-    let (status, message) = match &e {
-        htg::SrtmError::OutOfBounds { .. } => (StatusCode::BAD_REQUEST, e.to_string()),
-        htg::SrtmError::FileNotFound { .. } | htg::SrtmError::TileNotAvailable { .. } => {
-            (StatusCode::NOT_FOUND, e.to_string())
-        }
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-    };
-
     tracing::warn!(lat = lat, lon = lon, error = %e, "Elevation query failed");
 
-    (status, Json(ErrorResponse { error: message })).into_response()
+    error_body(&e)
+}
+
+/// Build the JSON error body for `e` with the HTTP status matching its code.
+fn error_body(e: &htg::SrtmError) -> axum::response::Response {
+    let body = ErrorResponse::from(e);
+    let status = match body.code {
+        ErrorCode::OutOfBounds | ErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+        ErrorCode::TileNotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (status, Json(body)).into_response()
 }
 
 /// Batch elevation query using GeoJSON.
@@ -225,6 +542,8 @@ fn error_response(lat: f64, lon: f64, e: htg::SrtmError) -> axum::response::Resp
 ///
 /// Supported geometry types: Point, MultiPoint, LineString, MultiLineString,
 /// Polygon, MultiPolygon, GeometryCollection.
+///
+/// Each query runs in a `geojson_query` tracing span with the `geometry` type.
 #[utoipa::path(
     post,
     path = "/elevation",
@@ -236,6 +555,8 @@ fn error_response(lat: f64, lon: f64, e: htg::SrtmError) -> axum::response::Resp
     responses(
         (status = 200, description = "Geometry with elevations added", content_type = "application/json"),
         (status = 400, description = "Invalid geometry or coordinates", body = ErrorResponse),
+        (status = 404, description = "Tile not available", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
     ),
     tag = "elevation"
 )]
@@ -244,29 +565,118 @@ pub async fn post_elevation(
     State(state): State<Arc<AppState>>,
     Json(geometry): Json<Geometry>,
 ) -> impl IntoResponse {
+    let span = tracing::info_span!("geojson_query", geometry = geometry.value.type_name());
+    geojson_query(state, geometry).instrument(span).await
+}
+
+/// Body of [`post_elevation`], run inside its span.
+async fn geojson_query(state: Arc<AppState>, geometry: Geometry) -> axum::response::Response {
     tracing::debug!(?geometry, "GeoJSON elevation query");
+    let start = Instant::now();
 
-    match htg::geojson::add_elevations_to_geometry(&state.srtm_service, geometry) {
+    let result = state
+        .srtm_service
+        .run(move |service| htg::geojson::add_elevations_to_geometry(service, geometry))
+        .await;
+    let response = match result {
         Ok(result) => {
             tracing::info!("GeoJSON elevation query successful");
             (StatusCode::OK, Json(result)).into_response()
         }
         Err(e) => {
             tracing::warn!(error = %e, "GeoJSON elevation query failed");
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
-            )
-                .into_response()
+            error_body(&e)
         }
+    };
+
+    metrics::record_query("/elevation", response.status(), start);
+    response
+}
+
+/// Batch elevation query for a plain coordinate array.
+///
+/// Looks up every `[lat, lon]` pair, loading each tile once. Results are
+/// aligned by index with the input; void data and missing tiles yield `null`
+/// instead of failing the whole request. Longitudes outside `[-180, 180)` are
+/// wrapped, as for `GET /elevation`.
+///
+/// Each query runs in a `batch_query` tracing span with the point `count` and
+/// `interpolate`, plus the number of `missing` (null) results once it completes.
+#[utoipa::path(
+    post,
+    path = "/elevations",
+    request_body = BatchElevationRequest,
+    responses(
+        (status = 200, description = "Elevations aligned with the input coordinates", body = BatchElevationResponse),
+        (status = 400, description = "Invalid coordinates", body = ErrorResponse),
+    ),
+    tag = "elevation"
+)]
+#[axum::debug_handler]
+pub async fn post_elevations(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BatchElevationRequest>,
+) -> impl IntoResponse {
+    let span = tracing::info_span!(
+        "batch_query",
+        count = request.coordinates.len(),
+        interpolate = request.interpolate,
+        missing = field::Empty,
+    );
+    batch_query(state, request).instrument(span).await
+}
+
+/// Body of [`post_elevations`], run inside its span.
+async fn batch_query(
+    state: Arc<AppState>,
+    request: BatchElevationRequest,
+) -> axum::response::Response {
+    tracing::debug!("Batch elevation query");
+    let start = Instant::now();
+
+    let (min_lat, max_lat) = state.srtm_service.inner().lat_bounds();
+    if let Some(&[lat, lon]) = request.coordinates.iter().find(|[lat, lon]| {
+        !(min_lat..=max_lat).contains(lat) || !(-180.0..180.0).contains(&normalize_lon(*lon))
+    }) {
+        let response = error_response(lat, lon, htg::SrtmError::OutOfBounds { lat, lon });
+        metrics::record_query("/elevations", response.status(), start);
+        return response;
     }
+
+    let coords: Vec<(f64, f64)> = request
+        .coordinates
+        .iter()
+        .map(|&[lat, lon]| (lat, normalize_lon(lon)))
+        .collect();
+
+    let count = coords.len();
+    let elevations = if request.interpolate {
+        state
+            .srtm_service
+            .get_elevations_batch_interpolated_opt(coords)
+            .await
+    } else {
+        state
+            .srtm_service
+            .get_elevations_batch(coords, htg::VOID_VALUE)
+            .await
+            .into_iter()
+            .map(|e| (e != htg::VOID_VALUE).then_some(f64::from(e)))
+            .collect()
+    };
+
+    let missing = elevations.iter().filter(|e| e.is_none()).count();
+    Span::current().record("missing", missing);
+    tracing::info!(count, "Batch elevation query successful");
+    metrics::record_query("/elevations", StatusCode::OK, start);
+    (StatusCode::OK, Json(BatchElevationResponse { elevations })).into_response()
 }
 
 /// Health check endpoint.
 ///
-/// Returns service status and version.
+/// Returns service status and version, how many tiles the data directory
+/// holds, and whether auto-download is enabled. The endpoint is open even
+/// with an API key, so it does not reveal the data directory path.
 #[utoipa::path(
     get,
     path = "/health",
@@ -275,10 +685,13 @@ pub async fn post_elevation(
     ),
     tag = "system"
 )]
-pub async fn health_check() -> Json<HealthResponse> {
+pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+    let service = state.srtm_service.inner();
     Json(HealthResponse {
         status: "healthy".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
+        tiles_available: state.tile_scan.count(&state.srtm_service).await,
+        auto_download: service.has_auto_download(),
     })
 }
 
@@ -294,20 +707,166 @@ pub async fn health_check() -> Json<HealthResponse> {
     tag = "system"
 )]
 pub async fn get_stats(State(state): State<Arc<AppState>>) -> Json<StatsResponse> {
-    let stats = state.srtm_service.cache_stats();
+    let stats = state.srtm_service.inner().cache_stats();
 
     Json(StatsResponse {
         cached_tiles: stats.entry_count,
         cache_hits: stats.hit_count,
         cache_misses: stats.miss_count,
         hit_rate: stats.hit_rate(),
+        resident_bytes: stats.resident_bytes,
+        eviction_count: stats.eviction_count,
     })
 }
 
+/// Reset cache statistics.
+///
+/// Zeroes the hit, miss, and eviction counters so the hit rate can be
+/// measured over a window. Cached tiles are kept.
+#[utoipa::path(
+    post,
+    path = "/stats/reset",
+    responses(
+        (status = 204, description = "Statistics reset"),
+    ),
+    tag = "system"
+)]
+pub async fn reset_stats(State(state): State<Arc<AppState>>) -> StatusCode {
+    state.srtm_service.inner().reset_stats();
+    StatusCode::NO_CONTENT
+}
+
+/// Warm the tile cache for a bounding box.
+///
+/// Loads every local tile overlapping the box into the cache, so that a web
+/// map can preload a region before the user pans there. Boxes touching more
+/// than the configured number of 1° tiles (`HTG_PREFETCH_MAX_TILES`,
+/// default 64) are rejected.
+#[utoipa::path(
+    post,
+    path = "/prefetch",
+    request_body = PrefetchRequest,
+    responses(
+        (status = 200, description = "Tiles preloaded", body = PrefetchResponse),
+        (status = 400, description = "Invalid or too large bounding box", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn prefetch(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<PrefetchRequest>,
+) -> impl IntoResponse {
+    let error = match request.tile_count() {
+        None => Some(format!(
+            "Invalid bounding box: min_lat={}, min_lon={}, max_lat={}, max_lon={}",
+            request.min_lat, request.min_lon, request.max_lat, request.max_lon
+        )),
+        Some(count) if count > state.prefetch_max_tiles => Some(format!(
+            "Bounding box covers {} tiles, the limit is {}",
+            count, state.prefetch_max_tiles
+        )),
+        Some(_) => None,
+    };
+    if let Some(error) = error {
+        tracing::warn!(error = %error, "Prefetch rejected");
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(ErrorCode::InvalidRequest, error)),
+        )
+            .into_response();
+    }
+
+    let bbox = htg::BoundingBox::new(
+        request.min_lat,
+        request.min_lon,
+        request.max_lat,
+        request.max_lon,
+    );
+    let stats = state.srtm_service.preload(Some(vec![bbox])).await;
+    tracing::info!(
+        tiles_loaded = stats.tiles_loaded,
+        tiles_already_cached = stats.tiles_already_cached,
+        tiles_failed = stats.tiles_failed,
+        "Prefetch complete"
+    );
+
+    (
+        StatusCode::OK,
+        Json(PrefetchResponse {
+            tiles_loaded: stats.tiles_loaded,
+            tiles_skipped: stats.tiles_already_cached,
+            tiles_failed: stats.tiles_failed,
+        }),
+    )
+        .into_response()
+}
+
+/// Evict the tiles overlapping a bounding box from the cache.
+///
+/// Use this after replacing a region's tiles on disk (e.g. with void-filled
+/// versions) so they are reloaded on their next query, without clearing the
+/// rest of the cache.
+#[utoipa::path(
+    post,
+    path = "/cache/invalidate",
+    request_body = InvalidateRequest,
+    responses(
+        (status = 204, description = "Tiles invalidated"),
+        (status = 400, description = "Invalid bounding box", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn invalidate_cache(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<InvalidateRequest>,
+) -> impl IntoResponse {
+    let InvalidateRequest {
+        min_lat,
+        min_lon,
+        max_lat,
+        max_lon,
+    } = request;
+    if !valid_bbox(min_lat, min_lon, max_lat, max_lon) {
+        let error = format!(
+            "Invalid bounding box: min_lat={}, min_lon={}, max_lat={}, max_lon={}",
+            min_lat, min_lon, max_lat, max_lon
+        );
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(ErrorCode::InvalidRequest, error)),
+        )
+            .into_response();
+    }
+
+    state
+        .srtm_service
+        .inner()
+        .invalidate_bbox(min_lat, min_lon, max_lat, max_lon);
+    tracing::info!(min_lat, min_lon, max_lat, max_lon, "Cache invalidated");
+    StatusCode::NO_CONTENT.into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prefetch_tile_count() {
+        let request = |min_lat, min_lon, max_lat, max_lon| PrefetchRequest {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+        };
+
+        assert_eq!(request(35.0, 138.0, 37.0, 140.0).tile_count(), Some(4));
+        assert_eq!(request(35.5, 138.5, 35.6, 138.6).tile_count(), Some(1));
+        assert_eq!(request(-0.5, -0.5, 0.5, 0.5).tile_count(), Some(4));
+        assert_eq!(request(36.0, 138.0, 35.0, 139.0).tile_count(), None);
+        assert_eq!(request(35.0, 138.0, f64::NAN, 139.0).tile_count(), None);
+        assert_eq!(request(35.0, 138.0, 95.0, 139.0).tile_count(), None);
+    }
+
     #[test]
     fn test_elevation_query_deserialize() {
         let json = r#"{"lat": 35.5, "lon": 138.7}"#;
@@ -320,12 +879,44 @@ mod tests {
     fn test_elevation_response_serialize() {
         let response = ElevationResponse {
             elevation: 1234,
+            units: Units::Meters,
             lat: 35.5,
             lon: 138.7,
+            method: InterpolationMethod::Nearest,
+            resolution: "SRTM3".to_string(),
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("1234"));
         assert!(json.contains("35.5"));
+        assert!(json.contains(r#""method":"nearest""#));
+        assert!(json.contains(r#""units":"meters""#));
+    }
+
+    #[test]
+    fn test_effective_method() {
+        let query: ElevationQuery = serde_json::from_str(r#"{"lat": 1.0, "lon": 2.0}"#).unwrap();
+        assert_eq!(query.effective_method(), InterpolationMethod::Nearest);
+
+        let query: ElevationQuery =
+            serde_json::from_str(r#"{"lat": 1.0, "lon": 2.0, "interpolate": true}"#).unwrap();
+        assert_eq!(query.effective_method(), InterpolationMethod::Bilinear);
+
+        let query: ElevationQuery = serde_json::from_str(
+            r#"{"lat": 1.0, "lon": 2.0, "interpolate": true, "method": "bicubic"}"#,
+        )
+        .unwrap();
+        assert_eq!(query.effective_method(), InterpolationMethod::Bicubic);
+    }
+
+    #[test]
+    fn test_void_policy_deserialize() {
+        let query: ElevationQuery = serde_json::from_str(r#"{"lat": 1.0, "lon": 2.0}"#).unwrap();
+        assert_eq!(query.void_policy, None);
+
+        let query: ElevationQuery =
+            serde_json::from_str(r#"{"lat": 1.0, "lon": 2.0, "void_policy": "ignore_void"}"#)
+                .unwrap();
+        assert_eq!(query.void_policy, Some(VoidPolicy::IgnoreVoid));
     }
 
     #[test]
@@ -333,6 +924,8 @@ mod tests {
         let response = HealthResponse {
             status: "healthy".to_string(),
             version: "0.1.0".to_string(),
+            tiles_available: 2,
+            auto_download: false,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("healthy"));
@@ -3,18 +3,117 @@
 //! HTTP handlers and types for the SRTM elevation service.
 //! This library is used by both the htg-service binary and integration tests.
 
+pub mod auth;
 pub mod handlers;
+pub mod metrics;
+pub mod openapi;
+pub mod rate_limit;
+#[cfg(feature = "raster")]
+pub mod terrain;
 
-use htg::SrtmService;
+use htg::async_service::AsyncSrtmService;
+
+/// Default for [`AppState::prefetch_max_tiles`].
+pub const DEFAULT_PREFETCH_MAX_TILES: u64 = 64;
+
+/// Default for [`AppState::terrain_max_tiles`].
+pub const DEFAULT_TERRAIN_MAX_TILES: u64 = 64;
 
 /// Application state shared across handlers.
+///
+/// Create with [`AppState::new`] and adjust with the `with_*` methods; the
+/// struct is non-exhaustive so new settings don't break callers.
+#[non_exhaustive]
 pub struct AppState {
-    /// SRTM service for elevation queries.
-    pub srtm_service: SrtmService,
+    /// SRTM service for elevation queries; tile loads and downloads run on
+    /// the blocking thread pool.
+    pub srtm_service: AsyncSrtmService,
+    /// Largest number of 1° tiles a `POST /prefetch` box may touch.
+    pub prefetch_max_tiles: u64,
+    /// Largest number of 1° tiles a `GET /terrain` tile may cover; lower
+    /// zooms would load (or download) too many tiles per request.
+    pub terrain_max_tiles: u64,
+    /// Count of the tiles on disk reported by `GET /health`.
+    pub tile_scan: handlers::TileScan,
+}
+
+impl AppState {
+    /// State serving `srtm_service` with default settings.
+    pub fn new(srtm_service: AsyncSrtmService) -> Self {
+        Self {
+            srtm_service,
+            prefetch_max_tiles: DEFAULT_PREFETCH_MAX_TILES,
+            terrain_max_tiles: DEFAULT_TERRAIN_MAX_TILES,
+            tile_scan: Default::default(),
+        }
+    }
+
+    /// Set the largest number of tiles a `POST /prefetch` box may touch.
+    pub fn with_prefetch_max_tiles(mut self, max_tiles: u64) -> Self {
+        self.prefetch_max_tiles = max_tiles;
+        self
+    }
+
+    /// Set the largest number of tiles a `GET /terrain` tile may cover.
+    pub fn with_terrain_max_tiles(mut self, max_tiles: u64) -> Self {
+        self.terrain_max_tiles = max_tiles;
+        self
+    }
+}
+
+/// Serve `app` on `listener` until `shutdown` resolves, then stop accepting
+/// connections and wait for in-flight requests to finish.
+///
+/// The binary passes [`shutdown_signal`]; tests can pass any future, such as
+/// a channel receiver.
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    app: axum::Router,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown.await;
+            tracing::info!("Shutdown signal received, draining in-flight requests");
+        })
+        .await
+}
+
+/// Resolve when the process is asked to stop: SIGINT (Ctrl+C), or SIGTERM
+/// on Unix. Pass it to [`serve`] so in-flight requests finish while new
+/// connections are refused.
+///
+/// The signal handlers are installed when this is called rather than when
+/// the future is first polled, so a signal in between is not lost.
+///
+/// # Panics
+///
+/// Must be called from within a tokio runtime.
+pub fn shutdown_signal() -> impl std::future::Future<Output = ()> {
+    #[cfg(unix)]
+    let mut signals = {
+        use tokio::signal::unix::{signal, SignalKind};
+        [SignalKind::interrupt(), SignalKind::terminate()]
+            .map(|kind| signal(kind).expect("failed to install signal handler"))
+    };
+
+    async move {
+        #[cfg(unix)]
+        {
+            let [interrupt, terminate] = &mut signals;
+            tokio::select! {
+                _ = interrupt.recv() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
 
 // Re-export commonly used types for convenience
 pub use handlers::{
-    ElevationQuery, ElevationResponse, ErrorResponse, HealthResponse,
-    InterpolatedElevationResponse, StatsResponse,
+    BatchElevationRequest, BatchElevationResponse, ElevationQuery, ElevationResponse, ErrorCode,
+    ErrorResponse, HealthResponse, InterpolatedElevationResponse, InterpolationMethod,
+    PrefetchRequest, PrefetchResponse, StatsResponse, TileScan, VoidElevationResponse,
 };
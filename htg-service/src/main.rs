@@ -12,62 +12,48 @@
 //! | `HTG_DOWNLOAD_SOURCE` | Named source: "ardupilot", "ardupilot-srtm1", "ardupilot-srtm3" | None |
 //! | `HTG_DOWNLOAD_URL` | URL template for auto-download | None |
 //! | `HTG_DOWNLOAD_GZIP` | Whether downloads are gzipped | false |
+//! | `HTG_WATCH` | Watch `HTG_DATA_DIR` and pick up added, replaced, or removed tiles (htg `watch` feature) | false |
+//! | `HTG_API_KEY` | Require this value in the `X-API-Key` header (except `/health`) | None |
+//! | `HTG_PREFETCH_MAX_TILES` | Most 1° tiles a `POST /prefetch` box may touch | 64 |
+//! | `HTG_TERRAIN_MAX_TILES` | Most 1° tiles a `GET /terrain` tile may cover | 64 |
+//! | `HTG_RATE_LIMIT` | Requests per second for the whole process, 0 = unlimited (not `/health`, `/metrics`) | 0 |
 //! | `RUST_LOG` | Log level (e.g., "info", "debug") | "info" |
 //!
 //! ## Endpoints
 //!
 //! - `GET /elevation?lat=X&lon=Y` - Get elevation at coordinates
 //! - `POST /elevation` - Batch elevation query with GeoJSON geometry
+//! - `POST /elevations` - Batch elevation query with a coordinate array
+//! - `GET /terrain/{z}/{x}/{y}.png` - Terrarium terrain-RGB tiles (`raster` feature)
 //! - `GET /health` - Health check
 //! - `GET /stats` - Cache statistics
+//! - `GET /metrics` - Prometheus metrics (`metrics` feature)
+//! - `POST /stats/reset` - Reset cache hit/miss/eviction counters
+//! - `POST /prefetch` - Load the tiles in a bounding box into the cache
+//! - `POST /cache/invalidate` - Evict the cached tiles in a bounding box so they are reloaded
 //! - `GET /docs` - OpenAPI documentation (Swagger UI)
+//! - `GET /api-docs/openapi.json` - OpenAPI specification
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::{routing::get, Router};
-use htg::{BoundingBox, SrtmServiceBuilder};
-use htg_service::{handlers, AppState};
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use htg::{async_service::AsyncSrtmService, BoundingBox, SrtmServiceBuilder};
+use htg_service::{
+    auth, handlers, openapi, rate_limit::RateLimiter, AppState, DEFAULT_PREFETCH_MAX_TILES,
+    DEFAULT_TERRAIN_MAX_TILES,
+};
 use tower_http::{
+    compression::CompressionLayer,
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
-
-/// OpenAPI documentation for the HTG service.
-#[derive(OpenApi)]
-#[openapi(
-    info(
-        title = "HTG Elevation Service",
-        version = "0.1.0",
-        description = "High-performance REST API for querying elevation data from SRTM files.",
-        license(name = "MIT", url = "https://opensource.org/licenses/MIT"),
-        contact(name = "Pedro Sanz Martinez", url = "https://github.com/pedrosanzmtz/htg")
-    ),
-    paths(
-        handlers::get_elevation,
-        handlers::post_elevation,
-        handlers::health_check,
-        handlers::get_stats,
-    ),
-    components(
-        schemas(
-            handlers::ElevationQuery,
-            handlers::ElevationResponse,
-            handlers::InterpolatedElevationResponse,
-            handlers::ErrorResponse,
-            handlers::HealthResponse,
-            handlers::StatsResponse,
-        )
-    ),
-    tags(
-        (name = "elevation", description = "Elevation query endpoints"),
-        (name = "system", description = "System and health endpoints")
-    )
-)]
-struct ApiDoc;
+use utoipa_swagger_ui::{Config, SwaggerUi};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -89,6 +75,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Build SRTM service from environment variables using the library
     // The library handles: HTG_DATA_DIR, HTG_CACHE_SIZE, HTG_DOWNLOAD_SOURCE,
     // HTG_DOWNLOAD_URL, HTG_DOWNLOAD_GZIP
+    let api_key = auth::api_key_from_env();
+    let rate_limiter = RateLimiter::from_env();
+
     let srtm_service = match SrtmServiceBuilder::from_env() {
         Ok(builder) => builder.build()?,
         Err(_) => {
@@ -102,10 +91,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         data_dir = %srtm_service.data_dir().display(),
         cache_capacity = srtm_service.cache_capacity(),
         auto_download = srtm_service.has_auto_download(),
+        api_key = api_key.is_some(),
+        rate_limit = rate_limiter.as_ref().map(|l| l.requests_per_second()),
         port = port,
         "Starting HTG service"
     );
 
+    // Handle HTG_WATCH environment variable; the guard must outlive the server
+    let _watcher = match std::env::var("HTG_WATCH").as_deref() {
+        Ok("true") | Ok("1") => {
+            tracing::info!("Watching data directory for tile changes");
+            Some(srtm_service.watch_data_dir()?)
+        }
+        _ => None,
+    };
+
     // Handle HTG_PRELOAD environment variable
     if let Ok(preload_val) = std::env::var("HTG_PRELOAD") {
         let bounds = parse_preload_bounds(&preload_val);
@@ -125,17 +125,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
-    let state = Arc::new(AppState { srtm_service });
+    let prefetch_max_tiles = std::env::var("HTG_PREFETCH_MAX_TILES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_PREFETCH_MAX_TILES);
+    let terrain_max_tiles = std::env::var("HTG_TERRAIN_MAX_TILES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_TERRAIN_MAX_TILES);
+
+    let state = Arc::new(
+        AppState::new(AsyncSrtmService::new(srtm_service))
+            .with_prefetch_max_tiles(prefetch_max_tiles)
+            .with_terrain_max_tiles(terrain_max_tiles),
+    );
 
     // Build router
     let app = Router::new()
-        .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .route(
             "/elevation",
             get(handlers::get_elevation).post(handlers::post_elevation),
         )
-        .route("/health", get(handlers::health_check))
+        .route("/elevations", post(handlers::post_elevations))
         .route("/stats", get(handlers::get_stats))
+        .route("/stats/reset", post(handlers::reset_stats))
+        .route("/prefetch", post(handlers::prefetch))
+        .route("/cache/invalidate", post(handlers::invalidate_cache));
+
+    #[cfg(feature = "raster")]
+    let app = app.route(
+        "/terrain/:z/:x/:y",
+        get(htg_service::terrain::get_terrain_tile),
+    );
+
+    // Route layers only wrap routes registered before them: the rate limit
+    // covers the query routes above, the key check everything but /health
+    let app = match rate_limiter {
+        Some(limiter) => app.route_layer(middleware::from_fn_with_state(
+            Arc::new(limiter),
+            htg_service::rate_limit::rate_limit,
+        )),
+        None => app,
+    };
+    // The spec has its own route; the Swagger UI only points at it
+    let app = app
+        .route(openapi::OPENAPI_PATH, get(openapi::get_openapi))
+        .merge(SwaggerUi::new("/docs").config(Config::from(openapi::OPENAPI_PATH)));
+
+    #[cfg(feature = "metrics")]
+    let app = app.route("/metrics", get(htg_service::metrics::get_metrics));
+
+    let app = match api_key {
+        Some(key) => app.route_layer(middleware::from_fn_with_state(key, auth::require_api_key)),
+        None => app,
+    };
+    let app = app.route("/health", get(handlers::health_check));
+
+    // Layers wrap bottom-up: CORS is outermost, tracing sees every request,
+    // and compression only encodes the final handler response.
+    let app = app
+        .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
         .layer(
             CorsLayer::new()
@@ -151,7 +200,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tracing::info!("Listening on http://{}", addr);
 
-    axum::serve(listener, app).await?;
+    htg_service::serve(listener, app, htg_service::shutdown_signal()).await?;
+    tracing::info!("Server stopped");
 
     Ok(())
 }
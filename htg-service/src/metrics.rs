@@ -0,0 +1,151 @@
+//! Prometheus metrics for the elevation service.
+//!
+//! With the `metrics` feature enabled, handlers record query counts,
+//! per-status responses, and latency into a process-wide registry that is
+//! exposed in Prometheus text format at `/metrics`. Without the feature,
+//! recording is a no-op.
+
+use axum::http::StatusCode;
+use std::time::Instant;
+
+#[cfg(feature = "metrics")]
+use {
+    crate::AppState,
+    axum::{extract::State, http::header, response::IntoResponse},
+    prometheus::{
+        Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+        TextEncoder,
+    },
+    std::sync::{Arc, OnceLock},
+};
+
+/// Metric handles registered in the service registry.
+#[cfg(feature = "metrics")]
+struct Metrics {
+    registry: Registry,
+    queries: IntCounter,
+    responses: IntCounterVec,
+    latency: HistogramVec,
+    cache_hits: IntGauge,
+    cache_misses: IntGauge,
+    cached_tiles: IntGauge,
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let queries = IntCounter::new("htg_queries_total", "Total elevation queries").unwrap();
+        let responses = IntCounterVec::new(
+            Opts::new(
+                "htg_responses_total",
+                "Elevation responses by endpoint and status",
+            ),
+            &["endpoint", "status"],
+        )
+        .unwrap();
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "htg_query_duration_seconds",
+                "Elevation query latency in seconds",
+            )
+            .buckets(vec![
+                0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+            ]),
+            &["endpoint"],
+        )
+        .unwrap();
+        let cache_hits = IntGauge::new("htg_cache_hits", "Tile cache hits").unwrap();
+        let cache_misses = IntGauge::new("htg_cache_misses", "Tile cache misses").unwrap();
+        let cached_tiles = IntGauge::new("htg_cached_tiles", "Tiles currently cached").unwrap();
+
+        registry.register(Box::new(queries.clone())).unwrap();
+        registry.register(Box::new(responses.clone())).unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+        registry.register(Box::new(cache_hits.clone())).unwrap();
+        registry.register(Box::new(cache_misses.clone())).unwrap();
+        registry.register(Box::new(cached_tiles.clone())).unwrap();
+
+        Self {
+            registry,
+            queries,
+            responses,
+            latency,
+            cache_hits,
+            cache_misses,
+            cached_tiles,
+        }
+    }
+
+    fn get() -> &'static Metrics {
+        static METRICS: OnceLock<Metrics> = OnceLock::new();
+        METRICS.get_or_init(Metrics::new)
+    }
+}
+
+/// Record a completed elevation query.
+///
+/// # Arguments
+///
+/// * `endpoint` - Route label (e.g., "/elevation")
+/// * `status` - Response status code
+/// * `start` - When the handler started processing
+#[cfg(feature = "metrics")]
+pub fn record_query(endpoint: &str, status: StatusCode, start: Instant) {
+    let metrics = Metrics::get();
+    metrics.queries.inc();
+    metrics
+        .responses
+        .with_label_values(&[endpoint, status.as_str()])
+        .inc();
+    metrics
+        .latency
+        .with_label_values(&[endpoint])
+        .observe(start.elapsed().as_secs_f64());
+}
+
+/// Record a completed elevation query (no-op without the `metrics` feature).
+#[cfg(not(feature = "metrics"))]
+#[inline]
+pub fn record_query(_endpoint: &str, _status: StatusCode, _start: Instant) {}
+
+/// OpenAPI documentation for the metrics endpoint, merged into the service docs.
+#[cfg(feature = "metrics")]
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(get_metrics))]
+pub struct MetricsApi;
+
+/// Prometheus metrics endpoint.
+///
+/// Returns query counters, latency histograms, and tile cache statistics in
+/// Prometheus text exposition format.
+#[cfg(feature = "metrics")]
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in Prometheus text format", content_type = "text/plain"),
+    ),
+    tag = "system"
+)]
+pub async fn get_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let metrics = Metrics::get();
+
+    let stats = state.srtm_service.inner().cache_stats();
+    metrics.cache_hits.set(stats.hit_count as i64);
+    metrics.cache_misses.set(stats.miss_count as i64);
+    metrics.cached_tiles.set(stats.entry_count as i64);
+
+    let mut buffer = Vec::new();
+    let encoder = TextEncoder::new();
+    match encoder.encode(&metrics.registry.gather(), &mut buffer) {
+        Ok(()) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, encoder.format_type().to_string())],
+            buffer,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
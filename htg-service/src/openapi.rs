@@ -0,0 +1,80 @@
+//! OpenAPI specification.
+//!
+//! The spec is served as JSON at [`OPENAPI_PATH`] by [`get_openapi`], a
+//! plain route that works without the Swagger UI at `/docs` (which reads
+//! the spec from the same path). The `info.version` tracks the crate
+//! version so generated client SDKs can be matched to a release.
+
+use std::sync::OnceLock;
+
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::handlers;
+
+/// Path the OpenAPI JSON is served at.
+pub const OPENAPI_PATH: &str = "/api-docs/openapi.json";
+
+/// OpenAPI documentation for the HTG service.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "HTG Elevation Service",
+        version = env!("CARGO_PKG_VERSION"),
+        description = "High-performance REST API for querying elevation data from SRTM files.",
+        license(name = "MIT", url = "https://opensource.org/licenses/MIT"),
+        contact(name = "Pedro Sanz Martinez", url = "https://github.com/pedrosanzmtz/htg")
+    ),
+    paths(
+        handlers::get_elevation,
+        handlers::post_elevation,
+        handlers::post_elevations,
+        handlers::health_check,
+        handlers::get_stats,
+        handlers::reset_stats,
+        handlers::prefetch,
+        handlers::invalidate_cache,
+    ),
+    components(
+        schemas(
+            handlers::ElevationQuery,
+            handlers::ElevationResponse,
+            handlers::InterpolatedElevationResponse,
+            handlers::VoidElevationResponse,
+            handlers::InterpolationMethod,
+            htg::VoidPolicy,
+            htg::Units,
+            handlers::BatchElevationRequest,
+            handlers::BatchElevationResponse,
+            handlers::ErrorCode,
+            handlers::ErrorResponse,
+            handlers::HealthResponse,
+            handlers::StatsResponse,
+            handlers::PrefetchRequest,
+            handlers::PrefetchResponse,
+            handlers::InvalidateRequest,
+        )
+    ),
+    tags(
+        (name = "elevation", description = "Elevation query endpoints"),
+        (name = "system", description = "System and health endpoints")
+    )
+)]
+pub struct ApiDoc;
+
+/// The full spec, including the endpoints of enabled optional features.
+pub fn api_doc() -> utoipa::openapi::OpenApi {
+    #[allow(unused_mut)]
+    let mut doc = ApiDoc::openapi();
+    #[cfg(feature = "metrics")]
+    doc.merge(crate::metrics::MetricsApi::openapi());
+    #[cfg(feature = "raster")]
+    doc.merge(crate::terrain::TerrainApi::openapi());
+    doc
+}
+
+/// OpenAPI specification as JSON.
+pub async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    static DOC: OnceLock<utoipa::openapi::OpenApi> = OnceLock::new();
+    Json(DOC.get_or_init(api_doc).clone())
+}
@@ -0,0 +1,145 @@
+//! Global request rate limiting.
+//!
+//! `HTG_RATE_LIMIT` caps the requests per second the service accepts on the
+//! elevation, terrain, and stats routes (`/health` and `/metrics` are exempt).
+//! The limit is a single token bucket shared by the whole process, not per
+//! client: it protects auto-download bandwidth and the tile cache, and a
+//! burst of up to one second's worth of requests is allowed. Requests over
+//! the limit get `429 Too Many Requests`. `0` or unset means unlimited.
+//!
+//! `tower::limit::RateLimitLayer` is not used: it applies backpressure,
+//! holding excess requests until the next window instead of rejecting them,
+//! and its service is not `Clone`, so axum needs a `Buffer` in front that
+//! queues requests without bound on the rate.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::handlers::{ErrorCode, ErrorResponse};
+
+/// Environment variable holding the limit in requests per second.
+pub const RATE_LIMIT_ENV: &str = "HTG_RATE_LIMIT";
+
+/// A token bucket refilled at a fixed rate.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second, also the bucket capacity.
+    rate: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `requests_per_second`, starting with a full
+    /// bucket. Returns `None` for `0` (unlimited).
+    pub fn new(requests_per_second: u32) -> Option<Self> {
+        if requests_per_second == 0 {
+            return None;
+        }
+        let rate = f64::from(requests_per_second);
+        Some(Self {
+            rate,
+            bucket: Mutex::new(Bucket {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// Build a limiter from `HTG_RATE_LIMIT`; `None` when unset, `0`, or
+    /// not a number.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(RATE_LIMIT_ENV).ok()?;
+        match value.trim().parse() {
+            Ok(rate) => Self::new(rate),
+            Err(_) => {
+                tracing::warn!(value = %value, "Invalid {}, rate limiting disabled", RATE_LIMIT_ENV);
+                None
+            }
+        }
+    }
+
+    /// Requests per second allowed by this limiter.
+    pub fn requests_per_second(&self) -> f64 {
+        self.rate
+    }
+
+    /// Take a token if one is available.
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.rate);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Middleware answering `429` once `limiter` runs out of tokens.
+///
+/// Install with [`axum::middleware::from_fn_with_state`] as a route layer.
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limiter.try_acquire() {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, "1")],
+        Json(ErrorResponse::new(
+            ErrorCode::RateLimited,
+            format!(
+                "Rate limit exceeded ({} requests per second)",
+                limiter.requests_per_second()
+            ),
+        )),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_zero_is_unlimited() {
+        assert!(RateLimiter::new(0).is_none());
+    }
+
+    #[test]
+    fn test_bucket_empties_and_refills() {
+        let limiter = RateLimiter::new(20).unwrap();
+
+        let granted = (0..40).filter(|_| limiter.try_acquire()).count();
+        assert!((20..25).contains(&granted), "granted {}", granted);
+        assert!(!limiter.try_acquire());
+
+        // 20 requests per second refills a token every 50ms
+        std::thread::sleep(Duration::from_millis(120));
+        assert!(limiter.try_acquire());
+    }
+}
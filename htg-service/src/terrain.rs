@@ -0,0 +1,284 @@
+//! Terrain-RGB raster tiles for web maps.
+//!
+//! Serves 256×256 PNG tiles in the Web Mercator XYZ scheme with elevations
+//! encoded in the Terrarium format used by MapLibre GL and Mapbox GL:
+//!
+//! ```text
+//! elevation = (R * 256 + G + B / 256) - 32768
+//! ```
+//!
+//! Void samples and areas without data are encoded as sea level (0m).
+//!
+//! Tiles carry an `ETag` built from `z/x/y` and the newest modification time
+//! of the SRTM files they cover. `Cache-Control: no-cache` lets clients store
+//! tiles but revalidate them, and a matching `If-None-Match` gets
+//! `304 Not Modified` without rendering.
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use htg::{async_service::AsyncSrtmService, filename::tiles_covering_bbox, VOID_VALUE};
+use utoipa::OpenApi;
+
+use crate::{
+    handlers::{ErrorCode, ErrorResponse},
+    AppState,
+};
+
+/// Width and height of a terrain tile in pixels.
+pub const TILE_SIZE: u32 = 256;
+
+/// Highest supported zoom level.
+pub const MAX_ZOOM: u32 = 20;
+
+/// `Cache-Control` for tiles: stored, but revalidated against the `ETag` so a
+/// changed source file is picked up on the next request. The API-key
+/// middleware turns `public` into `private`.
+pub const CACHE_CONTROL: &str = "public, no-cache";
+
+/// OpenAPI documentation for the terrain endpoint, merged into the service docs.
+#[derive(OpenApi)]
+#[openapi(paths(get_terrain_tile))]
+pub struct TerrainApi;
+
+/// Terrain-RGB tile in Terrarium encoding.
+///
+/// Returns a 256×256 PNG for Web Mercator tile `z/x/y`. Each pixel holds the
+/// nearest-neighbor elevation at its center. Send the returned `ETag` in
+/// `If-None-Match` to get `304 Not Modified` while the data is unchanged.
+///
+/// Tiles covering more 1° SRTM tiles than the configured limit
+/// (`HTG_TERRAIN_MAX_TILES`) are rejected rather than loading them all.
+#[utoipa::path(
+    get,
+    path = "/terrain/{z}/{x}/{y}.png",
+    params(
+        ("z" = u32, Path, description = "Zoom level (0 to 20)"),
+        ("x" = u32, Path, description = "Tile column"),
+        ("y" = u32, Path, description = "Tile row"),
+    ),
+    responses(
+        (status = 200, description = "Terrarium-encoded PNG tile", content_type = "image/png"),
+        (status = 304, description = "Tile unchanged since the ETag in If-None-Match"),
+        (status = 400, description = "Invalid tile coordinates, or a tile covering too many SRTM tiles", body = ErrorResponse),
+    ),
+    tag = "elevation"
+)]
+pub async fn get_terrain_tile(
+    State(state): State<Arc<AppState>>,
+    Path((z, x, y)): Path<(u32, u32, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // axum cannot match a parameter followed by a suffix within one segment
+    let Some(y) = y.strip_suffix(".png").and_then(|y| y.parse::<u32>().ok()) else {
+        return bad_request(format!("Invalid tile row '{}': expected <y>.png", y));
+    };
+    if z > MAX_ZOOM || x >= 1 << z || y >= 1 << z {
+        return bad_request(format!("Invalid tile {}/{}/{}", z, x, y));
+    }
+
+    let sources = Arc::new(source_tiles(z, x, y));
+    if sources.len() as u64 > state.terrain_max_tiles {
+        return bad_request(format!(
+            "Tile {}/{}/{} covers {} SRTM tiles, the limit is {}",
+            z,
+            x,
+            y,
+            sources.len(),
+            state.terrain_max_tiles
+        ));
+    }
+
+    let etag = tile_etag(&state.srtm_service, z, x, y, &sources).await;
+    let cache_headers = |etag: String| {
+        [
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        ]
+    };
+    if if_none_match(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers(etag)).into_response();
+    }
+
+    let coords = pixel_centers(z, x, y);
+    let elevations = state
+        .srtm_service
+        .get_elevations_batch(coords, VOID_VALUE)
+        .await;
+
+    // Rendering may have downloaded source tiles
+    let etag = if state.srtm_service.inner().has_auto_download() {
+        tile_etag(&state.srtm_service, z, x, y, &sources).await
+    } else {
+        etag
+    };
+
+    let mut rgb = Vec::with_capacity(elevations.len() * 3);
+    for elevation in elevations {
+        rgb.extend_from_slice(&terrarium_rgb(elevation));
+    }
+
+    match encode_png(&rgb) {
+        Ok(png) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "image/png")],
+            cache_headers(etag),
+            png,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(
+                ErrorCode::Internal,
+                format!("Failed to encode PNG: {}", e),
+            )),
+        )
+            .into_response(),
+    }
+}
+
+/// Filenames of the SRTM tiles covered by tile `z/x/y`.
+fn source_tiles(z: u32, x: u32, y: u32) -> Vec<String> {
+    let (north, west) = tile_corner(z, x, y);
+    let (south, east) = tile_corner(z, x + 1, y + 1);
+    tiles_covering_bbox(south, west, north, east)
+}
+
+/// Strong `ETag` for tile `z/x/y`: its coordinates and the newest modification
+/// time (in nanoseconds, 0 if none exist) of `sources`, the SRTM files it covers.
+async fn tile_etag(
+    service: &AsyncSrtmService,
+    z: u32,
+    x: u32,
+    y: u32,
+    sources: &Arc<Vec<String>>,
+) -> String {
+    let sources = Arc::clone(sources);
+    let modified = service
+        .run(move |s| {
+            sources
+                .iter()
+                .filter_map(|filename| s.tile_path(filename))
+                .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+                .filter_map(|time| time.duration_since(UNIX_EPOCH).ok())
+                .max()
+                .map_or(0, |age| age.as_nanos())
+        })
+        .await;
+    format!("\"{}-{}-{}-{:x}\"", z, x, y, modified)
+}
+
+/// Whether `If-None-Match` lists `etag` (compared weakly, per RFC 9110) or `*`.
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Latitude/longitude of the north-west corner of tile `z/x/y`.
+fn tile_corner(z: u32, x: u32, y: u32) -> (f64, f64) {
+    let n = (1u64 << z) as f64;
+    let lon = x as f64 / n * 360.0 - 180.0;
+    let lat = (PI * (1.0 - 2.0 * y as f64 / n)).sinh().atan().to_degrees();
+    (lat, lon)
+}
+
+/// Latitude/longitude of every pixel center, row by row from the north edge.
+fn pixel_centers(z: u32, x: u32, y: u32) -> Vec<(f64, f64)> {
+    let world = (TILE_SIZE << z) as f64;
+    let lons: Vec<f64> = (0..TILE_SIZE)
+        .map(|col| ((x * TILE_SIZE + col) as f64 + 0.5) / world * 360.0 - 180.0)
+        .collect();
+
+    let mut coords = Vec::with_capacity((TILE_SIZE * TILE_SIZE) as usize);
+    for row in 0..TILE_SIZE {
+        let n = PI * (1.0 - 2.0 * ((y * TILE_SIZE + row) as f64 + 0.5) / world);
+        let lat = n.sinh().atan().to_degrees();
+        coords.extend(lons.iter().map(|&lon| (lat, lon)));
+    }
+    coords
+}
+
+/// Terrarium RGB for an elevation, with void as sea level.
+fn terrarium_rgb(elevation: i16) -> [u8; 3] {
+    let elevation = if elevation == VOID_VALUE {
+        0
+    } else {
+        elevation
+    };
+    // Whole meters, so the fractional blue channel is always 0
+    let value = (elevation as i32 + 32768) as u16;
+    [(value >> 8) as u8, value as u8, 0]
+}
+
+/// Encode a 256×256 RGB buffer as PNG.
+fn encode_png(rgb: &[u8]) -> Result<Vec<u8>, png::EncodingError> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, TILE_SIZE, TILE_SIZE);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgb)?;
+    writer.finish()?;
+    Ok(png)
+}
+
+fn bad_request(error: String) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::new(ErrorCode::InvalidRequest, error)),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terrarium_rgb() {
+        assert_eq!(terrarium_rgb(0), [128, 0, 0]);
+        assert_eq!(terrarium_rgb(VOID_VALUE), [128, 0, 0]);
+        assert_eq!(terrarium_rgb(3776), [142, 192, 0]);
+        assert_eq!(terrarium_rgb(-100), [127, 156, 0]);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = "\"12-3-4-abc\"";
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+            headers
+        };
+
+        assert!(if_none_match(&headers(etag), etag));
+        assert!(if_none_match(&headers("\"x\", W/\"12-3-4-abc\""), etag));
+        assert!(if_none_match(&headers("*"), etag));
+        assert!(!if_none_match(&headers("\"12-3-4-abd\""), etag));
+        assert!(!if_none_match(&HeaderMap::new(), etag));
+    }
+
+    #[test]
+    fn test_pixel_centers_zoom_zero() {
+        let coords = pixel_centers(0, 0, 0);
+        assert_eq!(coords.len(), 256 * 256);
+
+        let (lat, lon) = coords[0];
+        assert!(lat > 84.0 && lon < -179.0);
+        // Equator and prime meridian lie between the middle pixels
+        let (lat, lon) = coords[128 * 256 + 128];
+        assert!(lat < 0.0 && lat > -1.5 && lon > 0.0 && lon < 1.5);
+    }
+}
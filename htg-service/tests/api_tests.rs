@@ -1,10 +1,13 @@
 //! Integration tests for the HTTP API.
 
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use axum_test::TestServer;
 use geojson::{Geometry, Value as GeoJsonValue};
-use htg::SrtmService;
-use htg_service::{handlers, AppState};
+use htg::{async_service::AsyncSrtmService, SrtmService, SrtmServiceBuilder};
+use htg_service::{handlers, rate_limit::RateLimiter, AppState};
 use serde_json::Value;
 use std::fs::File;
 use std::io::Write;
@@ -32,16 +35,70 @@ fn create_test_tile(dir: &std::path::Path, filename: &str, center_elevation: i16
 
 /// Create a test server with a mock SRTM service.
 async fn create_test_server(temp_dir: &TempDir) -> TestServer {
+    create_configured_test_server(temp_dir, None, 0).await
+}
+
+/// Create a test server layered like `HTG_API_KEY` and `HTG_RATE_LIMIT` do.
+async fn create_configured_test_server(
+    temp_dir: &TempDir,
+    api_key: Option<&str>,
+    rate_limit: u32,
+) -> TestServer {
     let srtm_service = SrtmService::new(temp_dir.path(), 10);
-    let state = Arc::new(AppState { srtm_service });
+    create_test_server_for(srtm_service, api_key, rate_limit)
+}
+
+/// [`create_configured_test_server`] over an already built service.
+fn create_test_server_for(
+    srtm_service: SrtmService,
+    api_key: Option<&str>,
+    rate_limit: u32,
+) -> TestServer {
+    let state = Arc::new(AppState::new(AsyncSrtmService::new(srtm_service)));
 
     let app = Router::new()
         .route(
             "/elevation",
             get(handlers::get_elevation).post(handlers::post_elevation),
         )
-        .route("/health", get(handlers::health_check))
+        .route("/elevations", post(handlers::post_elevations))
         .route("/stats", get(handlers::get_stats))
+        .route("/stats/reset", post(handlers::reset_stats))
+        .route("/prefetch", post(handlers::prefetch))
+        .route("/cache/invalidate", post(handlers::invalidate_cache));
+
+    #[cfg(feature = "raster")]
+    let app = app.route(
+        "/terrain/:z/:x/:y",
+        get(htg_service::terrain::get_terrain_tile),
+    );
+
+    let app = match RateLimiter::new(rate_limit) {
+        Some(limiter) => app.route_layer(axum::middleware::from_fn_with_state(
+            Arc::new(limiter),
+            htg_service::rate_limit::rate_limit,
+        )),
+        None => app,
+    };
+    let app = app.route(
+        htg_service::openapi::OPENAPI_PATH,
+        get(htg_service::openapi::get_openapi),
+    );
+
+    #[cfg(feature = "metrics")]
+    let app = app.route("/metrics", get(htg_service::metrics::get_metrics));
+
+    let app = match api_key {
+        Some(key) => app.route_layer(axum::middleware::from_fn_with_state(
+            Arc::<str>::from(key),
+            htg_service::auth::require_api_key,
+        )),
+        None => app,
+    };
+    let app = app.route("/health", get(handlers::health_check));
+
+    let app = app
+        .layer(tower_http::compression::CompressionLayer::new())
         .with_state(state);
 
     TestServer::new(app).unwrap()
@@ -63,6 +120,35 @@ async fn test_elevation_endpoint_success() {
     assert_eq!(json["lon"], 138.5);
 }
 
+#[tokio::test]
+async fn test_elevation_endpoint_units_feet() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_test_server(&temp_dir).await;
+
+    // 500m = 1640.42ft
+    let response = server.get("/elevation?lat=35.5&lon=138.5&units=feet").await;
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"], 1640);
+    assert_eq!(json["units"], "feet");
+
+    let json: Value = server
+        .get("/elevation?lat=35.5&lon=138.5&units=feet&method=bilinear")
+        .await
+        .json();
+    assert!((json["elevation"].as_f64().unwrap() - 1640.42).abs() < 0.01);
+
+    let json: Value = server.get("/elevation?lat=35.5&lon=138.5").await.json();
+    assert_eq!(json["elevation"], 500);
+    assert_eq!(json["units"], "meters");
+
+    server
+        .get("/elevation?lat=35.5&lon=138.5&units=yards")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_elevation_endpoint_invalid_coordinates() {
     let temp_dir = TempDir::new().unwrap();
@@ -72,6 +158,7 @@ async fn test_elevation_endpoint_invalid_coordinates() {
     let response = server.get("/elevation?lat=91.0&lon=0.0").await;
     response.assert_status(axum::http::StatusCode::BAD_REQUEST);
     let json: Value = response.json();
+    assert_eq!(json["code"], "out_of_bounds");
     assert!(json["error"].as_str().unwrap().contains("out of bounds"));
 }
 
@@ -83,6 +170,96 @@ async fn test_elevation_endpoint_missing_tile() {
     // No tile file exists
     let response = server.get("/elevation?lat=50.0&lon=50.0").await;
     response.assert_status(axum::http::StatusCode::NOT_FOUND);
+    let json: Value = response.json();
+    assert_eq!(json["code"], "tile_not_found");
+    assert!(json["error"].as_str().unwrap().contains("N50E050"));
+}
+
+#[tokio::test]
+async fn test_elevation_endpoint_void_sample() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", htg::VOID_VALUE);
+    let server = create_test_server(&temp_dir).await;
+
+    for query in ["", "&method=bilinear", "&method=bicubic"] {
+        let response = server
+            .get(&format!("/elevation?lat=35.5&lon=138.5{}", query))
+            .await;
+
+        response.assert_status_ok();
+        let json: Value = response.json();
+        assert_eq!(json["void"], true, "{}", query);
+        assert!(json["elevation"].is_null(), "{}", query);
+        assert_eq!(json["resolution"], "SRTM3");
+    }
+}
+
+#[tokio::test]
+async fn test_elevation_endpoint_void_policy() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", htg::VOID_VALUE);
+    let server = create_test_server(&temp_dir).await;
+
+    // A quarter of a cell south-east of the void sample at (600, 600)
+    let (lat, lon) = (36.0 - 600.25 / 1200.0, 138.0 + 600.25 / 1200.0);
+    let get = |policy: &str| {
+        server.get(&format!(
+            "/elevation?lat={}&lon={}&method=bilinear{}",
+            lat, lon, policy
+        ))
+    };
+
+    for policy in ["", "&void_policy=strict", "&void_policy=nearest"] {
+        let json: Value = get(policy).await.json();
+        assert_eq!(json["void"], true, "{}", policy);
+    }
+
+    let response = get("&void_policy=ignore_void").await;
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"], 0.0);
+    assert_eq!(json["interpolated"], true);
+
+    let response = get("&void_policy=bogus").await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_elevation_endpoint_missing_tile_is_not_void() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", htg::VOID_VALUE);
+    let server = create_test_server(&temp_dir).await;
+
+    for query in ["", "&method=bilinear", "&method=bicubic"] {
+        let response = server
+            .get(&format!("/elevation?lat=50.0&lon=50.0{}", query))
+            .await;
+
+        response.assert_status(axum::http::StatusCode::NOT_FOUND);
+        let json: Value = response.json();
+        assert!(json.get("void").is_none());
+        assert!(json["error"].as_str().is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_openapi_spec() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    let response = server.get("/api-docs/openapi.json").await;
+    response.assert_status_ok();
+    let json: Value = response.json();
+
+    assert_eq!(json["info"]["version"], env!("CARGO_PKG_VERSION"));
+    let paths = json["paths"].as_object().unwrap();
+    for path in ["/elevation", "/elevations", "/prefetch", "/stats/reset"] {
+        assert!(paths.contains_key(path), "{} missing", path);
+    }
+    #[cfg(feature = "raster")]
+    assert!(paths.contains_key("/terrain/{z}/{x}/{y}.png"));
+    #[cfg(feature = "metrics")]
+    assert!(paths.contains_key("/metrics"));
 }
 
 #[tokio::test]
@@ -96,6 +273,25 @@ async fn test_health_endpoint() {
     let json: Value = response.json();
     assert_eq!(json["status"], "healthy");
     assert!(json["version"].as_str().is_some());
+    assert!(json.get("data_dir").is_none());
+    assert_eq!(json["tiles_available"], 0);
+    assert_eq!(json["auto_download"], false);
+}
+
+#[tokio::test]
+async fn test_health_counts_tiles() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
+    let server = create_test_server(&temp_dir).await;
+
+    let json: Value = server.get("/health").await.json();
+    assert_eq!(json["tiles_available"], 2);
+
+    // The scan is cached, so a new tile shows up only after the interval
+    create_test_tile(temp_dir.path(), "N37E138.hgt", 700);
+    let json: Value = server.get("/health").await.json();
+    assert_eq!(json["tiles_available"], 2);
 }
 
 #[tokio::test]
@@ -111,6 +307,8 @@ async fn test_stats_endpoint() {
     let json: Value = response.json();
     assert_eq!(json["cache_hits"], 0);
     assert_eq!(json["cache_misses"], 0);
+    assert_eq!(json["resident_bytes"], 0);
+    assert_eq!(json["eviction_count"], 0);
 
     // Make a request to populate cache
     server.get("/elevation?lat=35.5&lon=138.5").await;
@@ -119,6 +317,7 @@ async fn test_stats_endpoint() {
     let response = server.get("/stats").await;
     let json: Value = response.json();
     assert_eq!(json["cache_misses"], 1);
+    assert_eq!(json["resident_bytes"], 1201 * 1201 * 2);
 
     // Make another request in same tile (cache hit)
     server.get("/elevation?lat=35.6&lon=138.6").await;
@@ -127,6 +326,43 @@ async fn test_stats_endpoint() {
     let json: Value = response.json();
     assert_eq!(json["cache_hits"], 1);
     assert_eq!(json["cache_misses"], 1);
+
+    // Interpolated queries load the tile once too
+    server
+        .get("/elevation?lat=35.6&lon=138.6&method=bilinear")
+        .await
+        .assert_status_ok();
+    server
+        .get("/elevation?lat=35.6&lon=138.6&method=bicubic")
+        .await
+        .assert_status_ok();
+
+    let json: Value = server.get("/stats").await.json();
+    assert_eq!(json["cache_hits"], 3);
+    assert_eq!(json["cache_misses"], 1);
+}
+
+#[tokio::test]
+async fn test_stats_reset_endpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    server.get("/elevation?lat=35.5&lon=138.5").await;
+    server.get("/elevation?lat=35.6&lon=138.6").await;
+
+    let response = server.post("/stats/reset").await;
+    response.assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let json: Value = server.get("/stats").await.json();
+    assert_eq!(json["cache_hits"], 0);
+    assert_eq!(json["cache_misses"], 0);
+
+    // Counting resumes; the tile is still cached
+    server.get("/elevation?lat=35.5&lon=138.5").await;
+    let json: Value = server.get("/stats").await.json();
+    assert_eq!(json["cache_hits"], 1);
+    assert_eq!(json["cache_misses"], 0);
 }
 
 #[tokio::test]
@@ -187,6 +423,102 @@ async fn test_elevation_endpoint_no_interpolation() {
     assert!(json.get("interpolated").is_none());
 }
 
+#[tokio::test]
+async fn test_elevation_reports_tile_resolution() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    let json: Value = server.get("/elevation?lat=35.5&lon=138.5").await.json();
+    assert_eq!(json["resolution"], "SRTM3");
+
+    let json: Value = server
+        .get("/elevation?lat=35.5&lon=138.5&method=bicubic")
+        .await
+        .json();
+    assert_eq!(json["resolution"], "SRTM3");
+}
+
+#[tokio::test]
+async fn test_elevation_method_nearest() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5&method=nearest")
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"], 500);
+    assert_eq!(json["method"], "nearest");
+    assert!(json.get("interpolated").is_none());
+}
+
+#[tokio::test]
+async fn test_elevation_method_bilinear() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5&method=bilinear")
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"].as_f64(), Some(500.0));
+    assert_eq!(json["method"], "bilinear");
+    assert_eq!(json["interpolated"], true);
+}
+
+#[tokio::test]
+async fn test_elevation_method_bicubic() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5&method=bicubic")
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"].as_f64(), Some(500.0));
+    assert_eq!(json["method"], "bicubic");
+    assert_eq!(json["interpolated"], true);
+}
+
+#[tokio::test]
+async fn test_elevation_interpolate_alias_reports_bilinear() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5&interpolate=true")
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["method"], "bilinear");
+}
+
+#[tokio::test]
+async fn test_elevation_invalid_method() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5&method=cubic")
+        .await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
 // GeoJSON POST endpoint tests
 
 #[tokio::test]
@@ -329,3 +661,688 @@ async fn test_geojson_invalid_coordinates() {
     let json: Value = response.json();
     assert!(json["error"].as_str().unwrap().contains("out of bounds"));
 }
+
+#[tokio::test]
+async fn test_geojson_corrupted_tile_is_internal_error() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("N35E138.hgt"), [0u8; 100]).unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    let geometry = Geometry::new(GeoJsonValue::Point(vec![138.5, 35.5]));
+
+    let response = server.post("/elevation").json(&geometry).await;
+
+    response.assert_status(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    let json: Value = response.json();
+    assert_eq!(json["code"], "internal");
+}
+
+// Batch coordinate array endpoint tests
+
+#[tokio::test]
+async fn test_batch_elevations_same_tile() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    // 1000 points in N35E138; every 10th is the 500m center sample
+    let coordinates: Vec<[f64; 2]> = (0..1000)
+        .map(|i| {
+            if i % 10 == 0 {
+                [35.5, 138.5]
+            } else {
+                [35.1 + i as f64 * 0.0005, 138.2]
+            }
+        })
+        .collect();
+
+    let response = server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": coordinates }))
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    let elevations = json["elevations"].as_array().unwrap();
+    assert_eq!(elevations.len(), 1000);
+    for (i, elevation) in elevations.iter().enumerate() {
+        let expected = if i % 10 == 0 { 500.0 } else { 0.0 };
+        assert_eq!(elevation.as_f64().unwrap(), expected, "index {}", i);
+    }
+
+    // The tile was loaded once for the whole batch
+    let stats: Value = server.get("/stats").await.json();
+    assert_eq!(stats["cache_misses"], 1);
+}
+
+#[tokio::test]
+async fn test_batch_elevations_missing_tile_is_null() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    for interpolate in [false, true] {
+        let response = server
+            .post("/elevations")
+            .json(&serde_json::json!({
+                "coordinates": [[35.5, 138.5], [50.5, 10.5]],
+                "interpolate": interpolate,
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let json: Value = response.json();
+        assert_eq!(json["elevations"][0].as_f64().unwrap(), 500.0);
+        assert!(json["elevations"][1].is_null());
+    }
+}
+
+#[tokio::test]
+async fn test_batch_elevations_invalid_coordinates() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    let response = server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": [[35.5, 138.5], [91.0, 0.0]] }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let json: Value = response.json();
+    assert!(json["error"].as_str().unwrap().contains("out of bounds"));
+}
+
+#[tokio::test]
+async fn test_batch_elevations_uses_service_lat_bounds() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N70E010.hgt", 500);
+
+    let srtm_service = SrtmServiceBuilder::new(temp_dir.path())
+        .lat_bounds(-60.0, 83.0)
+        .build()
+        .unwrap();
+    let server = create_test_server_for(srtm_service, None, 0);
+
+    let response = server.get("/elevation?lat=70.5&lon=10.5").await;
+    response.assert_status_ok();
+    let response = server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": [[70.5, 10.5]] }))
+        .await;
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevations"], serde_json::json!([500.0]));
+
+    let response = server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": [[84.0, 10.5]] }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_batch_elevations_wraps_longitude() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    // 498.5 and -221.5 both wrap to 138.5, as with GET /elevation
+    let response = server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": [[35.5, 498.5], [35.5, -221.5]] }))
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevations"], serde_json::json!([500.0, 500.0]));
+}
+
+// Metrics endpoint tests
+
+/// Value of the first sample line starting with `prefix` in a Prometheus scrape.
+#[cfg(feature = "metrics")]
+fn metric_value(body: &str, prefix: &str) -> f64 {
+    body.lines()
+        .find(|line| line.starts_with(prefix))
+        .unwrap_or_else(|| panic!("no metric line starting with {}", prefix))
+        .rsplit(' ')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    for _ in 0..3 {
+        server
+            .get("/elevation?lat=35.5&lon=138.5")
+            .await
+            .assert_status_ok();
+    }
+    server
+        .get("/elevation?lat=91.0&lon=0.0")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server.get("/metrics").await;
+    response.assert_status_ok();
+    let body = response.text();
+
+    // The registry is process-wide, so other tests may have added to it
+    assert!(metric_value(&body, "htg_queries_total") >= 4.0);
+    assert!(
+        metric_value(
+            &body,
+            "htg_responses_total{endpoint=\"/elevation\",status=\"200\"}"
+        ) >= 3.0
+    );
+    assert!(
+        metric_value(
+            &body,
+            "htg_responses_total{endpoint=\"/elevation\",status=\"400\"}"
+        ) >= 1.0
+    );
+    assert!(
+        metric_value(
+            &body,
+            "htg_query_duration_seconds_count{endpoint=\"/elevation\"}"
+        ) >= 4.0
+    );
+
+    // Cache gauges reflect this server's service: one miss, then hits
+    assert_eq!(metric_value(&body, "htg_cache_misses"), 1.0);
+    assert_eq!(metric_value(&body, "htg_cache_hits"), 2.0);
+}
+
+#[tokio::test]
+async fn test_large_multipoint_gzip_compressed() {
+    use std::io::Read;
+
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+    let server = create_test_server(&temp_dir).await;
+
+    let points: Vec<Vec<f64>> = (0..5000)
+        .map(|i| vec![138.0 + (i as f64) * 0.0001, 35.5])
+        .collect();
+    let geometry = Geometry::new(GeoJsonValue::MultiPoint(points));
+
+    let response = server
+        .post("/elevation")
+        .add_header(
+            axum::http::header::ACCEPT_ENCODING,
+            axum::http::HeaderValue::from_static("gzip"),
+        )
+        .json(&geometry)
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-encoding"), "gzip");
+
+    let mut body = String::new();
+    flate2::read::GzDecoder::new(&response.as_bytes()[..])
+        .read_to_string(&mut body)
+        .unwrap();
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["type"], "MultiPoint");
+    assert_eq!(json["coordinates"].as_array().unwrap().len(), 5000);
+    assert!(body.len() > response.as_bytes().len());
+}
+
+/// Tracing output written to a shared buffer.
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_elevation_query_span_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_test_server(&temp_dir).await;
+
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    server.get("/elevation?lat=35.5&lon=138.5").await;
+    server
+        .get("/elevation?lat=35.2&lon=138.2&method=bilinear")
+        .await;
+
+    let logs = logs.contents();
+    let closed: Vec<&str> = logs
+        .lines()
+        .filter(|line| line.contains("elevation_query{") && line.contains("close"))
+        .collect();
+    assert_eq!(closed.len(), 2, "{}", logs);
+    assert!(closed[0].contains(
+        "elevation_query{lat=35.5 lon=138.5 method=Nearest resolution=\"SRTM3\" cache_hit=false}"
+    ));
+    assert!(closed[1].contains(
+        "elevation_query{lat=35.2 lon=138.2 method=Bilinear resolution=\"SRTM3\" cache_hit=true}"
+    ));
+}
+
+#[tokio::test]
+async fn test_post_query_span_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_test_server(&temp_dir).await;
+
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let geometry = Geometry::new(GeoJsonValue::Point(vec![138.5, 35.5]));
+    server.post("/elevation").json(&geometry).await;
+    server
+        .post("/elevations")
+        .json(&serde_json::json!({ "coordinates": [[35.5, 138.5], [50.0, 50.0]] }))
+        .await;
+
+    let logs = logs.contents();
+    assert!(
+        logs.lines().any(
+            |line| line.contains("geojson_query{geometry=\"Point\"}") && line.contains("close")
+        ),
+        "{}",
+        logs
+    );
+    assert!(
+        logs.lines().any(
+            |line| line.contains("batch_query{count=2 interpolate=false missing=1}")
+                && line.contains("close")
+        ),
+        "{}",
+        logs
+    );
+}
+
+// Terrain tile tests
+
+/// Web Mercator XYZ tile containing a point.
+#[cfg(feature = "raster")]
+fn tile_for(lat: f64, lon: f64, z: u32) -> (u32, u32) {
+    let n = (1u32 << z) as f64;
+    let x = (lon + 180.0) / 360.0 * n;
+    let lat = lat.to_radians();
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0 * n;
+    (x as u32, y as u32)
+}
+
+/// Decode a PNG response into (width, height, RGB bytes).
+#[cfg(feature = "raster")]
+fn decode_png(bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+    let decoder = png::Decoder::new(bytes);
+    let mut reader = decoder.read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    buf.truncate(info.buffer_size());
+    (info.width, info.height, buf)
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_terrain_tile_center_elevation() {
+    let temp_dir = TempDir::new().unwrap();
+    let data: Vec<u8> = (0..SRTM3_SAMPLES * SRTM3_SAMPLES)
+        .flat_map(|_| 1234i16.to_be_bytes())
+        .collect();
+    std::fs::write(temp_dir.path().join("N35E138.hgt"), data).unwrap();
+
+    let server = create_test_server(&temp_dir).await;
+    let (x, y) = tile_for(35.5, 138.5, 12);
+    let response = server.get(&format!("/terrain/12/{}/{}.png", x, y)).await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "image/png");
+
+    let (width, height, rgb) = decode_png(response.as_bytes());
+    assert_eq!((width, height), (256, 256));
+    let center = (128 * 256 + 128) * 3;
+    let (r, g, b) = (
+        rgb[center] as f64,
+        rgb[center + 1] as f64,
+        rgb[center + 2] as f64,
+    );
+    assert_eq!(r * 256.0 + g + b / 256.0 - 32768.0, 1234.0);
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_terrain_tile_missing_data_is_sea_level() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    let (x, y) = tile_for(35.5, 138.5, 8);
+    let response = server.get(&format!("/terrain/8/{}/{}.png", x, y)).await;
+
+    response.assert_status_ok();
+    let (_, _, rgb) = decode_png(response.as_bytes());
+    assert!(rgb.chunks(3).all(|p| p == [128, 0, 0]));
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_terrain_tile_covering_too_many_tiles() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    let response = server.get("/terrain/0/0/0.png").await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let json: Value = response.json();
+    assert!(json["error"].as_str().unwrap().contains("the limit is 64"));
+
+    // Zoom 6 tiles cover at most 7x7 SRTM tiles
+    let (x, y) = tile_for(35.5, 138.5, 6);
+    server
+        .get(&format!("/terrain/6/{}/{}.png", x, y))
+        .await
+        .assert_status_ok();
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_terrain_tile_etag_not_modified() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 1234);
+    let server = create_test_server(&temp_dir).await;
+    let (x, y) = tile_for(35.5, 138.5, 12);
+    let path = format!("/terrain/12/{}/{}.png", x, y);
+
+    let response = server.get(&path).await;
+    response.assert_status_ok();
+    assert_eq!(response.header("cache-control"), "public, no-cache");
+    let etag = response.header("etag");
+
+    let response = server
+        .get(&path)
+        .add_header(axum::http::header::IF_NONE_MATCH, etag.clone())
+        .await;
+    response.assert_status(axum::http::StatusCode::NOT_MODIFIED);
+    assert!(response.as_bytes().is_empty());
+    assert_eq!(response.header("etag"), etag);
+
+    // Neighboring tiles over the same source file have their own ETag
+    let response = server
+        .get(&format!("/terrain/12/{}/{}.png", x + 1, y))
+        .add_header(axum::http::header::IF_NONE_MATCH, etag)
+        .await;
+    response.assert_status_ok();
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_terrain_tile_invalid() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    for path in [
+        "/terrain/2/4/0.png",
+        "/terrain/2/0/0.jpg",
+        "/terrain/21/0/0.png",
+    ] {
+        server
+            .get(path)
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_api_key_missing_or_wrong_is_unauthorized() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_configured_test_server(&temp_dir, Some("secret"), 0).await;
+
+    let response = server.get("/elevation?lat=35.5&lon=138.5").await;
+    response.assert_status(axum::http::StatusCode::UNAUTHORIZED);
+    let json: Value = response.json();
+    assert_eq!(json["code"], "unauthorized");
+    assert!(json["error"].as_str().unwrap().contains("X-API-Key"));
+
+    server
+        .get("/stats")
+        .add_header("X-API-Key", "wrong")
+        .await
+        .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_api_key_correct_header_succeeds() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_configured_test_server(&temp_dir, Some("secret"), 0).await;
+
+    let response = server
+        .get("/elevation?lat=35.5&lon=138.5")
+        .add_header("X-API-Key", "secret")
+        .await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["elevation"], 500);
+}
+
+#[cfg(feature = "raster")]
+#[tokio::test]
+async fn test_api_key_terrain_tiles_are_private() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_configured_test_server(&temp_dir, Some("secret"), 0).await;
+
+    let (x, y) = tile_for(35.5, 138.5, 8);
+    let response = server
+        .get(&format!("/terrain/8/{}/{}.png", x, y))
+        .add_header("X-API-Key", "secret")
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("cache-control"), "private, no-cache");
+}
+
+#[tokio::test]
+async fn test_api_key_health_is_always_open() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_configured_test_server(&temp_dir, Some("secret"), 0).await;
+
+    let response = server.get("/health").await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["status"], "healthy");
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejects_bursts() {
+    let temp_dir = TempDir::new().unwrap();
+    create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+    let server = create_configured_test_server(&temp_dir, None, 5).await;
+
+    let mut ok = 0;
+    let mut limited = 0;
+    for _ in 0..20 {
+        let response = server.get("/elevation?lat=35.5&lon=138.5").await;
+        match response.status_code() {
+            axum::http::StatusCode::OK => ok += 1,
+            axum::http::StatusCode::TOO_MANY_REQUESTS => {
+                let json: Value = response.json();
+                assert_eq!(json["code"], "rate_limited");
+                assert!(json["error"].as_str().unwrap().contains("Rate limit"));
+                limited += 1;
+            }
+            status => panic!("unexpected status {}", status),
+        }
+    }
+    assert!(ok >= 5, "only {} requests succeeded", ok);
+    assert!(limited > 0, "no request was rate limited");
+
+    // Health checks are never limited
+    server.get("/health").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_prefetch_loads_tiles_in_box() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["N35E138.hgt", "N35E139.hgt", "N36E138.hgt", "N36E139.hgt"] {
+        create_test_tile(temp_dir.path(), name, 500);
+    }
+    // Outside the box
+    create_test_tile(temp_dir.path(), "N40E140.hgt", 500);
+    let server = create_test_server(&temp_dir).await;
+
+    let body =
+        serde_json::json!({"min_lat": 35.0, "min_lon": 138.0, "max_lat": 37.0, "max_lon": 140.0});
+    let response = server.post("/prefetch").json(&body).await;
+
+    response.assert_status_ok();
+    let json: Value = response.json();
+    assert_eq!(json["tiles_loaded"], 4);
+    assert_eq!(json["tiles_skipped"], 0);
+
+    // A second prefetch finds everything cached
+    let json: Value = server.post("/prefetch").json(&body).await.json();
+    assert_eq!(json["tiles_loaded"], 0);
+    assert_eq!(json["tiles_skipped"], 4);
+}
+
+#[tokio::test]
+async fn test_prefetch_rejects_large_or_invalid_boxes() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = create_test_server(&temp_dir).await;
+
+    for body in [
+        serde_json::json!({"min_lat": 0.0, "min_lon": 0.0, "max_lat": 10.0, "max_lon": 10.0}),
+        serde_json::json!({"min_lat": 36.0, "min_lon": 138.0, "max_lat": 35.0, "max_lon": 139.0}),
+    ] {
+        let response = server.post("/prefetch").json(&body).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        let json: Value = response.json();
+        assert!(json["error"].as_str().is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_cache_invalidate_evicts_tiles_in_box() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["N35E138.hgt", "N35E139.hgt", "N36E138.hgt"] {
+        create_test_tile(temp_dir.path(), name, 500);
+    }
+    let server = create_test_server(&temp_dir).await;
+    let body =
+        serde_json::json!({"min_lat": 35.0, "min_lon": 138.0, "max_lat": 37.0, "max_lon": 140.0});
+    server
+        .post("/prefetch")
+        .json(&body)
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .post("/cache/invalidate")
+        .json(&serde_json::json!({"min_lat": 35.2, "min_lon": 139.2, "max_lat": 36.8, "max_lon": 139.8}))
+        .await;
+    response.assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    // Only N35E139 has to be loaded again
+    let json: Value = server.post("/prefetch").json(&body).await.json();
+    assert_eq!(json["tiles_loaded"], 1);
+    assert_eq!(json["tiles_skipped"], 2);
+
+    let response = server
+        .post("/cache/invalidate")
+        .json(&serde_json::json!({"min_lat": 36.0, "min_lon": 138.0, "max_lat": 35.0, "max_lon": 139.0}))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown_drains_in_flight_requests() {
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::{mpsc, oneshot, Mutex};
+
+    // The handler reports that it started, then waits until released
+    let (started_tx, mut started_rx) = mpsc::channel::<()>(1);
+    let (release_tx, release_rx) = oneshot::channel::<()>();
+    let release_rx = Arc::new(Mutex::new(Some(release_rx)));
+    let app = Router::new().route(
+        "/slow",
+        get(move || async move {
+            started_tx.send(()).await.unwrap();
+            let release_rx = release_rx.lock().await.take().unwrap();
+            release_rx.await.unwrap();
+            "done"
+        }),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(htg_service::serve(listener, app, async {
+        shutdown_rx.await.unwrap();
+    }));
+
+    let mut in_flight = TcpStream::connect(addr).await.unwrap();
+    in_flight
+        .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    started_rx.recv().await.unwrap();
+
+    shutdown_tx.send(()).unwrap();
+
+    // The listener closes while the slow request is still running
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while TcpStream::connect(addr).await.is_ok() {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("listener still accepting after shutdown");
+
+    release_tx.send(()).unwrap();
+    let mut response = String::new();
+    in_flight.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with("done"));
+
+    server.await.unwrap().unwrap();
+}
@@ -112,11 +112,48 @@ fn bench_batch_multi_tile(c: &mut Criterion) {
     });
 }
 
+fn bench_dense_profile_interpolated(c: &mut Criterion) {
+    let tmp = TempDir::new().unwrap();
+    create_tile(tmp.path(), "N35E138.hgt");
+
+    let plain = htg::SrtmService::new(tmp.path(), 10);
+    let builder = htg::SrtmServiceBuilder::new(tmp.path()).interpolation_cache(64);
+    #[cfg(feature = "download")]
+    let cached = builder.build().unwrap();
+    #[cfg(not(feature = "download"))]
+    let cached = builder.build();
+
+    // 1000 samples along a short profile: ~10 samples per grid cell
+    let coords: Vec<(f64, f64)> = (0..1000)
+        .map(|i| (35.5 + i as f64 * 0.00008, 138.5 + i as f64 * 0.00008))
+        .collect();
+
+    let _ = plain.get_elevation(35.5, 138.5);
+    let _ = cached.get_elevation(35.5, 138.5);
+
+    c.bench_function("dense_profile_interpolated", |b| {
+        b.iter(|| {
+            for &(lat, lon) in &coords {
+                black_box(plain.get_elevation_interpolated(lat, lon).unwrap());
+            }
+        });
+    });
+
+    c.bench_function("dense_profile_interpolated_quad_cache", |b| {
+        b.iter(|| {
+            for &(lat, lon) in &coords {
+                black_box(cached.get_elevation_interpolated(lat, lon).unwrap());
+            }
+        });
+    });
+}
+
 criterion_group!(
     benches,
     bench_single_nearest,
     bench_single_interpolated,
     bench_batch_same_tile,
     bench_batch_multi_tile,
+    bench_dense_profile_interpolated,
 );
 criterion_main!(benches);
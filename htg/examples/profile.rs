@@ -0,0 +1,64 @@
+//! Example sampling an elevation profile along a route, across tiles.
+//!
+//! Run with: cargo run --example profile -- /path/to/hgt/files 35.36 138.72 35.40 138.80
+
+use htg::{profile::ascent_descent, SrtmError, SrtmService};
+use std::env;
+
+/// Distance between profile samples in meters.
+const SPACING_M: f64 = 100.0;
+
+/// Ignore elevation changes smaller than this when totaling ascent/descent.
+const SMOOTHING_M: f64 = 5.0;
+
+fn main() -> Result<(), SrtmError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let coords: Option<Vec<f64>> = args.iter().skip(1).map(|a| a.parse().ok()).collect();
+    let (data_dir, coords) = match (args.first(), coords) {
+        (Some(dir), Some(coords)) if coords.len() == 4 => (dir, coords),
+        _ => {
+            eprintln!(
+                "Usage: cargo run --example profile -- /path/to/hgt/files \
+                 <from_lat> <from_lon> <to_lat> <to_lon>"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let service = SrtmService::new(data_dir, 10);
+    let route = [(coords[0], coords[1]), (coords[2], coords[3])];
+
+    // Bilinear interpolation between samples; points in missing tiles get a
+    // `None` elevation rather than failing the whole route
+    let interpolate = true;
+    let profile = service.elevation_profile(&route, SPACING_M, interpolate)?;
+
+    println!("{:>12}  {:>10}", "DISTANCE (m)", "ELEVATION");
+    println!("{:-<24}", "");
+    for point in &profile {
+        match point.elevation {
+            Some(elevation) => println!("{:>12.0}  {:>9.1}m", point.distance_m, elevation),
+            None => println!("{:>12.0}  {:>10}", point.distance_m, "no data"),
+        }
+    }
+
+    let missing = profile.iter().filter(|p| p.elevation.is_none()).count();
+    let (ascent, descent) = ascent_descent(&profile, SMOOTHING_M);
+    let length = profile.last().map_or(0.0, |p| p.distance_m);
+
+    println!(
+        "\nRoute length: {:.0}m in {} samples",
+        length,
+        profile.len()
+    );
+    if missing > 0 {
+        println!("Samples without data: {} (missing tile or void)", missing);
+    }
+    println!("Total ascent: {:.0}m", ascent);
+    println!("Total descent: {:.0}m", descent);
+
+    let stats = service.cache_stats();
+    println!("Tile cache misses: {}", stats.miss_count);
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+//! Async wrapper around [`SrtmService`] (requires the `async` feature).
+//!
+//! Tile loads read from disk and, with auto-download enabled, may fetch a
+//! tile over HTTP, so calling [`SrtmService`] directly from an async task
+//! blocks a runtime worker for as long as that takes. [`AsyncSrtmService`]
+//! runs each query on tokio's blocking thread pool with
+//! [`tokio::task::spawn_blocking`] instead. The tile cache is shared between
+//! all queries, so cache hits stay cheap.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::{async_service::AsyncSrtmService, SrtmService};
+//!
+//! let service = AsyncSrtmService::new(SrtmService::new("/data/srtm", 100));
+//!
+//! if let Some(elevation) = service.get_elevation(35.3606, 138.7274).await? {
+//!     println!("Mount Fuji: {}m", elevation);
+//! }
+//!
+//! // Any other query
+//! let profile = service
+//!     .run(move |s| s.elevation_profile(&route, 30.0, true))
+//!     .await?;
+//! ```
+
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::{BoundingBox, PreloadStats, SrtmService};
+
+/// An [`SrtmService`] whose queries run off the async runtime.
+///
+/// Cloning is cheap and shares the underlying service and its caches.
+#[derive(Clone)]
+pub struct AsyncSrtmService {
+    inner: Arc<SrtmService>,
+}
+
+impl AsyncSrtmService {
+    /// Wrap a service.
+    pub fn new(service: SrtmService) -> Self {
+        Self {
+            inner: Arc::new(service),
+        }
+    }
+
+    /// The wrapped service, for calls that never touch the disk or network
+    /// (e.g. [`SrtmService::cache_stats`]).
+    pub fn inner(&self) -> &SrtmService {
+        &self.inner
+    }
+
+    /// Run `f` against the service on the blocking thread pool.
+    ///
+    /// Use this for queries without a dedicated async method. A panic in
+    /// `f` is propagated to the caller.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn run<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&SrtmService) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let service = Arc::clone(&self.inner);
+        match tokio::task::spawn_blocking(move || f(&service)).await {
+            Ok(value) => value,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// Async [`SrtmService::get_elevation`].
+    pub async fn get_elevation(&self, lat: f64, lon: f64) -> Result<Option<i16>> {
+        self.run(move |s| s.get_elevation(lat, lon)).await
+    }
+
+    /// Async [`SrtmService::get_elevation_interpolated`].
+    pub async fn get_elevation_interpolated(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        self.run(move |s| s.get_elevation_interpolated(lat, lon))
+            .await
+    }
+
+    /// Async [`SrtmService::get_elevations_batch`].
+    pub async fn get_elevations_batch(&self, coords: Vec<(f64, f64)>, default: i16) -> Vec<i16> {
+        self.run(move |s| s.get_elevations_batch(&coords, default))
+            .await
+    }
+
+    /// Async [`SrtmService::get_elevations_batch_interpolated`].
+    pub async fn get_elevations_batch_interpolated(
+        &self,
+        coords: Vec<(f64, f64)>,
+        default: f64,
+    ) -> Vec<f64> {
+        self.run(move |s| s.get_elevations_batch_interpolated(&coords, default))
+            .await
+    }
+
+    /// Async [`SrtmService::get_elevations_batch_interpolated_opt`].
+    pub async fn get_elevations_batch_interpolated_opt(
+        &self,
+        coords: Vec<(f64, f64)>,
+    ) -> Vec<Option<f64>> {
+        self.run(move |s| s.get_elevations_batch_interpolated_opt(&coords))
+            .await
+    }
+
+    /// Async [`SrtmService::preload`].
+    pub async fn preload(&self, bounds: Option<Vec<BoundingBox>>) -> PreloadStats {
+        self.run(move |s| s.preload(bounds.as_deref())).await
+    }
+}
+
+impl From<SrtmService> for AsyncSrtmService {
+    fn from(service: SrtmService) -> Self {
+        Self::new(service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::write_test_tile;
+    use tempfile::TempDir;
+
+    /// SRTM3 tile at N35E138 where every sample holds its row index.
+    fn create_tile(dir: &TempDir) {
+        write_test_tile(&dir.path().join("N35E138.hgt"), |row, _| row as i16);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_queries() {
+        let dir = TempDir::new().unwrap();
+        create_tile(&dir);
+        let service = AsyncSrtmService::new(SrtmService::new(dir.path(), 4));
+
+        let tasks: Vec<_> = (0..64)
+            .map(|i| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    let row = i * 10;
+                    let lat = 36.0 - row as f64 / 1200.0 - 0.0001;
+                    (row, service.get_elevation(lat, 138.5).await.unwrap())
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            let (row, elevation) = task.await.unwrap();
+            assert_eq!(elevation, Some(row as i16));
+        }
+
+        let stats = service.inner().cache_stats();
+        assert_eq!(stats.hit_count + stats.miss_count, 64);
+    }
+
+    #[tokio::test]
+    async fn test_batch_and_missing_tiles() {
+        let dir = TempDir::new().unwrap();
+        create_tile(&dir);
+        let service = AsyncSrtmService::from(SrtmService::new(dir.path(), 4));
+
+        let coords = vec![(35.5, 138.5), (50.0, 50.0)];
+        assert_eq!(
+            service.get_elevations_batch(coords.clone(), -1).await,
+            vec![600, -1]
+        );
+        let interpolated = service
+            .get_elevations_batch_interpolated(coords, -1.0)
+            .await;
+        assert!((interpolated[0] - 600.0).abs() < 1e-6);
+        assert_eq!(interpolated[1], -1.0);
+
+        assert_eq!(service.get_elevation(50.0, 50.0).await.unwrap(), None);
+        assert!(service.get_elevation(61.0, 0.0).await.is_err());
+
+        let stats = service.preload(None).await;
+        assert_eq!(stats.tiles_matched, 1);
+        assert_eq!(
+            service.run(|s| s.scan_tile_files()).await,
+            vec!["N35E138.hgt".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "boom")]
+    async fn test_run_propagates_panics() {
+        let dir = TempDir::new().unwrap();
+        let service = AsyncSrtmService::new(SrtmService::new(dir.path(), 1));
+        service.run(|_| panic!("boom")).await
+    }
+}
@@ -0,0 +1,405 @@
+//! Contour line extraction.
+//!
+//! [`extract_contours`] samples the elevation grid over a bounding box and
+//! traces iso-lines at fixed intervals with marching squares. Enable the
+//! `geojson` feature to use this module.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::contours::extract_contours;
+//!
+//! // 100m contours around Mount Fuji
+//! let contours = extract_contours(&service, (35.3, 138.6, 35.45, 138.85), 100.0)?;
+//! println!("{}", geojson::GeoJson::from(contours));
+//! ```
+
+use std::collections::HashMap;
+
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject, Value as GeoJsonValue};
+
+use crate::error::{Result, SrtmError};
+use crate::grid::MAX_GRID_SAMPLES;
+use crate::{SrtmResolution, SrtmService, VOID_VALUE};
+
+/// Grid edge between two adjacent samples: `(row, col, horizontal)`.
+///
+/// A horizontal edge joins `(row, col)` and `(row, col + 1)`; a vertical
+/// edge joins `(row, col)` and `(row + 1, col)`.
+type Edge = (usize, usize, bool);
+
+/// Extract contour lines over a bounding box.
+///
+/// Elevations are sampled on the data's own grid (SRTM1 or SRTM3, from the
+/// tile at the center of `bbox`). A contour is traced at every multiple of
+/// `interval_m` between the lowest and highest sample. Each contour becomes
+/// a `LineString` feature with an `elevation` property; closed contours
+/// repeat their first position at the end.
+///
+/// Cells with a void (or missing-tile) corner produce no contour segments,
+/// so contours stop at the edge of voids instead of crossing them.
+///
+/// # Arguments
+///
+/// * `service` - The SRTM service to sample
+/// * `bbox` - `(min_lat, min_lon, max_lat, max_lon)` in decimal degrees
+/// * `interval_m` - Elevation difference between contours in meters
+///
+/// # Errors
+///
+/// Returns [`SrtmError::InvalidCoordinate`] if `interval_m` is not a positive
+/// finite number, the bounding box is not finite or is inverted, or it spans
+/// more than 16 million samples.
+pub fn extract_contours(
+    service: &SrtmService,
+    bbox: (f64, f64, f64, f64),
+    interval_m: f64,
+) -> Result<FeatureCollection> {
+    if !(interval_m > 0.0 && interval_m.is_finite()) {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!("Contour interval must be positive, got {}", interval_m),
+        });
+    }
+    let (min_lat, min_lon, max_lat, max_lon) = bbox;
+    if ![min_lat, min_lon, max_lat, max_lon]
+        .iter()
+        .all(|v| v.is_finite())
+    {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!("Bounding box must be finite, got {:?}", bbox),
+        });
+    }
+    if !(min_lat <= max_lat && min_lon <= max_lon) {
+        return Err(SrtmError::InvalidCoordinate {
+            message: "Bounding box minimums must not exceed maximums".to_string(),
+        });
+    }
+
+    // Sample on the data's own grid so every lookup hits a sample exactly
+    let step = service
+        .tile_resolution((min_lat + max_lat) / 2.0, (min_lon + max_lon) / 2.0)
+        .unwrap_or(SrtmResolution::Srtm3)
+        .pixel_spacing_deg();
+    // Size the grid before allocating it
+    let lat_range = grid_range(min_lat, max_lat, step);
+    let lon_range = grid_range(min_lon, max_lon, step);
+    let (rows, cols) = (range_len(lat_range), range_len(lon_range));
+    if rows.saturating_mul(cols) > MAX_GRID_SAMPLES {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!(
+                "Bounding box spans {}×{} samples, more than {}",
+                rows, cols, MAX_GRID_SAMPLES
+            ),
+        });
+    }
+    let lats = grid_positions(lat_range, step);
+    let lons = grid_positions(lon_range, step);
+
+    let coords: Vec<(f64, f64)> = lats
+        .iter()
+        .flat_map(|&lat| lons.iter().map(move |&lon| (lat, lon)))
+        .collect();
+    let grid = Grid {
+        values: service
+            .get_elevations_batch(&coords, VOID_VALUE)
+            .into_iter()
+            .map(|e| (e != VOID_VALUE).then_some(e as f64))
+            .collect(),
+        lats,
+        lons,
+    };
+
+    let (min, max) = grid
+        .values
+        .iter()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+
+    let mut features = Vec::new();
+    if min <= max {
+        let first = (min / interval_m).ceil() as i64;
+        let last = (max / interval_m).floor() as i64;
+        for k in first..=last {
+            let level = k as f64 * interval_m;
+            for line in trace_lines(&grid.segments(level)) {
+                features.push(contour_feature(&grid, &line, level));
+            }
+        }
+    }
+
+    Ok(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Indices `(first, last)` of the sample-grid lines within `[min, max]`.
+fn grid_range(min: f64, max: f64, step: f64) -> (i64, i64) {
+    let first = (min / step - 1e-6).ceil() as i64;
+    let last = (max / step + 1e-6).floor() as i64;
+    (first, last)
+}
+
+/// Number of grid lines in a [`grid_range`].
+fn range_len((first, last): (i64, i64)) -> usize {
+    usize::try_from(last.saturating_sub(first).saturating_add(1)).unwrap_or(0)
+}
+
+/// Positions of the grid lines in a [`grid_range`].
+fn grid_positions((first, last): (i64, i64), step: f64) -> Vec<f64> {
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Sampled elevations, row-major from the south-west corner.
+struct Grid {
+    /// `None` for void or missing data.
+    values: Vec<Option<f64>>,
+    lats: Vec<f64>,
+    lons: Vec<f64>,
+}
+
+impl Grid {
+    fn value(&self, row: usize, col: usize) -> Option<f64> {
+        self.values[row * self.lons.len() + col]
+    }
+
+    /// Marching-squares segments for `level` over every cell without voids.
+    fn segments(&self, level: f64) -> Vec<(Edge, Edge)> {
+        let mut segments = Vec::new();
+        for row in 0..self.lats.len().saturating_sub(1) {
+            for col in 0..self.lons.len().saturating_sub(1) {
+                let corners = [
+                    self.value(row, col),
+                    self.value(row, col + 1),
+                    self.value(row + 1, col + 1),
+                    self.value(row + 1, col),
+                ];
+                let [Some(sw), Some(se), Some(ne), Some(nw)] = corners else {
+                    continue;
+                };
+                let above = [sw >= level, se >= level, ne >= level, nw >= level];
+
+                let south = (row, col, true);
+                let east = (row, col + 1, false);
+                let north = (row + 1, col, true);
+                let west = (row, col, false);
+                // Edge i runs from corner i to corner i + 1
+                let crossed: Vec<Edge> = [south, east, north, west]
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(i, _)| above[i] != above[(i + 1) % 4])
+                    .map(|(_, edge)| edge)
+                    .collect();
+
+                match crossed[..] {
+                    [a, b] => segments.push((a, b)),
+                    [_, _, _, _] => {
+                        // Saddle: the cell center decides which corners connect
+                        let center_above = (sw + se + ne + nw) / 4.0 >= level;
+                        if above[0] == center_above {
+                            // SW and NE join through the center; cut off SE and NW
+                            segments.push((south, east));
+                            segments.push((north, west));
+                        } else {
+                            segments.push((south, west));
+                            segments.push((north, east));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        segments
+    }
+
+    /// `[lon, lat]` where `level` crosses `edge`, linearly interpolated.
+    fn crossing(&self, (row, col, horizontal): Edge, level: f64) -> Vec<f64> {
+        let (row2, col2) = if horizontal {
+            (row, col + 1)
+        } else {
+            (row + 1, col)
+        };
+        let a = self.value(row, col).unwrap_or(level);
+        let b = self.value(row2, col2).unwrap_or(level);
+        let t = if a == b { 0.5 } else { (level - a) / (b - a) };
+        vec![
+            self.lons[col] + t * (self.lons[col2] - self.lons[col]),
+            self.lats[row] + t * (self.lats[row2] - self.lats[row]),
+        ]
+    }
+}
+
+/// Join segments sharing an edge into polylines. Closed rings end with
+/// their first edge repeated.
+fn trace_lines(segments: &[(Edge, Edge)]) -> Vec<Vec<Edge>> {
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (i, &(a, b)) in segments.iter().enumerate() {
+        by_edge.entry(a).or_default().push(i);
+        by_edge.entry(b).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    // Append segments to the end of `line` until it closes or runs out
+    let extend = |line: &mut Vec<Edge>, used: &mut Vec<bool>| loop {
+        let end = *line.last().unwrap();
+        let Some(&next) = by_edge[&end].iter().find(|&&s| !used[s]) else {
+            return false;
+        };
+        used[next] = true;
+        let (a, b) = segments[next];
+        let other = if a == end { b } else { a };
+        line.push(other);
+        if other == line[0] {
+            return true;
+        }
+    };
+
+    let mut lines = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut line = vec![segments[start].0, segments[start].1];
+        if !extend(&mut line, &mut used) {
+            // Open line: grow the other end too
+            line.reverse();
+            extend(&mut line, &mut used);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A `LineString` feature for one traced contour.
+fn contour_feature(grid: &Grid, line: &[Edge], level: f64) -> Feature {
+    let positions = line
+        .iter()
+        .map(|&edge| grid.crossing(edge, level))
+        .collect();
+    let mut properties = JsonObject::new();
+    properties.insert("elevation".to_string(), level.into());
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(GeoJsonValue::LineString(positions))),
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{write_test_tile, SRTM3_SAMPLES};
+    use tempfile::TempDir;
+
+    /// SRTM3 cone at N35E138: 950m at the center, falling 2m per sample,
+    /// with rows for which `void_row` is true left void.
+    fn create_cone(dir: &TempDir, void_row: impl Fn(usize) -> bool) -> SrtmService {
+        let center = (SRTM3_SAMPLES / 2) as f64;
+        write_test_tile(&dir.path().join("N35E138.hgt"), |row, col| {
+            if void_row(row) {
+                VOID_VALUE
+            } else {
+                let distance = (row as f64 - center).hypot(col as f64 - center);
+                (950.0 - 2.0 * distance).max(0.0).round() as i16
+            }
+        });
+        SrtmService::new(dir.path(), 1)
+    }
+
+    fn line_positions(feature: &Feature) -> &Vec<Vec<f64>> {
+        match &feature.geometry.as_ref().unwrap().value {
+            GeoJsonValue::LineString(positions) => positions,
+            other => panic!("expected a LineString, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cone_gives_closed_rings() {
+        let dir = TempDir::new().unwrap();
+        let service = create_cone(&dir, |_| false);
+
+        let contours = extract_contours(&service, (35.05, 138.05, 35.95, 138.95), 200.0).unwrap();
+
+        let levels: Vec<f64> = contours
+            .features
+            .iter()
+            .map(|f| f.property("elevation").unwrap().as_f64().unwrap())
+            .collect();
+        assert_eq!(levels, vec![200.0, 400.0, 600.0, 800.0]);
+
+        for (feature, level) in contours.features.iter().zip(levels) {
+            let positions = line_positions(feature);
+            assert_eq!(positions.first(), positions.last(), "ring is closed");
+
+            // Every vertex lies on the circle where the cone reaches `level`
+            let radius = (950.0 - level) / 2.0 / 1200.0;
+            for position in positions {
+                let distance = (position[0] - 138.5).hypot(position[1] - 35.5);
+                assert!((distance - radius).abs() < 1.5 / 1200.0, "{:?}", position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_contours_stop_at_voids() {
+        let dir = TempDir::new().unwrap();
+        // A void band across the middle of the cone, rows 590-610
+        let service = create_cone(&dir, |row| (590..=610).contains(&row));
+
+        let contours = extract_contours(&service, (35.05, 138.05, 35.95, 138.95), 200.0).unwrap();
+
+        // Each ring is cut into a northern and a southern arc
+        assert_eq!(contours.features.len(), 8);
+        for feature in &contours.features {
+            let positions = line_positions(feature);
+            assert_ne!(positions.first(), positions.last());
+            for position in positions {
+                let row = (36.0 - position[1]) * 1200.0;
+                // Cells touching the void span rows 589-611
+                assert!(row <= 589.001 || row >= 610.999, "{:?}", position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_contours_errors() {
+        let dir = TempDir::new().unwrap();
+        let service = SrtmService::new(dir.path(), 1);
+
+        assert!(matches!(
+            extract_contours(&service, (35.0, 138.0, 35.5, 138.5), 0.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            extract_contours(&service, (35.5, 138.0, 35.0, 138.5), 10.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            extract_contours(&service, (0.0, 0.0, 10.0, 10.0), 10.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        // Rejected before the grid positions are allocated
+        assert!(matches!(
+            extract_contours(&service, (-1e300, 0.0, 1e300, 1.0), 10.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            extract_contours(&service, (f64::NEG_INFINITY, 0.0, 0.0, 1.0), 10.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            extract_contours(&service, (f64::NAN, 0.0, 0.0, 1.0), 10.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+
+        // No data: no contours
+        let contours = extract_contours(&service, (35.0, 138.0, 35.1, 138.1), 10.0).unwrap();
+        assert!(contours.features.is_empty());
+    }
+}
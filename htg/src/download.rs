@@ -17,13 +17,18 @@
 use std::fs::{self, File};
 use std::io::{Cursor, Read, Write};
 use std::path::Path;
+use std::time::Duration;
 
 use flate2::read::GzDecoder;
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RANGE};
+use reqwest::{Method, StatusCode};
 use zip::ZipArchive;
 
 use crate::error::{Result, SrtmError};
-use crate::filename::lat_lon_to_filename;
+use crate::filename::{filename_to_lat_lon, lat_lon_to_filename};
+use crate::geotiff::{geotiff_to_hgt, Raster};
+use crate::tile::{SrtmResolution, SRTM1_SIZE, SRTM3_SIZE};
 
 /// Compression format for downloaded SRTM files.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -92,6 +97,22 @@ pub enum SrtmSource {
         password: String,
     },
 
+    /// OpenTopography global DEM API (requires a free API key).
+    /// URL pattern: `https://portal.opentopography.org/API/globaldem?demtype=SRTMGL1&south={lat}&north={lat+1}&west={lon}&east={lon+1}&outputFormat=GTiff&API_Key={api_key}`
+    ///
+    /// Returns a GeoTIFF which is converted to `.hgt` after download.
+    OpenTopography {
+        /// OpenTopography API key
+        api_key: String,
+    },
+
+    /// CGIAR-CSI SRTM v4.1 (void-filled, 3 arc-second).
+    /// URL pattern: `https://srtm.csi.cgiar.org/wp-content/uploads/files/srtm_5x5/TIFF/srtm_{XX}_{YY}.zip`
+    ///
+    /// Data is distributed as 5°×5° GeoTIFF cells; the requested 1° tile is
+    /// cut out of the enclosing cell after download. Coverage is 60°N to 60°S.
+    CgiarCsi,
+
     /// Custom URL template.
     /// Use `{filename}` as placeholder for the tile name (e.g., "N35E138").
     /// Use `{lat_prefix}`, `{lat}`, `{lon_prefix}`, `{lon}` for individual components.
@@ -120,23 +141,89 @@ impl Default for SrtmSource {
     }
 }
 
+/// Delay between download retries: exponential backoff with random jitter.
+///
+/// Retry `n` (1-based) waits `initial_delay * multiplier^(n-1)`, capped at
+/// `max_delay`, then scaled by a random factor in `1 ± jitter`. Jitter keeps
+/// many clients from retrying a rate-limited mirror in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Delay before the first retry.
+    pub initial_delay: Duration,
+    /// Factor applied to the delay after each retry.
+    pub multiplier: f64,
+    /// Upper bound on the delay, including jitter.
+    pub max_delay: Duration,
+    /// Random spread as a fraction of the delay (0.0 to 1.0).
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    /// 500ms, 1s, 2s, ... up to 10s, with ±10% jitter.
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            jitter: 0.1,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry `retry` (1-based), without jitter.
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        Duration::from_secs_f64(secs.min(self.max_delay.as_secs_f64()))
+    }
+
+    /// Delay before retry `retry` with jitter, where `random` is uniform in
+    /// `[0, 1)`.
+    fn delay_with(&self, retry: u32, random: f64) -> Duration {
+        let spread = self.jitter.clamp(0.0, 1.0) * (2.0 * random - 1.0);
+        let secs = self.base_delay(retry).as_secs_f64() * (1.0 + spread);
+        Duration::from_secs_f64(secs.clamp(0.0, self.max_delay.as_secs_f64()))
+    }
+
+    /// Delay before retry `retry` (1-based), with random jitter.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.delay_with(retry, fastrand::f64())
+    }
+}
+
 /// Configuration for downloading SRTM tiles.
 #[derive(Debug, Clone)]
 pub struct DownloadConfig {
     /// The data source to download from.
     pub source: SrtmSource,
+    /// Additional sources tried in order when `source` fails.
+    pub fallbacks: Vec<SrtmSource>,
     /// Request timeout in seconds.
     pub timeout_secs: u64,
     /// Number of retry attempts on failure.
     pub max_retries: u32,
+    /// Delay between retry attempts.
+    pub retry_policy: RetryPolicy,
+    /// Custom `User-Agent` header (reqwest's default when `None`).
+    pub user_agent: Option<String>,
+    /// Extra HTTP headers sent with every request, as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+    /// Keep the downloaded `.hgt.gz`/`.hgt.zip` next to the extracted tile.
+    pub keep_archive: bool,
 }
 
 impl Default for DownloadConfig {
     fn default() -> Self {
         Self {
             source: SrtmSource::default(),
+            fallbacks: Vec::new(),
             timeout_secs: DEFAULT_TIMEOUT_SECS,
             max_retries: 3,
+            retry_policy: RetryPolicy::default(),
+            user_agent: None,
+            headers: Vec::new(),
+            keep_archive: false,
         }
     }
 }
@@ -229,6 +316,33 @@ impl DownloadConfig {
         }
     }
 
+    /// Create a configuration for the OpenTopography global DEM API.
+    ///
+    /// Requires an API key: <https://portal.opentopography.org/requestService>
+    ///
+    /// Tiles are fetched as SRTMGL1 GeoTIFFs and converted to `.hgt`.
+    pub fn open_topography(api_key: impl Into<String>) -> Self {
+        Self {
+            source: SrtmSource::OpenTopography {
+                api_key: api_key.into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Create a configuration for CGIAR-CSI SRTM v4.1 void-filled data.
+    ///
+    /// Uses <https://srtm.csi.cgiar.org/wp-content/uploads/files/srtm_5x5/TIFF/>
+    ///
+    /// Each download fetches the whole 5°×5° cell (~20-70MB) and extracts the
+    /// requested 1° SRTM3 tile from it.
+    pub fn cgiar_csi() -> Self {
+        Self {
+            source: SrtmSource::CgiarCsi,
+            ..Default::default()
+        }
+    }
+
     /// Create a configuration for ArduPilot terrain server (SRTM1 - high resolution).
     ///
     /// Uses <https://terrain.ardupilot.org/SRTM1/{continent}/{filename}.hgt.zip>
@@ -276,17 +390,116 @@ impl DownloadConfig {
         }
     }
 
+    /// Create a configuration from a named source.
+    ///
+    /// Recognized names (case-insensitive), as used by `HTG_DOWNLOAD_SOURCE`:
+    ///
+    /// - `ardupilot` or `ardupilot-srtm1` - [`ardupilot_srtm1`](Self::ardupilot_srtm1)
+    /// - `ardupilot-srtm3` - [`ardupilot_srtm3`](Self::ardupilot_srtm3)
+    ///
+    /// Returns `None` for unknown names.
+    pub fn from_source_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ardupilot" | "ardupilot-srtm1" => Some(Self::ardupilot_srtm1()),
+            "ardupilot-srtm3" => Some(Self::ardupilot_srtm3()),
+            _ => None,
+        }
+    }
+
+    /// Create a configuration that tries several sources in order.
+    ///
+    /// The first source is the primary; each following source is only tried
+    /// when all previous ones failed (after their retries). An empty list
+    /// yields the default (unconfigured) source.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use htg::download::{DownloadConfig, SrtmSource};
+    ///
+    /// let config = DownloadConfig::with_fallbacks(vec![
+    ///     SrtmSource::ArduPilotSrtm1,
+    ///     SrtmSource::CgiarCsi,
+    /// ]);
+    /// ```
+    pub fn with_fallbacks(sources: Vec<SrtmSource>) -> Self {
+        let mut sources = sources.into_iter();
+        Self {
+            source: sources.next().unwrap_or_default(),
+            fallbacks: sources.collect(),
+            ..Default::default()
+        }
+    }
+
+    /// All configured sources in the order they are tried.
+    pub fn sources(&self) -> impl Iterator<Item = &SrtmSource> {
+        std::iter::once(&self.source).chain(&self.fallbacks)
+    }
+
     /// Set the request timeout.
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = timeout_secs;
         self
     }
 
+    /// Set a custom `User-Agent` header.
+    ///
+    /// Some mirrors reject the default HTTP client user agent.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add an extra HTTP header sent with every download request.
+    ///
+    /// Can be called repeatedly to add several headers (e.g. `Referer`,
+    /// `Authorization`). NASA Earthdata basic auth is applied on top.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
     /// Set the maximum number of retry attempts.
     pub fn with_max_retries(mut self, max_retries: u32) -> Self {
         self.max_retries = max_retries;
         self
     }
+
+    /// Set the backoff between retry attempts.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Keep the raw archive after extracting the tile.
+    ///
+    /// When enabled, a zip or gzip download is also written to
+    /// `{dest}/{filename}.zip` or `{dest}/{filename}.gz` (e.g.
+    /// `N35E138.hgt.zip`), for checksum verification or redistribution. The
+    /// returned path is still the extracted `.hgt`. CGIAR-CSI and
+    /// OpenTopography downloads are converted rather than extracted, so
+    /// nothing extra is kept for them.
+    pub fn keep_archive(mut self, keep: bool) -> Self {
+        self.keep_archive = keep;
+        self
+    }
+}
+
+/// Write `data` to a temporary file and rename it into place, so an
+/// interrupted download never leaves a partial file at `path`.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let mut part_name = path.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    let part_path = path.with_file_name(part_name);
+    let result = File::create(&part_path)
+        .and_then(|mut file| file.write_all(data))
+        .and_then(|()| fs::rename(&part_path, path));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&part_path);
+        return Err(SrtmError::Io(e));
+    }
+    Ok(())
 }
 
 /// SRTM tile downloader.
@@ -298,13 +511,31 @@ pub struct Downloader {
 impl Downloader {
     /// Create a new downloader with the given configuration.
     pub fn new(config: DownloadConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_secs))
-            .build()
-            .map_err(|e| SrtmError::DownloadFailed {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name =
+                HeaderName::from_bytes(name.as_bytes()).map_err(|e| SrtmError::DownloadFailed {
+                    filename: String::new(),
+                    reason: format!("Invalid HTTP header name '{}': {}", name, e),
+                })?;
+            let value = HeaderValue::from_str(value).map_err(|e| SrtmError::DownloadFailed {
                 filename: String::new(),
-                reason: format!("Failed to create HTTP client: {}", e),
+                reason: format!("Invalid value for HTTP header '{}': {}", name, e),
             })?;
+            headers.append(name, value);
+        }
+
+        let mut builder = Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .default_headers(headers);
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+
+        let client = builder.build().map_err(|e| SrtmError::DownloadFailed {
+            filename: String::new(),
+            reason: format!("Failed to create HTTP client: {}", e),
+        })?;
 
         Ok(Self { client, config })
     }
@@ -319,8 +550,14 @@ impl Downloader {
     ///
     /// # Returns
     ///
-    /// The path to the downloaded `.hgt` file.
-    pub fn download_tile(&self, lat: f64, lon: f64, dest_dir: &Path) -> Result<std::path::PathBuf> {
+    /// The path to the downloaded `.hgt` file and the source that served it,
+    /// or `None` if the file was already present.
+    pub fn download_tile(
+        &self,
+        lat: f64,
+        lon: f64,
+        dest_dir: &Path,
+    ) -> Result<(std::path::PathBuf, Option<&SrtmSource>)> {
         let filename = lat_lon_to_filename(lat, lon);
         self.download_tile_by_name(&filename, dest_dir)
     }
@@ -331,35 +568,182 @@ impl Downloader {
     ///
     /// * `filename` - The tile filename (e.g., "N35E138.hgt")
     /// * `dest_dir` - Directory to save the downloaded file
+    ///
+    /// # Returns
+    ///
+    /// As for [`Self::download_tile`].
     pub fn download_tile_by_name(
         &self,
         filename: &str,
         dest_dir: &Path,
-    ) -> Result<std::path::PathBuf> {
+    ) -> Result<(std::path::PathBuf, Option<&SrtmSource>)> {
+        self.download_tile_with_progress(filename, dest_dir, |_, _| {})
+    }
+
+    /// Download a tile by its filename, reporting progress as the body streams in.
+    ///
+    /// The callback receives `(bytes_so_far, content_length)` after each chunk.
+    /// `content_length` is `None` when the server does not send a
+    /// `Content-Length` header. Byte counts refer to the transferred (possibly
+    /// compressed) payload. On retry the count restarts from zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The tile filename (e.g., "N35E138.hgt")
+    /// * `dest_dir` - Directory to save the downloaded file
+    /// * `on_progress` - Called with `(bytes_so_far, content_length)`
+    ///
+    /// # Returns
+    ///
+    /// As for [`Self::download_tile`].
+    pub fn download_tile_with_progress(
+        &self,
+        filename: &str,
+        dest_dir: &Path,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<(std::path::PathBuf, Option<&SrtmSource>)> {
         // Remove .hgt extension if present for URL building
         let base_name = filename.strip_suffix(".hgt").unwrap_or(filename);
-
-        let url = self.build_url(base_name)?;
         let dest_path = dest_dir.join(format!("{}.hgt", base_name));
 
         // Skip if file already exists
         if dest_path.exists() {
-            return Ok(dest_path);
+            return Ok((dest_path, None));
         }
 
         // Ensure destination directory exists
         fs::create_dir_all(dest_dir)?;
 
-        // Download with retries
+        // Try each source in order, collecting failures
+        let mut errors = Vec::new();
+        for source in self.config.sources() {
+            let result = self.build_url_for(source, base_name).and_then(|url| {
+                self.download_with_retries(source, &url, &dest_path, &mut on_progress)
+            });
+
+            match result {
+                Ok(()) => return Ok((dest_path, Some(source))),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // A single source keeps its original error; multiple sources are aggregated
+        if errors.len() == 1 {
+            return Err(errors.remove(0));
+        }
+
+        Err(SrtmError::DownloadFailed {
+            filename: filename.to_string(),
+            reason: errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("source {}: {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("; "),
+        })
+    }
+
+    /// Check whether a tile is available without downloading it.
+    ///
+    /// Sends an HTTP `HEAD` request to the tile's URL, falling back to a
+    /// `GET` for the first byte when the server does not support `HEAD`. A
+    /// success status means the tile exists and `404 Not Found` means it
+    /// does not; with fallbacks configured, each source is asked in turn.
+    /// Useful for skipping ocean tiles, which no mirror has, before a large
+    /// download. For CGIAR-CSI and OpenTopography this only confirms that
+    /// the archive or API endpoint responds.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The tile filename (e.g., "N35E138.hgt")
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::DownloadFailed`] if no source reports the tile
+    /// and at least one answered with something other than `404` (or could
+    /// not be reached), since its absence is then unknown.
+    pub fn tile_exists(&self, filename: &str) -> Result<bool> {
+        let base_name = filename.strip_suffix(".hgt").unwrap_or(filename);
+
+        let mut errors = Vec::new();
+        for source in self.config.sources() {
+            let result = self
+                .build_url_for(source, base_name)
+                .and_then(|url| self.probe(source, &url, filename));
+            match result {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(false),
+            1 => Err(errors.remove(0)),
+            _ => Err(SrtmError::DownloadFailed {
+                filename: filename.to_string(),
+                reason: errors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| format!("source {}: {}", i + 1, e))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            }),
+        }
+    }
+
+    /// Ask one source whether it has a tile: `Ok(false)` on 404, an error on
+    /// any other failure.
+    fn probe(&self, source: &SrtmSource, url: &str, filename: &str) -> Result<bool> {
+        let mut status = self.request(Method::HEAD, source, url).send()?.status();
+        if matches!(
+            status,
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+        ) {
+            status = self
+                .request(Method::GET, source, url)
+                .header(RANGE, "bytes=0-0")
+                .send()?
+                .status();
+        }
+
+        match status {
+            s if s.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            s => Err(SrtmError::DownloadFailed {
+                filename: filename.to_string(),
+                reason: format!("HTTP {}", s),
+            }),
+        }
+    }
+
+    /// Start a request to `url`, authenticating if the source requires it.
+    fn request(&self, method: Method, source: &SrtmSource, url: &str) -> RequestBuilder {
+        let request = self.client.request(method, url);
+        match source {
+            SrtmSource::NasaEarthdata { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+            _ => request,
+        }
+    }
+
+    /// Download from a single source, retrying per the configured [`RetryPolicy`].
+    fn download_with_retries(
+        &self,
+        source: &SrtmSource,
+        url: &str,
+        dest_path: &Path,
+        on_progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<()> {
         let mut last_error = None;
         for attempt in 0..=self.config.max_retries {
             if attempt > 0 {
-                // Brief delay before retry
-                std::thread::sleep(std::time::Duration::from_millis(500 * attempt as u64));
+                std::thread::sleep(self.config.retry_policy.delay(attempt));
             }
 
-            match self.do_download(&url, &dest_path) {
-                Ok(()) => return Ok(dest_path),
+            match self.do_download(source, url, dest_path, on_progress) {
+                Ok(()) => return Ok(()),
                 Err(e) => {
                     last_error = Some(e);
                 }
@@ -367,17 +751,26 @@ impl Downloader {
         }
 
         Err(last_error.unwrap_or_else(|| SrtmError::DownloadFailed {
-            filename: filename.to_string(),
+            filename: dest_path
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
             reason: "Unknown error".to_string(),
         }))
     }
 
-    /// Build the download URL for a tile.
+    /// Build the download URL for a tile from the primary source.
+    #[cfg(test)]
     fn build_url(&self, base_name: &str) -> Result<String> {
+        self.build_url_for(&self.config.source, base_name)
+    }
+
+    /// Build the download URL for a tile from the given source.
+    fn build_url_for(&self, source: &SrtmSource, base_name: &str) -> Result<String> {
         // Parse components from filename (e.g., "N35E138")
         let (lat_prefix, lat_str, lon_prefix, lon_str) = parse_filename_components(base_name)?;
 
-        match &self.config.source {
+        match source {
             SrtmSource::ArduPilotSrtm1 => {
                 // SRTM1 uses flat structure (no continent subdirectories)
                 Ok(format!(
@@ -411,6 +804,35 @@ impl Downloader {
                     base_name
                 ))
             }
+            SrtmSource::OpenTopography { api_key } => {
+                // Request exactly the 1° bounding box of the tile
+                let lat = parse_coord_from_components(lat_prefix, lat_str);
+                let lon = parse_coord_from_components(lon_prefix, lon_str);
+                Ok(format!(
+                    "https://portal.opentopography.org/API/globaldem?demtype=SRTMGL1&south={}&north={}&west={}&east={}&outputFormat=GTiff&API_Key={}",
+                    lat,
+                    lat + 1.0,
+                    lon,
+                    lon + 1.0,
+                    api_key
+                ))
+            }
+            SrtmSource::CgiarCsi => {
+                let lat = parse_coord_from_components(lat_prefix, lat_str) as i32;
+                let lon = parse_coord_from_components(lon_prefix, lon_str) as i32;
+                let (x, y) =
+                    cgiar_cell_index(lat, lon).ok_or_else(|| SrtmError::DownloadFailed {
+                        filename: format!("{}.hgt", base_name),
+                        reason: format!(
+                            "Coordinates ({}, {}) are outside CGIAR-CSI coverage (60N to 60S)",
+                            lat, lon
+                        ),
+                    })?;
+                Ok(format!(
+                    "https://srtm.csi.cgiar.org/wp-content/uploads/files/srtm_5x5/TIFF/srtm_{:02}_{:02}.zip",
+                    x, y
+                ))
+            }
             SrtmSource::Custom { url_template, .. } => {
                 if url_template.is_empty() {
                     return Err(SrtmError::DownloadFailed {
@@ -442,15 +864,14 @@ impl Downloader {
     }
 
     /// Perform the actual download.
-    fn do_download(&self, url: &str, dest_path: &Path) -> Result<()> {
-        let mut request = self.client.get(url);
-
-        // Add authentication if needed
-        if let SrtmSource::NasaEarthdata { username, password } = &self.config.source {
-            request = request.basic_auth(username, Some(password));
-        }
-
-        let response = request.send()?;
+    fn do_download(
+        &self,
+        source: &SrtmSource,
+        url: &str,
+        dest_path: &Path,
+        on_progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<()> {
+        let mut response = self.request(Method::GET, source, url).send()?;
 
         if !response.status().is_success() {
             return Err(SrtmError::DownloadFailed {
@@ -462,14 +883,27 @@ impl Downloader {
             });
         }
 
-        let bytes = response.bytes()?;
+        // Stream the body so callers can report progress on large tiles
+        let total = response.content_length();
+        let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+        let mut chunk = [0u8; 64 * 1024];
+        loop {
+            let n = response.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            bytes.extend_from_slice(&chunk[..n]);
+            on_progress(bytes.len() as u64, total);
+        }
 
         // Determine compression format
-        let compression = match &self.config.source {
+        let compression = match source {
             SrtmSource::Custom { compression, .. } => *compression,
             SrtmSource::ArduPilotSrtm1
             | SrtmSource::ArduPilotSrtm3
-            | SrtmSource::NasaEarthdata { .. } => Compression::Zip,
+            | SrtmSource::NasaEarthdata { .. }
+            | SrtmSource::CgiarCsi => Compression::Zip,
+            SrtmSource::OpenTopography { .. } => Compression::None,
         };
 
         let filename = dest_path
@@ -477,8 +911,20 @@ impl Downloader {
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        // Only archives that hold the .hgt itself are worth keeping
+        let archive_ext = match compression {
+            Compression::Gzip => Some("gz"),
+            Compression::Zip if !matches!(source, SrtmSource::CgiarCsi) => Some("zip"),
+            _ => None,
+        };
+
         let decompressed = match compression {
-            Compression::None => bytes.to_vec(),
+            // OpenTopography returns a GeoTIFF rather than a raw .hgt
+            Compression::None if matches!(source, SrtmSource::OpenTopography { .. }) => {
+                geotiff_to_hgt(&bytes, &filename)?
+            }
+            // Never an archive, so the download is not needed afterwards
+            Compression::None => std::mem::take(&mut bytes),
             Compression::Gzip => {
                 let mut decoder = GzDecoder::new(&bytes[..]);
                 let mut data = Vec::new();
@@ -490,13 +936,37 @@ impl Downloader {
                     })?;
                 data
             }
+            // CGIAR-CSI ships a 5°x5° GeoTIFF; cut out the requested tile
+            Compression::Zip if matches!(source, SrtmSource::CgiarCsi) => {
+                let tiff = Self::extract_from_zip(&bytes, &filename, ".tif")?;
+                let (lat, lon) =
+                    filename_to_lat_lon(&filename).ok_or_else(|| SrtmError::DownloadFailed {
+                        filename: filename.clone(),
+                        reason: "Invalid tile filename".to_string(),
+                    })?;
+                cgiar_extract_tile(&tiff, lat, lon, &filename)?
+            }
             Compression::Zip => Self::extract_hgt_from_zip(&bytes, &filename)?,
         };
 
-        let mut file = File::create(dest_path)?;
-        file.write_all(&decompressed)?;
+        // Reject truncated archives or HTML error pages before touching the destination
+        if SrtmResolution::from_file_size(decompressed.len()).is_none() {
+            return Err(SrtmError::DownloadFailed {
+                filename,
+                reason: format!(
+                    "Unexpected tile size: {} bytes (expected {} for SRTM1 or {} for SRTM3)",
+                    decompressed.len(),
+                    SRTM1_SIZE,
+                    SRTM3_SIZE
+                ),
+            });
+        }
+
+        if let Some(ext) = archive_ext.filter(|_| self.config.keep_archive) {
+            write_atomic(&dest_path.with_extension(format!("hgt.{}", ext)), &bytes)?;
+        }
 
-        Ok(())
+        write_atomic(dest_path, &decompressed)
     }
 
     /// Extract an .hgt file from a ZIP archive.
@@ -504,13 +974,17 @@ impl Downloader {
     /// Searches the archive for a file ending in ".hgt" (case-insensitive)
     /// and returns its contents.
     fn extract_hgt_from_zip(data: &[u8], filename: &str) -> Result<Vec<u8>> {
+        Self::extract_from_zip(data, filename, ".hgt")
+    }
+
+    /// Extract the first file ending in `extension` (case-insensitive) from a ZIP archive.
+    fn extract_from_zip(data: &[u8], filename: &str, extension: &str) -> Result<Vec<u8>> {
         let cursor = Cursor::new(data);
         let mut archive = ZipArchive::new(cursor).map_err(|e| SrtmError::DownloadFailed {
             filename: filename.to_string(),
             reason: format!("Failed to read ZIP archive: {}", e),
         })?;
 
-        // Search for an .hgt file in the archive
         for i in 0..archive.len() {
             let mut zip_file = archive.by_index(i).map_err(|e| SrtmError::DownloadFailed {
                 filename: filename.to_string(),
@@ -518,13 +992,13 @@ impl Downloader {
             })?;
 
             let name = zip_file.name().to_lowercase();
-            if name.ends_with(".hgt") {
+            if name.ends_with(extension) {
                 let mut contents = Vec::new();
                 zip_file
                     .read_to_end(&mut contents)
                     .map_err(|e| SrtmError::DownloadFailed {
                         filename: filename.to_string(),
-                        reason: format!("Failed to extract .hgt from ZIP: {}", e),
+                        reason: format!("Failed to extract {} from ZIP: {}", extension, e),
                     })?;
                 return Ok(contents);
             }
@@ -532,7 +1006,7 @@ impl Downloader {
 
         Err(SrtmError::DownloadFailed {
             filename: filename.to_string(),
-            reason: "No .hgt file found in ZIP archive".to_string(),
+            reason: format!("No {} file found in ZIP archive", extension),
         })
     }
 }
@@ -609,6 +1083,43 @@ fn parse_coord_from_components(prefix: &str, value: &str) -> f64 {
     }
 }
 
+/// Map a 1° tile (southwest corner) to its enclosing CGIAR-CSI 5° cell `(XX, YY)`.
+///
+/// Columns count eastwards from 180°W starting at 1; rows count southwards
+/// from 60°N starting at 1. Returns `None` outside the 60°N–60°S coverage.
+pub fn cgiar_cell_index(lat: i32, lon: i32) -> Option<(u32, u32)> {
+    if !(-60..60).contains(&lat) || !(-180..180).contains(&lon) {
+        return None;
+    }
+    let x = (lon + 180).div_euclid(5) + 1;
+    let y = (59 - lat).div_euclid(5) + 1;
+    Some((x as u32, y as u32))
+}
+
+/// Cut the 1° tile with southwest corner `(lat, lon)` out of a CGIAR-CSI cell GeoTIFF.
+fn cgiar_extract_tile(tiff: &[u8], lat: i32, lon: i32, filename: &str) -> Result<Vec<u8>> {
+    // 3 arc-second cells: 1200 pixels per degree (6000 or 6001 per cell)
+    const PER_DEGREE: usize = 1200;
+
+    let cell = Raster::decode(tiff, filename)?;
+    if cell.width < 5 * PER_DEGREE || cell.height < 5 * PER_DEGREE {
+        return Err(SrtmError::DownloadFailed {
+            filename: filename.to_string(),
+            reason: format!(
+                "Unexpected CGIAR cell dimensions {}x{}",
+                cell.width, cell.height
+            ),
+        });
+    }
+
+    let cell_top = 60 - 5 * (59 - lat).div_euclid(5);
+    let cell_left = -180 + 5 * (lon + 180).div_euclid(5);
+    let row0 = (cell_top - (lat + 1)) as usize * PER_DEGREE;
+    let col0 = (lon - cell_left) as usize * PER_DEGREE;
+
+    cell.window(row0, col0, PER_DEGREE + 1).to_hgt(filename)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -656,6 +1167,41 @@ mod tests {
         assert_eq!(config.max_retries, 5);
     }
 
+    #[test]
+    fn test_retry_policy_exponential_and_bounded() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(1),
+            jitter: 0.25,
+        };
+
+        let base: Vec<_> = (1..=6).map(|retry| policy.base_delay(retry)).collect();
+        assert_eq!(
+            base,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(policy.base_delay(u32::MAX), Duration::from_secs(1));
+
+        // Jitter spreads ±25% around the base delay, never past the cap
+        assert_eq!(policy.delay_with(2, 0.0), Duration::from_millis(150));
+        assert_eq!(policy.delay_with(2, 0.5), Duration::from_millis(200));
+        assert_eq!(policy.delay_with(2, 1.0), Duration::from_millis(250));
+        assert_eq!(policy.delay_with(5, 1.0), Duration::from_secs(1));
+        for retry in 1..=10 {
+            let delay = policy.delay(retry);
+            assert!(delay >= policy.base_delay(retry).mul_f64(0.75));
+            assert!(delay <= policy.max_delay);
+        }
+
+        let config = DownloadConfig::ardupilot_srtm3().with_retry_policy(policy);
+        assert_eq!(config.retry_policy, policy);
+        assert_eq!(
+            DownloadConfig::default().retry_policy.base_delay(1),
+            Duration::from_millis(500)
+        );
+    }
+
     #[test]
     fn test_compression_from_url() {
         assert_eq!(Compression::from_url("file.hgt"), Compression::None);
@@ -780,6 +1326,23 @@ mod tests {
         assert!(matches!(config.source, SrtmSource::ArduPilotSrtm3));
     }
 
+    #[test]
+    fn test_from_source_name() {
+        assert!(matches!(
+            DownloadConfig::from_source_name("ArduPilot")
+                .unwrap()
+                .source,
+            SrtmSource::ArduPilotSrtm1
+        ));
+        assert!(matches!(
+            DownloadConfig::from_source_name("ardupilot-srtm3")
+                .unwrap()
+                .source,
+            SrtmSource::ArduPilotSrtm3
+        ));
+        assert!(DownloadConfig::from_source_name("nasa").is_none());
+    }
+
     #[test]
     fn test_build_url_ardupilot_srtm1() {
         let config = DownloadConfig::ardupilot_srtm1();
@@ -850,6 +1413,353 @@ mod tests {
         assert_eq!(url, "https://example.com/North_America/N36W117.hgt.zip");
     }
 
+    #[test]
+    fn test_build_url_open_topography() {
+        let config = DownloadConfig::open_topography("secret");
+        let downloader = Downloader::new(config).unwrap();
+
+        let url = downloader.build_url("N35E138").unwrap();
+        assert_eq!(
+            url,
+            "https://portal.opentopography.org/API/globaldem?demtype=SRTMGL1&south=35&north=36&west=138&east=139&outputFormat=GTiff&API_Key=secret"
+        );
+
+        let url = downloader.build_url("S12W077").unwrap();
+        assert!(url.contains("south=-12&north=-11&west=-77&east=-76"));
+    }
+
+    #[test]
+    fn test_cgiar_cell_index() {
+        // N35E138 lies in the cell spanning 135-140E, 35-40N
+        assert_eq!(cgiar_cell_index(35, 138), Some((64, 5)));
+        // First and last cells
+        assert_eq!(cgiar_cell_index(59, -180), Some((1, 1)));
+        assert_eq!(cgiar_cell_index(-60, 179), Some((72, 24)));
+        // Southern/western hemispheres
+        assert_eq!(cgiar_cell_index(-12, -77), Some((21, 15)));
+        // Outside coverage
+        assert_eq!(cgiar_cell_index(60, 0), None);
+        assert_eq!(cgiar_cell_index(-61, 0), None);
+    }
+
+    #[test]
+    fn test_build_url_cgiar_csi() {
+        let downloader = Downloader::new(DownloadConfig::cgiar_csi()).unwrap();
+
+        let url = downloader.build_url("N35E138").unwrap();
+        assert_eq!(
+            url,
+            "https://srtm.csi.cgiar.org/wp-content/uploads/files/srtm_5x5/TIFF/srtm_64_05.zip"
+        );
+
+        let result = downloader.build_url("N65E010");
+        assert!(matches!(result, Err(SrtmError::DownloadFailed { .. })));
+    }
+
+    #[test]
+    fn test_cgiar_extract_tile() {
+        // Synthetic 6001x6001 cell for srtm_64_05 (135-140E, 35-40N)
+        let mut data = vec![0i16; 6001 * 6001];
+        // NW corner of N35E138 = lat 36, lon 138 -> row 4 * 1200, col 3 * 1200
+        data[4800 * 6001 + 3600] = 777;
+        let tiff = crate::geotiff::tests::encode_i16(6001, 6001, &data);
+
+        let hgt = cgiar_extract_tile(&tiff, 35, 138, "N35E138.hgt").unwrap();
+        assert_eq!(hgt.len(), SRTM3_SIZE);
+        assert_eq!(i16::from_be_bytes([hgt[0], hgt[1]]), 777);
+    }
+
+    /// Build an in-memory ZIP archive containing a single `.hgt` entry.
+    fn zip_bytes(entry_name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut zip_buffer = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut zip_buffer));
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            zip.start_file(entry_name, options).unwrap();
+            zip.write_all(payload).unwrap();
+            zip.finish().unwrap();
+        }
+        zip_buffer
+    }
+
+    #[test]
+    fn test_download_rejects_truncated_tile() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/N35E138.hgt.zip")
+            .with_status(200)
+            .with_body(zip_bytes("N35E138.hgt", &[0u8; 1000]))
+            .create();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt.zip", server.url()))
+                .with_max_retries(0);
+        let downloader = Downloader::new(config).unwrap();
+
+        let result = downloader.download_tile_by_name("N35E138.hgt", temp_dir.path());
+        mock.assert();
+
+        match result {
+            Err(SrtmError::DownloadFailed { reason, .. }) => assert!(reason.contains("1000")),
+            other => panic!("Expected DownloadFailed, got {:?}", other.map(|_| ())),
+        }
+        assert!(!temp_dir.path().join("N35E138.hgt").exists());
+        assert!(!temp_dir.path().join("N35E138.hgt.part").exists());
+    }
+
+    #[test]
+    fn test_download_writes_valid_tile() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/N35E138.hgt.zip")
+            .with_status(200)
+            .with_body(zip_bytes("N35E138.hgt", &vec![0u8; SRTM3_SIZE]))
+            .create();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt.zip", server.url()));
+        let downloader = Downloader::new(config).unwrap();
+
+        let (path, _) = downloader
+            .download_tile_by_name("N35E138.hgt", temp_dir.path())
+            .unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len() as usize, SRTM3_SIZE);
+        assert!(!temp_dir.path().join("N35E138.hgt.part").exists());
+        assert!(!temp_dir.path().join("N35E138.hgt.zip").exists());
+    }
+
+    #[test]
+    fn test_tile_exists() {
+        let mut server = mockito::Server::new();
+        let present = server
+            .mock("HEAD", "/N35E138.hgt.zip")
+            .with_status(200)
+            .create();
+        server
+            .mock("HEAD", "/N00W150.hgt.zip")
+            .with_status(404)
+            .create();
+        server
+            .mock("HEAD", "/N36E138.hgt.zip")
+            .with_status(500)
+            .create();
+        // A server without HEAD support is asked for the first byte instead
+        server
+            .mock("HEAD", "/N37E138.hgt.zip")
+            .with_status(405)
+            .create();
+        let ranged = server
+            .mock("GET", "/N37E138.hgt.zip")
+            .match_header("range", "bytes=0-0")
+            .with_status(206)
+            .with_body([0u8])
+            .create();
+
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt.zip", server.url()));
+        let downloader = Downloader::new(config).unwrap();
+
+        assert!(downloader.tile_exists("N35E138.hgt").unwrap());
+        assert!(!downloader.tile_exists("N00W150").unwrap());
+        assert!(downloader.tile_exists("N37E138.hgt").unwrap());
+        match downloader.tile_exists("N36E138.hgt") {
+            Err(SrtmError::DownloadFailed { reason, .. }) => assert!(reason.contains("500")),
+            other => panic!("Expected DownloadFailed, got {:?}", other),
+        }
+        present.assert();
+        ranged.assert();
+    }
+
+    #[test]
+    fn test_download_keeps_archive() {
+        let archive = zip_bytes("N35E138.hgt", &vec![0u8; SRTM3_SIZE]);
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/N35E138.hgt.zip")
+            .with_status(200)
+            .with_body(&archive)
+            .create();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt.zip", server.url()))
+                .keep_archive(true);
+        let downloader = Downloader::new(config).unwrap();
+
+        let (path, _) = downloader
+            .download_tile_by_name("N35E138.hgt", temp_dir.path())
+            .unwrap();
+        assert_eq!(path, temp_dir.path().join("N35E138.hgt"));
+        assert_eq!(fs::metadata(&path).unwrap().len() as usize, SRTM3_SIZE);
+        assert_eq!(
+            fs::read(temp_dir.path().join("N35E138.hgt.zip")).unwrap(),
+            archive
+        );
+        assert!(!temp_dir.path().join("N35E138.hgt.zip.part").exists());
+    }
+
+    #[test]
+    fn test_download_reports_progress() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/N35E138.hgt")
+            .with_status(200)
+            .with_body(vec![0u8; SRTM3_SIZE])
+            .create();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config =
+            DownloadConfig::with_url_template(format!("{}/{{filename}}.hgt", server.url()));
+        let downloader = Downloader::new(config).unwrap();
+
+        let mut calls = 0;
+        let mut last = (0, None);
+        let (path, _) = downloader
+            .download_tile_with_progress("N35E138.hgt", temp_dir.path(), |done, total| {
+                calls += 1;
+                last = (done, total);
+            })
+            .unwrap();
+
+        let file_size = fs::metadata(&path).unwrap().len();
+        assert!(calls >= 1);
+        assert_eq!(last.0, file_size);
+        assert_eq!(last.1, Some(file_size));
+    }
+
+    #[test]
+    fn test_download_falls_back_to_second_source() {
+        let mut primary = mockito::Server::new();
+        let primary_mock = primary
+            .mock("GET", "/N35E138.hgt")
+            .with_status(503)
+            .create();
+        let mut mirror = mockito::Server::new();
+        mirror
+            .mock("GET", "/N35E138.hgt")
+            .with_status(200)
+            .with_body(vec![0u8; SRTM3_SIZE])
+            .create();
+
+        let source = |server: &mockito::Server| SrtmSource::Custom {
+            url_template: format!("{}/{{filename}}.hgt", server.url()),
+            compression: Compression::None,
+        };
+        let config = DownloadConfig::with_fallbacks(vec![source(&primary), source(&mirror)])
+            .with_max_retries(0);
+        let downloader = Downloader::new(config).unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (path, source) = downloader
+            .download_tile_by_name("N35E138.hgt", temp_dir.path())
+            .unwrap();
+
+        primary_mock.assert();
+        assert!(path.exists());
+        match source {
+            Some(SrtmSource::Custom { url_template, .. }) => {
+                assert!(url_template.starts_with(&mirror.url()))
+            }
+            other => panic!("Unexpected source: {:?}", other),
+        }
+
+        // Already on disk: nothing is downloaded
+        let (_, source) = downloader
+            .download_tile_by_name("N35E138.hgt", temp_dir.path())
+            .unwrap();
+        assert!(source.is_none());
+    }
+
+    #[test]
+    fn test_download_fallbacks_aggregate_errors() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(503)
+            .create();
+
+        let config = DownloadConfig::with_fallbacks(vec![
+            SrtmSource::Custom {
+                url_template: format!("{}/a/{{filename}}.hgt", server.url()),
+                compression: Compression::None,
+            },
+            SrtmSource::Custom {
+                url_template: format!("{}/b/{{filename}}.hgt", server.url()),
+                compression: Compression::None,
+            },
+        ])
+        .with_max_retries(0);
+        let downloader = Downloader::new(config).unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        match downloader.download_tile_by_name("N35E138.hgt", temp_dir.path()) {
+            Err(SrtmError::DownloadFailed { reason, .. }) => {
+                assert!(reason.contains("source 1"));
+                assert!(reason.contains("source 2"));
+                assert!(reason.contains("503"));
+            }
+            other => panic!("Expected DownloadFailed, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_download_sends_custom_headers() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/N35E138.hgt")
+            .with_status_code_from_request(|request| {
+                let agent_ok = request
+                    .header("user-agent")
+                    .iter()
+                    .any(|v| *v == "htg-test/1.0");
+                let referer_ok = request
+                    .header("referer")
+                    .iter()
+                    .any(|v| *v == "https://example.com");
+                if agent_ok && referer_ok {
+                    200
+                } else {
+                    403
+                }
+            })
+            .with_body(vec![0u8; SRTM3_SIZE])
+            .create();
+        let template = format!("{}/{{filename}}.hgt", server.url());
+
+        // Without the headers the mirror refuses the request
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let plain = Downloader::new(
+            DownloadConfig::with_url_template(template.clone()).with_max_retries(0),
+        )
+        .unwrap();
+        let result = plain.download_tile_by_name("N35E138.hgt", temp_dir.path());
+        assert!(
+            matches!(result, Err(SrtmError::DownloadFailed { reason, .. }) if reason.contains("403"))
+        );
+
+        let config = DownloadConfig::with_url_template(template)
+            .with_user_agent("htg-test/1.0")
+            .with_header("Referer", "https://example.com")
+            .with_max_retries(0);
+        let downloader = Downloader::new(config).unwrap();
+        let (path, _) = downloader
+            .download_tile_by_name("N35E138.hgt", temp_dir.path())
+            .unwrap();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_invalid_header_rejected() {
+        let config = DownloadConfig::ardupilot().with_header("bad header", "x");
+        assert!(matches!(
+            Downloader::new(config),
+            Err(SrtmError::DownloadFailed { .. })
+        ));
+    }
+
     #[test]
     fn test_parse_coord_from_components() {
         assert_eq!(parse_coord_from_components("N", "35"), 35.0);
@@ -14,6 +14,10 @@ pub enum SrtmError {
     #[error("Invalid file size: {size} bytes (expected 25934402 for SRTM1 or 2884802 for SRTM3)")]
     InvalidFileSize { size: usize },
 
+    /// File size doesn't match the requested number of samples per row/column.
+    #[error("Invalid file size: {size} bytes does not match {samples}x{samples} samples")]
+    SampleCountMismatch { size: usize, samples: usize },
+
     /// Coordinates are outside valid SRTM coverage.
     #[error("Coordinates out of bounds: lat={lat}, lon={lon} (valid: lat ±60°, lon ±180°)")]
     OutOfBounds { lat: f64, lon: f64 },
@@ -39,6 +43,16 @@ pub enum SrtmError {
     /// Invalid coordinate format (e.g., too few elements).
     #[error("Invalid coordinate: {message}")]
     InvalidCoordinate { message: String },
+
+    /// Coordinate reference system not supported by the reprojection helpers.
+    #[cfg(feature = "proj")]
+    #[error("Unsupported CRS: EPSG:{epsg} (supported: 4326, 3857, 32601-32660, 32701-32760)")]
+    UnsupportedCrs { epsg: u32 },
+
+    /// The EGM96 geoid grid is not installed or could not be loaded.
+    #[cfg(feature = "geoid")]
+    #[error("EGM96 geoid unavailable: {reason}")]
+    GeoidUnavailable { reason: String },
 }
 
 /// Result type alias using [`SrtmError`].
@@ -48,8 +48,74 @@ pub fn coords_to_filename(lat_int: i32, lon_int: i32) -> String {
     )
 }
 
+/// Wrap a longitude into `[-180, 180)`.
+///
+/// Longitudes already in range are returned unchanged; others are shifted by
+/// a multiple of 360°, so `181.0` becomes `-179.0` and `-190.0` becomes
+/// `170.0`. The antimeridian always maps to `-180.0`: both `180.0` and
+/// `-180.0` select the `W180` tile, whose western edge is that meridian
+/// (there is no `E180` tile). Non-finite values are returned as-is.
+///
+/// # Examples
+///
+/// ```
+/// use htg::filename::normalize_lon;
+///
+/// assert_eq!(normalize_lon(138.5), 138.5);
+/// assert_eq!(normalize_lon(181.0), -179.0);
+/// assert_eq!(normalize_lon(180.0), -180.0);
+/// assert_eq!(normalize_lon(-180.0), -180.0);
+/// ```
+pub fn normalize_lon(lon: f64) -> f64 {
+    if (-180.0..180.0).contains(&lon) || !lon.is_finite() {
+        return lon;
+    }
+    let wrapped = (lon + 180.0).rem_euclid(360.0) - 180.0;
+    // rem_euclid can round up to exactly 360 for values just below a multiple
+    if wrapped >= 180.0 {
+        wrapped - 360.0
+    } else {
+        wrapped
+    }
+}
+
+/// Southwest corner `(lat, lon)` of the tile whose north or east edge a
+/// coordinate lies on, if any.
+///
+/// Neighboring tiles share their edge samples, so a coordinate on a whole
+/// degree lies on two tiles (four at a corner). [`SrtmService`](crate::SrtmService)
+/// reads it from the tile whose north and east edges it is first: `lat = 36.0`
+/// is row 0 of `N35`, and `lon = 139.0` the last column of `E138`. Only where
+/// there is no tile to the south or west does the coordinate go to the
+/// tile it starts: `-90.0` to `S90`, and `-180.0` to `W180`. Off whole
+/// degrees this is the tile containing the coordinate, as with
+/// [`lat_lon_to_filename`].
+///
+/// The longitude is wrapped with [`normalize_lon`] first, so `180.0` is the
+/// western edge of `W180`.
+///
+/// # Examples
+///
+/// ```
+/// use htg::filename::tile_origin;
+///
+/// assert_eq!(tile_origin(35.5, 138.7), (35, 138));
+/// assert_eq!(tile_origin(36.0, 139.0), (35, 138));
+/// assert_eq!(tile_origin(0.5, 180.0), (0, -180));
+/// ```
+pub fn tile_origin(lat: f64, lon: f64) -> (i32, i32) {
+    let lon = normalize_lon(lon);
+    (
+        (lat.ceil() as i32 - 1).max(-90),
+        (lon.ceil() as i32 - 1).max(-180),
+    )
+}
+
 /// Convert latitude and longitude to an SRTM `.hgt` filename.
 ///
+/// The longitude is wrapped with [`normalize_lon`] first, so `180.0` gives
+/// a `W180` filename.
+///
 /// # Arguments
 ///
 /// * `lat` - Latitude in decimal degrees (-60 to 60)
@@ -67,9 +133,10 @@ pub fn coords_to_filename(lat_int: i32, lon_int: i32) -> String {
 /// assert_eq!(lat_lon_to_filename(35.5, 138.7), "N35E138.hgt");
 /// assert_eq!(lat_lon_to_filename(-12.3, -77.1), "S13W078.hgt");
 /// assert_eq!(lat_lon_to_filename(0.5, -0.5), "N00W001.hgt");
+/// assert_eq!(lat_lon_to_filename(0.5, 180.0), "N00W180.hgt");
 /// ```
 pub fn lat_lon_to_filename(lat: f64, lon: f64) -> String {
-    coords_to_filename(lat.floor() as i32, lon.floor() as i32)
+    coords_to_filename(lat.floor() as i32, normalize_lon(lon).floor() as i32)
 }
 
 /// Parse an SRTM filename to extract the base coordinates.
@@ -78,6 +145,10 @@ pub fn lat_lon_to_filename(lat: f64, lon: f64) -> String {
 ///
 /// * `filename` - The filename (with or without path, with or without extension)
 ///
+/// A trailing `.hgt`, `.hgt.zip`, or `.hgt.gz`, and then a NASA Earthdata
+/// `.SRTMGL1`/`.SRTMGL3` infix, are stripped (case-insensitively) before the
+/// 7-character `N00E000` core is validated.
+///
 /// # Returns
 ///
 /// The (latitude, longitude) of the southwest corner, or `None` if parsing fails.
@@ -90,6 +161,7 @@ pub fn lat_lon_to_filename(lat: f64, lon: f64) -> String {
 /// assert_eq!(filename_to_lat_lon("N35E138.hgt"), Some((35, 138)));
 /// assert_eq!(filename_to_lat_lon("S12W077.hgt"), Some((-12, -77)));
 /// assert_eq!(filename_to_lat_lon("/path/to/N00E000.hgt"), Some((0, 0)));
+/// assert_eq!(filename_to_lat_lon("N35E138.SRTMGL1.hgt.zip"), Some((35, 138)));
 /// assert_eq!(filename_to_lat_lon("invalid"), None);
 /// ```
 pub fn filename_to_lat_lon(filename: &str) -> Option<(i32, i32)> {
@@ -102,28 +174,31 @@ pub fn filename_to_lat_lon(filename: &str) -> Option<(i32, i32)> {
         .next()
         .unwrap_or(filename);
 
-    // Remove .hgt extension if present
-    let name = name.strip_suffix(".hgt").unwrap_or(name);
+    // Remove the extension and NASA product infix if present
+    let name = strip_suffix_ignore_case(name, &[".hgt.zip", ".hgt.gz", ".hgt"]);
+    let name = strip_suffix_ignore_case(name, &[".srtmgl1", ".srtmgl3"]);
 
-    // Must be exactly 7 characters: N00E000
-    if name.len() != 7 {
+    // Must be exactly 7 ASCII characters: N00E000
+    let bytes = name.as_bytes();
+    if bytes.len() != 7
+        || !bytes[1..3].iter().all(u8::is_ascii_digit)
+        || !bytes[4..7].iter().all(u8::is_ascii_digit)
+    {
         return None;
     }
 
-    let chars: Vec<char> = name.chars().collect();
-
     // Parse latitude
-    let lat_sign = match chars[0] {
-        'N' | 'n' => 1,
-        'S' | 's' => -1,
+    let lat_sign = match bytes[0] {
+        b'N' | b'n' => 1,
+        b'S' | b's' => -1,
         _ => return None,
     };
     let lat: i32 = name[1..3].parse().ok()?;
 
     // Parse longitude
-    let lon_sign = match chars[3] {
-        'E' | 'e' => 1,
-        'W' | 'w' => -1,
+    let lon_sign = match bytes[3] {
+        b'E' | b'e' => 1,
+        b'W' | b'w' => -1,
         _ => return None,
     };
     let lon: i32 = name[4..7].parse().ok()?;
@@ -131,6 +206,20 @@ pub fn filename_to_lat_lon(filename: &str) -> Option<(i32, i32)> {
     Some((lat * lat_sign, lon * lon_sign))
 }
 
+/// Strip the first of `suffixes` (lowercase ASCII) that `name` ends with,
+/// ignoring case.
+fn strip_suffix_ignore_case<'a>(name: &'a str, suffixes: &[&str]) -> &'a str {
+    for suffix in suffixes {
+        let split = name.len().checked_sub(suffix.len());
+        if let Some(tail) = split.and_then(|i| name.get(i..)) {
+            if tail.eq_ignore_ascii_case(suffix) {
+                return &name[..name.len() - suffix.len()];
+            }
+        }
+    }
+    name
+}
+
 /// Validate that coordinates are within SRTM coverage.
 ///
 /// SRTM data covers latitudes from -60° to +60° and all longitudes.
@@ -147,10 +236,76 @@ pub fn is_valid_srtm_coord(lat: f64, lon: f64) -> bool {
     (-60.0..=60.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
 }
 
+/// List the filenames of all tiles overlapping a bounding box.
+///
+/// Tiles are chosen as with [`tile_origin`], so these are the tiles
+/// [`SrtmService`](crate::SrtmService) reads the box from: a whole-degree
+/// `min_lat` or `min_lon` also pulls in the tile to the south or west, whose
+/// edge it is, while a whole-degree `max_lat` or `max_lon` (e.g. `36.0` and
+/// tile `N36`) doesn't. The box is clipped to SRTM coverage (±60° latitude);
+/// an inverted box yields no tiles.
+///
+/// # Examples
+///
+/// ```
+/// use htg::filename::tiles_covering_bbox;
+///
+/// assert_eq!(
+///     tiles_covering_bbox(35.5, 138.5, 36.5, 139.0),
+///     vec!["N35E138.hgt", "N36E138.hgt"]
+/// );
+/// ```
+pub fn tiles_covering_bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Vec<String> {
+    if !(min_lat <= max_lat && min_lon <= max_lon) {
+        return Vec::new();
+    }
+
+    // Tile index along an axis, as with `tile_origin`
+    let origin = |v: f64| v.ceil() as i32 - 1;
+    let lat_range = origin(min_lat).max(-60)..=origin(max_lat).min(59);
+    let lon_range = origin(min_lon).max(-180)..=origin(max_lon).min(179);
+
+    lat_range
+        .flat_map(|lat| {
+            lon_range
+                .clone()
+                .map(move |lon| coords_to_filename(lat, lon))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_tiles_covering_bbox() {
+        assert_eq!(
+            tiles_covering_bbox(35.2, 138.2, 35.8, 138.8),
+            vec!["N35E138.hgt"]
+        );
+        assert_eq!(
+            tiles_covering_bbox(-0.5, -0.5, 0.5, 0.5),
+            vec!["S01W001.hgt", "S01E000.hgt", "N00W001.hgt", "N00E000.hgt"]
+        );
+        // Whole-degree minimums pull in the tiles whose edge they are;
+        // maximums that only touch a tile don't
+        assert_eq!(
+            tiles_covering_bbox(35.0, 138.0, 36.0, 139.0),
+            vec!["N34E137.hgt", "N34E138.hgt", "N35E137.hgt", "N35E138.hgt"]
+        );
+        assert_eq!(
+            tiles_covering_bbox(35.5, 138.5, 36.0, 139.0),
+            vec!["N35E138.hgt"]
+        );
+        // Clipped to coverage
+        assert_eq!(
+            tiles_covering_bbox(59.5, 10.2, 65.0, 10.4),
+            vec!["N59E010.hgt"]
+        );
+        assert!(tiles_covering_bbox(36.0, 138.0, 35.0, 139.0).is_empty());
+    }
+
     #[test]
     fn test_coords_to_filename() {
         assert_eq!(coords_to_filename(35, 138), "N35E138.hgt");
@@ -202,6 +357,36 @@ mod tests {
         assert_eq!(lat_lon_to_filename(-0.1, -0.1), "S01W001.hgt");
     }
 
+    #[test]
+    fn test_tile_origin() {
+        assert_eq!(tile_origin(35.5, 138.7), (35, 138));
+        // On a tile boundary: the tile whose north and east edges it is
+        assert_eq!(tile_origin(35.0, 138.0), (34, 137));
+        assert_eq!(tile_origin(36.0, 138.5), (35, 138));
+        assert_eq!(tile_origin(0.0, 0.0), (-1, -1));
+        // Nothing lies south of -90 or west of -180
+        assert_eq!(tile_origin(-90.0, -180.0), (-90, -180));
+        assert_eq!(tile_origin(-60.0, 180.0), (-61, -180));
+    }
+
+    #[test]
+    fn test_normalize_lon() {
+        assert_eq!(normalize_lon(0.0), 0.0);
+        assert_eq!(normalize_lon(179.999), 179.999);
+        assert_eq!(normalize_lon(180.0), -180.0);
+        assert_eq!(normalize_lon(-180.0), -180.0);
+        assert_eq!(normalize_lon(190.0), -170.0);
+        assert_eq!(normalize_lon(-190.0), 170.0);
+        assert_eq!(normalize_lon(540.0), -180.0);
+        assert_eq!(normalize_lon(-1e-17 - 180.0), -180.0);
+        assert!(normalize_lon(f64::NAN).is_nan());
+
+        assert_eq!(lat_lon_to_filename(35.5, 180.0), "N35W180.hgt");
+        assert_eq!(lat_lon_to_filename(35.5, -180.0), "N35W180.hgt");
+        assert_eq!(lat_lon_to_filename(35.5, 190.0), "N35W170.hgt");
+        assert_eq!(lat_lon_to_filename(35.5, 181.0), "N35W179.hgt");
+    }
+
     #[test]
     fn test_parse_filename() {
         assert_eq!(filename_to_lat_lon("N35E138.hgt"), Some((35, 138)));
@@ -231,6 +416,49 @@ mod tests {
         assert_eq!(filename_to_lat_lon("NAAE138.hgt"), None); // Non-numeric
     }
 
+    #[test]
+    fn test_parse_nasa_and_compressed_names() {
+        assert_eq!(filename_to_lat_lon("N35E138.SRTMGL1.hgt"), Some((35, 138)));
+        assert_eq!(
+            filename_to_lat_lon("S12W077.SRTMGL3.hgt.zip"),
+            Some((-12, -77))
+        );
+        assert_eq!(filename_to_lat_lon("n35e138.hgt.zip"), Some((35, 138)));
+        assert_eq!(filename_to_lat_lon("N35E138.HGT.GZ"), Some((35, 138)));
+        assert_eq!(filename_to_lat_lon("n35e138.srtmgl1"), Some((35, 138)));
+
+        // Only one extension and one infix are stripped, in that order
+        assert_eq!(filename_to_lat_lon("N35E138.hgt.hgt"), None);
+        assert_eq!(filename_to_lat_lon("N35E138.hgt.SRTMGL1"), None);
+        assert_eq!(filename_to_lat_lon("N35E138.SRTMGL2.hgt"), None);
+        assert_eq!(filename_to_lat_lon("N35E138.zip"), None);
+    }
+
+    #[test]
+    fn test_parse_filename_rejects_malformed_without_panicking() {
+        for name in [
+            "",
+            ".hgt",
+            ".SRTMGL1.hgt",
+            "N+5E138.hgt",
+            "N35E-38.hgt",
+            "N 5E138.hgt",
+            "N3\u{e9}138.hgt",
+            "\u{e9}35E138",
+            "N35\u{e9}13",
+            "N35E1\u{1f600}",
+            "hgt.N35E138",
+        ] {
+            assert_eq!(filename_to_lat_lon(name), None, "{:?}", name);
+        }
+
+        // Every truncation of a valid name either parses or is rejected cleanly
+        let name = "dir/n35e138.SRTMGL1.hgt.zip";
+        for end in 0..=name.len() {
+            let _ = filename_to_lat_lon(&name[..end]);
+        }
+    }
+
     #[test]
     fn test_parse_case_insensitive() {
         assert_eq!(filename_to_lat_lon("n35e138.hgt"), Some((35, 138)));
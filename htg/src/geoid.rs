@@ -0,0 +1,238 @@
+//! EGM96 geoid undulation for converting SRTM heights to ellipsoidal heights.
+//!
+//! SRTM elevations are orthometric: they are measured above the EGM96 geoid.
+//! GPS receivers report heights above the WGS84 ellipsoid. The two differ by
+//! the geoid undulation `N`, which ranges from about -107m to +85m:
+//!
+//! ```text
+//! ellipsoidal height = orthometric height + N
+//! ```
+//!
+//! Undulations come from the NGA 15-minute EGM96 grid (`WW15MGH.DAC`),
+//! bilinearly interpolated. The grid is not distributed with this crate. With
+//! the `geoid-bundled` feature it is embedded in the binary at build time
+//! from the file named by `HTG_EGM96_PATH`; otherwise load it with
+//! [`GeoidGrid::from_file`] and [`install_egm96`], or point `HTG_EGM96_PATH`
+//! at it at runtime.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::geoid::{egm96_undulation, install_egm96, GeoidGrid};
+//!
+//! install_egm96(GeoidGrid::from_file("/data/WW15MGH.DAC")?);
+//! let n = egm96_undulation(0.0, 0.0)?; // ~17.16m
+//! ```
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::error::{Result, SrtmError};
+
+/// Rows in the 15-minute EGM96 grid (90°N to 90°S).
+const EGM96_ROWS: usize = 721;
+
+/// Columns in the 15-minute EGM96 grid (0°E to 359.75°E).
+const EGM96_COLS: usize = 1440;
+
+/// Grid spacing in degrees.
+const EGM96_SPACING: f64 = 0.25;
+
+/// Environment variable naming the `WW15MGH.DAC` file to load on first use,
+/// or to embed when building with the `geoid-bundled` feature.
+pub const EGM96_PATH_ENV: &str = "HTG_EGM96_PATH";
+
+/// The `WW15MGH.DAC` grid embedded at build time.
+#[cfg(feature = "geoid-bundled")]
+const BUNDLED_DAC: &[u8] = include_bytes!(env!(
+    "HTG_EGM96_PATH",
+    "the `geoid-bundled` feature embeds WW15MGH.DAC: set HTG_EGM96_PATH to it when building"
+));
+
+#[cfg(feature = "geoid-bundled")]
+const _: () = assert!(
+    BUNDLED_DAC.len() == EGM96_ROWS * EGM96_COLS * 2,
+    "HTG_EGM96_PATH is not a 15-minute EGM96 grid (WW15MGH.DAC)"
+);
+
+/// A global grid of geoid undulations.
+#[derive(Debug, Clone)]
+pub struct GeoidGrid {
+    /// Undulations in centimeters, row-major from the north pole and 0°E.
+    data: Vec<i16>,
+}
+
+impl GeoidGrid {
+    /// Parse the NGA `WW15MGH.DAC` file: 721 × 1440 big-endian `i16`
+    /// undulations in centimeters, rows from 90°N to 90°S, columns eastwards
+    /// from 0°E at 15-minute spacing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::GeoidUnavailable`] if `bytes` is not exactly one
+    /// grid long.
+    pub fn from_dac_bytes(bytes: &[u8]) -> Result<Self> {
+        let expected = EGM96_ROWS * EGM96_COLS * 2;
+        if bytes.len() != expected {
+            return Err(SrtmError::GeoidUnavailable {
+                reason: format!(
+                    "EGM96 grid is {} bytes, expected {} (WW15MGH.DAC)",
+                    bytes.len(),
+                    expected
+                ),
+            });
+        }
+
+        let data = bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_be_bytes([b[0], b[1]]))
+            .collect();
+        Ok(Self { data })
+    }
+
+    /// The grid embedded at build time with the `geoid-bundled` feature.
+    #[cfg(feature = "geoid-bundled")]
+    pub fn bundled() -> Self {
+        Self::from_dac_bytes(BUNDLED_DAC).expect("bundled grid size is checked at build time")
+    }
+
+    /// Load a `WW15MGH.DAC` file. See [`from_dac_bytes`](Self::from_dac_bytes).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|e| SrtmError::GeoidUnavailable {
+            reason: format!("Failed to read {}: {}", path.as_ref().display(), e),
+        })?;
+        Self::from_dac_bytes(&bytes)
+    }
+
+    /// Geoid undulation in meters at `(lat, lon)`, bilinearly interpolated.
+    ///
+    /// Latitude is clamped to ±90°; longitude wraps around.
+    pub fn undulation(&self, lat: f64, lon: f64) -> f64 {
+        let row = (90.0 - lat.clamp(-90.0, 90.0)) / EGM96_SPACING;
+        let col = lon.rem_euclid(360.0) / EGM96_SPACING;
+
+        let row0 = (row.floor() as usize).min(EGM96_ROWS - 2);
+        let col0 = col.floor() as usize % EGM96_COLS;
+        let col1 = (col0 + 1) % EGM96_COLS;
+        let dr = row - row0 as f64;
+        let dc = col - col.floor();
+
+        let at = |r: usize, c: usize| self.data[r * EGM96_COLS + c] as f64 / 100.0;
+        let north = at(row0, col0) * (1.0 - dc) + at(row0, col1) * dc;
+        let south = at(row0 + 1, col0) * (1.0 - dc) + at(row0 + 1, col1) * dc;
+        north * (1.0 - dr) + south * dr
+    }
+}
+
+static EGM96: OnceLock<std::result::Result<GeoidGrid, String>> = OnceLock::new();
+
+/// Install the EGM96 grid used by [`egm96_undulation`].
+///
+/// Returns `false` if a grid was already installed or loaded, e.g. by a
+/// previous [`egm96_undulation`] call.
+pub fn install_egm96(grid: GeoidGrid) -> bool {
+    EGM96.set(Ok(grid)).is_ok()
+}
+
+/// The grid used when none was installed.
+#[cfg(feature = "geoid-bundled")]
+fn default_grid() -> std::result::Result<GeoidGrid, String> {
+    Ok(GeoidGrid::bundled())
+}
+
+/// The grid used when none was installed.
+#[cfg(not(feature = "geoid-bundled"))]
+fn default_grid() -> std::result::Result<GeoidGrid, String> {
+    let path = std::env::var(EGM96_PATH_ENV)
+        .map_err(|_| format!("No EGM96 grid installed and {} is not set", EGM96_PATH_ENV))?;
+    GeoidGrid::from_file(path).map_err(|e| e.to_string())
+}
+
+/// EGM96 geoid undulation in meters at `(lat, lon)`.
+///
+/// Uses the grid passed to [`install_egm96`]. Otherwise the grid is set up on
+/// first use: the bundled one with the `geoid-bundled` feature, or the file
+/// at `HTG_EGM96_PATH`.
+///
+/// # Errors
+///
+/// Returns [`SrtmError::GeoidUnavailable`] if no grid is installed or
+/// bundled, and `HTG_EGM96_PATH` is unset or does not point at a valid grid.
+pub fn egm96_undulation(lat: f64, lon: f64) -> Result<f64> {
+    let grid = EGM96.get_or_init(default_grid);
+
+    match grid {
+        Ok(grid) => Ok(grid.undulation(lat, lon)),
+        Err(reason) => Err(SrtmError::GeoidUnavailable {
+            reason: reason.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Grid with `f(lat, lon)` (in meters) at every node.
+    fn synthetic_grid(f: impl Fn(f64, f64) -> f64) -> GeoidGrid {
+        let mut bytes = Vec::with_capacity(EGM96_ROWS * EGM96_COLS * 2);
+        for row in 0..EGM96_ROWS {
+            for col in 0..EGM96_COLS {
+                let lat = 90.0 - row as f64 * EGM96_SPACING;
+                let lon = col as f64 * EGM96_SPACING;
+                let cm = (f(lat, lon) * 100.0).round() as i16;
+                bytes.extend_from_slice(&cm.to_be_bytes());
+            }
+        }
+        GeoidGrid::from_dac_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_undulation_nodes_and_interpolation() {
+        let grid = synthetic_grid(|lat, lon| lat / 10.0 + lon / 100.0);
+
+        assert!((grid.undulation(35.0, 138.0) - 4.88).abs() < 1e-9);
+        // Halfway between nodes in both directions
+        assert!((grid.undulation(35.125, 138.125) - (3.5125 + 1.38125)).abs() < 0.01);
+        // Negative longitudes wrap to the eastern half of the grid
+        assert!((grid.undulation(0.0, -90.0) - 2.70).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_undulation_edges() {
+        let grid = synthetic_grid(|lat, lon| if lon >= 359.0 { 10.0 } else { lat / 10.0 });
+
+        assert!((grid.undulation(90.0, 0.0) - 9.0).abs() < 1e-9);
+        assert!((grid.undulation(-90.0, 0.0) + 9.0).abs() < 1e-9);
+        assert!((grid.undulation(95.0, 0.0) - 9.0).abs() < 1e-9);
+        // Between 359.75°E and 0°E interpolates across the seam
+        assert!((grid.undulation(0.0, 359.875) - 5.0).abs() < 1e-9);
+        assert!((grid.undulation(0.0, -0.125) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_dac_bytes_rejects_wrong_size() {
+        assert!(matches!(
+            GeoidGrid::from_dac_bytes(&[0u8; 100]),
+            Err(SrtmError::GeoidUnavailable { .. })
+        ));
+    }
+
+    /// Reference undulations published by NGA for EGM96.
+    #[test]
+    #[cfg(feature = "geoid-bundled")]
+    fn test_published_egm96_values() {
+        let grid = GeoidGrid::bundled();
+        for (lat, lon, expected) in [(90.0, 0.0, 13.61), (-90.0, 0.0, -29.53), (0.0, 0.0, 17.16)] {
+            let n = grid.undulation(lat, lon);
+            assert!(
+                (n - expected).abs() < 0.05,
+                "N({}, {}) = {}, expected {}",
+                lat,
+                lon,
+                n,
+                expected
+            );
+        }
+    }
+}
@@ -25,6 +25,8 @@
 use geojson::{Geometry, Value as GeoJsonValue};
 
 use crate::error::{Result, SrtmError};
+use crate::grid::MAX_GRID_SAMPLES;
+use crate::tile::METERS_PER_DEGREE;
 use crate::SrtmService;
 
 /// Add elevations to all coordinates in a GeoJSON geometry.
@@ -73,31 +75,72 @@ use crate::SrtmService;
 /// // Each coordinate now has elevation: [[138.5, 35.5, 500.0], [138.6, 35.6, 750.0]]
 /// ```
 pub fn add_elevations_to_geometry(service: &SrtmService, geometry: Geometry) -> Result<Geometry> {
-    let new_value = match geometry.value {
-        GeoJsonValue::Point(coord) => {
-            let elevated = add_elevation_to_coord(service, &coord)?;
-            GeoJsonValue::Point(elevated)
-        }
-        GeoJsonValue::MultiPoint(coords) => {
-            let elevated = add_elevation_to_coords(service, &coords)?;
-            GeoJsonValue::MultiPoint(elevated)
-        }
-        GeoJsonValue::LineString(coords) => {
-            let elevated = add_elevation_to_coords(service, &coords)?;
-            GeoJsonValue::LineString(elevated)
+    map_coords(geometry, &|coord| add_elevation_to_coord(service, coord))
+}
+
+/// Add elevations to a GeoJSON geometry, leaving points without data 2D.
+///
+/// Like [`add_elevations_to_geometry`], but a coordinate whose elevation is
+/// void, whose tile is missing, or that lies outside SRTM coverage is
+/// returned as `[lon, lat]` (any input altitude is dropped) instead of
+/// getting a placeholder Z value or failing the whole geometry. Downstream
+/// consumers can then tell genuine elevations from absent data.
+///
+/// # Errors
+///
+/// Returns [`SrtmError::InvalidCoordinate`] if a coordinate has fewer than
+/// 2 elements, and any other error (e.g. a corrupted tile or an I/O error)
+/// as-is.
+///
+/// # Example
+///
+/// ```ignore
+/// use htg::geojson::add_elevations_to_geometry_lenient;
+///
+/// let coastline: Geometry = r#"{
+///     "type": "LineString",
+///     "coordinates": [[138.5, 35.5], [140.5, 35.5]]
+/// }"#.parse().unwrap();
+///
+/// let enriched = add_elevations_to_geometry_lenient(&service, coastline)?;
+/// // Offshore points stay 2D: [[138.5, 35.5, 500.0], [140.5, 35.5]]
+/// ```
+pub fn add_elevations_to_geometry_lenient(
+    service: &SrtmService,
+    geometry: Geometry,
+) -> Result<Geometry> {
+    map_coords(geometry, &|coord| {
+        let (lon, lat) = lon_lat(coord)?;
+        match service.get_elevation(lat, lon) {
+            Ok(Some(elevation)) => Ok(vec![lon, lat, elevation as f64]),
+            Ok(None)
+            | Err(SrtmError::FileNotFound { .. })
+            | Err(SrtmError::TileNotAvailable { .. })
+            | Err(SrtmError::OutOfBounds { .. }) => Ok(vec![lon, lat]),
+            Err(e) => Err(e),
         }
+    })
+}
+
+/// Rebuild `geometry` with every coordinate replaced by `f(coord)`.
+fn map_coords<F>(geometry: Geometry, f: &F) -> Result<Geometry>
+where
+    F: Fn(&[f64]) -> Result<Vec<f64>>,
+{
+    let map_all = |coords: &[Vec<f64>]| -> Result<Vec<Vec<f64>>> {
+        coords.iter().map(|coord| f(coord)).collect()
+    };
+
+    let new_value = match geometry.value {
+        GeoJsonValue::Point(coord) => GeoJsonValue::Point(f(&coord)?),
+        GeoJsonValue::MultiPoint(coords) => GeoJsonValue::MultiPoint(map_all(&coords)?),
+        GeoJsonValue::LineString(coords) => GeoJsonValue::LineString(map_all(&coords)?),
         GeoJsonValue::MultiLineString(lines) => {
-            let elevated: Result<Vec<_>> = lines
-                .iter()
-                .map(|line| add_elevation_to_coords(service, line))
-                .collect();
+            let elevated: Result<Vec<_>> = lines.iter().map(|line| map_all(line)).collect();
             GeoJsonValue::MultiLineString(elevated?)
         }
         GeoJsonValue::Polygon(rings) => {
-            let elevated: Result<Vec<_>> = rings
-                .iter()
-                .map(|ring| add_elevation_to_coords(service, ring))
-                .collect();
+            let elevated: Result<Vec<_>> = rings.iter().map(|ring| map_all(ring)).collect();
             GeoJsonValue::Polygon(elevated?)
         }
         GeoJsonValue::MultiPolygon(polygons) => {
@@ -106,17 +149,15 @@ pub fn add_elevations_to_geometry(service: &SrtmService, geometry: Geometry) ->
                 .map(|polygon| {
                     polygon
                         .iter()
-                        .map(|ring| add_elevation_to_coords(service, ring))
+                        .map(|ring| map_all(ring))
                         .collect::<Result<Vec<_>>>()
                 })
                 .collect();
             GeoJsonValue::MultiPolygon(elevated?)
         }
         GeoJsonValue::GeometryCollection(geometries) => {
-            let elevated: Result<Vec<_>> = geometries
-                .into_iter()
-                .map(|g| add_elevations_to_geometry(service, g))
-                .collect();
+            let elevated: Result<Vec<_>> =
+                geometries.into_iter().map(|g| map_coords(g, f)).collect();
             GeoJsonValue::GeometryCollection(elevated?)
         }
     };
@@ -124,6 +165,16 @@ pub fn add_elevations_to_geometry(service: &SrtmService, geometry: Geometry) ->
     Ok(Geometry::new(new_value))
 }
 
+/// The `(lon, lat)` of a GeoJSON position.
+fn lon_lat(coord: &[f64]) -> Result<(f64, f64)> {
+    match coord {
+        [lon, lat, ..] => Ok((*lon, *lat)),
+        _ => Err(SrtmError::InvalidCoordinate {
+            message: "Coordinate must have at least 2 elements (lon, lat)".to_string(),
+        }),
+    }
+}
+
 /// Add elevation to a single GeoJSON coordinate.
 ///
 /// Takes a coordinate in GeoJSON order `[lon, lat]` or `[lon, lat, alt]` and
@@ -154,14 +205,7 @@ pub fn add_elevations_to_geometry(service: &SrtmService, geometry: Geometry) ->
 /// println!("Elevation: {}m", elevated[2]);
 /// ```
 pub fn add_elevation_to_coord(service: &SrtmService, coord: &[f64]) -> Result<Vec<f64>> {
-    if coord.len() < 2 {
-        return Err(SrtmError::InvalidCoordinate {
-            message: "Coordinate must have at least 2 elements (lon, lat)".to_string(),
-        });
-    }
-
-    let lon = coord[0];
-    let lat = coord[1];
+    let (lon, lat) = lon_lat(coord)?;
 
     let elevation = service.get_elevation(lat, lon)?.unwrap_or(0) as f64;
 
@@ -194,6 +238,183 @@ pub fn add_elevation_to_coords(
         .collect()
 }
 
+/// Summary elevation statistics over the samples inside a polygon.
+///
+/// `min`, `max`, `mean`, and `std_dev` are NaN when no interior sample has
+/// data (`count == 0`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZonalStats {
+    /// Lowest elevation in meters.
+    pub min: f64,
+    /// Highest elevation in meters.
+    pub max: f64,
+    /// Mean elevation in meters.
+    pub mean: f64,
+    /// Population standard deviation in meters.
+    pub std_dev: f64,
+    /// Number of interior samples with data.
+    pub count: usize,
+    /// Number of interior samples that were void or had no tile.
+    pub void_count: usize,
+}
+
+/// Compute elevation statistics inside a Polygon or MultiPolygon.
+///
+/// The polygon's bounding box is sampled on a regular grid roughly
+/// `sample_spacing_m` apart, centered in the box, and samples inside the
+/// polygon (even-odd rule, so holes are excluded) are looked up with
+/// nearest-neighbor elevation. Coordinates are in GeoJSON order
+/// `[longitude, latitude]`.
+///
+/// # Errors
+///
+/// Returns [`SrtmError::InvalidCoordinate`] if the geometry is not a Polygon
+/// or MultiPolygon, a position has fewer than 2 elements,
+/// `sample_spacing_m` is not positive, or the sampling grid over the bounding
+/// box would exceed 16 million samples.
+///
+/// # Example
+///
+/// ```ignore
+/// use htg::geojson::zonal_stats;
+///
+/// let watershed: Geometry = r#"{
+///     "type": "Polygon",
+///     "coordinates": [[[138.5, 35.5], [138.7, 35.5], [138.7, 35.7], [138.5, 35.5]]]
+/// }"#.parse().unwrap();
+///
+/// let stats = zonal_stats(&service, &watershed, 90.0)?;
+/// println!("Mean elevation: {:.1}m over {} samples", stats.mean, stats.count);
+/// ```
+pub fn zonal_stats(
+    service: &SrtmService,
+    polygon: &Geometry,
+    sample_spacing_m: f64,
+) -> Result<ZonalStats> {
+    if !(sample_spacing_m > 0.0 && sample_spacing_m.is_finite()) {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!("Sample spacing must be positive, got {}", sample_spacing_m),
+        });
+    }
+
+    let polygons: Vec<&Vec<Vec<Vec<f64>>>> = match &polygon.value {
+        GeoJsonValue::Polygon(rings) => vec![rings],
+        GeoJsonValue::MultiPolygon(polygons) => polygons.iter().collect(),
+        _ => {
+            return Err(SrtmError::InvalidCoordinate {
+                message: "Zonal statistics require a Polygon or MultiPolygon".to_string(),
+            })
+        }
+    };
+
+    let positions = polygons.iter().flat_map(|rings| rings.iter().flatten());
+    let (mut min_lon, mut min_lat) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_lon, mut max_lat) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for position in positions {
+        let [lon, lat, ..] = position[..] else {
+            return Err(SrtmError::InvalidCoordinate {
+                message: "Coordinate must have at least 2 elements (lon, lat)".to_string(),
+            });
+        };
+        min_lon = min_lon.min(lon);
+        max_lon = max_lon.max(lon);
+        min_lat = min_lat.min(lat);
+        max_lat = max_lat.max(lat);
+    }
+
+    let mut samples = Vec::new();
+    if min_lat <= max_lat {
+        let lat_step = sample_spacing_m / METERS_PER_DEGREE;
+        let lon_step = lat_step / ((min_lat + max_lat) / 2.0).to_radians().cos().max(1e-6);
+        let (rows, cols) = (
+            step_count(min_lat, max_lat, lat_step),
+            step_count(min_lon, max_lon, lon_step),
+        );
+        if rows.saturating_mul(cols) > MAX_GRID_SAMPLES {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!(
+                    "Polygon bounding box spans {}×{} samples, more than {}",
+                    rows, cols, MAX_GRID_SAMPLES
+                ),
+            });
+        }
+        let lats = centered_steps(min_lat, max_lat, lat_step);
+        let lons = centered_steps(min_lon, max_lon, lon_step);
+        for &lat in &lats {
+            for &lon in &lons {
+                if polygons.iter().any(|rings| contains(rings, lon, lat)) {
+                    samples.push((lat, lon));
+                }
+            }
+        }
+    }
+
+    let elevations = service.get_elevations_batch(&samples, crate::VOID_VALUE);
+
+    // Welford's online mean and variance
+    let mut stats = ZonalStats {
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+        mean: 0.0,
+        std_dev: 0.0,
+        count: 0,
+        void_count: 0,
+    };
+    let mut m2 = 0.0;
+    for elevation in elevations {
+        if elevation == crate::VOID_VALUE {
+            stats.void_count += 1;
+            continue;
+        }
+        let elevation = elevation as f64;
+        stats.count += 1;
+        stats.min = stats.min.min(elevation);
+        stats.max = stats.max.max(elevation);
+        let delta = elevation - stats.mean;
+        stats.mean += delta / stats.count as f64;
+        m2 += delta * (elevation - stats.mean);
+    }
+
+    if stats.count == 0 {
+        stats.min = f64::NAN;
+        stats.max = f64::NAN;
+        stats.mean = f64::NAN;
+        stats.std_dev = f64::NAN;
+    } else {
+        stats.std_dev = (m2 / stats.count as f64).sqrt();
+    }
+    Ok(stats)
+}
+
+/// Number of points `step` apart that fit in `[min, max]`.
+fn step_count(min: f64, max: f64, step: f64) -> usize {
+    ((max - min) / step).floor() as usize + 1
+}
+
+/// Points `step` apart spanning `[min, max]`, centered in the interval.
+fn centered_steps(min: f64, max: f64, step: f64) -> Vec<f64> {
+    let n = step_count(min, max, step);
+    let offset = (max - min - (n - 1) as f64 * step) / 2.0;
+    (0..n).map(|i| min + offset + i as f64 * step).collect()
+}
+
+/// Even-odd point-in-polygon test over all rings (exterior and holes).
+fn contains(rings: &[Vec<Vec<f64>>], lon: f64, lat: f64) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        let mut j = ring.len().wrapping_sub(1);
+        for i in 0..ring.len() {
+            let (xi, yi) = (ring[i][0], ring[i][1]);
+            let (xj, yj) = (ring[j][0], ring[j][1]);
+            if (yi > lat) != (yj > lat) && lon < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+            j = i;
+        }
+    }
+    inside
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,4 +572,136 @@ mod tests {
             panic!("Expected GeometryCollection");
         }
     }
+
+    /// SRTM3 tile at N35E138 whose elevation equals the column index.
+    fn create_ramp_tile(dir: &Path) {
+        crate::test_util::write_test_tile(&dir.join("N35E138.hgt"), |_, col| col as i16);
+    }
+
+    #[test]
+    fn test_lenient_leaves_points_without_data_2d() {
+        let temp_dir = TempDir::new().unwrap();
+        create_ramp_tile(temp_dir.path());
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Runs east from N35E138 into the missing N35E139 tile
+        let line = Geometry::new(GeoJsonValue::LineString(vec![
+            vec![138.5, 35.5],
+            vec![138.75, 35.5, 12.0],
+            vec![139.25, 35.5],
+            vec![139.5, 35.5, 12.0],
+        ]));
+        let result = add_elevations_to_geometry_lenient(&service, line.clone()).unwrap();
+        assert_eq!(
+            result.value,
+            GeoJsonValue::LineString(vec![
+                vec![138.5, 35.5, 600.0],
+                vec![138.75, 35.5, 900.0],
+                vec![139.25, 35.5],
+                vec![139.5, 35.5],
+            ])
+        );
+
+        // Out-of-coverage points stay 2D too, where the strict version fails
+        let polar = Geometry::new(GeoJsonValue::Point(vec![138.5, 75.0]));
+        assert!(add_elevations_to_geometry(&service, polar.clone()).is_err());
+        assert_eq!(
+            add_elevations_to_geometry_lenient(&service, polar)
+                .unwrap()
+                .value,
+            GeoJsonValue::Point(vec![138.5, 75.0])
+        );
+
+        let malformed = Geometry::new(GeoJsonValue::Point(vec![138.5]));
+        assert!(add_elevations_to_geometry_lenient(&service, malformed).is_err());
+
+        // A corrupted tile is an error, not missing data
+        std::fs::write(temp_dir.path().join("N36E138.hgt"), [0u8; 100]).unwrap();
+        let corrupted = Geometry::new(GeoJsonValue::Point(vec![138.5, 36.5]));
+        assert!(add_elevations_to_geometry_lenient(&service, corrupted).is_err());
+    }
+
+    fn square(min: f64, max: f64, lat_min: f64, lat_max: f64) -> Vec<Vec<f64>> {
+        vec![
+            vec![min, lat_min],
+            vec![max, lat_min],
+            vec![max, lat_max],
+            vec![min, lat_max],
+            vec![min, lat_min],
+        ]
+    }
+
+    #[test]
+    fn test_zonal_stats_square_on_ramp() {
+        let temp_dir = TempDir::new().unwrap();
+        create_ramp_tile(temp_dir.path());
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Columns 300..=900: uniform, so mean 600 and std dev 600/sqrt(12)
+        let polygon = Geometry::new(GeoJsonValue::Polygon(vec![square(
+            138.25, 138.75, 35.25, 35.75,
+        )]));
+        let stats = zonal_stats(&service, &polygon, 250.0).unwrap();
+
+        assert!(stats.count > 40_000, "count = {}", stats.count);
+        assert_eq!(stats.void_count, 0);
+        assert!((stats.mean - 600.0).abs() < 0.5, "mean = {}", stats.mean);
+        assert!(stats.min >= 300.0 && stats.min < 305.0);
+        assert!(stats.max <= 900.0 && stats.max > 895.0);
+        let expected_std = 600.0 / 12f64.sqrt();
+        assert!((stats.std_dev - expected_std).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_zonal_stats_excludes_holes() {
+        let temp_dir = TempDir::new().unwrap();
+        create_ramp_tile(temp_dir.path());
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let outer = square(138.25, 138.75, 35.25, 35.75);
+        let full = zonal_stats(
+            &service,
+            &Geometry::new(GeoJsonValue::Polygon(vec![outer.clone()])),
+            500.0,
+        )
+        .unwrap();
+
+        // A hole covering columns 480..=720 (16% of the area) keeps the mean
+        let hole = square(138.4, 138.6, 35.4, 35.6);
+        let holed = Geometry::new(GeoJsonValue::Polygon(vec![outer, hole]));
+        let stats = zonal_stats(&service, &holed, 500.0).unwrap();
+
+        let ratio = stats.count as f64 / full.count as f64;
+        assert!((ratio - 0.84).abs() < 0.02, "ratio = {}", ratio);
+        assert!((stats.mean - 600.0).abs() < 1.0);
+        assert!(stats.std_dev > full.std_dev);
+    }
+
+    #[test]
+    fn test_zonal_stats_void_and_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        create_ramp_tile(temp_dir.path());
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Western half has no tile
+        let polygon = Geometry::new(GeoJsonValue::Polygon(vec![square(
+            137.9, 138.1, 35.4, 35.6,
+        )]));
+        let stats = zonal_stats(&service, &polygon, 500.0).unwrap();
+        assert!(stats.count > 0 && stats.void_count > 0);
+        assert!(stats.max <= 120.0);
+
+        let nowhere = Geometry::new(GeoJsonValue::Polygon(vec![square(10.0, 10.1, 10.0, 10.1)]));
+        let stats = zonal_stats(&service, &nowhere, 500.0).unwrap();
+        assert_eq!(stats.count, 0);
+        assert!(stats.mean.is_nan());
+
+        let point = Geometry::new(GeoJsonValue::Point(vec![138.5, 35.5]));
+        assert!(zonal_stats(&service, &point, 100.0).is_err());
+        assert!(zonal_stats(&service, &polygon, 0.0).is_err());
+
+        // A 1m grid over a 0.2° box is far over the sample limit
+        let err = zonal_stats(&service, &polygon, 1.0).unwrap_err();
+        assert!(err.to_string().contains("more than"), "{}", err);
+    }
 }
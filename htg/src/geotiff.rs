@@ -0,0 +1,177 @@
+//! Minimal GeoTIFF decoding for download sources that don't serve raw `.hgt`.
+//!
+//! Only single-band elevation rasters are supported. Georeferencing tags are
+//! not interpreted: the caller requests a raster covering exactly the tile it
+//! wants, so only the pixel grid matters.
+
+use std::io::Cursor;
+
+use tiff::decoder::{Decoder, DecodingResult};
+
+use crate::error::{Result, SrtmError};
+use crate::tile::{SrtmResolution, VOID_VALUE};
+
+/// A decoded single-band elevation raster in row-major order (north first).
+pub(crate) struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<i16>,
+}
+
+impl Raster {
+    /// Decode a GeoTIFF into an `i16` raster.
+    ///
+    /// Floating-point rasters are rounded; NaN and out-of-range values become
+    /// [`VOID_VALUE`].
+    pub fn decode(bytes: &[u8], filename: &str) -> Result<Self> {
+        let fail = |reason: String| SrtmError::DownloadFailed {
+            filename: filename.to_string(),
+            reason,
+        };
+
+        let mut decoder = Decoder::new(Cursor::new(bytes))
+            .map_err(|e| fail(format!("Invalid GeoTIFF: {}", e)))?;
+        let (width, height) = decoder
+            .dimensions()
+            .map_err(|e| fail(format!("Invalid GeoTIFF: {}", e)))?;
+        let image = decoder
+            .read_image()
+            .map_err(|e| fail(format!("Failed to decode GeoTIFF: {}", e)))?;
+
+        let from_f64 = |v: f64| {
+            if v.is_finite() && v >= i16::MIN as f64 && v <= i16::MAX as f64 {
+                v.round() as i16
+            } else {
+                VOID_VALUE
+            }
+        };
+
+        let data: Vec<i16> = match image {
+            DecodingResult::I16(v) => v,
+            DecodingResult::I32(v) => v.into_iter().map(|x| from_f64(x as f64)).collect(),
+            DecodingResult::U16(v) => v.into_iter().map(|x| from_f64(x as f64)).collect(),
+            DecodingResult::F32(v) => v.into_iter().map(|x| from_f64(x as f64)).collect(),
+            DecodingResult::F64(v) => v.into_iter().map(from_f64).collect(),
+            _ => return Err(fail("Unsupported GeoTIFF sample format".to_string())),
+        };
+
+        let (width, height) = (width as usize, height as usize);
+        if data.len() != width * height {
+            return Err(fail(
+                "GeoTIFF must contain a single elevation band".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Copy a `size` × `size` window starting at `(row0, col0)`.
+    ///
+    /// Rows and columns past the raster edge repeat the last row/column.
+    pub fn window(&self, row0: usize, col0: usize, size: usize) -> Raster {
+        let mut data = Vec::with_capacity(size * size);
+        for row in row0..row0 + size {
+            let src_row = row.min(self.height - 1);
+            for col in col0..col0 + size {
+                let src_col = col.min(self.width - 1);
+                data.push(self.data[src_row * self.width + src_col]);
+            }
+        }
+        Raster {
+            width: size,
+            height: size,
+            data,
+        }
+    }
+
+    /// Convert a raster covering exactly one 1°×1° tile into `.hgt` bytes.
+    ///
+    /// Accepts 3601/1201-sample grids (pixel-is-point, like `.hgt`) and
+    /// 3600/1200-sample grids (pixel-is-area), which are padded by repeating
+    /// the last row and column.
+    pub fn to_hgt(&self, filename: &str) -> Result<Vec<u8>> {
+        let samples = [SrtmResolution::Srtm1, SrtmResolution::Srtm3]
+            .iter()
+            .map(|r| r.samples())
+            .find(|&s| self.width == self.height && (self.width == s || self.width == s - 1))
+            .ok_or_else(|| SrtmError::DownloadFailed {
+                filename: filename.to_string(),
+                reason: format!(
+                    "Unexpected GeoTIFF dimensions {}x{} for a 1° tile",
+                    self.width, self.height
+                ),
+            })?;
+
+        let mut hgt = Vec::with_capacity(samples * samples * 2);
+        for row in 0..samples {
+            let src_row = row.min(self.height - 1);
+            for col in 0..samples {
+                let src_col = col.min(self.width - 1);
+                hgt.extend_from_slice(&self.data[src_row * self.width + src_col].to_be_bytes());
+            }
+        }
+        Ok(hgt)
+    }
+}
+
+/// Decode a GeoTIFF covering a single tile and convert it to `.hgt` bytes.
+pub(crate) fn geotiff_to_hgt(bytes: &[u8], filename: &str) -> Result<Vec<u8>> {
+    Raster::decode(bytes, filename)?.to_hgt(filename)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    /// Encode an `i16` raster as an (untagged) TIFF.
+    pub(crate) fn encode_i16(width: u32, height: u32, data: &[i16]) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        TiffEncoder::new(&mut buffer)
+            .unwrap()
+            .write_image::<colortype::GrayI16>(width, height, data)
+            .unwrap();
+        buffer.into_inner()
+    }
+
+    fn hgt_sample(hgt: &[u8], samples: usize, row: usize, col: usize) -> i16 {
+        let offset = (row * samples + col) * 2;
+        i16::from_be_bytes([hgt[offset], hgt[offset + 1]])
+    }
+
+    #[test]
+    fn test_geotiff_to_hgt_srtm3() {
+        let mut data = vec![0i16; 1201 * 1201];
+        data[0] = 1000;
+        data[600 * 1201 + 600] = 500;
+        data[1201 * 1201 - 1] = VOID_VALUE;
+
+        let hgt = geotiff_to_hgt(&encode_i16(1201, 1201, &data), "N35E138.hgt").unwrap();
+        assert_eq!(hgt.len(), 1201 * 1201 * 2);
+        assert_eq!(hgt_sample(&hgt, 1201, 0, 0), 1000);
+        assert_eq!(hgt_sample(&hgt, 1201, 600, 600), 500);
+        assert_eq!(hgt_sample(&hgt, 1201, 1200, 1200), VOID_VALUE);
+    }
+
+    #[test]
+    fn test_geotiff_to_hgt_pads_pixel_is_area() {
+        let mut data = vec![0i16; 1200 * 1200];
+        data[1200 * 1200 - 1] = 42;
+
+        let hgt = geotiff_to_hgt(&encode_i16(1200, 1200, &data), "N35E138.hgt").unwrap();
+        assert_eq!(hgt.len(), 1201 * 1201 * 2);
+        assert_eq!(hgt_sample(&hgt, 1201, 1199, 1199), 42);
+        assert_eq!(hgt_sample(&hgt, 1201, 1200, 1200), 42);
+    }
+
+    #[test]
+    fn test_geotiff_to_hgt_rejects_bad_dimensions() {
+        let data = vec![0i16; 10 * 10];
+        let result = geotiff_to_hgt(&encode_i16(10, 10, &data), "N35E138.hgt");
+        assert!(matches!(result, Err(SrtmError::DownloadFailed { .. })));
+    }
+}
@@ -0,0 +1,280 @@
+//! In-memory elevation rasters.
+//!
+//! [`ElevationGrid`] holds a row-major block of samples, such as a window
+//! read with [`SrtmTile::read_window`], and can resample it to another size.
+//! This puts SRTM1 and SRTM3 data on a common grid before mosaicking.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::grid::{ElevationGrid, Interpolation};
+//!
+//! // A 1201-sample SRTM3 row band brought up to SRTM1 spacing
+//! let window = srtm3_tile.read_window(0, 0, 101, 1201)?;
+//! let grid = ElevationGrid::new(1201, 101, window)?;
+//! let upsampled = grid.resample(3601, 301, Interpolation::Bilinear);
+//! ```
+//!
+//! [`SrtmTile::read_window`]: crate::SrtmTile::read_window
+
+use crate::error::{Result, SrtmError};
+use crate::tile::VOID_VALUE;
+
+/// Largest number of samples a single contour, zonal statistics, or
+/// hillshade request will look up.
+pub(crate) const MAX_GRID_SAMPLES: usize = 16_000_000;
+
+/// Resampling method for [`ElevationGrid::resample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Take the closest source sample.
+    #[default]
+    Nearest,
+    /// Blend the surrounding 2×2 source samples.
+    Bilinear,
+}
+
+/// A `width` × `height` raster of elevation samples in meters.
+///
+/// Samples are row-major, north to south and west to east, with
+/// [`VOID_VALUE`] marking missing data, matching [`SrtmTile::read_window`].
+///
+/// [`SrtmTile::read_window`]: crate::SrtmTile::read_window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElevationGrid {
+    width: usize,
+    height: usize,
+    data: Vec<i16>,
+}
+
+impl ElevationGrid {
+    /// Wrap row-major samples as a grid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::InvalidCoordinate`] if `data` does not hold exactly
+    /// `width * height` samples.
+    pub fn new(width: usize, height: usize, data: Vec<i16>) -> Result<Self> {
+        if width.checked_mul(height) != Some(data.len()) {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!(
+                    "{} samples do not form a {}x{} grid",
+                    data.len(),
+                    width,
+                    height
+                ),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The samples in row-major order.
+    pub fn data(&self) -> &[i16] {
+        &self.data
+    }
+
+    /// Consume the grid, returning its samples.
+    pub fn into_data(self) -> Vec<i16> {
+        self.data
+    }
+
+    /// The sample at `(row, col)`, or `None` if out of range.
+    pub fn get(&self, row: usize, col: usize) -> Option<i16> {
+        (row < self.height && col < self.width).then(|| self.data[row * self.width + col])
+    }
+
+    /// Resample to `out_width` × `out_height`.
+    ///
+    /// The corner samples of both grids are aligned, as SRTM tiles share
+    /// their edge samples: output corners equal the input corners, and
+    /// upsampling a `w`-wide grid to `2w - 1` keeps every input sample and
+    /// adds one between each pair. A single output row or column samples
+    /// the middle of the input.
+    ///
+    /// Under [`Interpolation::Nearest`] void samples are copied like any
+    /// other. Under [`Interpolation::Bilinear`] an output sample is void if
+    /// any source sample contributing to it is void; results are rounded to
+    /// the nearest meter.
+    ///
+    /// An empty input resamples to an all-void grid, and a zero output
+    /// dimension yields an empty grid.
+    pub fn resample(
+        &self,
+        out_width: usize,
+        out_height: usize,
+        method: Interpolation,
+    ) -> ElevationGrid {
+        if self.data.is_empty() || out_width == 0 || out_height == 0 {
+            return ElevationGrid {
+                width: out_width,
+                height: out_height,
+                data: vec![VOID_VALUE; out_width * out_height],
+            };
+        }
+
+        let xs = source_positions(self.width, out_width);
+        let ys = source_positions(self.height, out_height);
+
+        let mut data = Vec::with_capacity(out_width * out_height);
+        for &y in &ys {
+            for &x in &xs {
+                data.push(match method {
+                    Interpolation::Nearest => {
+                        self.data[y.round() as usize * self.width + x.round() as usize]
+                    }
+                    Interpolation::Bilinear => self.bilinear(y, x),
+                });
+            }
+        }
+
+        ElevationGrid {
+            width: out_width,
+            height: out_height,
+            data,
+        }
+    }
+
+    /// Bilinear sample at fractional `(y, x)`, void if any contributing
+    /// sample is void.
+    fn bilinear(&self, y: f64, x: f64) -> i16 {
+        let (row0, col0) = (y.floor() as usize, x.floor() as usize);
+        let (fy, fx) = (y - row0 as f64, x - col0 as f64);
+        // A zero weight means the neighbor does not contribute
+        let row1 = if fy > 0.0 { row0 + 1 } else { row0 };
+        let col1 = if fx > 0.0 { col0 + 1 } else { col0 };
+
+        let at = |row: usize, col: usize| self.data[row * self.width + col];
+        let corners = [
+            at(row0, col0),
+            at(row0, col1),
+            at(row1, col0),
+            at(row1, col1),
+        ];
+        if corners.contains(&VOID_VALUE) {
+            return VOID_VALUE;
+        }
+
+        let [v00, v01, v10, v11] = corners.map(f64::from);
+        let top = v00 + (v01 - v00) * fx;
+        let bottom = v10 + (v11 - v10) * fx;
+        (top + (bottom - top) * fy).round() as i16
+    }
+}
+
+/// Source coordinates of `out` samples spread corner to corner over `len`.
+fn source_positions(len: usize, out: usize) -> Vec<f64> {
+    let last = (len - 1) as f64;
+    if out == 1 {
+        return vec![last / 2.0];
+    }
+    let step = last / (out - 1) as f64;
+    // Clamp so rounding error never steps past the last sample
+    (0..out).map(|i| (i as f64 * step).min(last)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_3x3() -> ElevationGrid {
+        ElevationGrid::new(3, 3, vec![0, 10, 20, 30, 40, 50, 60, 70, 80]).unwrap()
+    }
+
+    #[test]
+    fn test_new_validates_length() {
+        assert!(ElevationGrid::new(2, 2, vec![0; 3]).is_err());
+        assert!(ElevationGrid::new(usize::MAX, 2, vec![]).is_err());
+
+        let grid = grid_3x3();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.get(1, 2), Some(50));
+        assert_eq!(grid.get(3, 0), None);
+    }
+
+    #[test]
+    fn test_bilinear_upsample_2x() {
+        let up = grid_3x3().resample(5, 5, Interpolation::Bilinear);
+        assert_eq!((up.width(), up.height()), (5, 5));
+
+        // Input samples are kept, including the edges and corners
+        assert_eq!(up.get(0, 0), Some(0));
+        assert_eq!(up.get(0, 4), Some(20));
+        assert_eq!(up.get(4, 0), Some(60));
+        assert_eq!(up.get(4, 4), Some(80));
+        assert_eq!(up.get(2, 2), Some(40));
+
+        // New samples are midway between their neighbors
+        assert_eq!(up.get(0, 1), Some(5));
+        assert_eq!(up.get(1, 0), Some(15));
+        assert_eq!(up.get(1, 1), Some(20));
+        assert_eq!(up.get(3, 3), Some(60));
+        assert_eq!(up.get(4, 3), Some(75));
+    }
+
+    #[test]
+    fn test_nearest_resample() {
+        let up = grid_3x3().resample(5, 5, Interpolation::Nearest);
+        assert_eq!(up.get(0, 0), Some(0));
+        assert_eq!(up.get(4, 4), Some(80));
+        assert_eq!(up.get(2, 2), Some(40));
+
+        // Downsampling to the corners
+        let down = grid_3x3().resample(2, 2, Interpolation::Nearest);
+        assert_eq!(down.into_data(), vec![0, 20, 60, 80]);
+
+        let single = grid_3x3().resample(1, 1, Interpolation::Bilinear);
+        assert_eq!(single.into_data(), vec![40]);
+    }
+
+    #[test]
+    fn test_void_propagation() {
+        let mut data = grid_3x3().into_data();
+        data[4] = VOID_VALUE; // center
+        let grid = ElevationGrid::new(3, 3, data).unwrap();
+
+        let up = grid.resample(5, 5, Interpolation::Bilinear);
+        // Every sample touching the center cell's neighborhood is void
+        assert_eq!(up.get(2, 2), Some(VOID_VALUE));
+        assert_eq!(up.get(1, 1), Some(VOID_VALUE));
+        assert_eq!(up.get(2, 1), Some(VOID_VALUE));
+        // Samples on the outer edges don't depend on the center
+        assert_eq!(up.get(0, 1), Some(5));
+        assert_eq!(up.get(4, 3), Some(75));
+
+        let nearest = grid.resample(5, 5, Interpolation::Nearest);
+        assert_eq!(nearest.get(2, 2), Some(VOID_VALUE));
+        assert_eq!(nearest.get(0, 0), Some(0));
+
+        let empty = ElevationGrid::new(0, 0, vec![]).unwrap();
+        assert_eq!(
+            empty.resample(2, 1, Interpolation::Bilinear).into_data(),
+            vec![VOID_VALUE; 2]
+        );
+    }
+
+    #[test]
+    fn test_resample_zero_output_dimension() {
+        let grid = grid_3x3();
+        for (width, height) in [(0, 0), (0, 3), (3, 0)] {
+            for method in [Interpolation::Nearest, Interpolation::Bilinear] {
+                let out = grid.resample(width, height, method);
+                assert_eq!((out.width(), out.height()), (width, height));
+                assert!(out.into_data().is_empty());
+            }
+        }
+    }
+}
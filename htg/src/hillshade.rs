@@ -0,0 +1,261 @@
+//! Shaded relief.
+//!
+//! [`hillshade`] renders a grayscale shaded-relief raster for a bounding box,
+//! lit from a given sun azimuth and altitude. Slopes are estimated per cell
+//! with Horn's 3×3 method and turned into slope/aspect by [`slope_aspect`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::hillshade::hillshade;
+//!
+//! // 512×512 relief around Mount Fuji, lit from the north-west at 45°
+//! let pixels = hillshade(&service, (35.3, 138.6, 35.45, 138.85), 512, 512, 315.0, 45.0)?;
+//! assert_eq!(pixels.len(), 512 * 512);
+//! ```
+
+use crate::error::{Result, SrtmError};
+use crate::grid::MAX_GRID_SAMPLES;
+use crate::tile::METERS_PER_DEGREE;
+use crate::{BoundingBox, SrtmService};
+
+/// Slope and aspect of a surface from its elevation gradient.
+///
+/// # Arguments
+///
+/// * `dz_east` - Elevation change per meter eastwards
+/// * `dz_north` - Elevation change per meter northwards
+///
+/// # Returns
+///
+/// `(slope_deg, aspect_deg)`: the slope from horizontal (0–90°) and the
+/// compass direction the slope faces (downhill), clockwise from north in
+/// `[0, 360)`. Flat surfaces have an aspect of 0.
+pub fn slope_aspect(dz_east: f64, dz_north: f64) -> (f64, f64) {
+    if dz_east == 0.0 && dz_north == 0.0 {
+        return (0.0, 0.0);
+    }
+    let slope = dz_east.hypot(dz_north).atan().to_degrees();
+    let aspect = (-dz_east).atan2(-dz_north).to_degrees().rem_euclid(360.0);
+    (slope, aspect)
+}
+
+/// Render a grayscale hillshade over a bounding box.
+///
+/// The output is a row-major `width * height` buffer, north-up, with one
+/// value per pixel: 255 for a surface facing the light, 0 for one in shadow
+/// (or facing away from it). Each pixel is lit by the standard
+/// `cos(zenith)·cos(slope) + sin(zenith)·sin(slope)·cos(azimuth − aspect)`
+/// model, with the slope taken from the interpolated elevations at the
+/// pixel's center and its 8 neighbors. Pixels next to void data or missing
+/// tiles are 0.
+///
+/// # Arguments
+///
+/// * `service` - The SRTM service to sample
+/// * `bbox` - `(min_lat, min_lon, max_lat, max_lon)` in decimal degrees
+/// * `width` - Output columns
+/// * `height` - Output rows
+/// * `azimuth_deg` - Direction the light comes from, clockwise from north
+///   (315 is the cartographic default)
+/// * `altitude_deg` - Light elevation above the horizon (0–90°)
+///
+/// # Errors
+///
+/// Returns [`SrtmError::InvalidCoordinate`] if `width` or `height` is zero,
+/// the output (plus its one-pixel margin) exceeds 16 million samples, or the
+/// bounding box is inverted.
+pub fn hillshade(
+    service: &SrtmService,
+    bbox: (f64, f64, f64, f64),
+    width: usize,
+    height: usize,
+    azimuth_deg: f64,
+    altitude_deg: f64,
+) -> Result<Vec<u8>> {
+    if width == 0 || height == 0 {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!(
+                "hillshade must have at least one row and column, got {}x{}",
+                width, height
+            ),
+        });
+    }
+    if height
+        .saturating_add(2)
+        .saturating_mul(width.saturating_add(2))
+        > MAX_GRID_SAMPLES
+    {
+        return Err(SrtmError::InvalidCoordinate {
+            message: format!(
+                "hillshade of {}x{} pixels needs more than {} samples",
+                width, height, MAX_GRID_SAMPLES
+            ),
+        });
+    }
+    let (min_lat, min_lon, max_lat, max_lon) = bbox;
+    if !(min_lat <= max_lat && min_lon <= max_lon) {
+        return Err(SrtmError::InvalidCoordinate {
+            message: "Bounding box minimums must not exceed maximums".to_string(),
+        });
+    }
+
+    // Elevations at every pixel center plus a one-pixel margin, so that edge
+    // pixels have all 8 neighbors
+    let lat_step = (max_lat - min_lat) / height as f64;
+    let lon_step = (max_lon - min_lon) / width as f64;
+    let padded = BoundingBox::new(
+        min_lat - lat_step / 2.0,
+        min_lon - lon_step / 2.0,
+        max_lat + lat_step / 2.0,
+        max_lon + lon_step / 2.0,
+    );
+    let (rows, cols) = (height + 2, width + 2);
+    let grid = service.interpolated_grid_flat(&padded, rows, cols, f32::NAN)?;
+    let z = |row: usize, col: usize| f64::from(grid[row * cols + col]);
+
+    let zenith = (90.0 - altitude_deg).to_radians();
+    let azimuth = azimuth_deg.to_radians();
+    let dy = lat_step * METERS_PER_DEGREE;
+
+    let mut pixels = vec![0u8; width * height];
+    for row in 1..=height {
+        let lat = max_lat - (row as f64 - 0.5) * lat_step;
+        let dx = lon_step * METERS_PER_DEGREE * lat.to_radians().cos();
+
+        for col in 1..=width {
+            // Horn's method over the 3×3 window; rows run southwards
+            let [a, b, c] = [z(row - 1, col - 1), z(row - 1, col), z(row - 1, col + 1)];
+            let [d, f] = [z(row, col - 1), z(row, col + 1)];
+            let [g, h, i] = [z(row + 1, col - 1), z(row + 1, col), z(row + 1, col + 1)];
+
+            let dz_east = ((c + 2.0 * f + i) - (a + 2.0 * d + g)) / (8.0 * dx);
+            let dz_north = ((a + 2.0 * b + c) - (g + 2.0 * h + i)) / (8.0 * dy);
+            if !(dz_east.is_finite() && dz_north.is_finite() && z(row, col).is_finite()) {
+                continue;
+            }
+
+            let (slope, aspect) = slope_aspect(dz_east, dz_north);
+            let (slope, aspect) = (slope.to_radians(), aspect.to_radians());
+            let shade =
+                zenith.cos() * slope.cos() + zenith.sin() * slope.sin() * (azimuth - aspect).cos();
+            pixels[(row - 1) * width + (col - 1)] = (255.0 * shade.max(0.0)).round() as u8;
+        }
+    }
+
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::write_test_tile;
+    use crate::VOID_VALUE;
+    use tempfile::TempDir;
+
+    /// SRTM3 tile at N35E138 with `f(row, col)` at every sample.
+    fn create_tile(dir: &TempDir, f: impl Fn(usize, usize) -> i16) -> SrtmService {
+        write_test_tile(&dir.path().join("N35E138.hgt"), f);
+        SrtmService::new(dir.path(), 1)
+    }
+
+    #[test]
+    fn test_slope_aspect() {
+        assert_eq!(slope_aspect(0.0, 0.0), (0.0, 0.0));
+
+        // Rising to the east: faces west
+        let (slope, aspect) = slope_aspect(1.0, 0.0);
+        assert!((slope - 45.0).abs() < 1e-9);
+        assert!((aspect - 270.0).abs() < 1e-9);
+
+        // Rising to the north: faces south
+        let (_, aspect) = slope_aspect(0.0, 0.5);
+        assert!((aspect - 180.0).abs() < 1e-9);
+
+        // Falling to the north-east: faces north-east
+        let (_, aspect) = slope_aspect(-0.1, -0.1);
+        assert!((aspect - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_planar_slope_is_uniform() {
+        let dir = TempDir::new().unwrap();
+        // Rises 2m per sample northwards (row 0 is the northern edge)
+        let service = create_tile(&dir, |row, _| (3000 - 2 * row) as i16);
+
+        let bbox = (35.2, 138.2, 35.8, 138.8);
+        for (azimuth, altitude) in [(180.0, 45.0), (315.0, 30.0), (90.0, 60.0)] {
+            let pixels = hillshade(&service, bbox, 16, 12, azimuth, altitude).unwrap();
+            assert_eq!(pixels.len(), 16 * 12);
+
+            // Analytic shade: surface normal (0, -g, 1) against the light vector
+            let gradient = 2.0 / (METERS_PER_DEGREE / 1200.0);
+            let (az, alt): (f64, f64) = (f64::to_radians(azimuth), f64::to_radians(altitude));
+            let dot = (-gradient * az.cos() * alt.cos() + alt.sin()) / gradient.hypot(1.0);
+            let expected = (255.0 * dot.max(0.0)).round() as u8;
+
+            assert!(
+                pixels.iter().all(|&p| p == expected),
+                "azimuth {}: {:?} != {}",
+                azimuth,
+                &pixels[..4],
+                expected
+            );
+        }
+
+        // Facing the light is brighter than facing away from it
+        let lit = hillshade(&service, bbox, 2, 2, 180.0, 30.0).unwrap();
+        let unlit = hillshade(&service, bbox, 2, 2, 0.0, 30.0).unwrap();
+        assert!(lit[0] > unlit[0]);
+    }
+
+    #[test]
+    fn test_void_and_missing_cells_are_black() {
+        let dir = TempDir::new().unwrap();
+        let service = create_tile(&dir, |row, _| if row < 600 { VOID_VALUE } else { 100 });
+
+        // Flat ground under a 90° sun is fully lit
+        let pixels = hillshade(&service, (35.1, 138.1, 35.4, 138.5), 4, 4, 315.0, 90.0).unwrap();
+        assert!(pixels.iter().all(|&p| p == 255));
+
+        // The one-pixel margin reaches into the missing tile to the south
+        let pixels = hillshade(&service, (35.0, 138.1, 35.4, 138.5), 4, 4, 315.0, 90.0).unwrap();
+        assert_eq!(pixels[..12], [255; 12]);
+        assert_eq!(pixels[12..], [0; 4]);
+
+        let pixels = hillshade(&service, (35.6, 138.0, 35.9, 138.5), 4, 4, 315.0, 90.0).unwrap();
+        assert!(pixels.iter().all(|&p| p == 0));
+
+        // No tile at all
+        let pixels = hillshade(&service, (40.1, 10.1, 40.2, 10.2), 3, 3, 315.0, 45.0).unwrap();
+        assert_eq!(pixels, vec![0; 9]);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let dir = TempDir::new().unwrap();
+        let service = SrtmService::new(dir.path(), 1);
+
+        assert!(hillshade(&service, (35.0, 138.0, 35.1, 138.1), 0, 4, 315.0, 45.0).is_err());
+        assert!(hillshade(&service, (35.1, 138.0, 35.0, 138.1), 4, 4, 315.0, 45.0).is_err());
+        // Rejected before any allocation or lookup
+        assert!(hillshade(
+            &service,
+            (35.0, 138.0, 35.1, 138.1),
+            5000,
+            5000,
+            315.0,
+            45.0
+        )
+        .is_err());
+        assert!(hillshade(
+            &service,
+            (35.0, 138.0, 35.1, 138.1),
+            usize::MAX,
+            usize::MAX,
+            315.0,
+            45.0
+        )
+        .is_err());
+    }
+}
@@ -10,6 +10,8 @@
 //! - **Automatic Detection**: Determines tile resolution (SRTM1/SRTM3) from file size
 //! - **Offline**: Works with local `.hgt` files, no internet required
 //! - **Auto-Download** (optional): Download missing tiles automatically
+//! - **Reprojection** (optional): Convert Web Mercator and UTM coordinates to WGS84
+//! - **Geoid** (optional): Convert SRTM heights to WGS84 ellipsoidal heights with EGM96
 //!
 //! ## Quick Start
 //!
@@ -91,18 +93,52 @@
 //! - <https://dwtkns.com/srtm30m/>
 //! - <https://earthexplorer.usgs.gov/>
 
+#[cfg(feature = "async")]
+pub mod async_service;
+
 #[cfg(feature = "download")]
 pub mod download;
 
+#[cfg(feature = "download")]
+mod geotiff;
+
+#[cfg(feature = "geojson")]
+pub mod contours;
+
 #[cfg(feature = "geojson")]
 pub mod geojson;
 
+#[cfg(feature = "geoid")]
+pub mod geoid;
+
+#[cfg(feature = "proj")]
+pub mod proj;
+
 pub mod error;
 pub mod filename;
+pub mod grid;
+pub mod hillshade;
+pub mod overview;
+pub mod profile;
 pub mod service;
 pub mod tile;
+pub mod tiling;
+
+#[cfg(any(test, feature = "test-util"))]
+#[doc(hidden)]
+pub mod test_util;
 
 // Re-export main types at crate root for convenience
 pub use error::{Result, SrtmError};
-pub use service::{BoundingBox, CacheStats, PreloadStats, SrtmService, SrtmServiceBuilder};
-pub use tile::{SrtmResolution, SrtmTile, VOID_VALUE};
+pub use grid::{ElevationGrid, Interpolation};
+pub use service::{
+    BoundingBox, CacheStats, ElevationLookup, PreloadStats, ProfilePoint, SrtmService,
+    SrtmServiceBuilder, DEFAULT_LAT_BOUNDS, MAX_NEAREST_RADIUS_M,
+};
+
+#[cfg(feature = "watch")]
+pub use service::DataDirWatcher;
+pub use tile::{
+    AccessPattern, SrtmResolution, SrtmTile, TileHealth, TileStats, Units, VoidPolicy,
+    FEET_PER_METER, SRTM_VERTICAL_ERROR_M, VOID_VALUE,
+};
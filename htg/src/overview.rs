@@ -0,0 +1,130 @@
+//! Overview (tile pyramid) generation.
+//!
+//! Overviews are downsampled copies of SRTM tiles for serving zoomed-out
+//! terrain. Level `n` averages `2^n` × `2^n` blocks of the source tile and is
+//! written as a big-endian `.hgt` grid named with an `_L{n}` suffix, e.g.
+//! `N35E138_L1.hgt` for the factor-2 overview of `N35E138.hgt`.
+//!
+//! Overview files have non-standard sizes (601 × 601 for a level-1 SRTM3
+//! tile), so they are not loadable with [`SrtmTile::from_file`].
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::tile::SrtmTile;
+
+/// Downsampling factor for an overview level (`2^level`).
+pub fn level_factor(level: u32) -> usize {
+    1usize << level
+}
+
+/// Filename of the overview at `level` for a source tile.
+///
+/// # Example
+///
+/// ```
+/// use htg::overview::overview_filename;
+///
+/// assert_eq!(overview_filename("N35E138.hgt", 2), "N35E138_L2.hgt");
+/// ```
+pub fn overview_filename(filename: &str, level: u32) -> String {
+    let base = filename.strip_suffix(".hgt").unwrap_or(filename);
+    format!("{}_L{}.hgt", base, level)
+}
+
+/// Write a single overview level of `tile` to `path`.
+///
+/// Returns the number of samples per row/column in the written file.
+pub fn write_overview<P: AsRef<Path>>(tile: &SrtmTile, level: u32, path: P) -> Result<usize> {
+    let factor = level_factor(level);
+    let samples = tile.downsample(factor);
+    let side = (tile.samples() - 1) / factor + 1;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    for elev in samples {
+        writer.write_all(&elev.to_be_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(side)
+}
+
+/// Write overview levels `1..=levels` for `tile` into `out_dir`.
+///
+/// `filename` is the source tile name (e.g., "N35E138.hgt") used to derive
+/// the output names. Returns the paths of the written files.
+pub fn build_overviews<P: AsRef<Path>>(
+    tile: &SrtmTile,
+    filename: &str,
+    out_dir: P,
+    levels: u32,
+) -> Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    fs::create_dir_all(out_dir)?;
+
+    let mut written = Vec::with_capacity(levels as usize);
+    for level in 1..=levels {
+        let path = out_dir.join(overview_filename(filename, level));
+        write_overview(tile, level, &path)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::VOID_VALUE;
+    use tempfile::TempDir;
+
+    const SRTM3_SAMPLES: usize = 1201;
+
+    fn set_sample(data: &mut [u8], row: usize, col: usize, elevation: i16) {
+        let offset = (row * SRTM3_SAMPLES + col) * 2;
+        data[offset..offset + 2].copy_from_slice(&elevation.to_be_bytes());
+    }
+
+    #[test]
+    fn test_overview_filename() {
+        assert_eq!(overview_filename("N35E138.hgt", 1), "N35E138_L1.hgt");
+        assert_eq!(overview_filename("S12W077", 3), "S12W077_L3.hgt");
+    }
+
+    #[test]
+    fn test_write_overview_level_1() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut data = vec![0u8; SRTM3_SAMPLES * SRTM3_SAMPLES * 2];
+
+        // Block at output (0, 0): 100, 200, 300, void -> mean of valid = 200
+        set_sample(&mut data, 0, 0, 100);
+        set_sample(&mut data, 0, 1, 200);
+        set_sample(&mut data, 1, 0, 300);
+        set_sample(&mut data, 1, 1, VOID_VALUE);
+
+        // Block at output (1, 1): entirely void
+        for (row, col) in [(2, 2), (2, 3), (3, 2), (3, 3)] {
+            set_sample(&mut data, row, col, VOID_VALUE);
+        }
+
+        let src = temp_dir.path().join("N35E138.hgt");
+        fs::write(&src, &data).unwrap();
+        let tile = SrtmTile::from_file_with_coords(&src, 35, 138).unwrap();
+
+        let written = build_overviews(&tile, "N35E138.hgt", temp_dir.path(), 1).unwrap();
+        assert_eq!(written, vec![temp_dir.path().join("N35E138_L1.hgt")]);
+
+        let out = fs::read(&written[0]).unwrap();
+        assert_eq!(out.len(), 601 * 601 * 2);
+
+        let sample = |row: usize, col: usize| {
+            let offset = (row * 601 + col) * 2;
+            i16::from_be_bytes([out[offset], out[offset + 1]])
+        };
+        assert_eq!(sample(0, 0), 200);
+        assert_eq!(sample(1, 1), VOID_VALUE);
+        assert_eq!(sample(300, 300), 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! Route profile statistics.
+//!
+//! [`ascent_descent`] totals the climbing and descending along an elevation
+//! profile such as the one returned by [`SrtmService::elevation_profile`],
+//! ignoring small wiggles so that noise in the data does not inflate the
+//! totals.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use htg::profile::ascent_descent;
+//!
+//! let profile = service.elevation_profile(&route, 30.0, true)?;
+//! let (ascent, descent) = ascent_descent(&profile, 5.0);
+//! println!("+{:.0} m / -{:.0} m", ascent, descent);
+//! ```
+//!
+//! [`SrtmService::elevation_profile`]: crate::SrtmService::elevation_profile
+
+use crate::ProfilePoint;
+
+/// Total ascent and descent along a profile, in meters.
+///
+/// Elevation changes are counted against the last elevation that was
+/// counted, and only once they reach `smoothing_threshold_m`: a bump smaller
+/// than the threshold that returns to where it started adds nothing, while a
+/// steady climb is counted in full (up to a remainder below the threshold at
+/// the very end). A threshold of `0` sums every change. Void samples
+/// (`elevation: None`) are skipped.
+///
+/// # Arguments
+///
+/// * `profile` - Profile samples in route order
+/// * `smoothing_threshold_m` - Smallest change in meters that is counted
+///
+/// # Returns
+///
+/// `(ascent_m, descent_m)`, both non-negative.
+pub fn ascent_descent(profile: &[ProfilePoint], smoothing_threshold_m: f64) -> (f64, f64) {
+    let mut ascent = 0.0;
+    let mut descent = 0.0;
+    let mut elevations = profile.iter().filter_map(|p| p.elevation);
+
+    if let Some(mut reference) = elevations.next() {
+        for elevation in elevations {
+            let change = elevation - reference;
+            if change.abs() < smoothing_threshold_m || change == 0.0 {
+                continue;
+            }
+            if change > 0.0 {
+                ascent += change;
+            } else {
+                descent -= change;
+            }
+            reference = elevation;
+        }
+    }
+
+    (ascent, descent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(elevations: &[Option<f64>]) -> Vec<ProfilePoint> {
+        elevations
+            .iter()
+            .enumerate()
+            .map(|(i, &elevation)| ProfilePoint {
+                distance_m: i as f64 * 30.0,
+                lat: 35.5,
+                lon: 138.5,
+                elevation,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_up_down_up_with_noise() {
+        // +100 up, a 2m spike, -50 down, +30 up
+        let elevations: Vec<Option<f64>> = [
+            100.0, 125.0, 150.0, 175.0, 200.0, 202.0, 200.0, 175.0, 150.0, 160.0, 170.0, 180.0,
+        ]
+        .into_iter()
+        .map(Some)
+        .collect();
+        let profile = profile(&elevations);
+
+        let (ascent, descent) = ascent_descent(&profile, 5.0);
+        assert_eq!((ascent, descent), (130.0, 50.0));
+
+        // Without smoothing the spike counts both ways
+        let (ascent, descent) = ascent_descent(&profile, 0.0);
+        assert_eq!((ascent, descent), (132.0, 52.0));
+    }
+
+    #[test]
+    fn test_gradual_climb_is_counted() {
+        // 1m steps, each below the threshold
+        let elevations: Vec<Option<f64>> = (0..=100).map(|e| Some(e as f64)).collect();
+        assert_eq!(ascent_descent(&profile(&elevations), 5.0), (100.0, 0.0));
+    }
+
+    #[test]
+    fn test_void_samples_are_skipped() {
+        let elevations = [None, Some(10.0), None, Some(30.0), Some(20.0), None];
+        assert_eq!(ascent_descent(&profile(&elevations), 0.0), (20.0, 10.0));
+        assert_eq!(ascent_descent(&profile(&[None, None]), 0.0), (0.0, 0.0));
+        assert_eq!(ascent_descent(&[], 5.0), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,366 @@
+//! Coordinate reprojection to and from WGS84.
+//!
+//! SRTM tiles are indexed by WGS84 latitude/longitude. This module converts
+//! coordinates from a few common projected systems so they can be looked up
+//! directly. The transforms are implemented in pure Rust (no PROJ system
+//! library), which limits the supported systems to:
+//!
+//! - **EPSG:4326** - WGS84 geographic (no-op)
+//! - **EPSG:3857** - Web Mercator
+//! - **EPSG:32601-32660 / 32701-32760** - WGS84 UTM zones, north / south
+//!
+//! # Example
+//!
+//! ```
+//! use htg::proj::Crs;
+//!
+//! let crs = Crs::from_epsg(3857).unwrap();
+//! let (lat, lon) = crs.to_wgs84(0.0, 0.0);
+//! assert!(lat.abs() < 1e-9 && lon.abs() < 1e-9);
+//! ```
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use crate::error::{Result, SrtmError};
+
+/// WGS84 semi-major axis in meters.
+const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS84 flattening.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// UTM scale factor on the central meridian.
+const UTM_K0: f64 = 0.9996;
+
+/// UTM false easting in meters.
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+
+/// UTM false northing for southern-hemisphere zones in meters.
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// A supported coordinate reference system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crs {
+    /// WGS84 geographic coordinates (EPSG:4326).
+    Wgs84,
+    /// Spherical Web Mercator (EPSG:3857).
+    WebMercator,
+    /// WGS84 UTM zone (EPSG:326xx north, EPSG:327xx south).
+    Utm {
+        /// Zone number, 1-60.
+        zone: u8,
+        /// `true` for the northern hemisphere.
+        north: bool,
+    },
+}
+
+impl Crs {
+    /// Look up a CRS by its EPSG code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::UnsupportedCrs`] for codes outside the supported set.
+    pub fn from_epsg(epsg: u32) -> Result<Self> {
+        match epsg {
+            4326 => Ok(Crs::Wgs84),
+            3857 => Ok(Crs::WebMercator),
+            32601..=32660 => Ok(Crs::Utm {
+                zone: (epsg - 32600) as u8,
+                north: true,
+            }),
+            32701..=32760 => Ok(Crs::Utm {
+                zone: (epsg - 32700) as u8,
+                north: false,
+            }),
+            _ => Err(SrtmError::UnsupportedCrs { epsg }),
+        }
+    }
+
+    /// The EPSG code of this CRS.
+    pub fn epsg(&self) -> u32 {
+        match *self {
+            Crs::Wgs84 => 4326,
+            Crs::WebMercator => 3857,
+            Crs::Utm { zone, north: true } => 32600 + zone as u32,
+            Crs::Utm { zone, north: false } => 32700 + zone as u32,
+        }
+    }
+
+    /// Convert `(x, y)` in this CRS to WGS84.
+    ///
+    /// For projected systems `x` is the easting and `y` the northing in
+    /// meters; for [`Crs::Wgs84`] they are longitude and latitude.
+    ///
+    /// # Returns
+    ///
+    /// `(lat, lon)` in decimal degrees, matching the argument order used by
+    /// the rest of the library.
+    pub fn to_wgs84(&self, x: f64, y: f64) -> (f64, f64) {
+        match *self {
+            Crs::Wgs84 => (y, x),
+            Crs::WebMercator => {
+                let lon = (x / WGS84_A).to_degrees();
+                let lat = (2.0 * (y / WGS84_A).exp().atan() - FRAC_PI_2).to_degrees();
+                (lat, lon)
+            }
+            Crs::Utm { zone, north } => utm_to_wgs84(zone, north, x, y),
+        }
+    }
+
+    /// Convert WGS84 `(lat, lon)` to `(x, y)` in this CRS.
+    ///
+    /// The inverse of [`to_wgs84`](Self::to_wgs84).
+    pub fn from_wgs84(&self, lat: f64, lon: f64) -> (f64, f64) {
+        match *self {
+            Crs::Wgs84 => (lon, lat),
+            Crs::WebMercator => {
+                let x = WGS84_A * lon.to_radians();
+                let y = WGS84_A * (FRAC_PI_4 + lat.to_radians() / 2.0).tan().ln();
+                (x, y)
+            }
+            Crs::Utm { zone, north } => wgs84_to_utm(zone, north, lat, lon),
+        }
+    }
+}
+
+/// Convert `(easting, northing)` in the CRS with EPSG code `epsg` to WGS84.
+///
+/// Shorthand for [`Crs::from_epsg`] followed by [`Crs::to_wgs84`]. For
+/// EPSG:4326 the arguments are longitude and latitude.
+///
+/// # Returns
+///
+/// `(lat, lon)` in decimal degrees.
+///
+/// # Errors
+///
+/// Returns [`SrtmError::UnsupportedCrs`] for codes outside the supported set.
+///
+/// # Example
+///
+/// ```
+/// use htg::proj::to_wgs84;
+///
+/// // UTM zone 54N
+/// let (lat, lon) = to_wgs84(273_243.63, 3_931_367.30, 32654).unwrap();
+/// assert!((lat - 35.5).abs() < 1e-6 && (lon - 138.5).abs() < 1e-6);
+/// ```
+pub fn to_wgs84(easting: f64, northing: f64, epsg: u32) -> Result<(f64, f64)> {
+    Ok(Crs::from_epsg(epsg)?.to_wgs84(easting, northing))
+}
+
+/// Central meridian of a UTM zone in radians.
+fn utm_central_meridian(zone: u8) -> f64 {
+    ((zone as f64 - 1.0) * 6.0 - 180.0 + 3.0).to_radians()
+}
+
+/// First eccentricity squared of the WGS84 ellipsoid.
+fn wgs84_e2() -> f64 {
+    WGS84_F * (2.0 - WGS84_F)
+}
+
+/// Meridian arc length from the equator to latitude `phi` (radians).
+fn meridian_arc(phi: f64) -> f64 {
+    let e2 = wgs84_e2();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
+
+/// Transverse Mercator forward projection (Snyder, USGS PP 1395, eq. 8-9 to 8-10).
+fn wgs84_to_utm(zone: u8, north: bool, lat: f64, lon: f64) -> (f64, f64) {
+    let e2 = wgs84_e2();
+    let ep2 = e2 / (1.0 - e2);
+
+    let phi = lat.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let tan_phi = phi.tan();
+
+    let n = WGS84_A / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+    let t = tan_phi * tan_phi;
+    let c = ep2 * cos_phi * cos_phi;
+    let a = cos_phi * (lon.to_radians() - utm_central_meridian(zone));
+
+    let x = UTM_K0
+        * n
+        * (a + (1.0 - t + c) * a.powi(3) / 6.0
+            + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+        + UTM_FALSE_EASTING;
+
+    let mut y = UTM_K0
+        * (meridian_arc(phi)
+            + n * tan_phi
+                * (a * a / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+    if !north {
+        y += UTM_FALSE_NORTHING_SOUTH;
+    }
+
+    (x, y)
+}
+
+/// Transverse Mercator inverse projection (Snyder, USGS PP 1395, eq. 8-18 to 8-25).
+fn utm_to_wgs84(zone: u8, north: bool, x: f64, y: f64) -> (f64, f64) {
+    let e2 = wgs84_e2();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    let ep2 = e2 / (1.0 - e2);
+
+    let y = if north {
+        y
+    } else {
+        y - UTM_FALSE_NORTHING_SOUTH
+    };
+
+    // Footpoint latitude
+    let m = y / UTM_K0;
+    let mu = m / (WGS84_A * (1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0));
+    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+    let phi1 = mu
+        + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+        + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+        + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+        + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+    let (sin_phi1, cos_phi1) = phi1.sin_cos();
+    let tan_phi1 = phi1.tan();
+    let c1 = ep2 * cos_phi1 * cos_phi1;
+    let t1 = tan_phi1 * tan_phi1;
+    let w = 1.0 - e2 * sin_phi1 * sin_phi1;
+    let n1 = WGS84_A / w.sqrt();
+    let r1 = WGS84_A * (1.0 - e2) / w.powf(1.5);
+    let d = (x - UTM_FALSE_EASTING) / (n1 * UTM_K0);
+
+    let phi = phi1
+        - (n1 * tan_phi1 / r1)
+            * (d * d / 2.0
+                - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1)
+                    * d.powi(6)
+                    / 720.0);
+
+    let lambda = utm_central_meridian(zone)
+        + (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1)
+                * d.powi(5)
+                / 120.0)
+            / cos_phi1;
+
+    (phi.to_degrees(), lambda.to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_epsg() {
+        assert_eq!(Crs::from_epsg(4326).unwrap(), Crs::Wgs84);
+        assert_eq!(Crs::from_epsg(3857).unwrap(), Crs::WebMercator);
+        assert_eq!(
+            Crs::from_epsg(32654).unwrap(),
+            Crs::Utm {
+                zone: 54,
+                north: true
+            }
+        );
+        assert_eq!(
+            Crs::from_epsg(32718).unwrap(),
+            Crs::Utm {
+                zone: 18,
+                north: false
+            }
+        );
+        assert!(matches!(
+            Crs::from_epsg(27700),
+            Err(SrtmError::UnsupportedCrs { epsg: 27700 })
+        ));
+        assert!(Crs::from_epsg(32600).is_err());
+        assert!(Crs::from_epsg(32761).is_err());
+
+        for epsg in [4326, 3857, 32601, 32660, 32701, 32760] {
+            assert_eq!(Crs::from_epsg(epsg).unwrap().epsg(), epsg);
+        }
+    }
+
+    #[test]
+    fn test_web_mercator_known_values() {
+        let crs = Crs::WebMercator;
+        let (x, y) = crs.from_wgs84(0.0, 180.0);
+        assert!((x - 20_037_508.342_789).abs() < 1e-3);
+        assert!(y.abs() < 1e-6);
+
+        let (lat, lon) = crs.to_wgs84(x, y);
+        assert!(lat.abs() < 1e-9);
+        assert!((lon - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_utm_known_values() {
+        // Equator on the central meridian of zone 31 (3°E)
+        let crs = Crs::from_epsg(32631).unwrap();
+        let (x, y) = crs.from_wgs84(0.0, 3.0);
+        assert!((x - 500_000.0).abs() < 1e-6);
+        assert!(y.abs() < 1e-6);
+
+        // 45°N on a central meridian: scaled meridian arc length
+        let (x, y) = crs.from_wgs84(45.0, 3.0);
+        assert!((x - 500_000.0).abs() < 1e-6);
+        assert!((y - 4_982_950.40).abs() < 0.1);
+
+        // Southern zones apply the 10,000 km false northing
+        let south = Crs::from_epsg(32731).unwrap();
+        let (_, y) = south.from_wgs84(0.0, 3.0);
+        assert!((y - 10_000_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_to_wgs84() {
+        // Independently computed zone 54N coordinates of (35.5, 138.5)
+        let (lat, lon) = to_wgs84(273_243.627, 3_931_367.297, 32654).unwrap();
+        assert!((lat - 35.5).abs() < 1e-8);
+        assert!((lon - 138.5).abs() < 1e-8);
+
+        assert_eq!(to_wgs84(138.5, 35.5, 4326).unwrap(), (35.5, 138.5));
+        assert!(matches!(
+            to_wgs84(0.0, 0.0, 27700),
+            Err(SrtmError::UnsupportedCrs { epsg: 27700 })
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let points: [(f64, f64); 3] = [
+            (35.3606, 138.7274),  // Mt. Fuji
+            (-12.0464, -77.0428), // Lima
+            (51.5007, -0.1246),   // London
+        ];
+        for (lat, lon) in points {
+            let zone = ((lon + 180.0) / 6.0).floor() as u32 + 1;
+            let utm = if lat >= 0.0 {
+                32600 + zone
+            } else {
+                32700 + zone
+            };
+            for epsg in [4326, 3857, utm] {
+                let crs = Crs::from_epsg(epsg).unwrap();
+                let (x, y) = crs.from_wgs84(lat, lon);
+                let (lat2, lon2) = crs.to_wgs84(x, y);
+                assert!(
+                    (lat - lat2).abs() < 1e-7 && (lon - lon2).abs() < 1e-7,
+                    "EPSG:{} round trip of ({}, {}) gave ({}, {})",
+                    epsg,
+                    lat,
+                    lon,
+                    lat2,
+                    lon2
+                );
+            }
+        }
+    }
+}
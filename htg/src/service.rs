@@ -25,20 +25,27 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use moka::sync::Cache;
 
 use crate::error::{Result, SrtmError};
-use crate::filename::{coords_to_filename, filename_to_lat_lon};
-use crate::tile::{SrtmTile, VOID_VALUE};
+use crate::filename::{
+    coords_to_filename, filename_to_lat_lon, normalize_lon, tile_origin, tiles_covering_bbox,
+};
+use crate::tile::{AccessPattern, SrtmResolution, SrtmTile, TileHealth, VoidPolicy, VOID_VALUE};
 
 #[cfg(feature = "download")]
 use crate::download::{DownloadConfig, Downloader};
 
 /// Statistics about cache usage.
-#[derive(Debug, Clone, Default)]
+///
+/// With the `serde` feature, serializes with an extra computed `hit_rate`
+/// field (ignored when deserializing).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub struct CacheStats {
     /// Number of tiles currently in the cache.
     pub entry_count: u64,
@@ -46,6 +53,10 @@ pub struct CacheStats {
     pub hit_count: u64,
     /// Number of cache misses (tiles loaded from disk).
     pub miss_count: u64,
+    /// Total mapped size of the cached tiles in bytes.
+    pub resident_bytes: u64,
+    /// Number of tiles evicted to make room for others.
+    pub eviction_count: u64,
 }
 
 impl CacheStats {
@@ -62,6 +73,25 @@ impl CacheStats {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for CacheStats {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CacheStats", 6)?;
+        state.serialize_field("entry_count", &self.entry_count)?;
+        state.serialize_field("hit_count", &self.hit_count)?;
+        state.serialize_field("miss_count", &self.miss_count)?;
+        state.serialize_field("resident_bytes", &self.resident_bytes)?;
+        state.serialize_field("eviction_count", &self.eviction_count)?;
+        state.serialize_field("hit_rate", &self.hit_rate())?;
+        state.end()
+    }
+}
+
 /// A geographic bounding box for filtering tiles during preload.
 ///
 /// Coordinates are in decimal degrees (WGS84).
@@ -125,11 +155,82 @@ pub struct PreloadStats {
     pub elapsed_ms: u64,
 }
 
+/// Mean Earth radius in meters, used for great-circle distances.
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Largest search radius of [`SrtmService::nearest_valid`]: one degree of
+/// latitude, the height of a tile (~111km).
+pub const MAX_NEAREST_RADIUS_M: f64 = EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
+
+/// A sample along an elevation profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfilePoint {
+    /// Cumulative great-circle distance from the first route point in meters.
+    pub distance_m: f64,
+    /// Latitude in decimal degrees.
+    pub lat: f64,
+    /// Longitude in decimal degrees.
+    pub lon: f64,
+    /// Elevation in meters, or `None` for void data or a missing tile.
+    pub elevation: Option<f64>,
+}
+
+/// A single elevation lookup with details about how it was served.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElevationLookup {
+    /// Elevation in meters, or `None` for void data.
+    pub elevation: Option<i16>,
+    /// Resolution of the tile the elevation came from.
+    pub resolution: SrtmResolution,
+    /// Whether the tile was already in the cache.
+    pub cache_hit: bool,
+}
+
+/// Effective Earth radius factor for line-of-sight checks (standard atmosphere).
+const EFFECTIVE_RADIUS_FACTOR: f64 = 4.0 / 3.0;
+
+/// Default latitude range accepted by [`SrtmService`], matching SRTM coverage.
+pub const DEFAULT_LAT_BOUNDS: (f64, f64) = (-60.0, 60.0);
+
+/// Haversine distance between two points in meters.
+fn haversine_m((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = phi2 - phi1;
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Point at fraction `f` along the great circle from `a` to `b`.
+///
+/// `delta` is the angular distance between the points in radians.
+fn great_circle_point(a: (f64, f64), b: (f64, f64), delta: f64, f: f64) -> (f64, f64) {
+    if delta < 1e-12 {
+        return a;
+    }
+    let (phi1, lambda1) = (a.0.to_radians(), a.1.to_radians());
+    let (phi2, lambda2) = (b.0.to_radians(), b.1.to_radians());
+
+    let wa = ((1.0 - f) * delta).sin() / delta.sin();
+    let wb = (f * delta).sin() / delta.sin();
+    let x = wa * phi1.cos() * lambda1.cos() + wb * phi2.cos() * lambda2.cos();
+    let y = wa * phi1.cos() * lambda1.sin() + wb * phi2.cos() * lambda2.sin();
+    let z = wa * phi1.sin() + wb * phi2.sin();
+
+    (
+        z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        y.atan2(x).to_degrees(),
+    )
+}
+
 /// High-level SRTM elevation service with automatic tile caching.
 ///
 /// `SrtmService` manages loading and caching of SRTM tiles, providing a simple
 /// interface to query elevation at any coordinate within the data directory.
 ///
+/// Cloning is cheap: clones are handles to the same tile cache, counters, and
+/// downloader, so one service can be handed to many worker threads.
+///
 /// # Example
 ///
 /// ```ignore
@@ -161,20 +262,168 @@ pub struct PreloadStats {
 ///     ))
 ///     .build()?;
 /// ```
+#[derive(Clone)]
 pub struct SrtmService {
     /// Directory containing .hgt files.
     data_dir: PathBuf,
     /// LRU cache of loaded tiles, keyed by (floor_lat, floor_lon).
     tile_cache: Cache<(i32, i32), Arc<SrtmTile>>,
     /// Number of cache hits.
-    hit_count: AtomicU64,
+    hit_count: Arc<AtomicU64>,
     /// Number of cache misses.
-    miss_count: AtomicU64,
+    miss_count: Arc<AtomicU64>,
+    /// Resident size and eviction count of `tile_cache`, kept up to date
+    /// by its eviction listener.
+    cache_usage: Arc<CacheUsage>,
+    /// Optional cache of interpolation quad corners, keyed by (tile_lat, tile_lon, row0, col0).
+    quad_cache: Option<Cache<QuadKey, [i16; 4]>>,
+    /// Optional set of tiles present in `data_dir`, consulted before touching the filesystem.
+    tile_index: Option<TileIndex>,
+    /// The data directories themselves, without the resolution
+    /// subdirectories of `tile_dirs`.
+    data_dirs: Vec<PathBuf>,
+    /// Directories searched for tiles, in order of preference (see
+    /// [`SrtmServiceBuilder::add_data_dir`] and
+    /// [`SrtmServiceBuilder::prefer_resolution`]); just `data_dir` by default.
+    tile_dirs: Vec<PathBuf>,
+    /// Accepted latitude range, [`DEFAULT_LAT_BOUNDS`] unless overridden with
+    /// [`SrtmServiceBuilder::lat_bounds`].
+    lat_bounds: (f64, f64),
     /// Optional downloader for auto-downloading missing tiles.
     #[cfg(feature = "download")]
-    downloader: Option<Downloader>,
+    downloader: Option<Arc<Downloader>>,
+    /// Number of filesystem existence checks, to verify the tile index in tests.
+    #[cfg(test)]
+    fs_checks: Arc<AtomicU64>,
+}
+
+/// Shared set of tile keys known to exist on disk.
+type TileIndex = Arc<RwLock<HashSet<(i32, i32)>>>;
+
+/// Callback receiving the filename of each evicted tile.
+type EvictCallback = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Tile cache usage, updated as tiles enter and leave the cache.
+#[derive(Default)]
+struct CacheUsage {
+    /// Total mapped size of the cached tiles in bytes.
+    resident_bytes: AtomicU64,
+    /// Number of tiles evicted for capacity or expiry.
+    eviction_count: AtomicU64,
+}
+
+/// Tile cache holding up to `capacity` tiles, each for at most `ttl`. Tiles
+/// leaving the cache for any reason are subtracted from `usage`; evicted
+/// (including expired) tiles are also counted and passed to `on_evict`.
+fn new_tile_cache(
+    capacity: u64,
+    ttl: Option<Duration>,
+    usage: &Arc<CacheUsage>,
+    on_evict: Option<EvictCallback>,
+) -> Cache<(i32, i32), Arc<SrtmTile>> {
+    let usage = Arc::clone(usage);
+    let mut builder = Cache::builder().max_capacity(capacity);
+    if let Some(ttl) = ttl {
+        builder = builder.time_to_live(ttl);
+    }
+    builder
+        .eviction_listener(move |key: Arc<(i32, i32)>, tile: Arc<SrtmTile>, cause| {
+            usage
+                .resident_bytes
+                .fetch_sub(tile.byte_len(), Ordering::Relaxed);
+            if cause.was_evicted() {
+                usage.eviction_count.fetch_add(1, Ordering::Relaxed);
+                if let Some(on_evict) = &on_evict {
+                    on_evict(coords_to_filename(key.0, key.1));
+                }
+            }
+        })
+        .build()
+}
+
+/// Directories searched for tiles, for each of `data_dirs` in order: with a
+/// preferred resolution, its `srtm1`/`srtm3` subdirectory, then the other
+/// one, then the directory itself.
+fn tile_dirs(data_dirs: &[PathBuf], prefer: Option<SrtmResolution>) -> Vec<PathBuf> {
+    let subdirs: &[&str] = match prefer {
+        Some(SrtmResolution::Srtm1) => &["srtm1", "srtm3"],
+        Some(SrtmResolution::Srtm3) => &["srtm3", "srtm1"],
+        Some(SrtmResolution::Custom { .. }) | None => &[],
+    };
+    data_dirs
+        .iter()
+        .flat_map(|dir| {
+            subdirs
+                .iter()
+                .map(|subdir| dir.join(subdir))
+                .chain(std::iter::once(dir.clone()))
+        })
+        .collect()
+}
+
+/// Scan `dirs` for `.hgt` and `.hgt.zip` files, returning canonical `.hgt` names.
+fn scan_tile_names(dirs: &[PathBuf]) -> HashSet<String> {
+    let mut filenames = HashSet::new();
+
+    let entries = dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten();
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if name.ends_with(".hgt.zip") {
+            // Strip .zip suffix to get the canonical .hgt name
+            let hgt_name = name.strip_suffix(".zip").unwrap();
+            filenames.insert(hgt_name.to_string());
+        } else if name.ends_with(".hgt") {
+            filenames.insert(name.to_string());
+        }
+    }
+
+    filenames
+}
+
+/// Collect the tile keys of every tile file in `dirs`.
+fn scan_tile_keys(dirs: &[PathBuf]) -> HashSet<(i32, i32)> {
+    scan_tile_names(dirs)
+        .iter()
+        .filter_map(|name| filename_to_lat_lon(name))
+        .collect()
+}
+
+/// Whether any of `dirs` holds `filename` as either `.hgt` or `.hgt.zip`.
+fn tile_file_present(dirs: &[PathBuf], filename: &str) -> bool {
+    dirs.iter()
+        .any(|dir| dir.join(filename).exists() || dir.join(format!("{}.zip", filename)).exists())
+}
+
+/// Whether `e` means a tile is absent (or could not be downloaded), as
+/// opposed to unreadable.
+fn is_missing_tile(e: &SrtmError) -> bool {
+    #[cfg(feature = "download")]
+    if matches!(e, SrtmError::DownloadFailed { .. }) {
+        return true;
+    }
+    matches!(
+        e,
+        SrtmError::FileNotFound { .. } | SrtmError::TileNotAvailable { .. }
+    )
+}
+
+/// Guard returned by [`SrtmService::watch_data_dir`].
+///
+/// The data directory is watched for as long as this value is alive.
+#[cfg(feature = "watch")]
+pub struct DataDirWatcher {
+    _watcher: notify::RecommendedWatcher,
 }
 
+/// Key of an interpolation quad: tile key plus the quad's northwest row/column.
+type QuadKey = (i32, i32, u32, u32);
+
 impl SrtmService {
     /// Create a new SRTM service.
     ///
@@ -192,13 +441,22 @@ impl SrtmService {
     /// let service = SrtmService::new("/data/srtm", 100);
     /// ```
     pub fn new<P: AsRef<Path>>(data_dir: P, cache_size: u64) -> Self {
+        let cache_usage = Arc::new(CacheUsage::default());
         Self {
             data_dir: data_dir.as_ref().to_path_buf(),
-            tile_cache: Cache::builder().max_capacity(cache_size).build(),
-            hit_count: AtomicU64::new(0),
-            miss_count: AtomicU64::new(0),
+            tile_cache: new_tile_cache(cache_size, None, &cache_usage, None),
+            hit_count: Arc::default(),
+            miss_count: Arc::default(),
+            cache_usage,
+            quad_cache: None,
+            tile_index: None,
+            data_dirs: vec![data_dir.as_ref().to_path_buf()],
+            tile_dirs: vec![data_dir.as_ref().to_path_buf()],
+            lat_bounds: DEFAULT_LAT_BOUNDS,
             #[cfg(feature = "download")]
             downloader: None,
+            #[cfg(test)]
+            fs_checks: Arc::default(),
         }
     }
 
@@ -256,6 +514,198 @@ impl SrtmService {
         }
     }
 
+    /// Get elevation and whether its tile was already in the cache.
+    ///
+    /// Behaves like [`Self::get_elevation`]; the flag lets callers attribute
+    /// cache hits to individual queries, which the counters in
+    /// [`Self::cache_stats`] cannot. A missing tile reports `(None, false)`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (elevation, cache_hit) = service.get_elevation_with_cache_hit(35.3606, 138.7274)?;
+    /// ```
+    pub fn get_elevation_with_cache_hit(&self, lat: f64, lon: f64) -> Result<(Option<i16>, bool)> {
+        match self.get_elevation_detailed(lat, lon) {
+            Ok(lookup) => Ok((lookup.elevation, lookup.cache_hit)),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                Ok((None, false))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get elevation together with the resolution of the tile it came from.
+    ///
+    /// Unlike [`Self::get_elevation`], a missing tile is an error, since there
+    /// is no resolution to report.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((Some(elevation), resolution))` - elevation in meters
+    /// - `Ok((None, resolution))` - void data in a loaded tile
+    /// - `Err(...)` - coordinates out of bounds, missing tile, corrupted file, or I/O error
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (elevation, resolution) = service.get_elevation_with_resolution(35.3606, 138.7274)?;
+    /// println!("{:?}m from {}", elevation, resolution.as_str());
+    /// ```
+    pub fn get_elevation_with_resolution(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<(Option<i16>, SrtmResolution)> {
+        let lookup = self.get_elevation_detailed(lat, lon)?;
+        Ok((lookup.elevation, lookup.resolution))
+    }
+
+    /// Get elevation together with the tile's resolution and whether the
+    /// tile was served from the cache, e.g. for per-query tracing.
+    ///
+    /// Like [`Self::get_elevation_with_resolution`], a missing tile is an
+    /// error.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let lookup = service.get_elevation_detailed(35.3606, 138.7274)?;
+    /// tracing::info!(cache_hit = lookup.cache_hit, "{:?}m", lookup.elevation);
+    /// ```
+    pub fn get_elevation_detailed(&self, lat: f64, lon: f64) -> Result<ElevationLookup> {
+        let (tile, cache_hit) = self.load_tile_for_coords_tracked(lat, lon)?;
+        let v = tile.get_elevation(lat, lon)?;
+        Ok(ElevationLookup {
+            elevation: if v == VOID_VALUE { None } else { Some(v) },
+            resolution: tile.resolution(),
+            cache_hit,
+        })
+    }
+
+    /// Get the resolution of the tile covering the coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are out of bounds or the tile is
+    /// missing or cannot be read.
+    pub fn tile_resolution(&self, lat: f64, lon: f64) -> Result<SrtmResolution> {
+        Ok(self.load_tile_for_coords(lat, lon)?.resolution())
+    }
+
+    /// Get the tile covering the coordinates, and whether it was served from
+    /// the cache.
+    ///
+    /// Lets callers run several lookups, e.g. an interpolation and the
+    /// tile's resolution, against a single load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are out of bounds or the tile is
+    /// missing or cannot be read.
+    pub fn tile_at(&self, lat: f64, lon: f64) -> Result<(Arc<SrtmTile>, bool)> {
+        self.load_tile_for_coords_tracked(lat, lon)
+    }
+
+    /// Get elevation from the tile of a specific resolution, bypassing the
+    /// usual preference order.
+    ///
+    /// The tile is loaded as usual (from the cache, disk, a local `.hgt.zip`,
+    /// or a download) and used if its resolution matches. Otherwise each data
+    /// directory is searched for the tile in its `srtm1`/`srtm3`
+    /// subdirectory (as used by [`SrtmServiceBuilder::prefer_resolution`])
+    /// and then directly, extracting `.hgt.zip` archives, taking the first
+    /// file whose size matches `resolution`. Such a tile is read straight from
+    /// disk and not cached, so this suits comparing resolutions rather than
+    /// bulk queries.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(elevation))` - elevation in meters
+    /// - `Ok(None)` - void data
+    /// - `Err(SrtmError::FileNotFound)` - no tile of that resolution on disk
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let srtm1 = service.get_elevation_at_resolution(35.3606, 138.7274, SrtmResolution::Srtm1)?;
+    /// let srtm3 = service.get_elevation_at_resolution(35.3606, 138.7274, SrtmResolution::Srtm3)?;
+    /// ```
+    pub fn get_elevation_at_resolution(
+        &self,
+        lat: f64,
+        lon: f64,
+        resolution: SrtmResolution,
+    ) -> Result<Option<i16>> {
+        let tile = match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) if tile.resolution() == resolution => tile,
+            Ok(_) => self.find_tile_with_resolution(lat, lon, resolution)?,
+            Err(e) if is_missing_tile(&e) => {
+                self.find_tile_with_resolution(lat, lon, resolution)?
+            }
+            Err(e) => return Err(e),
+        };
+        let v = tile.get_elevation(lat, lon)?;
+        Ok(if v == VOID_VALUE { None } else { Some(v) })
+    }
+
+    /// [`Self::load_tile_with_resolution`] for the tile covering the
+    /// coordinates, with the same whole-degree fallback as
+    /// [`Self::load_tile_for_coords_tracked`].
+    fn find_tile_with_resolution(
+        &self,
+        lat: f64,
+        lon: f64,
+        resolution: SrtmResolution,
+    ) -> Result<Arc<SrtmTile>> {
+        let key = self
+            .tile_key(lat, lon)
+            .ok_or(SrtmError::OutOfBounds { lat, lon })?;
+
+        match self.load_tile_with_resolution(key, resolution) {
+            Err(e) if is_missing_tile(&e) => match self.containing_tile_key(lat, lon, key) {
+                Some(containing) => self.load_tile_with_resolution(containing, resolution),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Read the tile at `key` from the first file of `resolution` on disk,
+    /// bypassing the cache (see [`Self::get_elevation_at_resolution`]).
+    fn load_tile_with_resolution(
+        &self,
+        key: (i32, i32),
+        resolution: SrtmResolution,
+    ) -> Result<Arc<SrtmTile>> {
+        let filename = coords_to_filename(key.0, key.1);
+        let zip_name = format!("{}.zip", filename);
+
+        for dir in &self.data_dirs {
+            for dir in [dir.join(resolution.as_str()), dir.clone()] {
+                let path = dir.join(&filename);
+                let zip_path = dir.join(&zip_name);
+                if !self.path_exists(&path) && self.path_exists(&zip_path) {
+                    self.extract_hgt_from_zip(&zip_path, &filename)?;
+                }
+                let size = match std::fs::metadata(&path) {
+                    Ok(metadata) => metadata.len() as usize,
+                    Err(_) => continue,
+                };
+                if SrtmResolution::from_file_size(size) == Some(resolution) {
+                    return Ok(Arc::new(SrtmTile::from_file_with_resolution(
+                        &path, key.0, key.1, resolution,
+                    )?));
+                }
+            }
+        }
+
+        Err(SrtmError::FileNotFound {
+            path: self.data_dir.join(resolution.as_str()).join(&filename),
+        })
+    }
+
     /// Get elevation using floor-based rounding (srtm.py compatible).
     ///
     /// This method uses `floor()` instead of `round()` for grid cell selection,
@@ -310,8 +760,33 @@ impl SrtmService {
     /// }
     /// ```
     pub fn get_elevation_interpolated(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        self.get_elevation_interpolated_with_policy(lat, lon, VoidPolicy::Strict)
+    }
+
+    /// Get elevation using bilinear interpolation, handling void corners
+    /// according to `policy`.
+    ///
+    /// See [`SrtmTile::get_elevation_interpolated_with_policy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    /// * `policy` - How to treat void corners of the interpolation cell
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(elevation))` - interpolated elevation in meters
+    /// - `Ok(None)` - void data (as decided by `policy`), missing tile, or tile not available
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn get_elevation_interpolated_with_policy(
+        &self,
+        lat: f64,
+        lon: f64,
+        policy: VoidPolicy,
+    ) -> Result<Option<f64>> {
         match self.load_tile_for_coords(lat, lon) {
-            Ok(tile) => tile.get_elevation_interpolated(lat, lon),
+            Ok(tile) => self.interpolate_in_tile(&tile, lat, lon, policy),
             Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
                 Ok(None)
             }
@@ -319,836 +794,3436 @@ impl SrtmService {
         }
     }
 
-    /// Get elevations for a batch of coordinates.
+    /// Get the elevation above the WGS84 ellipsoid, as reported by GPS.
     ///
-    /// Coordinates are grouped by tile so that each unique tile is loaded only
-    /// once, regardless of how many coordinates fall within it.
+    /// Adds the EGM96 geoid undulation ([`crate::geoid::egm96_undulation`])
+    /// to the bilinearly interpolated SRTM (orthometric) height.
     ///
-    /// Returns a vector of elevation values, one per input coordinate.
-    /// Uses `default` for void data, missing tiles, or errors.
+    /// # Returns
+    ///
+    /// * `Ok(Some(height))` - Ellipsoidal height in meters
+    /// * `Ok(None)` - Void data or missing tile
+    /// * `Err(SrtmError::GeoidUnavailable)` - No EGM96 grid installed
+    #[cfg(feature = "geoid")]
+    pub fn get_elevation_ellipsoidal(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        let Some(elevation) = self.get_elevation_interpolated(lat, lon)? else {
+            return Ok(None);
+        };
+        Ok(Some(elevation + crate::geoid::egm96_undulation(lat, lon)?))
+    }
+
+    /// Get elevation for projected coordinates using nearest-neighbor lookup.
+    ///
+    /// Reprojects with [`crate::proj::to_wgs84`] and then behaves like
+    /// [`get_elevation`](Self::get_elevation).
     ///
     /// # Arguments
     ///
-    /// * `coords` - Slice of (latitude, longitude) pairs
-    /// * `default` - Default value for void/missing/error results
+    /// * `easting` - Easting in meters (longitude for EPSG:4326)
+    /// * `northing` - Northing in meters (latitude for EPSG:4326)
+    /// * `epsg` - EPSG code of the coordinates' CRS (see [`crate::proj`])
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```ignore
-    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
-    /// let elevations = service.get_elevations_batch(&coords, 0);
-    /// ```
-    pub fn get_elevations_batch(&self, coords: &[(f64, f64)], default: i16) -> Vec<i16> {
-        self.batch_with_tile_grouping(coords, default, |tile, lat, lon| {
-            match tile.get_elevation(lat, lon) {
-                Ok(v) if v != VOID_VALUE => Some(v),
-                _ => None,
-            }
-        })
+    /// - `Ok(Some(elevation))` - elevation in meters
+    /// - `Ok(None)` - void data, missing tile, or tile not available
+    /// - `Err(...)` - unsupported CRS, coordinates out of bounds, corrupted
+    ///   file, or I/O error
+    #[cfg(feature = "proj")]
+    pub fn get_elevation_projected(
+        &self,
+        easting: f64,
+        northing: f64,
+        epsg: u32,
+    ) -> Result<Option<i16>> {
+        let (lat, lon) = crate::proj::to_wgs84(easting, northing, epsg)?;
+        self.get_elevation(lat, lon)
     }
 
-    /// Get elevations for a batch of coordinates using floor-based rounding.
-    ///
-    /// Coordinates are grouped by tile so that each unique tile is loaded only
-    /// once, regardless of how many coordinates fall within it.
+    /// Get elevation using bicubic (Catmull-Rom) interpolation.
     ///
-    /// Returns a vector of elevation values, one per input coordinate.
-    /// Uses floor-based rounding for srtm.py compatibility.
-    /// Uses `default` for void data, missing tiles, or errors.
+    /// See [`SrtmTile::get_elevation_bicubic`].
     ///
     /// # Arguments
     ///
-    /// * `coords` - Slice of (latitude, longitude) pairs
-    /// * `default` - Default value for void/missing/error results
-    pub fn get_elevations_batch_floor(&self, coords: &[(f64, f64)], default: i16) -> Vec<i16> {
-        self.batch_with_tile_grouping(coords, default, |tile, lat, lon| {
-            match tile.get_elevation_floor(lat, lon) {
-                Ok(v) if v != VOID_VALUE => Some(v),
-                _ => None,
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(elevation))` - interpolated elevation in meters
+    /// - `Ok(None)` - void data in the 4×4 window, missing tile, or tile not available
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn get_elevation_bicubic(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) => tile.get_elevation_bicubic(lat, lon),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                Ok(None)
             }
-        })
+            Err(e) => Err(e),
+        }
     }
 
-    /// Get interpolated elevations for a batch of coordinates.
+    /// Get the best available elevation, interpolated where possible.
     ///
-    /// Coordinates are grouped by tile so that each unique tile is loaded only
-    /// once, regardless of how many coordinates fall within it.
-    ///
-    /// Returns a vector of interpolated elevation values, one per input coordinate.
-    /// Uses bilinear interpolation for sub-pixel accuracy.
-    /// Uses `default` for void data, missing tiles, or errors.
+    /// Uses bilinear interpolation; if any sample in the 2×2 interpolation
+    /// window is void, falls back to the nearest-neighbor sample. Unlike the
+    /// other lookups a missing tile is an error rather than `None`.
     ///
     /// # Arguments
     ///
-    /// * `coords` - Slice of (latitude, longitude) pairs
-    /// * `default` - Default value for void/missing/error results
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```ignore
-    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
-    /// let elevations = service.get_elevations_batch_interpolated(&coords, 0.0);
-    /// ```
-    pub fn get_elevations_batch_interpolated(
-        &self,
-        coords: &[(f64, f64)],
-        default: f64,
-    ) -> Vec<f64> {
-        self.batch_with_tile_grouping(coords, default, |tile, lat, lon| {
-            tile.get_elevation_interpolated(lat, lon).ok().flatten()
-        })
+    /// - `Ok(Some(elevation))` - elevation in meters
+    /// - `Ok(None)` - the nearest sample is void as well
+    /// - `Err(...)` - coordinates out of bounds, missing or unavailable tile,
+    ///   corrupted file, or I/O error
+    pub fn get_elevation_smooth(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        let tile = self.load_tile_for_coords(lat, lon)?;
+        match self.interpolate_in_tile(&tile, lat, lon, VoidPolicy::Strict)? {
+            Some(elevation) => Ok(Some(elevation)),
+            None => {
+                let v = tile.get_elevation(lat, lon)?;
+                Ok((v != VOID_VALUE).then(|| f64::from(v)))
+            }
+        }
     }
 
-    /// Generic tile-grouped batch helper.
-    ///
-    /// Groups coordinates by tile key, loads each unique tile once, applies
-    /// the elevation function, and reassembles results in original input order.
-    ///
-    /// Includes a fast path: if all valid coordinates fall within a single tile,
-    /// the HashMap allocation is skipped entirely.
-    fn batch_with_tile_grouping<T: Copy>(
+    /// Bilinear interpolation within a loaded tile, via the quad cache if enabled.
+    fn interpolate_in_tile(
         &self,
-        coords: &[(f64, f64)],
-        default: T,
-        elevation_fn: impl Fn(&SrtmTile, f64, f64) -> Option<T>,
-    ) -> Vec<T> {
-        let mut results = vec![default; coords.len()];
-
-        // Fast path: check if all valid coords share the same tile key.
-        // This avoids HashMap allocation for the common case (e.g., route segments).
-        let mut common_key: Option<(i32, i32)> = None;
-        let mut all_same_tile = true;
-
-        for &(lat, lon) in coords {
-            if !(-60.0..=60.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
-                continue;
-            }
-            let key = (lat.floor() as i32, lon.floor() as i32);
-            match common_key {
-                None => common_key = Some(key),
-                Some(k) if k == key => {}
-                _ => {
-                    all_same_tile = false;
-                    break;
-                }
+        tile: &SrtmTile,
+        lat: f64,
+        lon: f64,
+        policy: VoidPolicy,
+    ) -> Result<Option<f64>> {
+        match &self.quad_cache {
+            Some(quad_cache) => {
+                let (row0, col0, row_weight, col_weight) = tile.interpolation_quad(lat, lon)?;
+                let key = (tile.base_lat(), tile.base_lon(), row0 as u32, col0 as u32);
+                let values = quad_cache.get_with(key, || tile.quad_values(row0, col0));
+                Ok(SrtmTile::interpolate_quad_with_policy(
+                    values, row_weight, col_weight, policy,
+                ))
             }
+            None => tile.get_elevation_interpolated_with_policy(lat, lon, policy),
         }
+    }
 
-        if all_same_tile {
-            if let Some(key) = common_key {
-                if let Ok(tile) = self.load_tile(key) {
-                    for (i, &(lat, lon)) in coords.iter().enumerate() {
-                        if !(-60.0..=60.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
-                            continue;
-                        }
-                        if let Some(v) = elevation_fn(&tile, lat, lon) {
-                            results[i] = v;
-                        }
-                    }
-                }
-            }
-            return results;
-        }
-
-        // Multi-tile path: group coordinate indices by tile key
-        let mut groups: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        for (i, &(lat, lon)) in coords.iter().enumerate() {
-            // Out-of-bounds coords get the default (skip grouping)
-            if !(-60.0..=60.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
-                continue;
+    /// Get elevation paired with a crude vertical uncertainty estimate.
+    ///
+    /// See [`SrtmTile::get_elevation_with_uncertainty`] for how the standard
+    /// deviation is derived.
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some((elevation, std_dev_m)))` - elevation and uncertainty in meters
+    /// - `Ok(None)` - void data, missing tile, or tile not available
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn get_elevation_with_uncertainty(&self, lat: f64, lon: f64) -> Result<Option<(f64, f64)>> {
+        match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) => tile.get_elevation_with_uncertainty(lat, lon),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                Ok(None)
             }
-            let key = (lat.floor() as i32, lon.floor() as i32);
-            groups.entry(key).or_default().push(i);
+            Err(e) => Err(e),
         }
+    }
 
-        // Process each tile group: 1 cache lookup per tile, not per coord
-        for (key, indices) in &groups {
-            let tile = match self.load_tile(*key) {
-                Ok(t) => t,
-                Err(_) => continue, // missing tile → all coords get default
-            };
-
-            for &i in indices {
-                let (lat, lon) = coords[i];
-                if let Some(v) = elevation_fn(&tile, lat, lon) {
-                    results[i] = v;
-                }
+    /// Get the Terrain Ruggedness Index at the coordinates.
+    ///
+    /// See [`SrtmTile::ruggedness`] for the definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(tri))` - mean absolute difference to the 8 neighbors in meters
+    /// - `Ok(None)` - void data in the neighborhood, missing tile, or tile not available
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn ruggedness(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) => tile.ruggedness(lat, lon),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                Ok(None)
             }
+            Err(e) => Err(e),
         }
-
-        results
     }
 
-    /// Validate coordinates and load the appropriate tile.
-    fn load_tile_for_coords(&self, lat: f64, lon: f64) -> Result<Arc<SrtmTile>> {
-        // Validate coordinates
-        if !(-60.0..=60.0).contains(&lat) {
-            return Err(SrtmError::OutOfBounds { lat, lon });
-        }
-        if !(-180.0..=180.0).contains(&lon) {
-            return Err(SrtmError::OutOfBounds { lat, lon });
+    /// Get elevation, filling voids from the closest valid sample.
+    ///
+    /// See [`SrtmTile::get_elevation_filled`] for the search strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    /// * `max_search` - Maximum search distance in grid cells
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(elevation))` - elevation in meters, possibly from a nearby sample
+    /// - `Ok(None)` - search window entirely void, missing tile, or tile not available
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn get_elevation_filled(
+        &self,
+        lat: f64,
+        lon: f64,
+        max_search: usize,
+    ) -> Result<Option<i16>> {
+        match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) => tile.get_elevation_filled(lat, lon, max_search),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                Ok(None)
+            }
+            Err(e) => Err(e),
         }
-
-        // Compute tile key directly — no heap allocation
-        let key = (lat.floor() as i32, lon.floor() as i32);
-
-        // Load tile (from cache or disk)
-        self.load_tile(key)
     }
 
-    /// Load a tile from cache, disk, or download if enabled.
-    fn load_tile(&self, key: (i32, i32)) -> Result<Arc<SrtmTile>> {
-        // Check cache first — no heap allocation for the key
-        if let Some(tile) = self.tile_cache.get(&key) {
-            self.hit_count.fetch_add(1, Ordering::Relaxed);
-            return Ok(tile);
+    /// Find the closest valid sample, searching across tile boundaries.
+    ///
+    /// Unlike [`Self::get_elevation_filled`], the search radius is a ground
+    /// distance and samples in neighboring tiles are considered, so a void
+    /// point near a tile edge (typically on a coastline) can be filled from
+    /// the adjacent tile. Every sample within `max_radius_m` is checked and
+    /// the one with the smallest great-circle distance wins. Missing tiles are
+    /// skipped; with auto-download enabled, neighboring tiles may be
+    /// downloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-60 to 60)
+    /// * `lon` - Longitude in decimal degrees (-180 to 180)
+    /// * `max_radius_m` - Maximum search distance in meters, capped at
+    ///   [`MAX_NEAREST_RADIUS_M`]
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some((lat, lon, elevation)))` - position and elevation of the
+    ///   closest valid sample
+    /// - `Ok(None)` - no valid sample within `max_radius_m`
+    /// - `Err(SrtmError::InvalidCoordinate)` - `max_radius_m` is negative or
+    ///   not finite
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    pub fn nearest_valid(
+        &self,
+        lat: f64,
+        lon: f64,
+        max_radius_m: f64,
+    ) -> Result<Option<(f64, f64, i16)>> {
+        self.tile_key(lat, lon)
+            .ok_or(SrtmError::OutOfBounds { lat, lon })?;
+        if !(max_radius_m.is_finite() && max_radius_m >= 0.0) {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!("search radius must be non-negative, got {}", max_radius_m),
+            });
         }
+        let max_radius_m = max_radius_m.min(MAX_NEAREST_RADIUS_M);
 
-        // Cache miss - generate filename string only now
-        self.miss_count.fetch_add(1, Ordering::Relaxed);
-
-        let filename = coords_to_filename(key.0, key.1);
-        let path = self.data_dir.join(&filename);
+        // Angular extent of the radius; longitude widens towards the poles
+        let dlat = (max_radius_m / EARTH_RADIUS_M).to_degrees();
+        let cos = (lat.abs() + dlat).min(90.0).to_radians().cos();
+        let dlon = if cos > 1e-9 {
+            (dlat / cos).min(180.0)
+        } else {
+            180.0
+        };
 
-        // If file doesn't exist, try zip extraction or download
-        if !path.exists() {
-            // Check for local .hgt.zip file
-            let zip_path = self.data_dir.join(format!("{}.zip", filename));
-            if zip_path.exists() {
-                self.extract_hgt_from_zip(&zip_path, &filename)?;
-            } else {
-                #[cfg(feature = "download")]
-                {
-                    if let Some(ref downloader) = self.downloader {
-                        // Try to download the tile
-                        downloader.download_tile_by_name(&filename, &self.data_dir)?;
-                    } else {
-                        return Err(SrtmError::TileNotAvailable { filename });
+        let mut best: Option<(f64, (f64, f64, i16))> = None;
+        for tile_lat in (lat - dlat).floor() as i32..=(lat + dlat).floor() as i32 {
+            // Unwrapped so the column arithmetic below works across the antimeridian
+            for tile_lon in (lon - dlon).floor() as i32..=(lon + dlon).floor() as i32 {
+                let Some(key) = self.tile_key(tile_lat as f64 + 0.5, tile_lon as f64 + 0.5) else {
+                    continue;
+                };
+                let tile = match self.load_tile(key) {
+                    Ok(tile) => tile,
+                    Err(SrtmError::FileNotFound { .. })
+                    | Err(SrtmError::TileNotAvailable { .. }) => continue,
+                    Err(e) => return Err(e),
+                };
+
+                let last = tile.samples() - 1;
+                let step = 1.0 / last as f64;
+                let row_of = |lat: f64| (tile_lat as f64 + 1.0 - lat) / step;
+                let col_of = |lon: f64| (lon - tile_lon as f64) / step;
+                let clamp = |index: f64| index.max(0.0).min(last as f64) as usize;
+
+                let rows = clamp(row_of(lat + dlat).floor())..=clamp(row_of(lat - dlat).ceil());
+                let cols = clamp(col_of(lon - dlon).floor())..=clamp(col_of(lon + dlon).ceil());
+                for row in rows {
+                    let sample_lat = tile_lat as f64 + 1.0 - row as f64 * step;
+                    for col in cols.clone() {
+                        let elevation = match tile.sample_at(row, col) {
+                            Some(v) if v != VOID_VALUE => v,
+                            _ => continue,
+                        };
+                        let sample_lon = tile_lon as f64 + col as f64 * step;
+                        let distance = haversine_m((lat, lon), (sample_lat, sample_lon));
+                        if distance <= max_radius_m
+                            && !matches!(best, Some((d, _)) if d <= distance)
+                        {
+                            best = Some((
+                                distance,
+                                (sample_lat, normalize_lon(sample_lon), elevation),
+                            ));
+                        }
                     }
                 }
-
-                #[cfg(not(feature = "download"))]
-                {
-                    return Err(SrtmError::FileNotFound { path });
-                }
-            }
-        }
-
-        let tile = Arc::new(SrtmTile::from_file_with_coords(&path, key.0, key.1)?);
-
-        // Insert into cache
-        self.tile_cache.insert(key, tile.clone());
-
-        Ok(tile)
-    }
-
-    /// Extract an .hgt file from a local .hgt.zip archive.
-    fn extract_hgt_from_zip(&self, zip_path: &Path, filename: &str) -> Result<()> {
-        let file = std::fs::File::open(zip_path).map_err(SrtmError::Io)?;
-        let mut archive = zip::ZipArchive::new(file)
-            .map_err(|e| SrtmError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
-
-        // Look for the .hgt file inside the archive
-        let mut found = false;
-        for i in 0..archive.len() {
-            let mut entry = archive.by_index(i).map_err(|e| {
-                SrtmError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
-            })?;
-
-            let entry_name = entry.name().to_string();
-            if entry_name.ends_with(".hgt") || entry_name == filename {
-                let out_path = self.data_dir.join(filename);
-                let mut out_file = std::fs::File::create(&out_path)?;
-                std::io::copy(&mut entry, &mut out_file)?;
-                found = true;
-                break;
             }
         }
 
-        if !found {
-            return Err(SrtmError::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                format!("No .hgt file found in {}", zip_path.display()),
-            )));
-        }
-
-        Ok(())
-    }
-
-    /// Check if auto-download is enabled.
-    #[cfg(feature = "download")]
-    pub fn has_auto_download(&self) -> bool {
-        self.downloader.is_some()
+        Ok(best.map(|(_, sample)| sample))
     }
 
-    /// Get cache statistics.
+    /// Check a tile for corruption heuristics.
     ///
-    /// Returns information about cache usage including hit rate.
-    pub fn cache_stats(&self) -> CacheStats {
-        CacheStats {
-            entry_count: self.tile_cache.entry_count(),
-            hit_count: self.hit_count.load(Ordering::Relaxed),
-            miss_count: self.miss_count.load(Ordering::Relaxed),
-        }
-    }
-
-    /// Get the data directory path.
-    pub fn data_dir(&self) -> &Path {
-        &self.data_dir
-    }
-
-    /// Get the maximum cache size.
-    pub fn cache_capacity(&self) -> u64 {
-        self.tile_cache.policy().max_capacity().unwrap_or(0)
-    }
-
-    /// Invalidate (remove) a specific tile from the cache.
+    /// Loads the tile (from cache, disk, `.hgt.zip`, or download) and runs
+    /// [`SrtmTile::health`] on it.
     ///
-    /// This can be useful if you know a tile file has been updated.
-    /// Accepts a filename (e.g., "N35E138.hgt") and parses the coordinates
-    /// to find the cache entry.
-    pub fn invalidate_tile(&self, filename: &str) {
-        if let Some(key) = filename_to_lat_lon(filename) {
-            self.tile_cache.invalidate(&key);
-        }
-    }
-
-    /// Clear all tiles from the cache.
-    pub fn clear_cache(&self) {
-        self.tile_cache.invalidate_all();
-    }
-
-    /// Scan the data directory for `.hgt` and `.hgt.zip` files.
+    /// # Arguments
     ///
-    /// Returns a sorted, deduplicated list of tile filenames (e.g., `["N35E138.hgt"]`).
-    /// Both `.hgt` and `.hgt.zip` files are discovered; duplicates are merged
-    /// (if both `N35E138.hgt` and `N35E138.hgt.zip` exist, only `N35E138.hgt` appears once).
-    pub fn scan_tile_files(&self) -> Vec<String> {
-        let mut filenames = HashSet::new();
-
-        let entries = match std::fs::read_dir(&self.data_dir) {
-            Ok(entries) => entries,
-            Err(_) => return Vec::new(),
-        };
-
-        for entry in entries.flatten() {
-            let name = entry.file_name();
-            let name = name.to_string_lossy();
-
-            if name.ends_with(".hgt.zip") {
-                // Strip .zip suffix to get the canonical .hgt name
-                let hgt_name = name.strip_suffix(".zip").unwrap();
-                filenames.insert(hgt_name.to_string());
-            } else if name.ends_with(".hgt") {
-                filenames.insert(name.to_string());
-            }
-        }
-
-        let mut result: Vec<String> = filenames.into_iter().collect();
-        result.sort();
-        result
+    /// * `filename` - The tile filename (e.g., "N35E138.hgt")
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filename is not a valid tile name or the tile
+    /// cannot be loaded.
+    pub fn validate_tile(&self, filename: &str) -> Result<TileHealth> {
+        let key = filename_to_lat_lon(filename).ok_or_else(|| SrtmError::InvalidCoordinate {
+            message: format!("Invalid tile filename: {}", filename),
+        })?;
+        Ok(self.load_tile(key)?.health())
     }
 
-    /// Preload tiles into the LRU cache.
+    /// Get elevations for a batch of coordinates.
     ///
-    /// Scans the data directory for `.hgt` and `.hgt.zip` files and loads them
-    /// into the cache. Optionally filters tiles by one or more bounding boxes.
+    /// Coordinates are grouped by tile so that each unique tile is loaded only
+    /// once, regardless of how many coordinates fall within it.
     ///
-    /// This is useful for warming the cache at startup to avoid cold-start latency
-    /// when tiles are stored on high-latency storage (e.g., NFS).
+    /// Returns a vector of elevation values, one per input coordinate.
+    /// Uses `default` for void data, missing tiles, or errors.
     ///
     /// # Arguments
     ///
-    /// * `bounds` - Optional slice of bounding boxes to filter tiles. If `None`,
-    ///   all discovered tiles are loaded. If `Some`, only tiles that overlap with
-    ///   at least one bounding box are loaded.
-    ///
-    /// # Returns
-    ///
-    /// Statistics about the preload operation.
+    /// * `coords` - Slice of (latitude, longitude) pairs
+    /// * `default` - Default value for void/missing/error results
     ///
     /// # Example
     ///
     /// ```ignore
-    /// use htg::{SrtmService, BoundingBox};
+    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
+    /// let elevations = service.get_elevations_batch(&coords, 0);
+    /// ```
+    pub fn get_elevations_batch(&self, coords: &[(f64, f64)], default: i16) -> Vec<i16> {
+        self.batch_with_tile_grouping(coords, default, |tile, lat, lon| {
+            match tile.get_elevation(lat, lon) {
+                Ok(v) if v != VOID_VALUE => Some(v),
+                _ => None,
+            }
+        })
+    }
+
+    /// Get elevations for a batch of coordinates using floor-based rounding.
     ///
-    /// let service = SrtmService::new("/data/srtm", 100);
+    /// Coordinates are grouped by tile so that each unique tile is loaded only
+    /// once, regardless of how many coordinates fall within it.
     ///
-    /// // Preload all tiles
-    /// let stats = service.preload(None);
-    /// println!("Loaded {} tiles in {}ms", stats.tiles_loaded, stats.elapsed_ms);
+    /// Returns a vector of elevation values, one per input coordinate.
+    /// Uses floor-based rounding for srtm.py compatibility.
+    /// Uses `default` for void data, missing tiles, or errors.
     ///
-    /// // Preload only CONUS tiles
-    /// let conus = BoundingBox::new(24.0, -125.0, 50.0, -66.0);
-    /// let stats = service.preload(Some(&[conus]));
-    /// ```
-    pub fn preload(&self, bounds: Option<&[BoundingBox]>) -> PreloadStats {
-        let start = Instant::now();
-        let mut stats = PreloadStats::default();
-
-        let filenames = self.scan_tile_files();
-
-        for filename in &filenames {
-            // Parse coordinates from filename to get tile key
-            let key = match filename_to_lat_lon(filename) {
-                Some(k) => k,
-                None => continue, // Can't parse coordinates, skip
-            };
-
-            // Filter by bounding boxes if provided
-            if let Some(boxes) = bounds {
-                if !boxes.iter().any(|b| b.overlaps_tile(key.0, key.1)) {
-                    continue;
-                }
-            }
-
-            stats.tiles_matched += 1;
-
-            // Check if already in cache
-            if self.tile_cache.get(&key).is_some() {
-                stats.tiles_already_cached += 1;
-                continue;
-            }
-
-            // Load the tile
-            match self.load_tile(key) {
-                Ok(_) => stats.tiles_loaded += 1,
-                Err(_) => stats.tiles_failed += 1,
+    /// # Arguments
+    ///
+    /// * `coords` - Slice of (latitude, longitude) pairs
+    /// * `default` - Default value for void/missing/error results
+    pub fn get_elevations_batch_floor(&self, coords: &[(f64, f64)], default: i16) -> Vec<i16> {
+        self.batch_with_tile_grouping(coords, default, |tile, lat, lon| {
+            match tile.get_elevation_floor(lat, lon) {
+                Ok(v) if v != VOID_VALUE => Some(v),
+                _ => None,
             }
-        }
-
-        stats.elapsed_ms = start.elapsed().as_millis() as u64;
-        stats
+        })
     }
-}
-
-/// Builder for creating [`SrtmService`] with custom configuration.
-///
-/// # Example
-///
-/// ```ignore
-/// use htg::SrtmServiceBuilder;
-///
-/// let service = SrtmServiceBuilder::new("/data/srtm")
-///     .cache_size(100)
-///     .build();
-/// ```
-///
-/// # With Auto-Download (requires `download` feature)
-///
-/// ```ignore
-/// use htg::{SrtmServiceBuilder, download::DownloadConfig};
-///
-/// let service = SrtmServiceBuilder::new("/data/srtm")
-///     .cache_size(100)
-///     .auto_download(DownloadConfig::with_url_template(
-///         "https://example.com/srtm/{filename}.hgt.gz", // compression auto-detected
-///     ))
-///     .build()?;
-/// ```
-pub struct SrtmServiceBuilder {
-    data_dir: PathBuf,
-    cache_size: u64,
-    #[cfg(feature = "download")]
-    download_config: Option<DownloadConfig>,
-}
 
-impl SrtmServiceBuilder {
-    /// Create a new builder with the specified data directory.
-    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
-        Self {
-            data_dir: data_dir.as_ref().to_path_buf(),
-            cache_size: 100, // Default cache size
-            #[cfg(feature = "download")]
-            download_config: None,
-        }
+    /// Get interpolated elevations for a batch of coordinates.
+    ///
+    /// Coordinates are grouped by tile so that each unique tile is loaded only
+    /// once, regardless of how many coordinates fall within it.
+    ///
+    /// Returns a vector of interpolated elevation values, one per input coordinate.
+    /// Each value matches [`Self::get_elevation_interpolated`] for the same
+    /// point (including the quad cache, if enabled).
+    /// Uses `default` where any contributing sample is void, for missing
+    /// tiles, or errors. Use [`Self::get_elevations_batch_interpolated_opt`]
+    /// to tell those apart from real heights.
+    ///
+    /// # Arguments
+    ///
+    /// * `coords` - Slice of (latitude, longitude) pairs
+    /// * `default` - Default value for void/missing/error results
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
+    /// let elevations = service.get_elevations_batch_interpolated(&coords, 0.0);
+    /// ```
+    pub fn get_elevations_batch_interpolated(
+        &self,
+        coords: &[(f64, f64)],
+        default: f64,
+    ) -> Vec<f64> {
+        self.get_elevations_batch_interpolated_opt(coords)
+            .into_iter()
+            .map(|e| e.unwrap_or(default))
+            .collect()
     }
 
-    /// Create a builder configured from environment variables.
+    /// Get interpolated elevations for a batch of coordinates, with `None`
+    /// for void data, missing tiles, or errors.
     ///
-    /// # Environment Variables
+    /// Otherwise the same as [`Self::get_elevations_batch_interpolated`].
     ///
-    /// | Variable | Description | Default |
-    /// |----------|-------------|---------|
-    /// | `HTG_DATA_DIR` | Directory containing .hgt files | Required |
-    /// | `HTG_CACHE_SIZE` | Maximum tiles in cache | 100 |
-    /// | `HTG_DOWNLOAD_SOURCE` | Named source: "ardupilot"* | None |
-    /// | `HTG_DOWNLOAD_URL` | URL template for custom downloads* | None |
-    /// | `HTG_DOWNLOAD_GZIP` | Whether URL serves gzip files* | false |
+    /// # Example
     ///
-    /// *Only used when `download` feature is enabled.
+    /// ```ignore
+    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
+    /// let elevations = service.get_elevations_batch_interpolated_opt(&coords);
+    /// ```
+    pub fn get_elevations_batch_interpolated_opt(&self, coords: &[(f64, f64)]) -> Vec<Option<f64>> {
+        self.batch_with_tile_grouping(coords, None, |tile, lat, lon| {
+            self.interpolate_in_tile(tile, lat, lon, VoidPolicy::Strict)
+                .ok()
+                .flatten()
+                .map(Some)
+        })
+    }
+
+    /// Sample a regular grid of interpolated elevations into a flat buffer.
     ///
-    /// # Named Sources
+    /// Returns a row-major `rows * cols` buffer, north-up: index `0` is the
+    /// north-west corner of `bbox` and index `rows * cols - 1` the south-east
+    /// corner. Grid nodes include the bbox edges, so with `cols > 1` the
+    /// longitude step is `(max_lon - min_lon) / (cols - 1)` (likewise for rows);
+    /// a single row or column samples the bbox midpoint.
     ///
-    /// - `ardupilot` or `ardupilot-srtm1` - ArduPilot SRTM1 (30m resolution, ~25MB/tile)
-    /// - `ardupilot-srtm3` - ArduPilot SRTM3 (90m resolution, ~2.8MB/tile)
+    /// Void samples, missing tiles, and cells outside SRTM coverage are set to
+    /// `void_fill`. The contiguous `f32` layout can be uploaded to a GPU
+    /// texture directly.
     ///
-    /// # URL Template Placeholders
+    /// # Errors
     ///
-    /// - `{filename}` - Full filename (e.g., "N35E138")
-    /// - `{lat_prefix}` - N or S
-    /// - `{lat}` - Latitude digits (e.g., "35")
-    /// - `{lon_prefix}` - E or W
-    /// - `{lon}` - Longitude digits (e.g., "138")
-    /// - `{continent}` - Continent subdirectory (e.g., "Eurasia", "North_America")
+    /// Returns [`SrtmError::InvalidCoordinate`] if `rows` or `cols` is zero or
+    /// the bounding box is inverted.
     ///
     /// # Example
     ///
-    /// ```bash
-    /// # Using ArduPilot source (recommended)
-    /// export HTG_DATA_DIR=/data/srtm
-    /// export HTG_DOWNLOAD_SOURCE=ardupilot
-    ///
-    /// # Or using custom URL template
-    /// export HTG_DATA_DIR=/data/srtm
-    /// export HTG_CACHE_SIZE=50
-    /// export HTG_DOWNLOAD_URL="https://example.com/srtm/{filename}.hgt.gz"
+    /// ```ignore
+    /// let bbox = BoundingBox::new(35.0, 138.0, 36.0, 139.0);
+    /// let heights = service.interpolated_grid_flat(&bbox, 512, 512, f32::NAN)?;
+    /// assert_eq!(heights.len(), 512 * 512);
     /// ```
+    pub fn interpolated_grid_flat(
+        &self,
+        bbox: &BoundingBox,
+        rows: usize,
+        cols: usize,
+        void_fill: f32,
+    ) -> Result<Vec<f32>> {
+        if rows == 0 || cols == 0 {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!(
+                    "grid must have at least one row and column, got {}x{}",
+                    rows, cols
+                ),
+            });
+        }
+        if bbox.min_lat > bbox.max_lat || bbox.min_lon > bbox.max_lon {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!(
+                    "inverted bounding box: ({}, {}) to ({}, {})",
+                    bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon
+                ),
+            });
+        }
+
+        let axis = |min: f64, max: f64, n: usize| -> Vec<f64> {
+            if n == 1 {
+                return vec![(min + max) / 2.0];
+            }
+            let step = (max - min) / (n - 1) as f64;
+            (0..n).map(|i| min + i as f64 * step).collect()
+        };
+        let lats = axis(bbox.min_lat, bbox.max_lat, rows);
+        let lons = axis(bbox.min_lon, bbox.max_lon, cols);
+
+        let coords: Vec<(f64, f64)> = lats
+            .iter()
+            .rev()
+            .flat_map(|&lat| lons.iter().map(move |&lon| (lat, lon)))
+            .collect();
+
+        Ok(
+            self.batch_with_tile_grouping(&coords, void_fill, |tile, lat, lon| {
+                tile.get_elevation_interpolated(lat, lon)
+                    .ok()
+                    .flatten()
+                    .map(|v| v as f32)
+            }),
+        )
+    }
+
+    /// Sample an elevation profile along a route at a fixed ground spacing.
     ///
-    /// ```ignore
-    /// use htg::SrtmServiceBuilder;
+    /// Each segment between consecutive `points` is densified along the great
+    /// circle so that samples are at most `sample_spacing_m` apart; every input
+    /// point is included exactly. All samples are then looked up in one
+    /// tile-grouped batch, so routes crossing tile boundaries load each tile
+    /// once.
     ///
-    /// let service = SrtmServiceBuilder::from_env()?.build()?;
-    /// ```
+    /// # Arguments
+    ///
+    /// * `points` - Route vertices as (latitude, longitude) pairs; longitudes
+    ///   outside `[-180, 180)` are wrapped with [`normalize_lon`], as are the
+    ///   returned sample longitudes
+    /// * `sample_spacing_m` - Maximum distance between samples in meters
+    /// * `interpolate` - Use bilinear interpolation instead of nearest-neighbor
     ///
     /// # Errors
     ///
-    /// Returns an error if `HTG_DATA_DIR` is not set.
-    pub fn from_env() -> Result<Self> {
-        let data_dir = std::env::var("HTG_DATA_DIR").map_err(|_| {
-            SrtmError::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "HTG_DATA_DIR environment variable not set",
-            ))
-        })?;
+    /// Returns [`SrtmError::InvalidCoordinate`] if `sample_spacing_m` is not a
+    /// positive finite number, or [`SrtmError::OutOfBounds`] if a route point is
+    /// outside SRTM coverage.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let route = [(35.36, 138.72), (35.40, 138.80)];
+    /// for p in service.elevation_profile(&route, 30.0, true)? {
+    ///     println!("{:.0} m: {:?}", p.distance_m, p.elevation);
+    /// }
+    /// ```
+    pub fn elevation_profile(
+        &self,
+        points: &[(f64, f64)],
+        sample_spacing_m: f64,
+        interpolate: bool,
+    ) -> Result<Vec<ProfilePoint>> {
+        if !(sample_spacing_m.is_finite() && sample_spacing_m > 0.0) {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!("sample spacing must be positive, got {}", sample_spacing_m),
+            });
+        }
+        let (min_lat, max_lat) = self.lat_bounds;
+        let mut vertices = Vec::with_capacity(points.len());
+        for &(lat, lon) in points {
+            let wrapped = normalize_lon(lon);
+            if !(min_lat..=max_lat).contains(&lat) || !(-180.0..180.0).contains(&wrapped) {
+                return Err(SrtmError::OutOfBounds { lat, lon });
+            }
+            vertices.push((lat, wrapped));
+        }
 
-        let cache_size: u64 = std::env::var("HTG_CACHE_SIZE")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(100);
+        let mut coords = Vec::new();
+        let mut distances = Vec::new();
+        let mut total = 0.0;
 
-        #[cfg(feature = "download")]
-        let download_config = {
-            // Check for named source first (e.g., "ardupilot")
-            if let Ok(source) = std::env::var("HTG_DOWNLOAD_SOURCE") {
-                match source.to_lowercase().as_str() {
-                    "ardupilot" | "ardupilot-srtm1" => Some(DownloadConfig::ardupilot_srtm1()),
-                    "ardupilot-srtm3" => Some(DownloadConfig::ardupilot_srtm3()),
-                    _ => {
-                        // Unknown source name, fall through to URL template
-                        None
-                    }
-                }
-            } else {
-                None
+        if let Some(&first) = vertices.first() {
+            coords.push(first);
+            distances.push(0.0);
+        }
+        for pair in vertices.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let length = haversine_m(a, b);
+            let delta = length / EARTH_RADIUS_M;
+            let steps = ((length / sample_spacing_m).ceil() as usize).max(1);
+
+            for i in 1..steps {
+                let f = i as f64 / steps as f64;
+                let (lat, lon) = great_circle_point(a, b, delta, f);
+                coords.push((lat, normalize_lon(lon)));
+                distances.push(total + length * f);
             }
-            .or_else(|| {
-                // Fall back to custom URL template
-                match std::env::var("HTG_DOWNLOAD_URL") {
-                    Ok(url_template) => {
-                        // Check for explicit compression setting, otherwise auto-detect from URL
-                        if let Ok(gzip_setting) = std::env::var("HTG_DOWNLOAD_GZIP") {
-                            let is_gzipped =
-                                gzip_setting.eq_ignore_ascii_case("true") || gzip_setting == "1";
-                            let compression = if is_gzipped {
-                                crate::download::Compression::Gzip
-                            } else {
-                                crate::download::Compression::None
-                            };
-                            Some(DownloadConfig::with_url_template_and_compression(
-                                url_template,
-                                compression,
-                            ))
-                        } else {
-                            Some(DownloadConfig::with_url_template(url_template))
-                        }
-                    }
-                    Err(_) => None,
-                }
+            total += length;
+            coords.push(b);
+            distances.push(total);
+        }
+
+        let elevations = if interpolate {
+            self.batch_with_tile_grouping(&coords, None, |tile, lat, lon| {
+                Some(tile.get_elevation_interpolated(lat, lon).ok().flatten())
+            })
+        } else {
+            self.batch_with_tile_grouping(&coords, None, |tile, lat, lon| {
+                Some(
+                    tile.get_elevation(lat, lon)
+                        .ok()
+                        .filter(|&v| v != VOID_VALUE)
+                        .map(f64::from),
+                )
             })
         };
 
-        Ok(Self {
-            data_dir: PathBuf::from(data_dir),
-            cache_size,
-            #[cfg(feature = "download")]
-            download_config,
-        })
+        Ok(coords
+            .into_iter()
+            .zip(distances)
+            .zip(elevations)
+            .map(|(((lat, lon), distance_m), elevation)| ProfilePoint {
+                distance_m,
+                lat,
+                lon,
+                elevation,
+            })
+            .collect())
     }
 
-    /// Set the data directory.
+    /// Get the elevation at a [`geo_types::Point`].
     ///
-    /// Overrides the directory set in the constructor or from environment.
-    pub fn data_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
-        self.data_dir = path.as_ref().to_path_buf();
-        self
+    /// `geo` points are `(x, y)` = `(longitude, latitude)`, the reverse of the
+    /// `(lat, lon)` order used by the rest of this API. This is equivalent to
+    /// `get_elevation(p.y(), p.x())`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Mount Fuji: x = longitude, y = latitude
+    /// let elevation = service.get_elevation_point(geo_types::Point::new(138.7274, 35.3606))?;
+    /// ```
+    #[cfg(feature = "geo-types")]
+    pub fn get_elevation_point(&self, p: geo_types::Point<f64>) -> Result<Option<i16>> {
+        self.get_elevation(p.y(), p.x())
     }
 
-    /// Set the maximum number of tiles to keep in cache.
+    /// Sample a bilinearly interpolated elevation profile along a
+    /// [`geo_types::LineString`].
     ///
-    /// Default is 100 tiles.
-    pub fn cache_size(mut self, size: u64) -> Self {
-        self.cache_size = size;
-        self
+    /// Coordinates are `(x, y)` = `(longitude, latitude)`; the returned
+    /// [`ProfilePoint`]s use `lat`/`lon` fields as usual. Equivalent to
+    /// [`Self::elevation_profile`] with the vertices swapped to `(lat, lon)`
+    /// and `interpolate` set.
+    #[cfg(feature = "geo-types")]
+    pub fn elevation_profile_line(
+        &self,
+        line: &geo_types::LineString<f64>,
+        spacing_m: f64,
+    ) -> Result<Vec<ProfilePoint>> {
+        let points: Vec<(f64, f64)> = line.coords().map(|c| (c.y, c.x)).collect();
+        self.elevation_profile(&points, spacing_m, true)
     }
 
-    /// Enable auto-download with the specified configuration.
+    /// Check whether two antenna tips can see each other over the terrain.
     ///
-    /// When enabled, missing tiles will be downloaded from the configured source.
-    /// Compression format is auto-detected from the URL extension (.gz, .zip).
+    /// `from` and `to` are `(lat, lon, height)` where `height` is meters above
+    /// the terrain at that point. The terrain is sampled along the great-circle
+    /// path at half the data resolution (see [`Self::elevation_profile`]) and
+    /// the line is blocked if any intermediate sample rises above the straight
+    /// sight ray between the two tips. Earth curvature is accounted for with
+    /// the standard 4/3 effective-radius refraction model.
+    ///
+    /// Void samples along the path never block the line; a void endpoint is
+    /// treated as sea level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either endpoint is out of bounds or its tile is
+    /// missing or cannot be read.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// use htg::{SrtmServiceBuilder, download::DownloadConfig};
-    ///
-    /// let service = SrtmServiceBuilder::new("/data/srtm")
-    ///     .auto_download(DownloadConfig::with_url_template(
-    ///         "https://example.com/{filename}.hgt.gz", // compression auto-detected
-    ///     ))
-    ///     .build()?;
+    /// // 10m mast to a drone flying 120m above ground
+    /// let visible = service.line_of_sight((35.36, 138.72, 10.0), (35.40, 138.80, 120.0), true)?;
     /// ```
-    #[cfg(feature = "download")]
-    pub fn auto_download(mut self, config: DownloadConfig) -> Self {
-        self.download_config = Some(config);
-        self
+    pub fn line_of_sight(
+        &self,
+        from: (f64, f64, f64),
+        to: (f64, f64, f64),
+        interpolate: bool,
+    ) -> Result<bool> {
+        let from_tile = self.load_tile_for_coords(from.0, from.1)?;
+        let to_tile = self.load_tile_for_coords(to.0, to.1)?;
+        let spacing = from_tile
+            .resolution()
+            .meters()
+            .min(to_tile.resolution().meters())
+            / 2.0;
+
+        let profile =
+            self.elevation_profile(&[(from.0, from.1), (to.0, to.1)], spacing, interpolate)?;
+        let (first, last) = (profile[0], profile[profile.len() - 1]);
+        let total = last.distance_m;
+
+        let from_tip = first.elevation.unwrap_or(0.0) + from.2;
+        let to_tip = last.elevation.unwrap_or(0.0) + to.2;
+        let effective_radius = EFFECTIVE_RADIUS_FACTOR * EARTH_RADIUS_M;
+
+        let blocked = profile[1..profile.len() - 1].iter().any(|p| {
+            let Some(ground) = p.elevation else {
+                return false;
+            };
+            let d = p.distance_m;
+            // Terrain bulge above the chord between the endpoints
+            let bulge = d * (total - d) / (2.0 * effective_radius);
+            let ray = from_tip + (to_tip - from_tip) * d / total;
+            ground + bulge > ray
+        });
+
+        Ok(!blocked)
     }
 
-    /// Build the [`SrtmService`].
+    /// D8 flow direction at the grid point nearest to a location.
     ///
-    /// # Errors
+    /// Returns the code of the steepest-descent neighbor among the 8 grid
+    /// points surrounding the cell, using the standard D8 encoding:
     ///
-    /// Returns an error if auto-download is enabled but the downloader
-    /// cannot be created (e.g., due to TLS initialization failure).
-    #[cfg(feature = "download")]
-    pub fn build(self) -> Result<SrtmService> {
-        let downloader = match self.download_config {
-            Some(config) => Some(Downloader::new(config)?),
-            None => None,
-        };
-
-        Ok(SrtmService {
-            data_dir: self.data_dir,
-            tile_cache: Cache::builder().max_capacity(self.cache_size).build(),
-            hit_count: AtomicU64::new(0),
-            miss_count: AtomicU64::new(0),
-            downloader,
-        })
+    /// ```text
+    ///  32  64 128
+    ///  16   x   1
+    ///   8   4   2
+    /// ```
+    ///
+    /// The drop to each neighbor is divided by its ground distance, so the
+    /// shorter east-west spacing at higher latitudes is taken into account.
+    /// Neighbors across a tile edge are read from the adjacent tile; missing
+    /// or void neighbors are skipped.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(code))` - D8 code (1, 2, 4, ..., 128), or `0` if no neighbor
+    ///   is lower than the cell (pit or flat)
+    /// - `Ok(None)` - the cell or all of its neighbors are void, or the tile is missing
+    /// - `Err(...)` - coordinates out of bounds, corrupted file, or I/O error
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(code) = service.flow_direction(35.3606, 138.7274)? {
+    ///     println!("Flow direction: {}", code);
+    /// }
+    /// ```
+    pub fn flow_direction(&self, lat: f64, lon: f64) -> Result<Option<u8>> {
+        const D8: [(u8, i32, i32); 8] = [
+            (1, 0, 1),
+            (2, -1, 1),
+            (4, -1, 0),
+            (8, -1, -1),
+            (16, 0, -1),
+            (32, 1, -1),
+            (64, 1, 0),
+            (128, 1, 1),
+        ];
+
+        let tile = match self.load_tile_for_coords(lat, lon) {
+            Ok(tile) => tile,
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. }) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+        let center = tile.get_elevation(lat, lon)?;
+        if center == VOID_VALUE {
+            return Ok(None);
+        }
+
+        // Snap to the grid point so neighbors are exactly one cell away
+        let step = tile.resolution().pixel_spacing_deg();
+        let center_lat =
+            tile.base_lat() as f64 + ((lat - tile.base_lat() as f64) / step).round() * step;
+        let center_lon =
+            tile.base_lon() as f64 + ((lon - tile.base_lon() as f64) / step).round() * step;
+        let dx = center_lat.to_radians().cos();
+
+        let mut best: Option<(u8, f64)> = None;
+        for (code, dlat, dlon) in D8 {
+            let neighbor = match self.get_elevation(
+                center_lat + dlat as f64 * step,
+                center_lon + dlon as f64 * step,
+            ) {
+                Ok(Some(v)) => v,
+                Ok(None) | Err(SrtmError::OutOfBounds { .. }) => continue,
+                Err(e) => return Err(e),
+            };
+            let distance = ((dlat * dlat) as f64 + (dlon as f64 * dx).powi(2)).sqrt();
+            let slope = (center as f64 - neighbor as f64) / distance;
+            if !matches!(best, Some((_, s)) if s >= slope) {
+                best = Some((code, slope));
+            }
+        }
+
+        Ok(best.map(|(code, slope)| if slope > 0.0 { code } else { 0 }))
+    }
+
+    /// Get elevations for a batch of coordinates together with the resolution
+    /// of the tile each one came from.
+    ///
+    /// Like [`Self::get_elevations_batch`], but each result also carries the
+    /// covering tile's [`SrtmResolution`]. The resolution is `None` when no tile
+    /// could be loaded (missing tile or out-of-bounds coordinate); void samples
+    /// in a loaded tile get `default` with the tile's resolution.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let coords = vec![(35.3606, 138.7274), (27.9881, 86.9250)];
+    /// for (elevation, resolution) in service.get_elevations_batch_with_resolution(&coords, 0) {
+    ///     println!("{} ({:?})", elevation, resolution.map(|r| r.as_str()));
+    /// }
+    /// ```
+    pub fn get_elevations_batch_with_resolution(
+        &self,
+        coords: &[(f64, f64)],
+        default: i16,
+    ) -> Vec<(i16, Option<SrtmResolution>)> {
+        self.batch_with_tile_grouping(coords, (default, None), |tile, lat, lon| {
+            let elevation = match tile.get_elevation(lat, lon) {
+                Ok(v) if v != VOID_VALUE => v,
+                _ => default,
+            };
+            Some((elevation, Some(tile.resolution())))
+        })
+    }
+
+    /// Generic tile-grouped batch helper.
+    ///
+    /// Groups coordinates by tile key, loads each unique tile once, applies
+    /// the elevation function, and reassembles results in original input order.
+    ///
+    /// Includes a fast path: if all valid coordinates fall within a single tile,
+    /// the HashMap allocation is skipped entirely.
+    fn batch_with_tile_grouping<T: Copy>(
+        &self,
+        coords: &[(f64, f64)],
+        default: T,
+        elevation_fn: impl Fn(&SrtmTile, f64, f64) -> Option<T>,
+    ) -> Vec<T> {
+        let mut results = vec![default; coords.len()];
+
+        // Fast path: check if all valid coords share the same tile key.
+        // This avoids HashMap allocation for the common case (e.g., route segments).
+        let mut common_key: Option<(i32, i32)> = None;
+        let mut all_same_tile = true;
+
+        for &(lat, lon) in coords {
+            let Some(key) = self.tile_key(lat, lon) else {
+                continue;
+            };
+            match common_key {
+                None => common_key = Some(key),
+                Some(k) if k == key => {}
+                _ => {
+                    all_same_tile = false;
+                    break;
+                }
+            }
+        }
+
+        if all_same_tile {
+            if let Some(key) = common_key {
+                match self.load_tile(key) {
+                    Ok(tile) => {
+                        for (i, &(lat, lon)) in coords.iter().enumerate() {
+                            if self.tile_key(lat, lon).is_none() {
+                                continue;
+                            }
+                            if let Some(v) = elevation_fn(&tile, lat, lon) {
+                                results[i] = v;
+                            }
+                        }
+                    }
+                    Err(e) if is_missing_tile(&e) => {
+                        let indices = coords
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, &(lat, lon))| self.tile_key(lat, lon).is_some())
+                            .map(|(i, _)| i);
+                        self.batch_fallback(coords, indices, key, &mut results, &elevation_fn);
+                    }
+                    Err(_) => {}
+                }
+            }
+            return results;
+        }
+
+        // Multi-tile path: group coordinate indices by tile key
+        let mut groups: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (i, &(lat, lon)) in coords.iter().enumerate() {
+            // Out-of-bounds coords get the default (skip grouping)
+            let Some(key) = self.tile_key(lat, lon) else {
+                continue;
+            };
+            groups.entry(key).or_default().push(i);
+        }
+
+        // Process each tile group: 1 cache lookup per tile, not per coord
+        for (key, indices) in &groups {
+            let tile = match self.load_tile(*key) {
+                Ok(t) => t,
+                Err(e) if is_missing_tile(&e) => {
+                    self.batch_fallback(
+                        coords,
+                        indices.iter().copied(),
+                        *key,
+                        &mut results,
+                        &elevation_fn,
+                    );
+                    continue;
+                }
+                Err(_) => continue, // unreadable tile → all coords get default
+            };
+
+            for &i in indices {
+                let (lat, lon) = coords[i];
+                if let Some(v) = elevation_fn(&tile, lat, lon) {
+                    results[i] = v;
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Fill in the coordinates at `indices`, whose tile `key` is missing,
+    /// from the tiles containing them. Only whole-degree coordinates have
+    /// such a tile; the rest keep the default.
+    fn batch_fallback<T>(
+        &self,
+        coords: &[(f64, f64)],
+        indices: impl IntoIterator<Item = usize>,
+        key: (i32, i32),
+        results: &mut [T],
+        elevation_fn: &impl Fn(&SrtmTile, f64, f64) -> Option<T>,
+    ) {
+        for i in indices {
+            let (lat, lon) = coords[i];
+            let Some(containing) = self.containing_tile_key(lat, lon, key) else {
+                continue;
+            };
+            if let Ok(tile) = self.load_tile(containing) {
+                if let Some(v) = elevation_fn(&tile, lat, lon) {
+                    results[i] = v;
+                }
+            }
+        }
+    }
+
+    /// Tile key `(lat, lon)` of the southwest corner for a coordinate.
+    ///
+    /// Latitude must be within the service's latitude bounds (±60° by
+    /// default); longitude is wrapped with [`normalize_lon`], so the
+    /// antimeridian maps to the `W180` tile. Returns `None` for out-of-range
+    /// or non-finite coordinates.
+    ///
+    /// Whole-degree coordinates go to the tile whose north or east edge they
+    /// are, as with [`tile_origin`], except on the southern latitude bound,
+    /// which has no tile below it. If that tile is missing they are read
+    /// from the tile containing them instead, see [`Self::containing_tile_key`].
+    fn tile_key(&self, lat: f64, lon: f64) -> Option<(i32, i32)> {
+        let lon = normalize_lon(lon);
+        let (min_lat, max_lat) = self.lat_bounds;
+        if !(min_lat..=max_lat).contains(&lat) || !(-180.0..180.0).contains(&lon) {
+            return None;
+        }
+        let (tile_lat, tile_lon) = tile_origin(lat, lon);
+        Some((tile_lat.max(min_lat.floor() as i32), tile_lon))
+    }
+
+    /// Key of the tile whose south or west edge a whole-degree coordinate is
+    /// (the tile containing it), where that differs from [`Self::tile_key`].
+    fn containing_tile_key(&self, lat: f64, lon: f64, key: (i32, i32)) -> Option<(i32, i32)> {
+        let containing = (lat.floor() as i32, normalize_lon(lon).floor() as i32);
+        (containing != key && containing.0 < self.lat_bounds.1.ceil() as i32).then_some(containing)
+    }
+
+    /// Validate coordinates and load the appropriate tile.
+    fn load_tile_for_coords(&self, lat: f64, lon: f64) -> Result<Arc<SrtmTile>> {
+        self.load_tile_for_coords_tracked(lat, lon)
+            .map(|(tile, _)| tile)
+    }
+
+    /// [`Self::load_tile_for_coords`], also returning whether the tile was a
+    /// cache hit.
+    fn load_tile_for_coords_tracked(&self, lat: f64, lon: f64) -> Result<(Arc<SrtmTile>, bool)> {
+        // Validate coordinates and compute the tile key — no heap allocation
+        let key = self
+            .tile_key(lat, lon)
+            .ok_or(SrtmError::OutOfBounds { lat, lon })?;
+
+        // Load tile (from cache or disk), falling back to the tile containing
+        // a whole-degree coordinate if the one whose edge it is is missing.
+        // If both are missing, the error names the containing tile.
+        match self.load_tile_tracked(key) {
+            Err(e) if is_missing_tile(&e) => match self.containing_tile_key(lat, lon, key) {
+                Some(containing) => self.load_tile_tracked(containing),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Load a tile from cache, disk, or download if enabled.
+    fn load_tile(&self, key: (i32, i32)) -> Result<Arc<SrtmTile>> {
+        self.load_tile_tracked(key).map(|(tile, _)| tile)
+    }
+
+    /// [`Self::load_tile`], also returning whether the tile was a cache hit.
+    fn load_tile_tracked(&self, key: (i32, i32)) -> Result<(Arc<SrtmTile>, bool)> {
+        // Check cache first — no heap allocation for the key
+        if let Some(tile) = self.tile_cache.get(&key) {
+            self.hit_count.fetch_add(1, Ordering::Relaxed);
+            return Ok((tile, true));
+        }
+
+        // Cache miss - generate filename string only now
+        self.miss_count.fetch_add(1, Ordering::Relaxed);
+
+        let filename = coords_to_filename(key.0, key.1);
+
+        // Known-absent tiles fail fast without touching the filesystem
+        if !self.tile_may_exist(key) {
+            #[cfg(feature = "download")]
+            return Err(SrtmError::TileNotAvailable { filename });
+
+            #[cfg(not(feature = "download"))]
+            return Err(SrtmError::FileNotFound {
+                path: self.data_dir.join(&filename),
+            });
+        }
+
+        let dir = self.locate_tile_dir(&filename);
+        let path = dir.join(&filename);
+
+        // If file doesn't exist, try zip extraction or download
+        if !self.path_exists(&path) {
+            // Check for local .hgt.zip file
+            let zip_path = dir.join(format!("{}.zip", filename));
+            if self.path_exists(&zip_path) {
+                self.extract_hgt_from_zip(&zip_path, &filename)?;
+            } else {
+                #[cfg(feature = "download")]
+                {
+                    if let Some(ref downloader) = self.downloader {
+                        // Try to download the tile
+                        downloader.download_tile_by_name(&filename, &self.data_dir)?;
+                        self.set_indexed(key, true);
+                    } else {
+                        self.set_indexed(key, false);
+                        return Err(SrtmError::TileNotAvailable { filename });
+                    }
+                }
+
+                #[cfg(not(feature = "download"))]
+                {
+                    self.set_indexed(key, false);
+                    return Err(SrtmError::FileNotFound { path });
+                }
+            }
+        }
+
+        let tile = Arc::new(SrtmTile::from_file_with_advice(
+            &path,
+            key.0,
+            key.1,
+            AccessPattern::Random,
+        )?);
+
+        // Insert into cache
+        self.cache_usage
+            .resident_bytes
+            .fetch_add(tile.byte_len(), Ordering::Relaxed);
+        self.tile_cache.insert(key, tile.clone());
+
+        Ok((tile, false))
+    }
+
+    /// The first tile directory holding `filename` (as `.hgt` or `.hgt.zip`),
+    /// or `data_dir` if none does, so downloads land in `data_dir`.
+    fn locate_tile_dir(&self, filename: &str) -> &Path {
+        if self.tile_dirs.len() > 1 {
+            let zip_name = format!("{}.zip", filename);
+            for dir in &self.tile_dirs {
+                if self.path_exists(&dir.join(filename)) || self.path_exists(&dir.join(&zip_name)) {
+                    return dir;
+                }
+            }
+        }
+        &self.data_dir
+    }
+
+    /// Whether a tile could be loaded: always true without a tile index or
+    /// with auto-download, otherwise whether the index lists it.
+    fn tile_may_exist(&self, key: (i32, i32)) -> bool {
+        #[cfg(feature = "download")]
+        if self.downloader.is_some() {
+            return true;
+        }
+        match &self.tile_index {
+            Some(index) => index.read().unwrap().contains(&key),
+            None => true,
+        }
+    }
+
+    /// Record whether a tile is present on disk, if the tile index is enabled.
+    fn set_indexed(&self, key: (i32, i32), present: bool) {
+        if let Some(index) = &self.tile_index {
+            let mut index = index.write().unwrap();
+            if present {
+                index.insert(key);
+            } else {
+                index.remove(&key);
+            }
+        }
+    }
+
+    /// `Path::exists`, counted in tests to verify the tile index avoids syscalls.
+    fn path_exists(&self, path: &Path) -> bool {
+        #[cfg(test)]
+        self.fs_checks.fetch_add(1, Ordering::Relaxed);
+        path.exists()
+    }
+
+    /// Extract an .hgt file from a local .hgt.zip archive.
+    fn extract_hgt_from_zip(&self, zip_path: &Path, filename: &str) -> Result<()> {
+        let file = std::fs::File::open(zip_path).map_err(SrtmError::Io)?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| SrtmError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+
+        // Look for the .hgt file inside the archive
+        let mut found = false;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| {
+                SrtmError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            })?;
+
+            let entry_name = entry.name().to_string();
+            if entry_name.ends_with(".hgt") || entry_name == filename {
+                let out_path = zip_path.with_file_name(filename);
+                let mut out_file = std::fs::File::create(&out_path)?;
+                std::io::copy(&mut entry, &mut out_file)?;
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            return Err(SrtmError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No .hgt file found in {}", zip_path.display()),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check if auto-download is enabled.
+    #[cfg(feature = "download")]
+    pub fn has_auto_download(&self) -> bool {
+        self.downloader.is_some()
+    }
+
+    /// Get cache statistics.
+    ///
+    /// Returns information about cache usage including hit rate.
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            entry_count: self.tile_cache.entry_count(),
+            hit_count: self.hit_count.load(Ordering::Relaxed),
+            miss_count: self.miss_count.load(Ordering::Relaxed),
+            resident_bytes: self.memory_usage_bytes(),
+            eviction_count: self.cache_usage.eviction_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset the hit, miss, and eviction counters to zero.
+    ///
+    /// Lets callers measure the hit rate over a window. `entry_count` and
+    /// `resident_bytes` describe what is cached right now and are unaffected.
+    pub fn reset_stats(&self) {
+        self.hit_count.store(0, Ordering::Relaxed);
+        self.miss_count.store(0, Ordering::Relaxed);
+        self.cache_usage.eviction_count.store(0, Ordering::Relaxed);
+    }
+
+    /// Total mapped size in bytes of the tiles currently in the cache.
+    ///
+    /// SRTM1 tiles take ~25MB each and SRTM3 tiles ~2.8MB, so this is a
+    /// better guide to RAM usage than the entry count.
+    pub fn memory_usage_bytes(&self) -> u64 {
+        self.cache_usage.resident_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Get the data directory path.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Accepted latitude range as `(min, max)`, set with
+    /// [`SrtmServiceBuilder::lat_bounds`].
+    pub fn lat_bounds(&self) -> (f64, f64) {
+        self.lat_bounds
+    }
+
+    /// Path of a tile file on disk, e.g. for its modification time.
+    ///
+    /// Searches the tile directories in order of preference for `filename`
+    /// (e.g. "N35E138.hgt"), falling back to a not yet extracted
+    /// `.hgt.zip`. Returns `None` if neither exists.
+    pub fn tile_path(&self, filename: &str) -> Option<PathBuf> {
+        let zip_name = format!("{}.zip", filename);
+        self.tile_dirs.iter().find_map(|dir| {
+            [dir.join(filename), dir.join(&zip_name)]
+                .into_iter()
+                .find(|path| path.exists())
+        })
+    }
+
+    /// Get the maximum cache size.
+    pub fn cache_capacity(&self) -> u64 {
+        self.tile_cache.policy().max_capacity().unwrap_or(0)
+    }
+
+    /// Invalidate (remove) a specific tile from the cache.
+    ///
+    /// This can be useful if you know a tile file has been updated.
+    /// Accepts a filename (e.g., "N35E138.hgt") and parses the coordinates
+    /// to find the cache entry.
+    pub fn invalidate_tile(&self, filename: &str) {
+        if let Some(key) = filename_to_lat_lon(filename) {
+            self.tile_cache.invalidate(&key);
+            if let Some(quad_cache) = &self.quad_cache {
+                quad_cache.invalidate_all();
+            }
+            if self.tile_index.is_some() {
+                let name = format!("{}.hgt", filename.trim_end_matches(".hgt"));
+                self.set_indexed(key, tile_file_present(&self.tile_dirs, &name));
+            }
+        }
+    }
+
+    /// Invalidate every tile overlapping a bounding box.
+    ///
+    /// Use this after replacing the tiles of a region on disk, e.g. with
+    /// void-filled versions, to reload just those tiles. Tiles are enumerated
+    /// with [`tiles_covering_bbox`] and passed to
+    /// [`invalidate_tile`](Self::invalidate_tile); an inverted box does
+    /// nothing.
+    pub fn invalidate_bbox(&self, min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) {
+        for filename in tiles_covering_bbox(min_lat, min_lon, max_lat, max_lon) {
+            self.invalidate_tile(&filename);
+        }
+    }
+
+    /// Rescan the data directory into the tile index.
+    ///
+    /// Only needed when the index is enabled (see
+    /// [`SrtmServiceBuilder::tile_index`]) and files were added or removed
+    /// without [`watch_data_dir`](Self::watch_data_dir) running. Does nothing
+    /// otherwise.
+    pub fn refresh_tile_index(&self) {
+        if let Some(index) = &self.tile_index {
+            *index.write().unwrap() = scan_tile_keys(&self.tile_dirs);
+        }
+    }
+
+    /// Watch the data directory and keep the cache in sync with it.
+    ///
+    /// Whenever an `.hgt` or `.hgt.zip` file is created, modified, or removed
+    /// in [`data_dir`](Self::data_dir), the corresponding tile is invalidated
+    /// and the tile index (if enabled) updated, so the next query sees the
+    /// file's current state. This lets a service run alongside a process that
+    /// syncs tiles without restarting.
+    ///
+    /// Watching stops when the returned [`DataDirWatcher`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::Io`] if the platform watcher cannot be created or
+    /// the directory cannot be watched.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let service = SrtmService::new("/data/srtm", 100);
+    /// let _watcher = service.watch_data_dir()?;
+    /// ```
+    #[cfg(feature = "watch")]
+    pub fn watch_data_dir(&self) -> Result<DataDirWatcher> {
+        use notify::{EventKind, RecursiveMode, Watcher};
+
+        let tile_cache = self.tile_cache.clone();
+        let quad_cache = self.quad_cache.clone();
+        let tile_index = self.tile_index.clone();
+        let tile_dirs = self.tile_dirs.clone();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in &event.paths {
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                let name = name.strip_suffix(".zip").unwrap_or(name);
+                if !name.ends_with(".hgt") {
+                    continue;
+                }
+                if let Some(key) = filename_to_lat_lon(name) {
+                    tile_cache.invalidate(&key);
+                    if let Some(quad_cache) = &quad_cache {
+                        quad_cache.invalidate_all();
+                    }
+                    if let Some(index) = &tile_index {
+                        let mut index = index.write().unwrap();
+                        if tile_file_present(&tile_dirs, name) {
+                            index.insert(key);
+                        } else {
+                            index.remove(&key);
+                        }
+                    }
+                }
+            }
+        })
+        .map_err(|e| SrtmError::Io(std::io::Error::other(e)))?;
+
+        watcher
+            .watch(&self.data_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| SrtmError::Io(std::io::Error::other(e)))?;
+        for dir in &self.tile_dirs {
+            if dir != &self.data_dir && dir.is_dir() {
+                watcher
+                    .watch(dir, RecursiveMode::NonRecursive)
+                    .map_err(|e| SrtmError::Io(std::io::Error::other(e)))?;
+            }
+        }
+
+        Ok(DataDirWatcher { _watcher: watcher })
+    }
+
+    /// Clear all tiles from the cache.
+    pub fn clear_cache(&self) {
+        self.tile_cache.invalidate_all();
+        // Evict now so memory_usage_bytes drops immediately
+        self.tile_cache.run_pending_tasks();
+        if let Some(quad_cache) = &self.quad_cache {
+            quad_cache.invalidate_all();
+        }
+    }
+
+    /// Scan the data directory for `.hgt` and `.hgt.zip` files.
+    ///
+    /// Returns a sorted, deduplicated list of tile filenames (e.g., `["N35E138.hgt"]`).
+    /// Both `.hgt` and `.hgt.zip` files are discovered; duplicates are merged
+    /// (if both `N35E138.hgt` and `N35E138.hgt.zip` exist, only `N35E138.hgt` appears once).
+    pub fn scan_tile_files(&self) -> Vec<String> {
+        let mut result: Vec<String> = scan_tile_names(&self.tile_dirs).into_iter().collect();
+        result.sort();
+        result
+    }
+
+    /// Preload tiles into the LRU cache.
+    ///
+    /// Scans the data directory for `.hgt` and `.hgt.zip` files and loads them
+    /// into the cache. Optionally filters tiles by one or more bounding boxes.
+    ///
+    /// This is useful for warming the cache at startup to avoid cold-start latency
+    /// when tiles are stored on high-latency storage (e.g., NFS).
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - Optional slice of bounding boxes to filter tiles. If `None`,
+    ///   all discovered tiles are loaded. If `Some`, only tiles that overlap with
+    ///   at least one bounding box are loaded.
+    ///
+    /// # Returns
+    ///
+    /// Statistics about the preload operation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use htg::{SrtmService, BoundingBox};
+    ///
+    /// let service = SrtmService::new("/data/srtm", 100);
+    ///
+    /// // Preload all tiles
+    /// let stats = service.preload(None);
+    /// println!("Loaded {} tiles in {}ms", stats.tiles_loaded, stats.elapsed_ms);
+    ///
+    /// // Preload only CONUS tiles
+    /// let conus = BoundingBox::new(24.0, -125.0, 50.0, -66.0);
+    /// let stats = service.preload(Some(&[conus]));
+    /// ```
+    pub fn preload(&self, bounds: Option<&[BoundingBox]>) -> PreloadStats {
+        let start = Instant::now();
+        let mut stats = PreloadStats::default();
+
+        let filenames = self.scan_tile_files();
+
+        for filename in &filenames {
+            // Parse coordinates from filename to get tile key
+            let key = match filename_to_lat_lon(filename) {
+                Some(k) => k,
+                None => continue, // Can't parse coordinates, skip
+            };
+
+            // Filter by bounding boxes if provided
+            if let Some(boxes) = bounds {
+                if !boxes.iter().any(|b| b.overlaps_tile(key.0, key.1)) {
+                    continue;
+                }
+            }
+
+            stats.tiles_matched += 1;
+
+            // Check if already in cache
+            if self.tile_cache.get(&key).is_some() {
+                stats.tiles_already_cached += 1;
+                continue;
+            }
+
+            // Load the tile
+            match self.load_tile(key) {
+                Ok(_) => stats.tiles_loaded += 1,
+                Err(_) => stats.tiles_failed += 1,
+            }
+        }
+
+        stats.elapsed_ms = start.elapsed().as_millis() as u64;
+        stats
+    }
+
+    /// Load the named tiles into the LRU cache.
+    ///
+    /// Unlike [`preload`](Self::preload), which scans the data directory,
+    /// this takes an explicit list of tile names, such as the output of
+    /// [`crate::filename::tiles_covering_bbox`]. Tiles are loaded the same way
+    /// as on a query: from the cache, from disk, or downloaded if
+    /// auto-download is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `filenames` - Tile names, e.g. `"N35E138.hgt"` (see
+    ///   [`crate::filename::filename_to_lat_lon`] for accepted forms)
+    ///
+    /// # Returns
+    ///
+    /// One `(filename, outcome)` pair per input, in order. Names that cannot be
+    /// parsed fail with [`SrtmError::InvalidCoordinate`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use htg::filename::tiles_covering_bbox;
+    ///
+    /// let tiles = tiles_covering_bbox(35.0, 138.0, 36.5, 139.5);
+    /// for (filename, outcome) in service.prefetch_tiles(&tiles) {
+    ///     if let Err(e) = outcome {
+    ///         eprintln!("{} unavailable: {}", filename, e);
+    ///     }
+    /// }
+    /// ```
+    pub fn prefetch_tiles(&self, filenames: &[String]) -> Vec<(String, Result<()>)> {
+        filenames
+            .iter()
+            .map(|filename| {
+                let outcome = match filename_to_lat_lon(filename) {
+                    Some(key) => self.load_tile(key).map(|_| ()),
+                    None => Err(SrtmError::InvalidCoordinate {
+                        message: format!("Invalid tile filename: {}", filename),
+                    }),
+                };
+                (filename.clone(), outcome)
+            })
+            .collect()
+    }
+}
+
+/// Builder for creating [`SrtmService`] with custom configuration.
+///
+/// # Example
+///
+/// ```ignore
+/// use htg::SrtmServiceBuilder;
+///
+/// let service = SrtmServiceBuilder::new("/data/srtm")
+///     .cache_size(100)
+///     .build();
+/// ```
+///
+/// # With Auto-Download (requires `download` feature)
+///
+/// ```ignore
+/// use htg::{SrtmServiceBuilder, download::DownloadConfig};
+///
+/// let service = SrtmServiceBuilder::new("/data/srtm")
+///     .cache_size(100)
+///     .auto_download(DownloadConfig::with_url_template(
+///         "https://example.com/srtm/{filename}.hgt.gz", // compression auto-detected
+///     ))
+///     .build()?;
+/// ```
+pub struct SrtmServiceBuilder {
+    data_dir: PathBuf,
+    extra_data_dirs: Vec<PathBuf>,
+    cache_size: u64,
+    cache_ttl: Option<Duration>,
+    interpolation_cache_size: Option<u64>,
+    tile_index: bool,
+    prefer_resolution: Option<SrtmResolution>,
+    on_evict: Option<EvictCallback>,
+    lat_bounds: (f64, f64),
+    validate_data_dir: bool,
+    #[cfg(feature = "download")]
+    download_config: Option<DownloadConfig>,
+}
+
+impl SrtmServiceBuilder {
+    /// Create a new builder with the specified data directory.
+    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
+        Self {
+            data_dir: data_dir.as_ref().to_path_buf(),
+            extra_data_dirs: Vec::new(),
+            cache_size: 100, // Default cache size
+            cache_ttl: None,
+            interpolation_cache_size: None,
+            tile_index: false,
+            prefer_resolution: None,
+            on_evict: None,
+            lat_bounds: DEFAULT_LAT_BOUNDS,
+            validate_data_dir: false,
+            #[cfg(feature = "download")]
+            download_config: None,
+        }
+    }
+
+    /// Create a builder configured from environment variables.
+    ///
+    /// # Environment Variables
+    ///
+    /// | Variable | Description | Default |
+    /// |----------|-------------|---------|
+    /// | `HTG_DATA_DIR` | Directory containing .hgt files | Required |
+    /// | `HTG_CACHE_SIZE` | Maximum tiles in cache | 100 |
+    /// | `HTG_DOWNLOAD_SOURCE` | Named source: "ardupilot"* | None |
+    /// | `HTG_DOWNLOAD_URL` | URL template for custom downloads* | None |
+    /// | `HTG_DOWNLOAD_GZIP` | Whether URL serves gzip files* | false |
+    ///
+    /// *Only used when `download` feature is enabled.
+    ///
+    /// # Named Sources
+    ///
+    /// - `ardupilot` or `ardupilot-srtm1` - ArduPilot SRTM1 (30m resolution, ~25MB/tile)
+    /// - `ardupilot-srtm3` - ArduPilot SRTM3 (90m resolution, ~2.8MB/tile)
+    ///
+    /// # URL Template Placeholders
+    ///
+    /// - `{filename}` - Full filename (e.g., "N35E138")
+    /// - `{lat_prefix}` - N or S
+    /// - `{lat}` - Latitude digits (e.g., "35")
+    /// - `{lon_prefix}` - E or W
+    /// - `{lon}` - Longitude digits (e.g., "138")
+    /// - `{continent}` - Continent subdirectory (e.g., "Eurasia", "North_America")
+    ///
+    /// # Example
+    ///
+    /// ```bash
+    /// # Using ArduPilot source (recommended)
+    /// export HTG_DATA_DIR=/data/srtm
+    /// export HTG_DOWNLOAD_SOURCE=ardupilot
+    ///
+    /// # Or using custom URL template
+    /// export HTG_DATA_DIR=/data/srtm
+    /// export HTG_CACHE_SIZE=50
+    /// export HTG_DOWNLOAD_URL="https://example.com/srtm/{filename}.hgt.gz"
+    /// ```
+    ///
+    /// ```ignore
+    /// use htg::SrtmServiceBuilder;
+    ///
+    /// let service = SrtmServiceBuilder::from_env()?.build()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `HTG_DATA_DIR` is not set.
+    pub fn from_env() -> Result<Self> {
+        let data_dir = std::env::var("HTG_DATA_DIR").map_err(|_| {
+            SrtmError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HTG_DATA_DIR environment variable not set",
+            ))
+        })?;
+
+        let cache_size: u64 = std::env::var("HTG_CACHE_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(100);
+
+        #[cfg(feature = "download")]
+        let download_config = {
+            // Check for named source first (e.g., "ardupilot")
+            // Unknown source names fall through to the URL template
+            std::env::var("HTG_DOWNLOAD_SOURCE")
+                .ok()
+                .and_then(|source| DownloadConfig::from_source_name(&source))
+                .or_else(|| {
+                    // Fall back to custom URL template
+                    match std::env::var("HTG_DOWNLOAD_URL") {
+                        Ok(url_template) => {
+                            // Check for explicit compression setting, otherwise auto-detect from URL
+                            if let Ok(gzip_setting) = std::env::var("HTG_DOWNLOAD_GZIP") {
+                                let is_gzipped = gzip_setting.eq_ignore_ascii_case("true")
+                                    || gzip_setting == "1";
+                                let compression = if is_gzipped {
+                                    crate::download::Compression::Gzip
+                                } else {
+                                    crate::download::Compression::None
+                                };
+                                Some(DownloadConfig::with_url_template_and_compression(
+                                    url_template,
+                                    compression,
+                                ))
+                            } else {
+                                Some(DownloadConfig::with_url_template(url_template))
+                            }
+                        }
+                        Err(_) => None,
+                    }
+                })
+        };
+
+        Ok(Self {
+            data_dir: PathBuf::from(data_dir),
+            extra_data_dirs: Vec::new(),
+            cache_size,
+            cache_ttl: None,
+            interpolation_cache_size: None,
+            tile_index: false,
+            prefer_resolution: None,
+            on_evict: None,
+            lat_bounds: DEFAULT_LAT_BOUNDS,
+            validate_data_dir: false,
+            #[cfg(feature = "download")]
+            download_config,
+        })
+    }
+
+    /// Set the data directory.
+    ///
+    /// Overrides the directory set in the constructor or from environment.
+    pub fn data_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.data_dir = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Add a directory to search for tiles after the data directory.
+    ///
+    /// Directories are searched in the order they were added, e.g. a fast
+    /// local disk holding common tiles as the data directory and a network
+    /// mount for the rest. Auto-downloaded tiles are saved to the data
+    /// directory.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let service = SrtmServiceBuilder::new("/ssd/srtm")
+    ///     .add_data_dir("/mnt/nas/srtm")
+    ///     .build()?;
+    /// ```
+    pub fn add_data_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.extra_data_dirs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the maximum number of tiles to keep in cache.
+    ///
+    /// Default is 100 tiles.
+    pub fn cache_size(mut self, size: u64) -> Self {
+        self.cache_size = size;
+        self
+    }
+
+    /// Expire cached tiles `ttl` after they were loaded.
+    ///
+    /// Useful when tiles in the data directory are replaced periodically:
+    /// an expired tile is re-read from disk on its next query.
+    /// [`SrtmService::invalidate_tile`] still drops a tile immediately.
+    /// Expired tiles count towards [`CacheStats::eviction_count`]. Tiles
+    /// never expire by default.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Cache the corner values of recently used interpolation quads.
+    ///
+    /// Dense sequential sampling (e.g. high-resolution profiles) often hits the
+    /// same grid cell repeatedly; with this enabled,
+    /// [`SrtmService::get_elevation_interpolated`] reuses the cached corners
+    /// instead of re-reading them from the tile. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of quads to keep
+    pub fn interpolation_cache(mut self, capacity: u64) -> Self {
+        self.interpolation_cache_size = Some(capacity);
+        self
+    }
+
+    /// Call `callback` with the filename (e.g. `"N35E138.hgt"`) of each tile
+    /// evicted from the cache to make room for another.
+    ///
+    /// Explicit invalidation ([`SrtmService::invalidate_tile`],
+    /// [`SrtmService::clear_cache`]) does not count as eviction. Frequent
+    /// evictions of tiles that are queried again suggest raising
+    /// `cache_size`; see also [`CacheStats::eviction_count`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let service = SrtmServiceBuilder::new("/data/srtm")
+    ///     .cache_size(10)
+    ///     .on_evict(|tile| eprintln!("evicted {}", tile))
+    ///     .build()?;
+    /// ```
+    pub fn on_evict<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        self.on_evict = Some(Arc::new(callback));
+        self
+    }
+
+    /// Keep an in-memory index of the tiles present in the data directory.
+    ///
+    /// The directory is scanned once at build time. Lookups for tiles not in
+    /// the index then fail immediately with `TileNotAvailable` (or
+    /// `FileNotFound` without the `download` feature) instead of checking the
+    /// filesystem on every miss. The index is ignored when auto-download is
+    /// enabled, since any tile may still be fetched.
+    ///
+    /// Files added after build are only seen after
+    /// [`SrtmService::refresh_tile_index`], [`SrtmService::invalidate_tile`],
+    /// or while [`SrtmService::watch_data_dir`] is running. Disabled by default.
+    pub fn tile_index(mut self, enabled: bool) -> Self {
+        self.tile_index = enabled;
+        self
+    }
+
+    /// Check at build time that the data directory exists.
+    ///
+    /// When enabled, building fails with an [`SrtmError::Io`] error unless at
+    /// least one of the data directories (see [`Self::add_data_dir`]) exists
+    /// and can be read, so a mistyped path is reported up front rather than as
+    /// `FileNotFound` on every query. Disabled by default.
+    ///
+    /// Without the `download` feature [`build`](Self::build) cannot return
+    /// an error, so it only prints a warning to stderr and builds the service
+    /// anyway; use [`try_build`](Self::try_build) to get the error.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let service = SrtmServiceBuilder::new("/data/srtm")
+    ///     .validate_data_dir(true)
+    ///     .try_build()?;
+    /// ```
+    pub fn validate_data_dir(mut self, enabled: bool) -> Self {
+        self.validate_data_dir = enabled;
+        self
+    }
+
+    /// Prefer tiles of one resolution when both are available.
+    ///
+    /// Tiles are then looked up in the `srtm1/` and `srtm3/` subdirectories
+    /// of the data directory, preferred resolution first, before the data
+    /// directory itself. With an ArduPilot auto-download source and no
+    /// explicit fallbacks, the matching ArduPilot resolution is downloaded
+    /// first with the other as fallback; downloads are saved to the data
+    /// directory. `Custom` resolutions have no effect.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Uses srtm1/N35E138.hgt if present, else srtm3/N35E138.hgt
+    /// let service = SrtmServiceBuilder::new("/data/srtm")
+    ///     .prefer_resolution(SrtmResolution::Srtm1)
+    ///     .build()?;
+    /// ```
+    pub fn prefer_resolution(mut self, resolution: SrtmResolution) -> Self {
+        self.prefer_resolution = Some(resolution);
+        self
+    }
+
+    /// Override the accepted latitude range (default `(-60.0, 60.0)`).
+    ///
+    /// Coordinates outside `min..=max` are rejected with
+    /// [`SrtmError::OutOfBounds`] before any tile is looked up. Widen it for
+    /// datasets that extend past SRTM coverage, such as ViewFinderPanoramas
+    /// tiles up to ~83°N.
+    ///
+    /// The range must lie within ±90° with `min <= max`; otherwise
+    /// [`try_build`](Self::try_build) fails with
+    /// [`SrtmError::InvalidCoordinate`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let service = SrtmServiceBuilder::new("/data/viewfinder")
+    ///     .lat_bounds(-60.0, 84.0)
+    ///     .build()?;
+    /// ```
+    pub fn lat_bounds(mut self, min: f64, max: f64) -> Self {
+        self.lat_bounds = (min, max);
+        self
+    }
+
+    /// Enable auto-download with the specified configuration.
+    ///
+    /// When enabled, missing tiles will be downloaded from the configured source.
+    /// Compression format is auto-detected from the URL extension (.gz, .zip).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use htg::{SrtmServiceBuilder, download::DownloadConfig};
+    ///
+    /// let service = SrtmServiceBuilder::new("/data/srtm")
+    ///     .auto_download(DownloadConfig::with_url_template(
+    ///         "https://example.com/{filename}.hgt.gz", // compression auto-detected
+    ///     ))
+    ///     .build()?;
+    /// ```
+    #[cfg(feature = "download")]
+    pub fn auto_download(mut self, config: DownloadConfig) -> Self {
+        self.download_config = Some(config);
+        self
+    }
+
+    /// Build the [`SrtmService`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if auto-download is enabled but the downloader
+    /// cannot be created (e.g., due to TLS initialization failure), or if
+    /// [`validate_data_dir`](Self::validate_data_dir) is enabled and no data
+    /// directory can be read.
+    #[cfg(feature = "download")]
+    pub fn build(self) -> Result<SrtmService> {
+        self.check_lat_bounds()?;
+        self.check_data_dirs()?;
+
+        let downloader = match self.download_config {
+            Some(config) => Some(Arc::new(Downloader::new(prefer_ardupilot_source(
+                config,
+                self.prefer_resolution,
+            ))?)),
+            None => None,
+        };
+
+        let data_dirs: Vec<PathBuf> = std::iter::once(self.data_dir.clone())
+            .chain(self.extra_data_dirs)
+            .collect();
+        let tile_dirs = tile_dirs(&data_dirs, self.prefer_resolution);
+        let tile_index = self
+            .tile_index
+            .then(|| Arc::new(RwLock::new(scan_tile_keys(&tile_dirs))));
+
+        let cache_usage = Arc::new(CacheUsage::default());
+        Ok(SrtmService {
+            data_dir: self.data_dir,
+            tile_cache: new_tile_cache(
+                self.cache_size,
+                self.cache_ttl,
+                &cache_usage,
+                self.on_evict,
+            ),
+            hit_count: Arc::default(),
+            miss_count: Arc::default(),
+            cache_usage,
+            quad_cache: self
+                .interpolation_cache_size
+                .map(|size| Cache::builder().max_capacity(size).build()),
+            tile_index,
+            data_dirs,
+            tile_dirs,
+            lat_bounds: self.lat_bounds,
+            downloader,
+            #[cfg(test)]
+            fs_checks: Arc::default(),
+        })
+    }
+
+    /// Build the [`SrtmService`].
+    ///
+    /// If [`validate_data_dir`](Self::validate_data_dir) is enabled and no
+    /// data directory can be read, a warning is printed to stderr and the
+    /// service is built anyway; use [`try_build`](Self::try_build) to handle
+    /// that as an error.
+    #[cfg(not(feature = "download"))]
+    pub fn build(self) -> SrtmService {
+        if let Err(e) = self.check_data_dirs() {
+            eprintln!("htg: warning: {}", e);
+        }
+
+        let data_dirs: Vec<PathBuf> = std::iter::once(self.data_dir.clone())
+            .chain(self.extra_data_dirs)
+            .collect();
+        let tile_dirs = tile_dirs(&data_dirs, self.prefer_resolution);
+        let tile_index = self
+            .tile_index
+            .then(|| Arc::new(RwLock::new(scan_tile_keys(&tile_dirs))));
+
+        let cache_usage = Arc::new(CacheUsage::default());
+        SrtmService {
+            data_dir: self.data_dir,
+            tile_cache: new_tile_cache(
+                self.cache_size,
+                self.cache_ttl,
+                &cache_usage,
+                self.on_evict,
+            ),
+            hit_count: Arc::default(),
+            miss_count: Arc::default(),
+            cache_usage,
+            quad_cache: self
+                .interpolation_cache_size
+                .map(|size| Cache::builder().max_capacity(size).build()),
+            tile_index,
+            data_dirs,
+            tile_dirs,
+            lat_bounds: self.lat_bounds,
+            #[cfg(test)]
+            fs_checks: Arc::default(),
+        }
+    }
+
+    /// Build the [`SrtmService`], returning any error.
+    ///
+    /// The same as [`build`](Self::build) with the `download` feature. Without
+    /// it, this is the way to handle a failed
+    /// [`validate_data_dir`](Self::validate_data_dir) check or invalid
+    /// [`lat_bounds`](Self::lat_bounds).
+    pub fn try_build(self) -> Result<SrtmService> {
+        #[cfg(feature = "download")]
+        return self.build();
+
+        #[cfg(not(feature = "download"))]
+        {
+            self.check_lat_bounds()?;
+            self.check_data_dirs()?;
+            Ok(self.build())
+        }
+    }
+
+    /// Fail unless the latitude bounds are an ordered range within ±90°.
+    fn check_lat_bounds(&self) -> Result<()> {
+        let (min, max) = self.lat_bounds;
+        if (-90.0..=90.0).contains(&min) && (-90.0..=90.0).contains(&max) && min <= max {
+            return Ok(());
+        }
+        Err(SrtmError::InvalidCoordinate {
+            message: format!(
+                "latitude bounds must satisfy -90 <= min <= max <= 90, got ({}, {})",
+                min, max
+            ),
+        })
+    }
+
+    /// With [`validate_data_dir`](Self::validate_data_dir) enabled, fail
+    /// unless some data directory can be read.
+    fn check_data_dirs(&self) -> Result<()> {
+        if !self.validate_data_dir {
+            return Ok(());
+        }
+        // Report the first unreadable directory if none can be read
+        let mut result = Ok(());
+        for dir in std::iter::once(&self.data_dir).chain(&self.extra_data_dirs) {
+            match std::fs::read_dir(dir) {
+                Ok(_) => return Ok(()),
+                Err(e) if result.is_ok() => {
+                    result = Err(SrtmError::Io(std::io::Error::new(
+                        e.kind(),
+                        format!("data directory {} is not readable: {}", dir.display(), e),
+                    )));
+                }
+                Err(_) => {}
+            }
+        }
+        result
+    }
+}
+
+/// Order ArduPilot sources so the preferred resolution is tried first and
+/// the other is the fallback. Other sources, or explicit fallbacks, are kept.
+#[cfg(feature = "download")]
+fn prefer_ardupilot_source(
+    mut config: DownloadConfig,
+    prefer: Option<SrtmResolution>,
+) -> DownloadConfig {
+    use crate::download::SrtmSource::{ArduPilotSrtm1, ArduPilotSrtm3};
+
+    let is_ardupilot = matches!(config.source, ArduPilotSrtm1 | ArduPilotSrtm3);
+    if !is_ardupilot || !config.fallbacks.is_empty() {
+        return config;
+    }
+    let (source, fallback) = match prefer {
+        Some(SrtmResolution::Srtm1) => (ArduPilotSrtm1, ArduPilotSrtm3),
+        Some(SrtmResolution::Srtm3) => (ArduPilotSrtm3, ArduPilotSrtm1),
+        _ => return config,
+    };
+    config.source = source;
+    config.fallbacks = vec![fallback];
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::write_test_tile;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// File size for SRTM3 (1201 × 1201 × 2 bytes)
+    const SRTM3_SIZE: usize = 1201 * 1201 * 2;
+    const SRTM3_SAMPLES: usize = 1201;
+
+    /// Create a test SRTM3 file with elevation = 500m at center
+    fn create_test_tile(dir: &Path, filename: &str, center_elevation: i16) {
+        let mut data = vec![0u8; SRTM3_SIZE];
+
+        // Set center elevation (row 600, col 600)
+        let center_offset = (600 * SRTM3_SAMPLES + 600) * 2;
+        let bytes = center_elevation.to_be_bytes();
+        data[center_offset] = bytes[0];
+        data[center_offset + 1] = bytes[1];
+
+        let path = dir.join(filename);
+        let mut file = fs::File::create(path).unwrap();
+        file.write_all(&data).unwrap();
+    }
+
+    /// Create a test SRTM3 file with elevations from a (row, col) function
+    fn create_gradient_tile(dir: &Path, filename: &str, f: impl Fn(usize, usize) -> i16) {
+        write_test_tile(&dir.join(filename), f);
+    }
+
+    #[test]
+    fn test_whole_degree_edges_use_lower_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        // Distinct first/last rows and columns; the neighbors disagree
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            (row * 2 + col % 2 + 1) as i16
+        });
+        create_gradient_tile(temp_dir.path(), "N36E138.hgt", |_, _| 9000);
+        create_gradient_tile(temp_dir.path(), "N35E139.hgt", |_, _| 8000);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // lat 36.0 is N35's row 0, not N36's bottom row
+        assert_eq!(service.get_elevation(36.0, 138.5).unwrap(), Some(1));
+        assert_eq!(
+            service.get_elevation_interpolated(36.0, 138.5).unwrap(),
+            Some(1.0)
+        );
+        // lon 139.0 is N35E138's last column (1200, even)
+        assert_eq!(service.get_elevation(35.5, 139.0).unwrap(), Some(1201));
+        // The north-east corner is row 0, last column
+        assert_eq!(service.get_elevation(36.0, 139.0).unwrap(), Some(1));
+        assert_eq!(
+            service.get_elevations_batch(&[(36.0, 138.5), (36.0001, 138.5), (35.5, 139.0001)], 0),
+            vec![1, 9000, 8000]
+        );
+
+        // N34E138 is missing, so lat 35.0 falls back to N35's bottom row
+        assert_eq!(service.get_elevation(35.0, 138.5).unwrap(), Some(2401));
+        assert_eq!(
+            service.get_elevations_batch(&[(35.0, 138.5), (35.0, 139.0)], 0),
+            vec![2401, 8000]
+        );
+        assert_eq!(
+            service.get_elevations_batch(&[(35.0, 138.5)], 0),
+            vec![2401]
+        );
+    }
+
+    #[test]
+    fn test_service_basic() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Query center of tile
+        let elevation = service.get_elevation(35.5, 138.5).unwrap();
+        assert_eq!(elevation, Some(500));
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // First query - cache miss
+        let _ = service.get_elevation(35.5, 138.5).unwrap();
+        let stats1 = service.cache_stats();
+        assert_eq!(stats1.miss_count, 1);
+        assert_eq!(stats1.hit_count, 0);
+
+        // Second query in same tile - cache hit
+        let _ = service.get_elevation(35.6, 138.6).unwrap();
+        let stats2 = service.cache_stats();
+        assert_eq!(stats2.miss_count, 1);
+        assert_eq!(stats2.hit_count, 1);
+
+        // Note: entry_count may be lazy, so we just verify hit/miss counts
+    }
+
+    #[test]
+    fn test_multiple_tiles() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 1000);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let elev1 = service.get_elevation(35.5, 138.5).unwrap();
+        let elev2 = service.get_elevation(36.5, 138.5).unwrap();
+
+        assert_eq!(elev1, Some(500));
+        assert_eq!(elev2, Some(1000));
+
+        let stats = service.cache_stats();
+        // Verify miss count (entry_count may be lazy)
+        assert_eq!(stats.miss_count, 2);
+    }
+
+    #[test]
+    fn test_invalid_coordinates() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Latitude out of SRTM coverage
+        assert!(service.get_elevation(70.0, 0.0).is_err());
+        assert!(service.get_elevation(-70.0, 0.0).is_err());
+
+        // Longitudes past ±180 wrap around (to missing tiles here); only
+        // non-finite ones are invalid
+        assert_eq!(service.get_elevation(0.0, 200.0).unwrap(), None);
+        assert_eq!(service.get_elevation(0.0, -200.0).unwrap(), None);
+        assert!(service.get_elevation(0.0, f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Query for a tile that doesn't exist — returns Ok(None)
+        let result = service.get_elevation(50.0, 50.0).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_missing_file_interpolated() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Query for a tile that doesn't exist — returns Ok(None)
+        let result = service.get_elevation_interpolated(50.0, 50.0).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_get_elevation_with_uncertainty() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Center sample is a 500m spike on flat ground -> rougher than nominal
+        let (elevation, std_dev) = service
+            .get_elevation_with_uncertainty(35.5, 138.5)
+            .unwrap()
+            .unwrap();
+        assert_eq!(elevation, 500.0);
+        assert!(std_dev > crate::SRTM_VERTICAL_ERROR_M);
+
+        // Missing tile -> None
+        assert_eq!(
+            service.get_elevation_with_uncertainty(50.0, 50.0).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_elevation_interpolated_with_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        // NW corner of the quad at (600, 600) is void
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            match (row, col) {
+                (600, 600) => VOID_VALUE,
+                (600, 601) => 200,
+                (601, 600) => 300,
+                _ => 400,
+            }
+        });
+        let (lat, lon) = (36.0 - 600.5 / 1200.0, 138.0 + 600.5 / 1200.0);
+
+        let uncached = SrtmService::new(temp_dir.path(), 10);
+        let builder = SrtmServiceBuilder::new(temp_dir.path()).interpolation_cache(16);
+        #[cfg(feature = "download")]
+        let cached = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let cached = builder.build();
+        for service in [&uncached, &cached] {
+            let at = |policy| {
+                service
+                    .get_elevation_interpolated_with_policy(lat, lon, policy)
+                    .unwrap()
+            };
+            assert_eq!(at(VoidPolicy::Strict), None);
+            let ignored = at(VoidPolicy::IgnoreVoid).unwrap();
+            assert!((ignored - 300.0).abs() < 1e-6, "{}", ignored);
+            // Nearest to the center of the cell is a tie; step towards SE
+            assert_eq!(
+                service
+                    .get_elevation_interpolated_with_policy(
+                        lat - 0.2 / 1200.0,
+                        lon + 0.2 / 1200.0,
+                        VoidPolicy::Nearest
+                    )
+                    .unwrap(),
+                Some(400.0)
+            );
+            assert_eq!(service.get_elevation_interpolated(lat, lon).unwrap(), None);
+            assert_eq!(
+                service
+                    .get_elevation_interpolated_with_policy(50.0, 50.0, VoidPolicy::Nearest)
+                    .unwrap(),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_data_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("srmt");
+
+        let result = SrtmServiceBuilder::new(&missing)
+            .validate_data_dir(true)
+            .try_build();
+        match result {
+            Err(SrtmError::Io(e)) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+                assert!(e.to_string().contains("srmt"));
+            }
+            other => panic!("Expected Io error, got {:?}", other.map(|_| ())),
+        }
+        #[cfg(feature = "download")]
+        assert!(SrtmServiceBuilder::new(&missing)
+            .validate_data_dir(true)
+            .build()
+            .is_err());
+        // Without `download`, build() warns instead of panicking
+        #[cfg(not(feature = "download"))]
+        SrtmServiceBuilder::new(&missing)
+            .validate_data_dir(true)
+            .build();
+
+        // Off by default, and one readable directory is enough
+        assert!(SrtmServiceBuilder::new(&missing).try_build().is_ok());
+        assert!(SrtmServiceBuilder::new(&missing)
+            .add_data_dir(temp_dir.path())
+            .validate_data_dir(true)
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_prefetch_tiles() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let filenames = vec![
+            "N35E138.hgt".to_string(),
+            "N36E138.hgt".to_string(),
+            "bogus".to_string(),
+        ];
+        let results = service.prefetch_tiles(&filenames);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, "N35E138.hgt");
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, "N36E138.hgt");
+        assert!(matches!(
+            results[1].1,
+            Err(SrtmError::TileNotAvailable { .. }) | Err(SrtmError::FileNotFound { .. })
+        ));
+        assert!(matches!(
+            results[2].1,
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+
+        // The present tile is now served from the cache
+        assert_eq!(service.cache_stats().miss_count, 2);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        assert_eq!(service.cache_stats().hit_count, 1);
+    }
+
+    #[test]
+    fn test_lat_bounds() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N70E010.hgt", 500);
+
+        let default = SrtmService::new(temp_dir.path(), 10);
+        assert!(matches!(
+            default.get_elevation(70.5, 10.5),
+            Err(SrtmError::OutOfBounds { .. })
+        ));
+        assert_eq!(default.get_elevations_batch(&[(70.5, 10.5)], -1), vec![-1]);
+
+        let builder = SrtmServiceBuilder::new(temp_dir.path()).lat_bounds(-60.0, 84.0);
+        #[cfg(feature = "download")]
+        let expanded = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let expanded = builder.build();
+        assert_eq!(expanded.get_elevation(70.5, 10.5).unwrap(), Some(500));
+        assert_eq!(
+            expanded.get_elevations_batch(&[(70.5, 10.5)], -1),
+            vec![500]
+        );
+        assert!(matches!(
+            expanded.get_elevation(85.0, 10.5),
+            Err(SrtmError::OutOfBounds { .. })
+        ));
+
+        for (min, max) in [(10.0, -10.0), (f64::NAN, 60.0), (-60.0, 95.0)] {
+            let builder = SrtmServiceBuilder::new(temp_dir.path()).lat_bounds(min, max);
+            assert!(matches!(
+                builder.try_build(),
+                Err(SrtmError::InvalidCoordinate { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ruggedness() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // 500m spike on flat ground: every neighbor differs by 500m
+        assert_eq!(service.ruggedness(35.5, 138.5).unwrap(), Some(500.0));
+        assert_eq!(service.ruggedness(35.25, 138.25).unwrap(), Some(0.0));
+        assert_eq!(service.ruggedness(50.0, 50.0).unwrap(), None);
+        assert!(service.ruggedness(61.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_get_elevation_filled() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            if (row, col) == (600, 600) {
+                VOID_VALUE
+            } else {
+                300
+            }
+        });
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), None);
+        assert_eq!(
+            service.get_elevation_filled(35.5, 138.5, 1).unwrap(),
+            Some(300)
+        );
+        assert_eq!(service.get_elevation_filled(35.5, 138.5, 0).unwrap(), None);
+
+        // Missing tile
+        assert_eq!(service.get_elevation_filled(50.5, 10.5, 3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_nearest_valid_crosses_tile_boundary() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |_, _| VOID_VALUE);
+        create_gradient_tile(temp_dir.path(), "N35E139.hgt", |row, _| row as i16);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // ~45m west of the shared edge, inside the all-void tile
+        let (lat, lon) = (35.5, 138.9995);
+        assert_eq!(service.get_elevation(lat, lon).unwrap(), None);
+        assert_eq!(service.get_elevation_filled(lat, lon, 10).unwrap(), None);
+
+        // Row 600 of the western column of N35E139
+        let (found_lat, found_lon, elevation) =
+            service.nearest_valid(lat, lon, 100.0).unwrap().unwrap();
+        assert!((found_lat - 35.5).abs() < 1e-9);
+        assert!((found_lon - 139.0).abs() < 1e-9);
+        assert_eq!(elevation, 600);
+
+        // Nothing within 30m, and missing tiles are skipped
+        assert_eq!(service.nearest_valid(lat, lon, 30.0).unwrap(), None);
+        assert_eq!(service.nearest_valid(50.5, 10.5, 1000.0).unwrap(), None);
+        assert!(service.nearest_valid(61.0, 0.0, 100.0).is_err());
+
+        for radius in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                service.nearest_valid(lat, lon, radius),
+                Err(SrtmError::InvalidCoordinate { .. })
+            ));
+        }
+        // Huge radii are capped to about a tile, not an Earth-wide scan
+        assert_eq!(
+            service.nearest_valid(lat, lon, 1e9).unwrap(),
+            service.nearest_valid(lat, lon, 100.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_elevation_bicubic() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let center = service.get_elevation_bicubic(35.5, 138.5).unwrap().unwrap();
+        assert!((center - 500.0).abs() < 1e-6);
+        assert_eq!(service.get_elevation_bicubic(50.5, 10.5).unwrap(), None);
+        assert!(service.get_elevation_bicubic(61.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_get_elevation_smooth_interpolated() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert_eq!(
+            service.get_elevation_smooth(35.5, 138.5).unwrap(),
+            Some(500.0)
+        );
+
+        // Halfway between the 500m center and its 0m east neighbor
+        let lon = 138.0 + 600.5 / 1200.0;
+        let smooth = service.get_elevation_smooth(35.5, lon).unwrap();
+        assert!((smooth.unwrap() - 250.0).abs() < 1e-6);
+        assert_eq!(
+            smooth,
+            service.get_elevation_interpolated(35.5, lon).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_elevation_smooth_void_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            match (row, col) {
+                (600, 600) => 500,
+                (600, 601) | (300, 300) => VOID_VALUE,
+                _ => 0,
+            }
+        });
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Void in the interpolation window: nearest sample (col 600) is used
+        let lon = 138.0 + 600.2 / 1200.0;
+        assert_eq!(service.get_elevation_interpolated(35.5, lon).unwrap(), None);
+        assert_eq!(
+            service.get_elevation_smooth(35.5, lon).unwrap(),
+            Some(500.0)
+        );
+
+        // Nearest sample void too
+        assert_eq!(service.get_elevation_smooth(35.75, 138.25).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_elevation_smooth_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert!(matches!(
+            service.get_elevation_smooth(61.0, 0.0),
+            Err(SrtmError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            service.get_elevation_smooth(35.5, 138.5),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_void_data_returns_none() {
+        let temp_dir = TempDir::new().unwrap();
+        // Create tile where center elevation is VOID_VALUE
+        create_test_tile(temp_dir.path(), "N35E138.hgt", VOID_VALUE);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Void data returns None
+        let result = service.get_elevation(35.5, 138.5).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_antimeridian_longitudes_wrap() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35W180.hgt", 100);
+        create_test_tile(temp_dir.path(), "N35W170.hgt", 200);
+        create_test_tile(temp_dir.path(), "N35E179.hgt", 300);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Both sides of the antimeridian select W180 (there is no E180 tile);
+        // its western column is zero-filled in the test tile
+        assert_eq!(service.get_elevation(35.5, 180.0).unwrap(), Some(0));
+        assert_eq!(service.get_elevation(35.5, -180.0).unwrap(), Some(0));
+        assert_eq!(service.get_elevation(35.5, 180.5).unwrap(), Some(100));
+        assert_eq!(service.get_elevation(35.5, 190.5).unwrap(), Some(200));
+        assert_eq!(service.get_elevation(35.5, -180.5).unwrap(), Some(300));
+        assert_eq!(
+            service.get_elevations_batch(&[(35.5, 180.5), (35.5, 190.5)], -1),
+            vec![100, 200]
+        );
+
+        // Latitude is never wrapped
+        assert!(matches!(
+            service.get_elevation(61.0, 180.5),
+            Err(SrtmError::OutOfBounds { .. })
+        ));
+        assert!(service.get_elevation(35.5, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_get_elevations_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let coords = vec![
+            (35.5, 138.5), // valid tile, center = 500
+            (50.0, 50.0),  // missing tile
+            (35.1, 138.1), // valid tile, edge = 0 (default data)
+        ];
+        let results = service.get_elevations_batch(&coords, -1);
+
+        assert_eq!(results[0], 500);
+        assert_eq!(results[1], -1); // missing tile → default
+                                    // Third result is 0 (zero-filled tile data), which is a valid value
+        assert_eq!(results[2], 0);
+    }
+
+    #[test]
+    fn test_get_elevations_batch_interpolated() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let coords = vec![
+            (35.5, 138.5), // valid tile, center = 500.0
+            (50.0, 50.0),  // missing tile
+        ];
+        let results = service.get_elevations_batch_interpolated(&coords, -1.0);
+
+        assert_eq!(results.len(), 2);
+        assert!((results[0] - 500.0).abs() < 1.0); // interpolated, close to 500
+        assert_eq!(results[1], -1.0); // missing tile → default
+    }
+
+    #[test]
+    fn test_get_elevations_batch_interpolated_matches_per_point() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            (row * 3 + col) as i16
+        });
+        create_gradient_tile(temp_dir.path(), "N36E139.hgt", |row, col| {
+            if row == 600 && col == 600 {
+                VOID_VALUE
+            } else {
+                (1000 + row) as i16 - col as i16
+            }
+        });
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let coords = vec![
+            (35.123, 138.456),
+            (36.5, 139.5),    // next to the void sample
+            (36.501, 139.25), // other tile
+            (35.999, 138.001),
+            (50.0, 50.0),   // missing tile
+            (70.0, 138.0),  // out of bounds
+            (36.2, 139.75), // back to the second tile
+            (35.5, 138.5),
+        ];
+        let results = service.get_elevations_batch_interpolated_opt(&coords);
+
+        assert_eq!(results.len(), coords.len());
+        for (&(lat, lon), &batch) in coords.iter().zip(&results) {
+            let expected = service.get_elevation_interpolated(lat, lon).ok().flatten();
+            assert_eq!(batch, expected, "({}, {})", lat, lon);
+        }
+        assert_eq!(results[1], None);
+        assert!(results[2].is_some());
+        assert_eq!(
+            service.get_elevations_batch_interpolated(&coords, -1.0)[1],
+            -1.0
+        );
+    }
+
+    #[test]
+    fn test_interpolation_cache_matches_uncached() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            if (590..610).contains(&row) && (590..610).contains(&col) {
+                (row * 7 + col * 13) as i16
+            } else {
+                0
+            }
+        });
+
+        let plain = SrtmService::new(temp_dir.path(), 10);
+        let builder = SrtmServiceBuilder::new(temp_dir.path()).interpolation_cache(16);
+        #[cfg(feature = "download")]
+        let cached = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let cached = builder.build();
+
+        // Dense sequential samples, many landing in the same quad
+        for i in 0..200 {
+            let lat = 35.5 + i as f64 * 0.0001;
+            let lon = 138.5 - i as f64 * 0.00007;
+            assert_eq!(
+                plain.get_elevation_interpolated(lat, lon).unwrap(),
+                cached.get_elevation_interpolated(lat, lon).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpolated_grid_flat() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Lons 138.0, 138.5, 139.0, 139.5; lats 35.5, 35.25, 35.0 (north-up).
+        // Tile E139 is missing, so the two eastern columns are void.
+        let bbox = BoundingBox::new(35.0, 138.0, 35.5, 139.5);
+        let grid = service.interpolated_grid_flat(&bbox, 3, 4, -1.0).unwrap();
+
+        assert_eq!(grid.len(), 12);
+        // North-west corner (35.5, 138.0) is a flat zero sample
+        assert_eq!(grid[0], 0.0);
+        // (35.5, 138.5) is the tile centre, row 0 col 1
+        assert_eq!(grid[1], 500.0);
+        // South-west corner (35.0, 138.0)
+        assert_eq!(grid[8], 0.0);
+        // North-east and south-east corners fall in the missing tile
+        assert_eq!(grid[3], -1.0);
+        assert_eq!(grid[11], -1.0);
+    }
+
+    #[test]
+    fn test_interpolated_grid_flat_invalid() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let bbox = BoundingBox::new(35.0, 138.0, 36.0, 139.0);
+
+        assert!(matches!(
+            service.interpolated_grid_flat(&bbox, 0, 4, 0.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        let inverted = BoundingBox::new(36.0, 138.0, 35.0, 139.0);
+        assert!(matches!(
+            service.interpolated_grid_flat(&inverted, 2, 2, 0.0),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_elevation_profile_two_tiles() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N35E139.hgt", 1000);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // ~90 km east along 35.5°N, crossing from E138 into E139
+        let route = [(35.5, 138.5), (35.5, 139.5)];
+        for interpolate in [false, true] {
+            let profile = service
+                .elevation_profile(&route, 1000.0, interpolate)
+                .unwrap();
+
+            assert!(profile.len() > 90);
+            assert!(profile
+                .windows(2)
+                .all(|w| w[1].distance_m > w[0].distance_m));
+            assert!(profile
+                .windows(2)
+                .all(|w| w[1].distance_m - w[0].distance_m <= 1000.0 + 1e-6));
+
+            let first = profile.first().unwrap();
+            let last = profile.last().unwrap();
+            assert_eq!((first.lat, first.lon, first.distance_m), (35.5, 138.5, 0.0));
+            assert_eq!((last.lat, last.lon), (35.5, 139.5));
+            assert!((last.distance_m - 90_600.0).abs() < 500.0);
+            assert_eq!(first.elevation, Some(500.0));
+            assert_eq!(last.elevation, Some(1000.0));
+        }
+
+        // Both tiles were loaded once for the whole profile
+        assert_eq!(service.cache_stats().miss_count, 2);
+    }
+
+    #[cfg(feature = "geo-types")]
+    #[test]
+    fn test_geo_types_match_tuple_calls() {
+        let temp_dir = TempDir::new().unwrap();
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            (row + 2 * col) as i16
+        });
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // geo uses (x, y) = (lon, lat)
+        let point = geo_types::Point::new(138.25, 35.75);
+        assert_eq!(
+            service.get_elevation_point(point).unwrap(),
+            service.get_elevation(35.75, 138.25).unwrap()
+        );
+        assert_eq!(service.get_elevation_point(point).unwrap(), Some(900));
+
+        let line = geo_types::LineString::from(vec![(138.2, 35.2), (138.3, 35.4), (138.6, 35.4)]);
+        let profile = service.elevation_profile_line(&line, 500.0).unwrap();
+        let expected = service
+            .elevation_profile(&[(35.2, 138.2), (35.4, 138.3), (35.4, 138.6)], 500.0, true)
+            .unwrap();
+        assert_eq!(profile.len(), expected.len());
+        for (a, b) in profile.iter().zip(&expected) {
+            assert_eq!((a.lat, a.lon, a.distance_m), (b.lat, b.lon, b.distance_m));
+            assert_eq!(a.elevation, b.elevation);
+        }
+        assert_eq!((profile[0].lat, profile[0].lon), (35.2, 138.2));
+    }
+
+    #[test]
+    fn test_elevation_profile_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert!(service
+            .elevation_profile(&[], 100.0, false)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            service.elevation_profile(&[(35.5, 138.5)], 0.0, false),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            service.elevation_profile(&[(35.5, 138.5), (70.0, 138.5)], 100.0, false),
+            Err(SrtmError::OutOfBounds { .. })
+        ));
+
+        // Missing tiles yield `None` elevations rather than an error
+        let profile = service
+            .elevation_profile(&[(35.5, 138.5)], 100.0, false)
+            .unwrap();
+        assert_eq!(profile.len(), 1);
+        assert_eq!(profile[0].elevation, None);
+    }
+
+    #[test]
+    fn test_elevation_profile_wrapped_longitude() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35W180.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // A GPS feed past the antimeridian: 180.5 is -179.5
+        let profile = service
+            .elevation_profile(&[(35.5, 179.9), (35.5, 180.5)], 1000.0, false)
+            .unwrap();
+        let last = profile.last().unwrap();
+        assert_eq!((last.lat, last.lon), (35.5, -179.5));
+        assert_eq!(last.elevation, Some(500.0));
+        // The route crosses 180° (~60 km), not back around the globe
+        assert!(last.distance_m < 60_000.0);
+        assert!(profile.iter().all(|p| (-180.0..180.0).contains(&p.lon)));
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Flat tile with a 3-sample wide, 500m high north-south ridge at lon 138.5
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |_, col| {
+            if (599..=601).contains(&col) {
+                500
+            } else {
+                0
+            }
+        });
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        for interpolate in [false, true] {
+            // 10m masts on either side of the ridge
+            assert!(!service
+                .line_of_sight((35.5, 138.4, 10.0), (35.5, 138.6, 10.0), interpolate)
+                .unwrap());
+
+            // Tall enough to clear the ridge
+            assert!(service
+                .line_of_sight((35.5, 138.4, 600.0), (35.5, 138.6, 600.0), interpolate)
+                .unwrap());
+
+            // Both on the same side of the ridge over flat ground
+            assert!(service
+                .line_of_sight((35.5, 138.25, 10.0), (35.5, 138.4, 10.0), interpolate)
+                .unwrap());
+        }
+
+        assert!(service
+            .line_of_sight((35.5, 138.4, 10.0), (50.5, 10.5, 10.0), false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_line_of_sight_earth_curvature() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 0);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // ~90 km over flat ground: the 4/3-radius bulge at the midpoint is
+        // ~120m, hiding 10m masts but not 200m towers
+        assert!(!service
+            .line_of_sight((35.0, 138.0, 10.0), (35.9, 138.0, 10.0), false)
+            .unwrap());
+        assert!(service
+            .line_of_sight((35.0, 138.0, 200.0), (35.9, 138.0, 200.0), false)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_flow_direction_downhill_gradient() {
+        let temp_dir = TempDir::new().unwrap();
+        // Downhill towards the east, continuing into the next tile
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |_, col| {
+            3000 - 2 * col as i16
+        });
+        create_gradient_tile(temp_dir.path(), "N35E139.hgt", |_, col| {
+            600 - 2 * col as i16
+        });
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert_eq!(service.flow_direction(35.5, 138.5).unwrap(), Some(1));
+        // East edge: steepest neighbor is in the adjacent tile
+        assert_eq!(service.flow_direction(35.5, 138.99999).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_flow_direction_codes_and_pits() {
+        let temp_dir = TempDir::new().unwrap();
+        // Downhill towards the north (row 0 is the northern edge), with a pit
+        // and a void cell
+        create_gradient_tile(temp_dir.path(), "N35E138.hgt", |row, col| {
+            match (row, col) {
+                (300, 300) => 0,
+                (900, 900) => VOID_VALUE,
+                _ => 1000 + row as i16,
+            }
+        });
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert_eq!(service.flow_direction(35.5, 138.5).unwrap(), Some(64));
+        assert_eq!(service.flow_direction(35.75, 138.25).unwrap(), Some(0));
+        assert_eq!(service.flow_direction(35.25, 138.75).unwrap(), None);
+        assert_eq!(service.flow_direction(50.5, 10.5).unwrap(), None);
+        assert!(service.flow_direction(61.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_get_elevations_batch_with_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        fs::write(
+            temp_dir.path().join("N36E138.hgt"),
+            vec![0u8; 3601 * 3601 * 2],
+        )
+        .unwrap();
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let coords = vec![(35.5, 138.5), (36.5, 138.5), (50.0, 50.0)];
+        let results = service.get_elevations_batch_with_resolution(&coords, -1);
+
+        assert_eq!(results[0], (500, Some(SrtmResolution::Srtm3)));
+        assert_eq!(results[1], (0, Some(SrtmResolution::Srtm1)));
+        assert_eq!(results[2], (-1, None));
+        assert_eq!(results[1].1.unwrap().as_str(), "srtm1");
+    }
+
+    #[test]
+    fn test_validate_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let health = service.validate_tile("N35E138.hgt").unwrap();
+        assert!(!health.all_zero);
+        assert!(!health.suspicious);
+
+        assert!(matches!(
+            service.validate_tile("N50E050.hgt"),
+            Err(SrtmError::FileNotFound { .. }) | Err(SrtmError::TileNotAvailable { .. })
+        ));
+        assert!(matches!(
+            service.validate_tile("garbage.hgt"),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_hgt_zip_extraction() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Create a .hgt file and zip it
+        let hgt_data = vec![0u8; SRTM3_SIZE];
+        let zip_path = temp_dir.path().join("N40E010.hgt.zip");
+        let file = fs::File::create(&zip_path).unwrap();
+        let mut zip_writer = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        zip_writer.start_file("N40E010.hgt", options).unwrap();
+        zip_writer.write_all(&hgt_data).unwrap();
+        zip_writer.finish().unwrap();
+
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Query should extract from zip and return elevation
+        let result = service.get_elevation(40.5, 10.5).unwrap();
+        assert_eq!(result, Some(0)); // zero-filled data
+
+        // Extracted .hgt file should now exist
+        assert!(temp_dir.path().join("N40E010.hgt").exists());
+    }
+
+    #[cfg(feature = "watch")]
+    fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        condition()
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_picks_up_new_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let _watcher = service.watch_data_dir().unwrap();
+
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), None);
+
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        assert!(wait_for(|| service
+            .get_elevation(35.5, 138.5)
+            .ok()
+            .flatten()
+            == Some(500)));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_reloads_replaced_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let _watcher = service.watch_data_dir().unwrap();
+
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+
+        // Replace atomically, as a sync tool would; the cached mapping still
+        // points at the old file until the watcher invalidates it
+        let staging = TempDir::new_in(temp_dir.path()).unwrap();
+        create_test_tile(staging.path(), "N35E138.hgt", 800);
+        fs::rename(
+            staging.path().join("N35E138.hgt"),
+            temp_dir.path().join("N35E138.hgt"),
+        )
+        .unwrap();
+
+        assert!(wait_for(|| service
+            .get_elevation(35.5, 138.5)
+            .ok()
+            .flatten()
+            == Some(800)));
     }
 
-    /// Build the [`SrtmService`].
-    #[cfg(not(feature = "download"))]
-    pub fn build(self) -> SrtmService {
-        SrtmService {
-            data_dir: self.data_dir,
-            tile_cache: Cache::builder().max_capacity(self.cache_size).build(),
-            hit_count: AtomicU64::new(0),
-            miss_count: AtomicU64::new(0),
-        }
+    fn build_preferring(dir: &Path, resolution: SrtmResolution) -> SrtmService {
+        let builder = SrtmServiceBuilder::new(dir).prefer_resolution(resolution);
+        #[cfg(feature = "download")]
+        let service = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let service = builder.build();
+        service
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::io::Write;
-    use tempfile::TempDir;
+    #[test]
+    fn test_prefer_resolution_falls_back_to_other_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("srtm3")).unwrap();
+        create_test_tile(&temp_dir.path().join("srtm3"), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
+
+        let service = build_preferring(temp_dir.path(), SrtmResolution::Srtm1);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        assert_eq!(
+            service.tile_resolution(35.5, 138.5).unwrap(),
+            SrtmResolution::Srtm3
+        );
+        // Tiles directly in the data directory are still found
+        assert_eq!(service.get_elevation(36.5, 138.5).unwrap(), Some(600));
+        assert_eq!(
+            service.scan_tile_files(),
+            vec!["N35E138.hgt".to_string(), "N36E138.hgt".to_string()]
+        );
+    }
 
-    /// File size for SRTM3 (1201 × 1201 × 2 bytes)
-    const SRTM3_SIZE: usize = 1201 * 1201 * 2;
-    const SRTM3_SAMPLES: usize = 1201;
+    #[test]
+    fn test_add_data_dir_searches_every_directory() {
+        let ssd = TempDir::new().unwrap();
+        let nas = TempDir::new().unwrap();
+        create_test_tile(ssd.path(), "N35E138.hgt", 500);
+        create_test_tile(nas.path(), "N36E138.hgt", 600);
+        // The first directory wins when both hold a tile
+        create_test_tile(ssd.path(), "N37E138.hgt", 700);
+        create_test_tile(nas.path(), "N37E138.hgt", 701);
+
+        let builder = SrtmServiceBuilder::new(ssd.path()).add_data_dir(nas.path());
+        #[cfg(feature = "download")]
+        let service = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let service = builder.build();
+
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        assert_eq!(service.get_elevation(36.5, 138.5).unwrap(), Some(600));
+        assert_eq!(service.get_elevation(37.5, 138.5).unwrap(), Some(700));
+        assert_eq!(service.get_elevation(38.5, 138.5).unwrap(), None);
+        assert_eq!(service.data_dir(), ssd.path());
+        assert_eq!(service.scan_tile_files().len(), 3);
+        assert_eq!(
+            service.tile_path("N37E138.hgt"),
+            Some(ssd.path().join("N37E138.hgt"))
+        );
+        assert_eq!(
+            service.tile_path("N36E138.hgt"),
+            Some(nas.path().join("N36E138.hgt"))
+        );
+        assert_eq!(service.tile_path("N38E138.hgt"), None);
+    }
 
-    /// Create a test SRTM3 file with elevation = 500m at center
-    fn create_test_tile(dir: &Path, filename: &str, center_elevation: i16) {
-        let mut data = vec![0u8; SRTM3_SIZE];
+    #[test]
+    fn test_prefer_resolution_picks_preferred_tile() {
+        let temp_dir = TempDir::new().unwrap();
+        let srtm1_dir = temp_dir.path().join("srtm1");
+        let srtm3_dir = temp_dir.path().join("srtm3");
+        fs::create_dir(&srtm1_dir).unwrap();
+        fs::create_dir(&srtm3_dir).unwrap();
+        fs::write(
+            srtm1_dir.join("N35E138.hgt"),
+            1000i16.to_be_bytes().repeat(3601 * 3601),
+        )
+        .unwrap();
+        create_test_tile(&srtm3_dir, "N35E138.hgt", 500);
+
+        let service = build_preferring(temp_dir.path(), SrtmResolution::Srtm1);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(1000));
+
+        let service = build_preferring(temp_dir.path(), SrtmResolution::Srtm3);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+    }
 
-        // Set center elevation (row 600, col 600)
-        let center_offset = (600 * SRTM3_SAMPLES + 600) * 2;
-        let bytes = center_elevation.to_be_bytes();
-        data[center_offset] = bytes[0];
-        data[center_offset + 1] = bytes[1];
+    #[test]
+    fn test_get_elevation_at_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        let srtm1_dir = temp_dir.path().join("srtm1");
+        fs::create_dir(&srtm1_dir).unwrap();
+        fs::write(
+            srtm1_dir.join("N35E138.hgt"),
+            1000i16.to_be_bytes().repeat(3601 * 3601),
+        )
+        .unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
 
-        let path = dir.join(filename);
-        let mut file = fs::File::create(path).unwrap();
-        file.write_all(&data).unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+        // Without a preference the tile in the data directory is used
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        assert_eq!(
+            service
+                .get_elevation_at_resolution(35.5, 138.5, SrtmResolution::Srtm1)
+                .unwrap(),
+            Some(1000)
+        );
+        assert_eq!(
+            service
+                .get_elevation_at_resolution(35.5, 138.5, SrtmResolution::Srtm3)
+                .unwrap(),
+            Some(500)
+        );
+
+        // No SRTM1 file for this tile
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
+        assert!(matches!(
+            service.get_elevation_at_resolution(36.5, 138.5, SrtmResolution::Srtm1),
+            Err(SrtmError::FileNotFound { .. })
+        ));
     }
 
     #[test]
-    fn test_service_basic() {
+    fn test_get_elevation_at_resolution_zip_and_cache() {
         let temp_dir = TempDir::new().unwrap();
+        let srtm1_dir = temp_dir.path().join("srtm1");
+        fs::create_dir(&srtm1_dir).unwrap();
+        let file = fs::File::create(srtm1_dir.join("N35E138.hgt.zip")).unwrap();
+        let mut zip_writer = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        zip_writer.start_file("N35E138.hgt", options).unwrap();
+        zip_writer
+            .write_all(&1000i16.to_be_bytes().repeat(3601 * 3601))
+            .unwrap();
+        zip_writer.finish().unwrap();
         create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
 
         let service = SrtmService::new(temp_dir.path(), 10);
+        assert_eq!(
+            service
+                .get_elevation_at_resolution(35.5, 138.5, SrtmResolution::Srtm1)
+                .unwrap(),
+            Some(1000)
+        );
+        assert!(srtm1_dir.join("N35E138.hgt").exists());
+
+        // The preferred tile already has the requested resolution, so it
+        // comes from the cache
+        service.get_elevation(35.5, 138.5).unwrap();
+        let misses = service.cache_stats().miss_count;
+        assert_eq!(
+            service
+                .get_elevation_at_resolution(35.5, 138.5, SrtmResolution::Srtm3)
+                .unwrap(),
+            Some(500)
+        );
+        assert_eq!(service.cache_stats().miss_count, misses);
+    }
 
-        // Query center of tile
-        let elevation = service.get_elevation(35.5, 138.5).unwrap();
-        assert_eq!(elevation, Some(500));
+    #[test]
+    fn test_get_elevation_at_resolution_whole_degree_latitude() {
+        let temp_dir = TempDir::new().unwrap();
+        let srtm1_dir = temp_dir.path().join("srtm1");
+        fs::create_dir(&srtm1_dir).unwrap();
+        fs::write(
+            srtm1_dir.join("N36E138.hgt"),
+            1000i16.to_be_bytes().repeat(3601 * 3601),
+        )
+        .unwrap();
+        write_test_tile(&temp_dir.path().join("N36E138.hgt"), |_, _| 500);
+
+        // lat 36.0 is the north edge of the missing N35E138, so both
+        // resolutions fall back to the tile containing it
+        for service in [
+            SrtmService::new(temp_dir.path(), 10),
+            build_preferring(temp_dir.path(), SrtmResolution::Srtm3),
+        ] {
+            assert_eq!(
+                service
+                    .get_elevation_at_resolution(36.0, 138.5, SrtmResolution::Srtm1)
+                    .unwrap(),
+                Some(1000)
+            );
+            assert_eq!(
+                service
+                    .get_elevation_at_resolution(36.0, 138.5, SrtmResolution::Srtm3)
+                    .unwrap(),
+                Some(500)
+            );
+        }
+    }
+
+    fn build_indexed(dir: &Path) -> SrtmService {
+        let builder = SrtmServiceBuilder::new(dir).tile_index(true);
+        #[cfg(feature = "download")]
+        let service = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let service = builder.build();
+        service
     }
 
     #[test]
-    fn test_cache_hit() {
+    fn test_tile_index_skips_filesystem_for_absent_tiles() {
         let temp_dir = TempDir::new().unwrap();
         create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = build_indexed(temp_dir.path());
 
-        let service = SrtmService::new(temp_dir.path(), 10);
+        for _ in 0..5 {
+            assert_eq!(service.get_elevation(50.5, 10.5).unwrap(), None);
+        }
+        assert_eq!(service.fs_checks.load(Ordering::Relaxed), 0);
 
-        // First query - cache miss
-        let _ = service.get_elevation(35.5, 138.5).unwrap();
-        let stats1 = service.cache_stats();
-        assert_eq!(stats1.miss_count, 1);
-        assert_eq!(stats1.hit_count, 0);
+        // Indexed tiles are still loaded from disk
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        assert!(service.fs_checks.load(Ordering::Relaxed) > 0);
+    }
 
-        // Second query in same tile - cache hit
-        let _ = service.get_elevation(35.6, 138.6).unwrap();
-        let stats2 = service.cache_stats();
-        assert_eq!(stats2.miss_count, 1);
-        assert_eq!(stats2.hit_count, 1);
+    #[test]
+    fn test_tile_index_without_index_stats_every_miss() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
 
-        // Note: entry_count may be lazy, so we just verify hit/miss counts
+        for _ in 0..5 {
+            assert_eq!(service.get_elevation(50.5, 10.5).unwrap(), None);
+        }
+        // .hgt and .hgt.zip checked on every miss
+        assert_eq!(service.fs_checks.load(Ordering::Relaxed), 10);
     }
 
     #[test]
-    fn test_multiple_tiles() {
+    fn test_tile_index_refresh() {
         let temp_dir = TempDir::new().unwrap();
+        let service = build_indexed(temp_dir.path());
+
         create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
-        create_test_tile(temp_dir.path(), "N36E138.hgt", 1000);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), None);
 
-        let service = SrtmService::new(temp_dir.path(), 10);
+        service.refresh_tile_index();
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
 
-        let elev1 = service.get_elevation(35.5, 138.5).unwrap();
-        let elev2 = service.get_elevation(36.5, 138.5).unwrap();
+        // Removing the file and invalidating the tile drops it from the index
+        fs::remove_file(temp_dir.path().join("N35E138.hgt")).unwrap();
+        service.invalidate_tile("N35E138.hgt");
+        let checks = service.fs_checks.load(Ordering::Relaxed);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), None);
+        assert_eq!(service.fs_checks.load(Ordering::Relaxed), checks);
+    }
 
-        assert_eq!(elev1, Some(500));
-        assert_eq!(elev2, Some(1000));
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_updates_tile_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = build_indexed(temp_dir.path());
+        let _watcher = service.watch_data_dir().unwrap();
 
-        let stats = service.cache_stats();
-        // Verify miss count (entry_count may be lazy)
-        assert_eq!(stats.miss_count, 2);
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), None);
+
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        assert!(wait_for(|| service
+            .get_elevation(35.5, 138.5)
+            .ok()
+            .flatten()
+            == Some(500)));
     }
 
     #[test]
-    fn test_invalid_coordinates() {
+    fn test_invalidate_bbox() {
         let temp_dir = TempDir::new().unwrap();
+        let tiles = [(35, 138), (35, 139), (36, 138), (36, 139)];
+        for (lat, lon) in tiles {
+            create_test_tile(temp_dir.path(), &coords_to_filename(lat, lon), 500);
+        }
         let service = SrtmService::new(temp_dir.path(), 10);
+        let query_all = || {
+            for (lat, lon) in tiles {
+                service
+                    .get_elevation(lat as f64 + 0.5, lon as f64 + 0.5)
+                    .unwrap();
+            }
+        };
+        query_all();
+        assert_eq!(service.cache_stats().miss_count, 4);
 
-        // Latitude out of SRTM coverage
-        assert!(service.get_elevation(70.0, 0.0).is_err());
-        assert!(service.get_elevation(-70.0, 0.0).is_err());
+        // The western column only
+        service.invalidate_bbox(35.2, 138.2, 36.8, 138.8);
+        service.reset_stats();
+        query_all();
 
-        // Longitude out of range
-        assert!(service.get_elevation(0.0, 200.0).is_err());
-        assert!(service.get_elevation(0.0, -200.0).is_err());
+        let stats = service.cache_stats();
+        assert_eq!((stats.miss_count, stats.hit_count), (2, 2));
+        assert!(service.get_elevation_with_cache_hit(35.5, 139.5).unwrap().1);
     }
 
     #[test]
-    fn test_missing_file() {
-        let temp_dir = TempDir::new().unwrap();
-        let service = SrtmService::new(temp_dir.path(), 10);
+    fn test_cache_stats() {
+        let stats = CacheStats {
+            entry_count: 5,
+            hit_count: 80,
+            miss_count: 20,
+            resident_bytes: 0,
+            eviction_count: 0,
+        };
 
-        // Query for a tile that doesn't exist — returns Ok(None)
-        let result = service.get_elevation(50.0, 50.0).unwrap();
-        assert_eq!(result, None);
+        assert_eq!(stats.hit_rate(), 0.8);
+
+        let empty_stats = CacheStats::default();
+        assert_eq!(empty_stats.hit_rate(), 0.0);
     }
 
     #[test]
-    fn test_missing_file_interpolated() {
+    fn test_memory_usage_bytes() {
         let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
+
         let service = SrtmService::new(temp_dir.path(), 10);
+        assert_eq!(service.memory_usage_bytes(), 0);
 
-        // Query for a tile that doesn't exist — returns Ok(None)
-        let result = service.get_elevation_interpolated(50.0, 50.0).unwrap();
-        assert_eq!(result, None);
+        service.get_elevation(35.5, 138.5).unwrap();
+        service.get_elevation(36.5, 138.5).unwrap();
+        service.get_elevation(36.6, 138.6).unwrap();
+        assert_eq!(service.memory_usage_bytes(), 2 * SRTM3_SIZE as u64);
+        assert_eq!(service.cache_stats().resident_bytes, 2 * SRTM3_SIZE as u64);
+
+        service.clear_cache();
+        assert_eq!(service.memory_usage_bytes(), 0);
     }
 
     #[test]
-    fn test_void_data_returns_none() {
+    fn test_memory_usage_bytes_after_eviction() {
         let temp_dir = TempDir::new().unwrap();
-        // Create tile where center elevation is VOID_VALUE
-        create_test_tile(temp_dir.path(), "N35E138.hgt", VOID_VALUE);
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
 
-        let service = SrtmService::new(temp_dir.path(), 10);
+        let service = SrtmService::new(temp_dir.path(), 1);
+        service.get_elevation(35.5, 138.5).unwrap();
+        service.get_elevation(36.5, 138.5).unwrap();
+        service.tile_cache.run_pending_tasks();
 
-        // Void data returns None
-        let result = service.get_elevation(35.5, 138.5).unwrap();
-        assert_eq!(result, None);
+        assert_eq!(service.tile_cache.entry_count(), 1);
+        assert_eq!(service.memory_usage_bytes(), SRTM3_SIZE as u64);
     }
 
     #[test]
-    fn test_get_elevations_batch() {
+    fn test_on_evict_reports_evicted_tile() {
         let temp_dir = TempDir::new().unwrap();
         create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        create_test_tile(temp_dir.path(), "N36E138.hgt", 600);
+
+        let evicted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let builder = SrtmServiceBuilder::new(temp_dir.path())
+            .cache_size(1)
+            .on_evict({
+                let evicted = Arc::clone(&evicted);
+                move |tile| evicted.lock().unwrap().push(tile)
+            });
+        #[cfg(feature = "download")]
+        let service = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let service = builder.build();
 
-        let service = SrtmService::new(temp_dir.path(), 10);
+        service.get_elevation(35.5, 138.5).unwrap();
+        service.tile_cache.run_pending_tasks();
+        service.get_elevation(36.5, 138.5).unwrap();
+        service.tile_cache.run_pending_tasks();
 
-        let coords = vec![
-            (35.5, 138.5), // valid tile, center = 500
-            (50.0, 50.0),  // missing tile
-            (35.1, 138.1), // valid tile, edge = 0 (default data)
-        ];
-        let results = service.get_elevations_batch(&coords, -1);
+        assert_eq!(*evicted.lock().unwrap(), vec!["N35E138.hgt".to_string()]);
+        assert_eq!(service.cache_stats().eviction_count, 1);
 
-        assert_eq!(results[0], 500);
-        assert_eq!(results[1], -1); // missing tile → default
-                                    // Third result is 0 (zero-filled tile data), which is a valid value
-        assert_eq!(results[2], 0);
+        // Clearing the cache is not an eviction
+        service.clear_cache();
+        assert_eq!(evicted.lock().unwrap().len(), 1);
+        assert_eq!(service.cache_stats().eviction_count, 1);
     }
 
     #[test]
-    fn test_get_elevations_batch_interpolated() {
+    fn test_cache_ttl_expires_tiles() {
         let temp_dir = TempDir::new().unwrap();
         create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
 
-        let service = SrtmService::new(temp_dir.path(), 10);
+        // Long enough that the two lookups below always land within it
+        let builder = SrtmServiceBuilder::new(temp_dir.path()).cache_ttl(Duration::from_secs(1));
+        #[cfg(feature = "download")]
+        let service = builder.build().unwrap();
+        #[cfg(not(feature = "download"))]
+        let service = builder.build();
 
-        let coords = vec![
-            (35.5, 138.5), // valid tile, center = 500.0
-            (50.0, 50.0),  // missing tile
-        ];
-        let results = service.get_elevations_batch_interpolated(&coords, -1.0);
+        service.get_elevation(35.5, 138.5).unwrap();
+        service.get_elevation(35.5, 138.5).unwrap();
+        assert_eq!(service.cache_stats().hit_count, 1);
+        assert_eq!(service.cache_stats().miss_count, 1);
 
-        assert_eq!(results.len(), 2);
-        assert!((results[0] - 500.0).abs() < 1.0); // interpolated, close to 500
-        assert_eq!(results[1], -1.0); // missing tile → default
+        // Poll until the tile expires and has to be reloaded
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while service.cache_stats().miss_count == 1 {
+            assert!(Instant::now() < deadline, "tile never expired");
+            std::thread::sleep(Duration::from_millis(50));
+            assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        }
+        assert_eq!(service.cache_stats().miss_count, 2);
     }
 
     #[test]
-    fn test_hgt_zip_extraction() {
+    fn test_reset_stats() {
         let temp_dir = TempDir::new().unwrap();
-
-        // Create a .hgt file and zip it
-        let hgt_data = vec![0u8; SRTM3_SIZE];
-        let zip_path = temp_dir.path().join("N40E010.hgt.zip");
-        let file = fs::File::create(&zip_path).unwrap();
-        let mut zip_writer = zip::ZipWriter::new(file);
-        let options = zip::write::SimpleFileOptions::default()
-            .compression_method(zip::CompressionMethod::Stored);
-        zip_writer.start_file("N40E010.hgt", options).unwrap();
-        zip_writer.write_all(&hgt_data).unwrap();
-        zip_writer.finish().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
 
         let service = SrtmService::new(temp_dir.path(), 10);
+        service.get_elevation(35.5, 138.5).unwrap();
+        service.get_elevation(35.5, 138.5).unwrap();
 
-        // Query should extract from zip and return elevation
-        let result = service.get_elevation(40.5, 10.5).unwrap();
-        assert_eq!(result, Some(0)); // zero-filled data
+        service.reset_stats();
+        let stats = service.cache_stats();
+        assert_eq!((stats.hit_count, stats.miss_count), (0, 0));
+        // The tile stays cached
+        assert_eq!(stats.resident_bytes, SRTM3_SIZE as u64);
 
-        // Extracted .hgt file should now exist
-        assert!(temp_dir.path().join("N40E010.hgt").exists());
+        service.get_elevation(35.5, 138.5).unwrap();
+        let stats = service.cache_stats();
+        assert_eq!((stats.hit_count, stats.miss_count), (1, 0));
     }
 
     #[test]
-    fn test_cache_stats() {
-        let stats = CacheStats {
-            entry_count: 5,
-            hit_count: 80,
-            miss_count: 20,
-        };
+    fn test_clone_shares_cache_and_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
 
-        assert_eq!(stats.hit_rate(), 0.8);
+        let service = SrtmService::new(temp_dir.path(), 10);
+        let handle = service.clone();
+        std::thread::spawn(move || {
+            assert_eq!(handle.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        })
+        .join()
+        .unwrap();
 
-        let empty_stats = CacheStats::default();
-        assert_eq!(empty_stats.hit_rate(), 0.0);
+        // The tile loaded through the clone is a hit here
+        assert_eq!(service.get_elevation(35.5, 138.5).unwrap(), Some(500));
+        let stats = service.clone().cache_stats();
+        assert_eq!((stats.hit_count, stats.miss_count), (1, 1));
+        assert_eq!(stats.resident_bytes, SRTM3_SIZE as u64);
+
+        service.reset_stats();
+        assert_eq!(service.clone().cache_stats().hit_count, 0);
     }
 
     #[test]
@@ -1178,6 +4253,142 @@ mod tests {
         assert_eq!(service.cache_capacity(), 100);
     }
 
+    #[cfg(feature = "geoid")]
+    #[test]
+    fn test_get_elevation_ellipsoidal() {
+        use crate::geoid::{install_egm96, GeoidGrid};
+
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // Constant 36.70m undulation everywhere
+        let grid = 3670i16.to_be_bytes().repeat(721 * 1440);
+        install_egm96(GeoidGrid::from_dac_bytes(&grid).unwrap());
+
+        let height = service.get_elevation_ellipsoidal(35.5, 138.5).unwrap();
+        assert!((height.unwrap() - 536.7).abs() < 1e-9);
+        assert_eq!(service.get_elevation_ellipsoidal(10.5, 10.5).unwrap(), None);
+    }
+
+    #[cfg(feature = "proj")]
+    #[test]
+    fn test_get_elevation_projected() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        // The tile center (35.5, 138.5) in UTM zone 54N
+        assert_eq!(
+            service
+                .get_elevation_projected(273_243.63, 3_931_367.30, 32654)
+                .unwrap(),
+            Some(500)
+        );
+        assert!(matches!(
+            service.get_elevation_projected(0.0, 0.0, 27700),
+            Err(SrtmError::UnsupportedCrs { epsg: 27700 })
+        ));
+    }
+
+    #[test]
+    fn test_get_elevation_with_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let (elevation, resolution) = service.get_elevation_with_resolution(35.5, 138.5).unwrap();
+        assert_eq!(elevation, Some(500));
+        assert_eq!(resolution, SrtmResolution::Srtm3);
+        assert_eq!(
+            service.tile_resolution(35.1, 138.1).unwrap(),
+            SrtmResolution::Srtm3
+        );
+
+        assert!(service.get_elevation_with_resolution(50.5, 10.5).is_err());
+        assert!(service.tile_resolution(61.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_get_elevation_detailed_reports_cache_hits() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let first = service.get_elevation_detailed(35.5, 138.5).unwrap();
+        assert_eq!(
+            first,
+            ElevationLookup {
+                elevation: Some(500),
+                resolution: SrtmResolution::Srtm3,
+                cache_hit: false,
+            }
+        );
+        assert!(
+            service
+                .get_elevation_detailed(35.2, 138.2)
+                .unwrap()
+                .cache_hit
+        );
+        assert!(service.get_elevation_detailed(50.5, 10.5).is_err());
+    }
+
+    #[test]
+    fn test_tile_at() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        let (tile, cache_hit) = service.tile_at(35.5, 138.5).unwrap();
+        assert!(!cache_hit);
+        assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), 500);
+        assert!(service.tile_at(35.7, 138.2).unwrap().1);
+        assert_eq!(service.cache_stats().miss_count, 1);
+        assert!(service.tile_at(50.5, 50.5).is_err());
+    }
+
+    #[test]
+    fn test_get_elevation_with_cache_hit() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert_eq!(
+            service.get_elevation_with_cache_hit(35.5, 138.5).unwrap(),
+            (Some(500), false)
+        );
+        assert_eq!(
+            service.get_elevation_with_cache_hit(35.1, 138.1).unwrap(),
+            (Some(0), true)
+        );
+        assert_eq!(
+            service.get_elevation_with_cache_hit(50.5, 10.5).unwrap(),
+            (None, false)
+        );
+        assert!(service.get_elevation_with_cache_hit(61.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_get_elevation_with_resolution_mixed_tiles() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_tile(temp_dir.path(), "N35E138.hgt", 500);
+        fs::write(
+            temp_dir.path().join("N36E138.hgt"),
+            1000i16.to_be_bytes().repeat(3601 * 3601),
+        )
+        .unwrap();
+        let service = SrtmService::new(temp_dir.path(), 10);
+
+        assert_eq!(
+            service.get_elevation_with_resolution(35.5, 138.5).unwrap(),
+            (Some(500), SrtmResolution::Srtm3)
+        );
+        assert_eq!(
+            service.get_elevation_with_resolution(36.5, 138.5).unwrap(),
+            (Some(1000), SrtmResolution::Srtm1)
+        );
+    }
+
     #[test]
     fn test_get_elevation_floor() {
         let temp_dir = TempDir::new().unwrap();
@@ -1478,4 +4689,22 @@ mod tests {
         assert_eq!(files.len(), 1);
         assert_eq!(files[0], "N35E138.hgt");
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cache_stats_serde_round_trip() {
+        let stats = CacheStats {
+            entry_count: 2,
+            hit_count: 3,
+            miss_count: 1,
+            resident_bytes: 5_769_604,
+            eviction_count: 0,
+        };
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["hit_count"], 3);
+        assert_eq!(json["hit_rate"], 0.75);
+
+        assert_eq!(serde_json::from_value::<CacheStats>(json).unwrap(), stats);
+    }
 }
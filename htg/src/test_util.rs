@@ -0,0 +1,22 @@
+//! Fixtures shared by the tests of this crate and, with the `test-util`
+//! feature, of crates depending on it.
+
+use std::path::Path;
+
+/// Samples per row and column of an SRTM3 tile.
+pub const SRTM3_SAMPLES: usize = 1201;
+
+/// Write an SRTM3 tile to `path` with `f(row, col)` at every sample.
+///
+/// # Panics
+///
+/// Panics if the file cannot be written.
+pub fn write_test_tile(path: &Path, f: impl Fn(usize, usize) -> i16) {
+    let mut data = Vec::with_capacity(SRTM3_SAMPLES * SRTM3_SAMPLES * 2);
+    for row in 0..SRTM3_SAMPLES {
+        for col in 0..SRTM3_SAMPLES {
+            data.extend_from_slice(&f(row, col).to_be_bytes());
+        }
+    }
+    std::fs::write(path, data).unwrap();
+}
@@ -6,15 +6,15 @@
 use std::fs::File;
 use std::path::Path;
 
-use memmap2::Mmap;
+use memmap2::{Mmap, MmapMut, MmapOptions};
 
 use crate::error::{Result, SrtmError};
 
 /// File size for SRTM1 (1 arc-second, ~30m resolution): 3601 × 3601 × 2 bytes
-const SRTM1_SIZE: usize = 3601 * 3601 * 2; // 25,934,402 bytes
+pub(crate) const SRTM1_SIZE: usize = 3601 * 3601 * 2; // 25,934,402 bytes
 
 /// File size for SRTM3 (3 arc-second, ~90m resolution): 1201 × 1201 × 2 bytes
-const SRTM3_SIZE: usize = 1201 * 1201 * 2; // 2,884,802 bytes
+pub(crate) const SRTM3_SIZE: usize = 1201 * 1201 * 2; // 2,884,802 bytes
 
 /// Number of samples per row/column for SRTM1
 const SRTM1_SAMPLES: usize = 3601;
@@ -25,13 +25,62 @@ const SRTM3_SAMPLES: usize = 1201;
 /// Value indicating no data (void) in SRTM files
 pub const VOID_VALUE: i16 = -32768;
 
+/// Nominal absolute vertical error of SRTM data in meters (90% linear error spec).
+pub const SRTM_VERTICAL_ERROR_M: f64 = 16.0;
+
+/// International feet per meter, for reporting elevations in feet.
+pub const FEET_PER_METER: f64 = 3.28084;
+
+/// Unit for reported elevations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum Units {
+    /// Meters, as stored in SRTM tiles.
+    #[default]
+    Meters,
+    /// International feet.
+    Feet,
+}
+
+impl Units {
+    /// Convert an elevation in meters to these units.
+    pub fn convert(self, meters: f64) -> f64 {
+        match self {
+            Units::Meters => meters,
+            Units::Feet => meters * FEET_PER_METER,
+        }
+    }
+
+    /// Convert a whole-meter elevation, rounding to whole units.
+    pub fn convert_whole(self, meters: i16) -> i16 {
+        match self {
+            Units::Meters => meters,
+            // Even 8848m is only 29029ft, well within i16
+            Units::Feet => self.convert(f64::from(meters)).round() as i16,
+        }
+    }
+}
+
+/// Approximate length of one degree of latitude in meters.
+pub(crate) const METERS_PER_DEGREE: f64 = 111_320.0;
+
 /// Resolution type of an SRTM tile.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SrtmResolution {
     /// SRTM1: 1 arc-second (~30m) resolution
     Srtm1,
     /// SRTM3: 3 arc-second (~90m) resolution
     Srtm3,
+    /// Nonstandard square grid, loaded with [`SrtmTile::from_file_with_samples`]
+    Custom {
+        /// Number of samples per row/column
+        samples: usize,
+    },
 }
 
 impl SrtmResolution {
@@ -40,16 +89,108 @@ impl SrtmResolution {
         match self {
             SrtmResolution::Srtm1 => SRTM1_SAMPLES,
             SrtmResolution::Srtm3 => SRTM3_SAMPLES,
+            SrtmResolution::Custom { samples } => *samples,
+        }
+    }
+
+    /// Returns a lowercase label for this resolution (`"srtm1"`, `"srtm3"`, or `"custom"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SrtmResolution::Srtm1 => "srtm1",
+            SrtmResolution::Srtm3 => "srtm3",
+            SrtmResolution::Custom { .. } => "custom",
         }
     }
 
     /// Returns the approximate resolution in meters.
+    ///
+    /// For a custom grid this is the sample spacing along a meridian.
     pub fn meters(&self) -> f64 {
         match self {
             SrtmResolution::Srtm1 => 30.0,
             SrtmResolution::Srtm3 => 90.0,
+            SrtmResolution::Custom { .. } => METERS_PER_DEGREE * self.pixel_spacing_deg(),
+        }
+    }
+
+    /// Detects SRTM1 or SRTM3 from the size of a `.hgt` file in bytes.
+    ///
+    /// Returns `None` for any other size.
+    pub fn from_file_size(size: usize) -> Option<SrtmResolution> {
+        match size {
+            SRTM1_SIZE => Some(SrtmResolution::Srtm1),
+            SRTM3_SIZE => Some(SrtmResolution::Srtm3),
+            _ => None,
         }
     }
+
+    /// Returns the spacing between samples in degrees (1/3600 for SRTM1,
+    /// 3/3600 for SRTM3).
+    pub fn pixel_spacing_deg(&self) -> f64 {
+        1.0 / (self.samples() - 1) as f64
+    }
+}
+
+/// How bilinear interpolation treats a quad with void corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum VoidPolicy {
+    /// Return `None` if any of the 4 corners is void (the default).
+    #[default]
+    Strict,
+    /// Interpolate over the non-void corners only, renormalizing their
+    /// bilinear weights; `None` only if every corner is void.
+    IgnoreVoid,
+    /// Fall back to the nearest sample; `None` only if that sample is void.
+    Nearest,
+}
+
+/// Expected access pattern for a memory-mapped tile, passed to the kernel as
+/// an `madvise` hint on Unix.
+///
+/// Only affects read-ahead and paging, never the values read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccessPattern {
+    /// Scattered point lookups: disable read-ahead (the default).
+    #[default]
+    Random,
+    /// Whole-tile scans such as statistics or exports: read ahead aggressively.
+    Sequential,
+}
+
+/// Corruption heuristics for a tile, as reported by [`SrtmTile::health`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub struct TileHealth {
+    /// Every sample is [`VOID_VALUE`].
+    pub all_void: bool,
+    /// Every sample is exactly 0 (typical of a zero-filled, failed download).
+    pub all_zero: bool,
+    /// Fraction of samples that are void (0.0 to 1.0).
+    pub void_ratio: f64,
+    /// The tile looks corrupt: all void, all zero, or striped (many
+    /// consecutive duplicated, non-constant rows).
+    pub suspicious: bool,
+}
+
+/// Summary statistics over all samples of a tile, from [`SrtmTile::compute_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub struct TileStats {
+    /// Lowest non-void elevation in meters (`None` if every sample is void).
+    pub min: Option<i16>,
+    /// Highest non-void elevation in meters (`None` if every sample is void).
+    pub max: Option<i16>,
+    /// Mean of the non-void elevations in meters (`None` if every sample is void).
+    pub mean: Option<f64>,
+    /// Number of void samples.
+    pub void_count: u64,
+    /// Total number of samples (`samples * samples`).
+    pub sample_count: u64,
 }
 
 /// A memory-mapped SRTM tile for fast elevation lookups.
@@ -66,7 +207,7 @@ impl SrtmResolution {
 pub struct SrtmTile {
     /// Memory-mapped file data
     data: Mmap,
-    /// Number of samples per row/column (1201 or 3601)
+    /// Number of samples per row/column (1201, 3601, or custom)
     samples: usize,
     /// Resolution type
     resolution: SrtmResolution,
@@ -108,24 +249,107 @@ impl SrtmTile {
         base_lat: i32,
         base_lon: i32,
     ) -> Result<Self> {
-        let file = File::open(&path)?;
+        Self::from_file_with_advice(path, base_lat, base_lon, AccessPattern::Random)
+    }
 
-        // SAFETY: Memory mapping is safe as long as the file is not modified
-        // while mapped. We open the file read-only and don't expose the mapping.
-        let mmap = unsafe { Mmap::map(&file)? };
+    /// Load an SRTM tile with explicit base coordinates and access pattern.
+    ///
+    /// Use [`AccessPattern::Sequential`] when reading the whole tile (e.g.
+    /// [`compute_stats`](Self::compute_stats)) and [`AccessPattern::Random`]
+    /// for point queries. The hint only affects performance.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the `.hgt` file
+    /// * `base_lat` - Latitude of the southwest corner (integer)
+    /// * `base_lon` - Longitude of the southwest corner (integer)
+    /// * `advice` - Expected access pattern
+    pub fn from_file_with_advice<P: AsRef<Path>>(
+        path: P,
+        base_lat: i32,
+        base_lon: i32,
+        advice: AccessPattern,
+    ) -> Result<Self> {
+        Self::from_mmap(Self::map_file(path, advice, None)?, base_lat, base_lon)
+    }
 
-        // Hint the kernel that access will be random (no sequential read-ahead)
-        #[cfg(unix)]
-        {
-            use memmap2::Advice;
-            let _ = mmap.advise(Advice::Random);
+    /// Load an SRTM tile from an in-memory copy of a `.hgt` file.
+    ///
+    /// The bytes are copied into an anonymous memory map, so lookups behave
+    /// exactly like a tile loaded from disk. The resolution is detected from
+    /// the data length.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Contents of a `.hgt` file
+    /// * `base_lat` - Latitude of the southwest corner (integer)
+    /// * `base_lon` - Longitude of the southwest corner (integer)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::InvalidFileSize`] if the length doesn't match SRTM1
+    /// or SRTM3.
+    pub fn from_bytes(data: &[u8], base_lat: i32, base_lon: i32) -> Result<Self> {
+        if SrtmResolution::from_file_size(data.len()).is_none() {
+            return Err(SrtmError::InvalidFileSize { size: data.len() });
+        }
+        let mut mmap = MmapMut::map_anon(data.len())?;
+        mmap.copy_from_slice(data);
+        Self::from_mmap(mmap.make_read_only()?, base_lat, base_lon)
+    }
+
+    /// Wrap mapped SRTM1/SRTM3 data, detecting the resolution from its size.
+    fn from_mmap(mmap: Mmap, base_lat: i32, base_lon: i32) -> Result<Self> {
+        let resolution = SrtmResolution::from_file_size(mmap.len())
+            .ok_or(SrtmError::InvalidFileSize { size: mmap.len() })?;
+
+        Ok(Self {
+            data: mmap,
+            samples: resolution.samples(),
+            resolution,
+            base_lat,
+            base_lon,
+        })
+    }
+
+    /// Load a square tile with an explicit number of samples per row/column.
+    ///
+    /// Accepts any file of exactly `samples * samples * 2` bytes, for DEMs in
+    /// nonstandard sizes (e.g. 2001×2001). Standard SRTM1/SRTM3 sample counts
+    /// yield [`SrtmResolution::Srtm1`]/[`SrtmResolution::Srtm3`]; anything else
+    /// is [`SrtmResolution::Custom`], whose spacing is derived from covering
+    /// one degree.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the raw big-endian `i16` grid
+    /// * `samples` - Number of samples per row/column (at least 2)
+    /// * `base_lat` - Latitude of the southwest corner (integer)
+    /// * `base_lon` - Longitude of the southwest corner (integer)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::SampleCountMismatch`] if the file size doesn't
+    /// match `samples`, or an I/O error if the file cannot be mapped.
+    pub fn from_file_with_samples<P: AsRef<Path>>(
+        path: P,
+        samples: usize,
+        base_lat: i32,
+        base_lon: i32,
+    ) -> Result<Self> {
+        let mmap = Self::map_file(path, AccessPattern::Random, None)?;
+
+        if samples < 2 || samples.checked_mul(samples * 2) != Some(mmap.len()) {
+            return Err(SrtmError::SampleCountMismatch {
+                size: mmap.len(),
+                samples,
+            });
         }
 
-        // Detect resolution from file size
-        let (samples, resolution) = match mmap.len() {
-            SRTM1_SIZE => (SRTM1_SAMPLES, SrtmResolution::Srtm1),
-            SRTM3_SIZE => (SRTM3_SAMPLES, SrtmResolution::Srtm3),
-            size => return Err(SrtmError::InvalidFileSize { size }),
+        let resolution = match samples {
+            SRTM1_SAMPLES => SrtmResolution::Srtm1,
+            SRTM3_SAMPLES => SrtmResolution::Srtm3,
+            _ => SrtmResolution::Custom { samples },
         };
 
         Ok(Self {
@@ -137,6 +361,83 @@ impl SrtmTile {
         })
     }
 
+    /// Load a tile whose resolution is given rather than detected.
+    ///
+    /// Only the first `resolution.samples()² * 2` bytes of the file are
+    /// mapped; anything after them is ignored. This loads tiles with padding
+    /// or trailing data, files concatenated from several grids, and custom
+    /// grids whose size would be misdetected (use
+    /// [`SrtmResolution::Custom`] for those).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the raw big-endian `i16` grid
+    /// * `base_lat` - Latitude of the southwest corner (integer)
+    /// * `base_lon` - Longitude of the southwest corner (integer)
+    /// * `resolution` - Resolution of the grid at the start of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::SampleCountMismatch`] if the file is shorter than
+    /// the grid (or the resolution has fewer than 2 samples), or an I/O error
+    /// if the file cannot be mapped.
+    pub fn from_file_with_resolution<P: AsRef<Path>>(
+        path: P,
+        base_lat: i32,
+        base_lon: i32,
+        resolution: SrtmResolution,
+    ) -> Result<Self> {
+        let samples = resolution.samples();
+        let size = std::fs::metadata(&path)?.len();
+        let needed = samples.checked_mul(samples * 2).filter(|_| samples >= 2);
+        let Some(needed) = needed.filter(|&n| n as u64 <= size) else {
+            return Err(SrtmError::SampleCountMismatch {
+                size: size as usize,
+                samples,
+            });
+        };
+
+        Ok(Self {
+            data: Self::map_file(path, AccessPattern::Random, Some(needed))?,
+            samples,
+            resolution,
+            base_lat,
+            base_lon,
+        })
+    }
+
+    /// Memory-map a tile file read-only, optionally just its first `len` bytes.
+    fn map_file<P: AsRef<Path>>(
+        path: P,
+        advice: AccessPattern,
+        len: Option<usize>,
+    ) -> Result<Mmap> {
+        let file = File::open(&path)?;
+
+        // SAFETY: Memory mapping is safe as long as the file is not modified
+        // while mapped. We open the file read-only and don't expose the mapping.
+        let mmap = unsafe {
+            match len {
+                Some(len) => MmapOptions::new().len(len).map(&file)?,
+                None => Mmap::map(&file)?,
+            }
+        };
+
+        // Hint the kernel whether to read ahead
+        #[cfg(unix)]
+        {
+            use memmap2::Advice;
+            let _ = mmap.advise(match advice {
+                AccessPattern::Random => Advice::Random,
+                AccessPattern::Sequential => Advice::Sequential,
+            });
+        }
+        #[cfg(not(unix))]
+        let _ = advice;
+
+        Ok(mmap)
+    }
+
     /// Get the elevation at the specified coordinates using nearest-neighbor lookup.
     ///
     /// This method returns the elevation of the nearest grid point (using `round()`).
@@ -182,16 +483,37 @@ impl SrtmTile {
         self.get_elevation_inner(lat, lon, f64::floor)
     }
 
-    /// Internal elevation lookup with configurable rounding function.
-    fn get_elevation_inner(&self, lat: f64, lon: f64, rounding_fn: fn(f64) -> f64) -> Result<i16> {
-        // Calculate fractional position within tile
-        let lat_frac = lat - lat.floor();
-        let lon_frac = lon - lon.floor();
+    /// Fractional position of `(lat, lon)` within the tile, 0.0 at the south
+    /// and west edges and 1.0 at the north and east edges.
+    ///
+    /// Coordinates within the tile's base square are measured from its
+    /// corner, so both edges are reachable. Any other coordinate is taken
+    /// relative to the tile whose north and east edges include it, as with
+    /// [`tile_origin`](crate::filename::tile_origin): a whole degree is the
+    /// north or east edge (1.0). This lets tiles loaded without their real
+    /// base coordinates be queried with real-world coordinates.
+    fn tile_fractions(&self, lat: f64, lon: f64) -> Result<(f64, f64)> {
+        let fraction = |v: f64, base: i32| {
+            let offset = v - f64::from(base);
+            if (0.0..=1.0).contains(&offset) {
+                offset
+            } else {
+                v - (v.ceil() - 1.0)
+            }
+        };
+        let lat_frac = fraction(lat, self.base_lat);
+        let lon_frac = fraction(lon, self.base_lon);
 
         // Validate bounds (should be 0.0 to 1.0)
         if !(0.0..=1.0).contains(&lat_frac) || !(0.0..=1.0).contains(&lon_frac) {
             return Err(SrtmError::OutOfBounds { lat, lon });
         }
+        Ok((lat_frac, lon_frac))
+    }
+
+    /// Internal elevation lookup with configurable rounding function.
+    fn get_elevation_inner(&self, lat: f64, lon: f64, rounding_fn: fn(f64) -> f64) -> Result<i16> {
+        let (lat_frac, lon_frac) = self.tile_fractions(lat, lon)?;
 
         // Convert to row/col indices
         // IMPORTANT: Rows are inverted - row 0 is the north edge (top of file)
@@ -202,6 +524,71 @@ impl SrtmTile {
         Ok(self.get_elevation_at(row, col))
     }
 
+    /// Get the nearest elevation, filling voids from the closest valid sample.
+    ///
+    /// Behaves like [`get_elevation`](Self::get_elevation) when the nearest
+    /// sample is valid. If it is [`VOID_VALUE`], the search spirals outward ring
+    /// by ring, up to `max_search` cells away, and returns the valid sample with
+    /// the smallest Euclidean grid distance. Samples outside the tile are not
+    /// considered.
+    ///
+    /// # Returns
+    ///
+    /// The elevation in meters, or `None` if every sample within `max_search`
+    /// cells is void.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are outside the tile bounds.
+    pub fn get_elevation_filled(
+        &self,
+        lat: f64,
+        lon: f64,
+        max_search: usize,
+    ) -> Result<Option<i16>> {
+        let (lat_frac, lon_frac) = self.tile_fractions(lat, lon)?;
+
+        let row = ((1.0 - lat_frac) * (self.samples - 1) as f64).round() as isize;
+        let col = (lon_frac * (self.samples - 1) as f64).round() as isize;
+
+        let elevation = self.get_elevation_at(row as usize, col as usize);
+        if elevation != VOID_VALUE {
+            return Ok(Some(elevation));
+        }
+
+        let last = self.samples as isize - 1;
+        let mut best: Option<(isize, i16)> = None;
+
+        for radius in 1..=max_search as isize {
+            for dr in -radius..=radius {
+                for dc in -radius..=radius {
+                    // Only visit the ring at this radius
+                    if dr.abs() != radius && dc.abs() != radius {
+                        continue;
+                    }
+                    let (r, c) = (row + dr, col + dc);
+                    if r < 0 || c < 0 || r > last || c > last {
+                        continue;
+                    }
+                    let v = self.get_elevation_at(r as usize, c as usize);
+                    let dist2 = dr * dr + dc * dc;
+                    if v != VOID_VALUE && !matches!(best, Some((d, _)) if d <= dist2) {
+                        best = Some((dist2, v));
+                    }
+                }
+            }
+
+            // Every sample in the next ring is at least `radius + 1` away
+            if let Some((d, v)) = best {
+                if d <= (radius + 1) * (radius + 1) {
+                    return Ok(Some(v));
+                }
+            }
+        }
+
+        Ok(best.map(|(_, v)| v))
+    }
+
     /// Get the elevation at the specified coordinates using bilinear interpolation.
     ///
     /// This method interpolates between the 4 surrounding grid points for sub-pixel
@@ -237,39 +624,119 @@ impl SrtmTile {
     /// println!("Nearest elevation: {}m", elevation);
     /// ```
     pub fn get_elevation_interpolated(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
-        // Calculate fractional position within tile
-        let lat_frac = lat - lat.floor();
-        let lon_frac = lon - lon.floor();
+        self.get_elevation_interpolated_with_policy(lat, lon, VoidPolicy::Strict)
+    }
 
-        // Validate bounds (should be 0.0 to 1.0)
-        if !(0.0..=1.0).contains(&lat_frac) || !(0.0..=1.0).contains(&lon_frac) {
-            return Err(SrtmError::OutOfBounds { lat, lon });
+    /// Bilinear interpolation with a choice of how void corners are handled.
+    ///
+    /// With [`VoidPolicy::Strict`] this is
+    /// [`get_elevation_interpolated`](Self::get_elevation_interpolated).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are outside the tile bounds.
+    pub fn get_elevation_interpolated_with_policy(
+        &self,
+        lat: f64,
+        lon: f64,
+        policy: VoidPolicy,
+    ) -> Result<Option<f64>> {
+        let (row0, col0, row_weight, col_weight) = self.interpolation_quad(lat, lon)?;
+        let values = self.quad_values(row0, col0);
+        Ok(Self::interpolate_quad_with_policy(
+            values, row_weight, col_weight, policy,
+        ))
+    }
+
+    /// Get the elevation at the specified coordinates using bicubic interpolation.
+    ///
+    /// Fits a Catmull-Rom spline through the 4×4 grid points around the
+    /// location. Compared to [`get_elevation_interpolated`](Self::get_elevation_interpolated),
+    /// the surface is smooth across grid cells (continuous slope), at the cost of
+    /// reading 16 samples instead of 4. Linear terrain is reproduced exactly.
+    ///
+    /// Near the tile edge, missing neighbors are replaced by the edge sample.
+    ///
+    /// # Returns
+    ///
+    /// The interpolated elevation in meters, or `None` if any of the 16 grid
+    /// points contains [`VOID_VALUE`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are outside the tile bounds.
+    pub fn get_elevation_bicubic(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        let (row0, col0, row_weight, col_weight) = self.interpolation_quad(lat, lon)?;
+
+        let mut rows = [0.0; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            let r = (row0 + i).saturating_sub(1);
+            let mut values = [0.0; 4];
+            for (j, value) in values.iter_mut().enumerate() {
+                let v = self.get_elevation_at(r, (col0 + j).saturating_sub(1));
+                if v == VOID_VALUE {
+                    return Ok(None);
+                }
+                *value = v as f64;
+            }
+            *row = catmull_rom(values, col_weight);
         }
 
+        Ok(Some(catmull_rom(rows, row_weight)))
+    }
+
+    /// Locate the grid cell used for bilinear interpolation.
+    ///
+    /// Returns `(row0, col0, row_weight, col_weight)`: the northwest corner of
+    /// the surrounding quad and the fractional position within it.
+    pub(crate) fn interpolation_quad(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<(usize, usize, f64, f64)> {
+        let (lat_frac, lon_frac) = self.tile_fractions(lat, lon)?;
+
         // Convert to continuous row/col position
         // IMPORTANT: Rows are inverted - row 0 is the north edge (top of file)
         let row_pos = (1.0 - lat_frac) * (self.samples - 1) as f64;
         let col_pos = lon_frac * (self.samples - 1) as f64;
 
-        // Get integer indices for the 4 surrounding points
+        // Get integer indices for the northwest corner of the quad
         let row0 = row_pos.floor() as usize;
         let col0 = col_pos.floor() as usize;
-        let row1 = (row0 + 1).min(self.samples - 1);
-        let col1 = (col0 + 1).min(self.samples - 1);
 
         // Get fractional weights for interpolation
         let row_weight = row_pos - row0 as f64;
         let col_weight = col_pos - col0 as f64;
 
-        // Get the 4 surrounding elevation values
-        let v00 = self.get_elevation_at(row0, col0);
-        let v10 = self.get_elevation_at(row0, col1);
-        let v01 = self.get_elevation_at(row1, col0);
-        let v11 = self.get_elevation_at(row1, col1);
+        Ok((row0, col0, row_weight, col_weight))
+    }
+
+    /// Read the 4 corner values of the quad at `(row0, col0)`.
+    ///
+    /// Returned as `[v00, v10, v01, v11]` (NW, NE, SW, SE).
+    pub(crate) fn quad_values(&self, row0: usize, col0: usize) -> [i16; 4] {
+        let row1 = (row0 + 1).min(self.samples - 1);
+        let col1 = (col0 + 1).min(self.samples - 1);
+        [
+            self.get_elevation_at(row0, col0),
+            self.get_elevation_at(row0, col1),
+            self.get_elevation_at(row1, col0),
+            self.get_elevation_at(row1, col1),
+        ]
+    }
+
+    /// Bilinearly interpolate quad corner values; `None` if any corner is void.
+    pub(crate) fn interpolate_quad(
+        values: [i16; 4],
+        row_weight: f64,
+        col_weight: f64,
+    ) -> Option<f64> {
+        let [v00, v10, v01, v11] = values;
 
         // Check for void values - if any surrounding point is void, return None
         if v00 == VOID_VALUE || v10 == VOID_VALUE || v01 == VOID_VALUE || v11 == VOID_VALUE {
-            return Ok(None);
+            return None;
         }
 
         // Bilinear interpolation
@@ -278,9 +745,283 @@ impl SrtmTile {
         let v1 = v01 as f64 + (v11 as f64 - v01 as f64) * col_weight;
 
         // Then interpolate vertically between the two horizontal results
-        let elevation = v0 + (v1 - v0) * row_weight;
+        Some(v0 + (v1 - v0) * row_weight)
+    }
+
+    /// Interpolate quad corner values, handling void corners per `policy`.
+    pub(crate) fn interpolate_quad_with_policy(
+        values: [i16; 4],
+        row_weight: f64,
+        col_weight: f64,
+        policy: VoidPolicy,
+    ) -> Option<f64> {
+        let interpolated = Self::interpolate_quad(values, row_weight, col_weight);
+        if interpolated.is_some() {
+            return interpolated;
+        }
+
+        match policy {
+            VoidPolicy::Strict => None,
+            VoidPolicy::IgnoreVoid => {
+                let weights = [
+                    (1.0 - row_weight) * (1.0 - col_weight),
+                    (1.0 - row_weight) * col_weight,
+                    row_weight * (1.0 - col_weight),
+                    row_weight * col_weight,
+                ];
+                let valid: Vec<(f64, f64)> = values
+                    .iter()
+                    .zip(weights)
+                    .filter(|(&v, _)| v != VOID_VALUE)
+                    .map(|(&v, w)| (v as f64, w))
+                    .collect();
+                if valid.is_empty() {
+                    return None;
+                }
+
+                // On a void sample the valid corners all weigh 0: use their mean
+                let total: f64 = valid.iter().map(|(_, w)| w).sum();
+                if total > 0.0 {
+                    Some(valid.iter().map(|(v, w)| v * w).sum::<f64>() / total)
+                } else {
+                    Some(valid.iter().map(|(v, _)| v).sum::<f64>() / valid.len() as f64)
+                }
+            }
+            VoidPolicy::Nearest => {
+                let index = usize::from(row_weight >= 0.5) * 2 + usize::from(col_weight >= 0.5);
+                let v = values[index];
+                (v != VOID_VALUE).then_some(v as f64)
+            }
+        }
+    }
+
+    /// Get the nearest-neighbor elevation together with a crude vertical uncertainty.
+    ///
+    /// The uncertainty combines the nominal SRTM vertical error
+    /// ([`SRTM_VERTICAL_ERROR_M`]) with the local terrain roughness, measured as
+    /// the standard deviation of the non-void samples in the 3×3 neighborhood:
+    /// `sqrt(nominal² + roughness²)`. Flat terrain therefore yields the nominal
+    /// error, rough terrain something larger.
+    ///
+    /// # Returns
+    ///
+    /// `(elevation, std_dev_m)`, or `None` if the nearest sample is void.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are outside the tile bounds.
+    pub fn get_elevation_with_uncertainty(&self, lat: f64, lon: f64) -> Result<Option<(f64, f64)>> {
+        let (lat_frac, lon_frac) = self.tile_fractions(lat, lon)?;
+
+        let row = ((1.0 - lat_frac) * (self.samples - 1) as f64).round() as usize;
+        let col = (lon_frac * (self.samples - 1) as f64).round() as usize;
+
+        let elevation = self.get_elevation_at(row, col);
+        if elevation == VOID_VALUE {
+            return Ok(None);
+        }
+
+        // Population standard deviation of the valid 3x3 neighborhood
+        let mut values = Vec::with_capacity(9);
+        for r in row.saturating_sub(1)..=(row + 1).min(self.samples - 1) {
+            for c in col.saturating_sub(1)..=(col + 1).min(self.samples - 1) {
+                let v = self.get_elevation_at(r, c);
+                if v != VOID_VALUE {
+                    values.push(v as f64);
+                }
+            }
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
 
-        Ok(Some(elevation))
+        let std_dev = (SRTM_VERTICAL_ERROR_M.powi(2) + variance).sqrt();
+        Ok(Some((elevation as f64, std_dev)))
+    }
+
+    /// Terrain Ruggedness Index at the sample nearest `(lat, lon)`.
+    ///
+    /// The TRI is the mean absolute elevation difference in meters between
+    /// the sample and its 8 neighbors; flat terrain scores 0. On the tile
+    /// edge only the neighbors inside the tile are used.
+    ///
+    /// # Returns
+    ///
+    /// The index in meters, or `None` if the sample or any neighbor is void.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are outside the tile bounds.
+    pub fn ruggedness(&self, lat: f64, lon: f64) -> Result<Option<f64>> {
+        let (lat_frac, lon_frac) = self.tile_fractions(lat, lon)?;
+
+        let row = ((1.0 - lat_frac) * (self.samples - 1) as f64).round() as usize;
+        let col = (lon_frac * (self.samples - 1) as f64).round() as usize;
+
+        let center = self.get_elevation_at(row, col);
+        if center == VOID_VALUE {
+            return Ok(None);
+        }
+
+        let mut total = 0.0;
+        let mut count = 0;
+        for r in row.saturating_sub(1)..=(row + 1).min(self.samples - 1) {
+            for c in col.saturating_sub(1)..=(col + 1).min(self.samples - 1) {
+                if (r, c) == (row, col) {
+                    continue;
+                }
+                let v = self.get_elevation_at(r, c);
+                if v == VOID_VALUE {
+                    return Ok(None);
+                }
+                total += (f64::from(v) - f64::from(center)).abs();
+                count += 1;
+            }
+        }
+
+        Ok(Some(total / count as f64))
+    }
+
+    /// Iterate over every raw sample in file order (north to south, west to east).
+    pub(crate) fn raw_samples(&self) -> impl Iterator<Item = i16> + '_ {
+        self.data
+            .chunks_exact(2)
+            .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+    }
+
+    /// Iterate over every sample as `(lat, lon, elevation)`, in file order.
+    ///
+    /// The first item is the north-west corner (`base_lat + 1`, `base_lon`)
+    /// and the last the south-east corner (`base_lat`, `base_lon + 1`).
+    /// Void samples are yielded as [`VOID_VALUE`].
+    pub fn iter_samples(&self) -> impl Iterator<Item = (f64, f64, i16)> + '_ {
+        let samples = self.samples;
+        let step = self.resolution.pixel_spacing_deg();
+        let north = f64::from(self.base_lat) + 1.0;
+        let west = f64::from(self.base_lon);
+
+        self.raw_samples().enumerate().map(move |(i, v)| {
+            let (row, col) = (i / samples, i % samples);
+            (north - row as f64 * step, west + col as f64 * step, v)
+        })
+    }
+
+    /// Compute min/max/mean elevation and the void count in a single pass.
+    ///
+    /// This scans every sample (~1.4M for SRTM3, ~13M for SRTM1), so callers
+    /// that need the result repeatedly should cache it.
+    pub fn compute_stats(&self) -> TileStats {
+        let mut min = i16::MAX;
+        let mut max = i16::MIN;
+        let mut sum = 0i64;
+        let mut void_count = 0u64;
+        let mut sample_count = 0u64;
+
+        for v in self.raw_samples() {
+            sample_count += 1;
+            if v == VOID_VALUE {
+                void_count += 1;
+            } else {
+                min = min.min(v);
+                max = max.max(v);
+                sum += v as i64;
+            }
+        }
+
+        let valid = sample_count - void_count;
+        TileStats {
+            min: (valid > 0).then_some(min),
+            max: (valid > 0).then_some(max),
+            mean: (valid > 0).then(|| sum as f64 / valid as f64),
+            void_count,
+            sample_count,
+        }
+    }
+
+    /// Count non-void samples in `bin_size_m`-wide elevation bins.
+    ///
+    /// Bins start at the tile's minimum elevation and run up to its maximum,
+    /// so the result is `(bin_start, count)` pairs in ascending order with
+    /// every bin in between present, including empty ones. A `bin_size_m`
+    /// below 1 is treated as 1. An all-void tile yields an empty histogram.
+    ///
+    /// Like [`compute_stats`](Self::compute_stats), this scans every sample.
+    pub fn elevation_histogram(&self, bin_size_m: i16) -> Vec<(i16, u64)> {
+        let Some((min, max)) =
+            self.raw_samples()
+                .filter(|&v| v != VOID_VALUE)
+                .fold(None, |range, v| match range {
+                    None => Some((v, v)),
+                    Some((lo, hi)) => Some((v.min(lo), v.max(hi))),
+                })
+        else {
+            return Vec::new();
+        };
+
+        let bin_size = i32::from(bin_size_m.max(1));
+        let bin = |v: i16| ((i32::from(v) - i32::from(min)) / bin_size) as usize;
+        let mut counts = vec![0u64; bin(max) + 1];
+        for v in self.raw_samples().filter(|&v| v != VOID_VALUE) {
+            counts[bin(v)] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| ((i32::from(min) + i as i32 * bin_size) as i16, count))
+            .collect()
+    }
+
+    /// Check the tile for common signs of corruption.
+    ///
+    /// Flags tiles that are entirely void or zero, and "striped" tiles where
+    /// at least 10% of rows are exact copies of the row above while not being
+    /// constant (flat rows such as open ocean are legitimately repeated).
+    pub fn health(&self) -> TileHealth {
+        let total = self.samples * self.samples;
+        let mut void_count = 0usize;
+        let mut zero_count = 0usize;
+        for v in self.raw_samples() {
+            if v == VOID_VALUE {
+                void_count += 1;
+            } else if v == 0 {
+                zero_count += 1;
+            }
+        }
+
+        let row_bytes = self.samples * 2;
+        let rows: Vec<&[u8]> = self.data.chunks_exact(row_bytes).collect();
+        let repeated_rows = rows
+            .windows(2)
+            .filter(|pair| {
+                let (prev, row) = (pair[0], pair[1]);
+                row == prev && row.chunks_exact(2).any(|s| s != &row[..2])
+            })
+            .count();
+
+        let all_void = void_count == total;
+        let all_zero = zero_count == total;
+        let striped = repeated_rows * 10 >= self.samples;
+
+        TileHealth {
+            all_void,
+            all_zero,
+            void_ratio: void_count as f64 / total as f64,
+            suspicious: all_void || all_zero || striped,
+        }
+    }
+
+    /// Get the raw sample at a row/column index of the tile grid.
+    ///
+    /// Rows run north to south and columns west to east: `(0, 0)` is the
+    /// north-west corner and `(samples - 1, samples - 1)` the south-east
+    /// corner, matching the file layout.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(elevation)` - the sample, which is [`VOID_VALUE`] for void cells
+    /// - `None` - `row` or `col` is not below [`samples`](Self::samples)
+    pub fn sample_at(&self, row: usize, col: usize) -> Option<i16> {
+        (row < self.samples && col < self.samples).then(|| self.get_elevation_at(row, col))
     }
 
     /// Get elevation at a specific row/column index.
@@ -317,6 +1058,95 @@ impl SrtmTile {
         }
     }
 
+    /// Downsample the tile by averaging `factor` × `factor` blocks of samples.
+    ///
+    /// Output sample `(r, c)` averages the source block starting at
+    /// `(r * factor, c * factor)`, clamped at the tile edge, so the result has
+    /// `(samples - 1) / factor + 1` samples per side and keeps the shared edge
+    /// row/column. Void samples are skipped; a block with no valid samples
+    /// becomes [`VOID_VALUE`].
+    ///
+    /// Returns the samples in row-major order (north to south, west to east).
+    pub fn downsample(&self, factor: usize) -> Vec<i16> {
+        let factor = factor.max(1);
+        let out_samples = (self.samples - 1) / factor + 1;
+        let mut out = Vec::with_capacity(out_samples * out_samples);
+
+        for out_row in 0..out_samples {
+            let row_start = out_row * factor;
+            let row_end = (row_start + factor).min(self.samples);
+            for out_col in 0..out_samples {
+                let col_start = out_col * factor;
+                let col_end = (col_start + factor).min(self.samples);
+
+                let mut sum = 0i64;
+                let mut count = 0i64;
+                for row in row_start..row_end {
+                    for col in col_start..col_end {
+                        let elev = self.get_elevation_at(row, col);
+                        if elev != VOID_VALUE {
+                            sum += elev as i64;
+                            count += 1;
+                        }
+                    }
+                }
+
+                out.push(if count == 0 {
+                    VOID_VALUE
+                } else {
+                    (sum as f64 / count as f64).round() as i16
+                });
+            }
+        }
+
+        out
+    }
+
+    /// Read a `rows` × `cols` window of raw samples starting at `(row, col)`.
+    ///
+    /// Row `0` is the north edge and column `0` the west edge. Returns the
+    /// samples in row-major order (north to south, west to east), including
+    /// [`VOID_VALUE`] samples; wrap them in an
+    /// [`ElevationGrid`](crate::ElevationGrid) to resample.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SrtmError::InvalidCoordinate`] if the window extends past the
+    /// tile edge.
+    pub fn read_window(
+        &self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<i16>> {
+        if row + rows > self.samples || col + cols > self.samples {
+            return Err(SrtmError::InvalidCoordinate {
+                message: format!(
+                    "window {}x{} at ({}, {}) exceeds {}x{} tile",
+                    rows, cols, row, col, self.samples, self.samples
+                ),
+            });
+        }
+
+        let mut out = Vec::with_capacity(rows * cols);
+        for r in row..row + rows {
+            let start = (r * self.samples + col) * 2;
+            let bytes = &self.data[start..start + cols * 2];
+            out.extend(
+                bytes
+                    .chunks_exact(2)
+                    .map(|b| i16::from_be_bytes([b[0], b[1]])),
+            );
+        }
+        Ok(out)
+    }
+
+    /// Size of the mapped tile data in bytes.
+    pub fn byte_len(&self) -> u64 {
+        self.data.len() as u64
+    }
+
     /// Returns the resolution of this tile.
     pub fn resolution(&self) -> SrtmResolution {
         self.resolution
@@ -338,6 +1168,14 @@ impl SrtmTile {
     }
 }
 
+/// Evaluate the Catmull-Rom spline through `p` at `t` in `[0, 1]` between `p[1]` and `p[2]`.
+fn catmull_rom(p: [f64; 4], t: f64) -> f64 {
+    0.5 * (2.0 * p[1]
+        + (p[2] - p[0]) * t
+        + (2.0 * p[0] - 5.0 * p[1] + 4.0 * p[2] - p[3]) * t * t
+        + (3.0 * (p[1] - p[2]) + p[3] - p[0]) * t * t * t)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -379,6 +1217,95 @@ mod tests {
         assert_eq!(tile.samples(), SRTM3_SAMPLES);
     }
 
+    #[test]
+    fn test_from_bytes_matches_file() {
+        let file = create_test_srtm3_file();
+        let data = std::fs::read(file.path()).unwrap();
+
+        let tile = SrtmTile::from_bytes(&data, 35, 138).unwrap();
+        let from_file = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+        assert_eq!(tile.resolution(), SrtmResolution::Srtm3);
+        assert_eq!(tile.base_lat(), 35);
+        assert_eq!(tile.base_lon(), 138);
+        for (lat, lon) in [(35.5, 138.5), (35.0, 138.0), (35.99, 138.01)] {
+            assert_eq!(
+                tile.get_elevation(lat, lon).unwrap(),
+                from_file.get_elevation(lat, lon).unwrap()
+            );
+        }
+
+        assert!(matches!(
+            SrtmTile::from_bytes(&data[..1000], 35, 138),
+            Err(SrtmError::InvalidFileSize { size: 1000 })
+        ));
+    }
+
+    #[test]
+    fn test_from_file_with_advice() {
+        let file = create_test_srtm3_file();
+        let default = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        for advice in [AccessPattern::Random, AccessPattern::Sequential] {
+            let tile = SrtmTile::from_file_with_advice(file.path(), 35, 138, advice).unwrap();
+            assert_eq!(tile.resolution(), SrtmResolution::Srtm3);
+            assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), 500);
+            for (lat, lon) in [(35.0, 138.0), (35.25, 138.75), (35.99, 138.01)] {
+                assert_eq!(
+                    tile.get_elevation(lat, lon).unwrap(),
+                    default.get_elevation(lat, lon).unwrap()
+                );
+            }
+            assert_eq!(tile.compute_stats(), default.compute_stats());
+        }
+    }
+
+    #[test]
+    fn test_from_file_with_resolution() {
+        // An SRTM3 grid followed by a trailer, too large to be detected
+        let mut file = create_test_srtm3_file();
+        file.write_all(&[0xAB; 4096]).unwrap();
+        file.flush().unwrap();
+        assert!(matches!(
+            SrtmTile::from_file(file.path()),
+            Err(SrtmError::InvalidFileSize { .. })
+        ));
+
+        let tile = SrtmTile::from_file_with_resolution(file.path(), 35, 138, SrtmResolution::Srtm3)
+            .unwrap();
+        assert_eq!(tile.samples(), SRTM3_SAMPLES);
+        assert_eq!(tile.byte_len(), SRTM3_SIZE as u64);
+        assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), 500);
+        assert_eq!(tile.compute_stats().sample_count, (1201 * 1201) as u64);
+
+        // Too short for the requested resolution
+        assert!(matches!(
+            SrtmTile::from_file_with_resolution(file.path(), 35, 138, SrtmResolution::Srtm1),
+            Err(SrtmError::SampleCountMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_window() {
+        let file = create_test_srtm3_file();
+        let tile = SrtmTile::from_file(file.path()).unwrap();
+
+        let window = tile.read_window(599, 599, 2, 3).unwrap();
+        assert_eq!(window, vec![0, 0, 0, 0, 500, 0]);
+
+        let corners = tile.read_window(0, 0, 1, 1).unwrap();
+        assert_eq!(corners, vec![1000]);
+        let whole = tile
+            .read_window(0, 0, SRTM3_SAMPLES, SRTM3_SAMPLES)
+            .unwrap();
+        assert_eq!(whole.len(), SRTM3_SAMPLES * SRTM3_SAMPLES);
+        assert_eq!(whole[whole.len() - 1], 100);
+
+        assert!(matches!(
+            tile.read_window(1200, 0, 2, 1),
+            Err(SrtmError::InvalidCoordinate { .. })
+        ));
+    }
+
     #[test]
     fn test_invalid_file_size() {
         let mut file = NamedTempFile::new().unwrap();
@@ -429,6 +1356,70 @@ mod tests {
         assert_eq!(SrtmResolution::Srtm3.meters(), 90.0);
     }
 
+    #[test]
+    fn test_resolution_from_file_size() {
+        assert_eq!(
+            SrtmResolution::from_file_size(25_934_402),
+            Some(SrtmResolution::Srtm1)
+        );
+        assert_eq!(
+            SrtmResolution::from_file_size(2_884_802),
+            Some(SrtmResolution::Srtm3)
+        );
+        assert_eq!(SrtmResolution::from_file_size(2_884_801), None);
+        assert_eq!(SrtmResolution::from_file_size(0), None);
+
+        assert_eq!(SrtmResolution::Srtm1.pixel_spacing_deg(), 1.0 / 3600.0);
+        assert_eq!(SrtmResolution::Srtm3.pixel_spacing_deg(), 3.0 / 3600.0);
+    }
+
+    #[test]
+    fn test_custom_resolution_2001() {
+        const SAMPLES: usize = 2001;
+        let mut file = NamedTempFile::new().unwrap();
+        let mut data = vec![0u8; SAMPLES * SAMPLES * 2];
+        let center = (1000 * SAMPLES + 1000) * 2;
+        data[center..center + 2].copy_from_slice(&1234i16.to_be_bytes());
+        file.write_all(&data).unwrap();
+
+        let tile = SrtmTile::from_file_with_samples(file.path(), SAMPLES, 35, 138).unwrap();
+        assert_eq!(tile.samples(), SAMPLES);
+        assert_eq!(tile.resolution().samples(), SAMPLES);
+        assert_eq!(tile.resolution().as_str(), "custom");
+        assert!((tile.resolution().meters() - 111_320.0 / 2000.0).abs() < 1e-9);
+
+        assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), 1234);
+        assert_eq!(
+            tile.get_elevation_interpolated(35.5, 138.5).unwrap(),
+            Some(1234.0)
+        );
+        // One sample off-center is flat ground
+        assert_eq!(tile.get_elevation(35.5, 138.5 + 1.0 / 2000.0).unwrap(), 0);
+
+        // Auto-detection still rejects the nonstandard size
+        assert!(matches!(
+            SrtmTile::from_file(file.path()),
+            Err(SrtmError::InvalidFileSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_file_with_samples_standard_and_mismatch() {
+        let file = create_test_srtm3_file();
+
+        let tile = SrtmTile::from_file_with_samples(file.path(), 1201, 35, 138).unwrap();
+        assert_eq!(tile.resolution(), SrtmResolution::Srtm3);
+        assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), 500);
+
+        assert!(matches!(
+            SrtmTile::from_file_with_samples(file.path(), 2001, 35, 138),
+            Err(SrtmError::SampleCountMismatch {
+                size: SRTM3_SIZE,
+                samples: 2001
+            })
+        ));
+    }
+
     /// Create a test file with a 2x2 grid of known values for interpolation testing.
     /// Sets values at rows 600-601, cols 600-601 to form a gradient.
     fn create_interpolation_test_file() -> NamedTempFile {
@@ -496,6 +1487,55 @@ mod tests {
         assert!((elev - 250.0).abs() < 5.0, "Expected ~250, got {}", elev);
     }
 
+    #[test]
+    fn test_bicubic_reproduces_plane() {
+        let file = write_tile(|row, col| (row as i16 % 500) * 2 + (col as i16 % 500) * 3);
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        // Inside a linear patch bicubic matches bilinear exactly
+        let lat = 35.0 + (1.0 - 600.3 / 1200.0);
+        let lon = 138.0 + 600.7 / 1200.0;
+        let bicubic = tile.get_elevation_bicubic(lat, lon).unwrap().unwrap();
+        let bilinear = tile.get_elevation_interpolated(lat, lon).unwrap().unwrap();
+        assert!(
+            (bicubic - bilinear).abs() < 1e-6,
+            "{} vs {}",
+            bicubic,
+            bilinear
+        );
+
+        // At grid points it returns the sample
+        let exact = tile.get_elevation_bicubic(35.5, 138.5).unwrap().unwrap();
+        assert!((exact - tile.get_elevation(35.5, 138.5).unwrap() as f64).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bicubic_void_neighborhood() {
+        // Void one sample outside the bilinear quad, inside the 4x4 window
+        let file = write_tile(|row, col| {
+            if (row, col) == (599, 599) {
+                VOID_VALUE
+            } else {
+                100
+            }
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        let lat = 35.0 + (1.0 - 600.5 / 1200.0);
+        let lon = 138.0 + 600.5 / 1200.0;
+        assert_eq!(
+            tile.get_elevation_interpolated(lat, lon).unwrap(),
+            Some(100.0)
+        );
+        assert_eq!(tile.get_elevation_bicubic(lat, lon).unwrap(), None);
+
+        // Tile corner: edge samples stand in for missing neighbors
+        assert_eq!(
+            tile.get_elevation_bicubic(35.0, 138.0).unwrap(),
+            Some(100.0)
+        );
+    }
+
     #[test]
     fn test_interpolation_horizontal() {
         let file = create_interpolation_test_file();
@@ -516,6 +1556,59 @@ mod tests {
         assert!((elev - 150.0).abs() < 10.0, "Expected ~150, got {}", elev);
     }
 
+    #[test]
+    fn test_interpolation_void_policies() {
+        // NW corner of the quad at (600, 600) is void
+        let file = write_tile(|row, col| match (row, col) {
+            (600, 600) => VOID_VALUE,
+            (600, 601) => 200,
+            (601, 600) => 300,
+            (601, 601) => 400,
+            _ => 0,
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+        let at = |row: f64, col: f64, policy| {
+            tile.get_elevation_interpolated_with_policy(
+                36.0 - row / 1200.0,
+                138.0 + col / 1200.0,
+                policy,
+            )
+            .unwrap()
+        };
+
+        // Center of the quad
+        assert_eq!(at(600.5, 600.5, VoidPolicy::Strict), None);
+        let ignored = at(600.5, 600.5, VoidPolicy::IgnoreVoid).unwrap();
+        assert!((ignored - 300.0).abs() < 1e-6, "{}", ignored);
+        assert_eq!(at(600.75, 600.75, VoidPolicy::Nearest), Some(400.0));
+
+        // Close to the void corner
+        let ignored = at(600.25, 600.25, VoidPolicy::IgnoreVoid).unwrap();
+        let expected = (200.0 * 0.1875 + 300.0 * 0.1875 + 400.0 * 0.0625) / 0.4375;
+        assert!((ignored - expected).abs() < 1e-6, "{}", ignored);
+        assert_eq!(at(600.25, 600.25, VoidPolicy::Nearest), None);
+        assert_eq!(at(600.25, 600.75, VoidPolicy::Nearest), Some(200.0));
+
+        // Exactly on the void sample, the other corners weigh nothing
+        let ignored = at(600.0, 600.0, VoidPolicy::IgnoreVoid).unwrap();
+        assert!(ignored.is_finite());
+
+        // Policies only matter when a corner is void
+        for policy in [
+            VoidPolicy::Strict,
+            VoidPolicy::IgnoreVoid,
+            VoidPolicy::Nearest,
+        ] {
+            let value = at(601.5, 601.5, policy).unwrap();
+            assert_eq!(Some(value), at(601.5, 601.5, VoidPolicy::Strict));
+        }
+        assert_eq!(
+            tile.get_elevation_interpolated(36.0 - 600.5 / 1200.0, 138.0 + 600.5 / 1200.0)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_interpolation_void_value() {
         let mut file = NamedTempFile::new().unwrap();
@@ -574,6 +1667,325 @@ mod tests {
         file
     }
 
+    #[test]
+    fn test_uncertainty_flat_tile() {
+        let file = create_test_srtm3_file();
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        // Away from the seeded values the tile is flat (all zeros)
+        let (elevation, std_dev) = tile
+            .get_elevation_with_uncertainty(35.25, 138.25)
+            .unwrap()
+            .unwrap();
+        assert_eq!(elevation, 0.0);
+        assert!((std_dev - SRTM_VERTICAL_ERROR_M).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_uncertainty_rough_tile() {
+        let mut file = NamedTempFile::new().unwrap();
+        let mut data = vec![0u8; SRTM3_SIZE];
+
+        // Checkerboard of 0m / 100m around the center sample
+        for row in 599..=601 {
+            for col in 599..=601 {
+                let elevation: i16 = if (row + col) % 2 == 0 { 100 } else { 0 };
+                let offset = (row * SRTM3_SAMPLES + col) * 2;
+                data[offset..offset + 2].copy_from_slice(&elevation.to_be_bytes());
+            }
+        }
+        file.write_all(&data).unwrap();
+
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+        let (elevation, std_dev) = tile
+            .get_elevation_with_uncertainty(35.5, 138.5)
+            .unwrap()
+            .unwrap();
+        assert_eq!(elevation, 100.0);
+        assert!(std_dev > SRTM_VERTICAL_ERROR_M + 10.0);
+    }
+
+    #[test]
+    fn test_ruggedness_flat_tile() {
+        let file = write_tile(|_, _| 250);
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        assert_eq!(tile.ruggedness(35.5, 138.5).unwrap(), Some(0.0));
+    }
+
+    #[test]
+    fn test_ruggedness_corner_samples() {
+        // Slope rising 10m per sample south and east
+        let file = write_tile(|row, col| ((row + col) * 10) as i16);
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        // Interior: 4 neighbors differ by 10m, 2 by 20m, 2 by 0m
+        assert_eq!(tile.ruggedness(35.5, 138.5).unwrap(), Some(10.0));
+        // North-west corner (row 0, col 0) and south-east corner (row 1200,
+        // col 1200) have only 3 neighbors: (10 + 10 + 20) / 3
+        let corner = Some(40.0 / 3.0);
+        assert_eq!(tile.ruggedness(36.0, 138.0).unwrap(), corner);
+        assert_eq!(tile.ruggedness(35.0, 139.0).unwrap(), corner);
+        // Edge sample on row 1200 has 5 neighbors: (10 + 10 + 10 + 20 + 0) / 5
+        assert_eq!(tile.ruggedness(35.0, 138.5).unwrap(), Some(10.0));
+    }
+
+    #[test]
+    fn test_ruggedness_checkerboard() {
+        let file = write_tile(|row, col| if (row + col) % 2 == 0 { 100 } else { 0 });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        // Diagonal neighbors match the center, the 4 orthogonal ones differ by
+        // 100m: (4 * 0 + 4 * 100) / 8 = 50
+        assert_eq!(tile.ruggedness(35.5, 138.5).unwrap(), Some(50.0));
+        let one_sample = 1.0 / 1200.0;
+        assert_eq!(
+            tile.ruggedness(35.5, 138.5 + one_sample).unwrap(),
+            Some(50.0)
+        );
+    }
+
+    #[test]
+    fn test_ruggedness_void_neighbor() {
+        let file = write_tile(|row, col| {
+            if (row, col) == (601, 600) {
+                VOID_VALUE
+            } else {
+                10
+            }
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        assert_eq!(tile.ruggedness(35.5, 138.5).unwrap(), None);
+        // Two samples away the void is out of the neighborhood
+        assert_eq!(
+            tile.ruggedness(35.5, 138.5 - 2.0 / 1200.0).unwrap(),
+            Some(0.0)
+        );
+    }
+
+    fn write_tile(samples: impl Fn(usize, usize) -> i16) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        crate::test_util::write_test_tile(file.path(), samples);
+        file
+    }
+
+    #[test]
+    fn test_get_elevation_filled_void_hole() {
+        // 3x3 void hole around the center sample, surrounded by 250m
+        let file = write_tile(|row, col| {
+            if (599..=601).contains(&row) && (599..=601).contains(&col) {
+                VOID_VALUE
+            } else {
+                250
+            }
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        assert_eq!(tile.get_elevation(35.5, 138.5).unwrap(), VOID_VALUE);
+        assert_eq!(
+            tile.get_elevation_filled(35.5, 138.5, 2).unwrap(),
+            Some(250)
+        );
+        // The whole 1-cell window is void
+        assert_eq!(tile.get_elevation_filled(35.5, 138.5, 1).unwrap(), None);
+        // Valid samples are returned as-is
+        assert_eq!(
+            tile.get_elevation_filled(35.25, 138.25, 0).unwrap(),
+            Some(250)
+        );
+    }
+
+    #[test]
+    fn test_get_elevation_filled_picks_closest() {
+        // Void everywhere except a sample 2 cells east (distance 2) and one
+        // 2 cells north-east diagonally (distance ~2.83) of the center
+        let file = write_tile(|row, col| match (row, col) {
+            (600, 602) => 100,
+            (598, 602) => 200,
+            _ => VOID_VALUE,
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        assert_eq!(
+            tile.get_elevation_filled(35.5, 138.5, 5).unwrap(),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn test_compute_stats_matches_brute_force() {
+        let file = write_tile(|row, col| match (row * 31 + col * 17) % 97 {
+            0 => VOID_VALUE,
+            n => n as i16 * 10 - 300,
+        });
+        let tile = SrtmTile::from_file(file.path()).unwrap();
+        let stats = tile.compute_stats();
+
+        let (mut min, mut max, mut sum, mut voids) = (i16::MAX, i16::MIN, 0i64, 0u64);
+        for row in 0..SRTM3_SAMPLES {
+            for col in 0..SRTM3_SAMPLES {
+                let v = tile.get_elevation_at(row, col);
+                if v == VOID_VALUE {
+                    voids += 1;
+                } else {
+                    min = min.min(v);
+                    max = max.max(v);
+                    sum += v as i64;
+                }
+            }
+        }
+        let total = (SRTM3_SAMPLES * SRTM3_SAMPLES) as u64;
+
+        assert_eq!(stats.sample_count, total);
+        assert_eq!(stats.void_count, voids);
+        assert_eq!(stats.min, Some(min));
+        assert_eq!(stats.max, Some(max));
+        let mean = sum as f64 / (total - voids) as f64;
+        assert!((stats.mean.unwrap() - mean).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_iter_samples() {
+        let file = write_tile(|row, col| (row * 2 + col) as i16);
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        assert_eq!(tile.iter_samples().count(), SRTM3_SAMPLES * SRTM3_SAMPLES);
+
+        let mut samples = tile.iter_samples();
+        assert_eq!(samples.next(), Some((36.0, 138.0, 0)));
+        let (lat, lon, elevation) = samples.next().unwrap();
+        assert_eq!((lat, elevation), (36.0, 1));
+        assert!((lon - (138.0 + 1.0 / 1200.0)).abs() < 1e-12);
+
+        let (lat, lon, elevation) = tile.iter_samples().last().unwrap();
+        assert!((lat - 35.0).abs() < 1e-9 && (lon - 139.0).abs() < 1e-9);
+        assert_eq!(elevation, 3600);
+
+        // Interior coordinates round-trip through the nearest-neighbor lookup
+        // (the north and east edges belong to the neighboring tiles)
+        let interior = tile
+            .iter_samples()
+            .filter(|&(lat, lon, _)| lat < 36.0 && lon < 139.0);
+        for (lat, lon, elevation) in interior.step_by(7919) {
+            assert_eq!(tile.get_elevation(lat, lon).unwrap(), elevation);
+        }
+    }
+
+    #[test]
+    fn test_sample_at() {
+        let file = write_tile(|row, col| match (row, col) {
+            (0, 0) => 1,
+            (0, 1200) => 2,
+            (1200, 0) => 3,
+            (1200, 1200) => 4,
+            (600, 600) => VOID_VALUE,
+            _ => 0,
+        });
+        let tile = SrtmTile::from_file_with_coords(file.path(), 35, 138).unwrap();
+
+        // Row 0 is the north edge
+        assert_eq!(tile.sample_at(0, 0), Some(1));
+        assert_eq!(tile.get_elevation(35.9999, 138.0).unwrap(), 1);
+        assert_eq!(tile.sample_at(0, 1200), Some(2));
+        assert_eq!(tile.sample_at(1200, 0), Some(3));
+        assert_eq!(tile.sample_at(1200, 1200), Some(4));
+
+        assert_eq!(tile.sample_at(600, 600), Some(VOID_VALUE));
+
+        assert_eq!(tile.sample_at(1201, 0), None);
+        assert_eq!(tile.sample_at(0, 1201), None);
+        assert_eq!(tile.sample_at(usize::MAX, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_compute_stats_all_void() {
+        let file = write_tile(|_, _| VOID_VALUE);
+        let stats = SrtmTile::from_file(file.path()).unwrap().compute_stats();
+        assert_eq!(stats.min, None);
+        assert_eq!(stats.max, None);
+        assert_eq!(stats.mean, None);
+        assert_eq!(stats.void_count, stats.sample_count);
+    }
+
+    #[test]
+    fn test_elevation_histogram() {
+        // Northern half a plateau at 100m, southern half at 420m, one void
+        let file = write_tile(|row, col| match (row, col) {
+            (0, 0) => VOID_VALUE,
+            (row, _) if row < 600 => 100,
+            _ => 420,
+        });
+        let tile = SrtmTile::from_file(file.path()).unwrap();
+        let north = (600 * SRTM3_SAMPLES - 1) as u64;
+        let south = (601 * SRTM3_SAMPLES) as u64;
+
+        let histogram = tile.elevation_histogram(100);
+        assert_eq!(
+            histogram,
+            vec![(100, north), (200, 0), (300, 0), (400, south)]
+        );
+
+        // The top bin holds the maximum even when it starts on a boundary
+        assert_eq!(
+            tile.elevation_histogram(320),
+            vec![(100, north), (420, south)]
+        );
+        assert_eq!(tile.elevation_histogram(1000), vec![(100, north + south)]);
+        assert_eq!(tile.elevation_histogram(0).len(), 321);
+
+        let void = write_tile(|_, _| VOID_VALUE);
+        assert!(SrtmTile::from_file(void.path())
+            .unwrap()
+            .elevation_histogram(10)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_health_all_zero() {
+        let file = write_tile(|_, _| 0);
+        let health = SrtmTile::from_file(file.path()).unwrap().health();
+        assert!(health.all_zero);
+        assert!(!health.all_void);
+        assert_eq!(health.void_ratio, 0.0);
+        assert!(health.suspicious);
+    }
+
+    #[test]
+    fn test_health_all_void() {
+        let file = write_tile(|_, _| VOID_VALUE);
+        let health = SrtmTile::from_file(file.path()).unwrap().health();
+        assert!(health.all_void);
+        assert!(!health.all_zero);
+        assert_eq!(health.void_ratio, 1.0);
+        assert!(health.suspicious);
+    }
+
+    #[test]
+    fn test_health_gradient() {
+        let file = write_tile(|row, col| (row + col) as i16);
+        let health = SrtmTile::from_file(file.path()).unwrap().health();
+        assert!(!health.all_void);
+        assert!(!health.all_zero);
+        assert_eq!(health.void_ratio, 0.0);
+        assert!(!health.suspicious);
+    }
+
+    #[test]
+    fn test_health_striped() {
+        // Bottom half repeats the same non-constant row
+        let file = write_tile(|row, col| {
+            if row < 600 {
+                (row + col) as i16
+            } else {
+                col as i16
+            }
+        });
+        let health = SrtmTile::from_file(file.path()).unwrap().health();
+        assert!(!health.all_zero);
+        assert!(health.suspicious);
+    }
+
     #[test]
     fn test_floor_vs_round_different_results() {
         let file = create_rounding_test_file();
@@ -611,4 +2023,43 @@ mod tests {
         assert_eq!(elev_round, elev_floor);
         assert_eq!(elev_round, 500);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        for resolution in [
+            SrtmResolution::Srtm1,
+            SrtmResolution::Srtm3,
+            SrtmResolution::Custom { samples: 601 },
+        ] {
+            let json = serde_json::to_string(&resolution).unwrap();
+            assert_eq!(
+                serde_json::from_str::<SrtmResolution>(&json).unwrap(),
+                resolution
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&SrtmResolution::Srtm1).unwrap(),
+            "\"srtm1\""
+        );
+
+        let stats = TileStats {
+            min: Some(-5),
+            max: None,
+            mean: Some(12.5),
+            void_count: 3,
+            sample_count: 1_442_401,
+        };
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(serde_json::from_str::<TileStats>(&json).unwrap(), stats);
+
+        let health = TileHealth {
+            all_void: false,
+            all_zero: true,
+            void_ratio: 0.25,
+            suspicious: true,
+        };
+        let json = serde_json::to_string(&health).unwrap();
+        assert_eq!(serde_json::from_str::<TileHealth>(&json).unwrap(), health);
+    }
 }
@@ -0,0 +1,130 @@
+//! Web Mercator (slippy map) tile math.
+//!
+//! Converts between geographic coordinates and the `z/x/y` tile scheme used
+//! by XYZ web map tiles, where zoom `z` has `2^z × 2^z` tiles, `x` grows
+//! eastwards from 180°W and `y` grows southwards from the north edge.
+//!
+//! Web Mercator only covers latitudes up to ±[`MAX_LATITUDE`] (~85.05°);
+//! SRTM data only covers ±60°, so tiles near the poles have no elevations.
+//!
+//! # Example
+//!
+//! ```
+//! use htg::tiling::{lat_lon_to_tile, tile_to_bounds};
+//!
+//! // Tokyo at zoom 10
+//! let (x, y) = lat_lon_to_tile(35.6762, 139.6503, 10);
+//! assert_eq!((x, y), (909, 403));
+//!
+//! let (min_lat, min_lon, max_lat, max_lon) = tile_to_bounds(10, x, y);
+//! assert!(min_lat < 35.6762 && 35.6762 < max_lat);
+//! assert!(min_lon < 139.6503 && 139.6503 < max_lon);
+//! ```
+
+use std::f64::consts::PI;
+
+/// Northern and southern limit of Web Mercator in degrees.
+pub const MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// Geographic extent of tile `z/x/y` as `(min_lat, min_lon, max_lat, max_lon)`,
+/// the same order as [`BoundingBox::new`](crate::BoundingBox::new).
+///
+/// `x` and `y` are not range-checked; values outside `0..2^z` extrapolate
+/// beyond the world.
+pub fn tile_to_bounds(z: u8, x: u32, y: u32) -> (f64, f64, f64, f64) {
+    let n = tiles_per_side(z);
+    let lon = |x: f64| x / n * 360.0 - 180.0;
+    let lat = |y: f64| (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+
+    let (x, y) = (x as f64, y as f64);
+    (lat(y + 1.0), lon(x), lat(y), lon(x + 1.0))
+}
+
+/// The `(x, y)` tile containing `(lat, lon)` at zoom `z`.
+///
+/// Latitudes are clamped to ±[`MAX_LATITUDE`] and longitudes to ±180°, so
+/// the result is always a valid tile at zoom `z`.
+pub fn lat_lon_to_tile(lat: f64, lon: f64, z: u8) -> (u32, u32) {
+    let n = tiles_per_side(z);
+    let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let lon = lon.clamp(-180.0, 180.0);
+
+    let x = (lon + 180.0) / 360.0 * n;
+    let y = (1.0 - lat.tan().asinh() / PI) / 2.0 * n;
+
+    let max = n - 1.0;
+    (
+        x.floor().clamp(0.0, max) as u32,
+        y.floor().clamp(0.0, max) as u32,
+    )
+}
+
+/// Number of tiles along each axis at zoom `z`.
+fn tiles_per_side(z: u8) -> f64 {
+    2f64.powi(z as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_bounds(actual: (f64, f64, f64, f64), expected: (f64, f64, f64, f64)) {
+        let pairs = [
+            (actual.0, expected.0),
+            (actual.1, expected.1),
+            (actual.2, expected.2),
+            (actual.3, expected.3),
+        ];
+        for (a, e) in pairs {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_zoom_zero_covers_world() {
+        assert_bounds(
+            tile_to_bounds(0, 0, 0),
+            (-MAX_LATITUDE, -180.0, MAX_LATITUDE, 180.0),
+        );
+        assert_eq!(lat_lon_to_tile(0.0, 0.0, 0), (0, 0));
+        assert_eq!(lat_lon_to_tile(89.0, 180.0, 0), (0, 0));
+    }
+
+    #[test]
+    fn test_tokyo_zoom_10() {
+        assert_eq!(lat_lon_to_tile(35.6762, 139.6503, 10), (909, 403));
+        assert_bounds(
+            tile_to_bounds(10, 909, 403),
+            (
+                35.460_669_951_495_3,
+                139.570_312_5,
+                35.746_512_259_918_5,
+                139.921_875,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_quadrants_and_clamping() {
+        assert_eq!(lat_lon_to_tile(10.0, -10.0, 1), (0, 0));
+        assert_eq!(lat_lon_to_tile(10.0, 10.0, 1), (1, 0));
+        assert_eq!(lat_lon_to_tile(-10.0, -10.0, 1), (0, 1));
+        assert_eq!(lat_lon_to_tile(-10.0, 10.0, 1), (1, 1));
+
+        // Poles and the antimeridian map to the edge tiles
+        assert_eq!(lat_lon_to_tile(90.0, 180.0, 4), (15, 0));
+        assert_eq!(lat_lon_to_tile(-90.0, -180.0, 4), (0, 15));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (lat, lon) in [(-59.5, -179.9), (0.1, 0.1), (45.0, 7.5), (59.9, 179.9)] {
+            for z in [0, 5, 12, 18] {
+                let (x, y) = lat_lon_to_tile(lat, lon, z);
+                let (min_lat, min_lon, max_lat, max_lon) = tile_to_bounds(z, x, y);
+                assert!((min_lat..=max_lat).contains(&lat), "{} z{}", lat, z);
+                assert!((min_lon..=max_lon).contains(&lon), "{} z{}", lon, z);
+            }
+        }
+    }
+}